@@ -0,0 +1,52 @@
+// Synthetic microbenchmark: Nord delta frame decode
+// Parses a {"delta": ...} frame with 20 levels per side 200k times.
+// Rust counterpart: rust/nord/examples/bench_decode.rs
+
+const LEVELS = 20;
+
+const side = (base: number, step: number) =>
+	Array.from({ length: LEVELS }, (_, i) => ({
+		price: base + step * i,
+		size: 1 + i * 0.125,
+	}));
+
+const SAMPLE_MSG = JSON.stringify({
+	delta: {
+		e: "delta",
+		last_update_id: 1000,
+		update_id: 1001,
+		market_symbol: "BTCUSD",
+		asks: side(43568.0, 0.5),
+		bids: side(43567.5, -0.5),
+		timestamp: 1700000000000,
+	},
+});
+
+type Delta = {
+	delta: { update_id: number; asks: { price: number; size: number }[] };
+};
+
+const ITERATIONS = 200_000;
+
+// Warm up JIT
+for (let i = 0; i < 10000; i++) {
+	const _msg = JSON.parse(SAMPLE_MSG) as Delta;
+}
+
+const t0 = process.hrtime.bigint();
+
+let sum = 0;
+for (let i = 0; i < ITERATIONS; i++) {
+	const msg = JSON.parse(SAMPLE_MSG) as Delta;
+	sum += msg.delta.asks[0].price; // prevent dead code elimination
+}
+
+const elapsed = Number(process.hrtime.bigint() - t0);
+const elapsedMs = elapsed / 1e6;
+const perIterUs = elapsed / ITERATIONS / 1000;
+
+console.log(`TypeScript delta decode benchmark`);
+console.log(`  Iterations: ${ITERATIONS.toLocaleString()}, levels per side: ${LEVELS}`);
+console.log(`  Total time: ${elapsedMs.toFixed(1)} ms`);
+console.log(`  Per frame: ${perIterUs.toFixed(2)} us`);
+console.log(`  (sum=${sum} to prevent DCE)`);
@@ -0,0 +1,97 @@
+//! Synthetic benchmark: Nord delta frame decode.
+//!
+//! Decodes a `{"delta": ...}` frame with 20 levels per side 200k times, via
+//! the old `serde_json::Value` + sub-tree `clone()` + `from_value` path and
+//! via [`WebSocketMessage::from_json`], then compares handing the decoded
+//! update to three subscribers by deep clone and by `Arc`. Compare with
+//! `bench_decode.ts` at the repository root.
+//!
+//! ```text
+//!   cargo run --release -p nord --example bench_decode
+//! ```
+
+use std::hint::black_box;
+use std::sync::Arc;
+use std::time::Instant;
+
+use nord::ws::events::{WebSocketDeltaUpdate, WebSocketMessage};
+
+const ITERATIONS: u32 = 200_000;
+const LEVELS: usize = 20;
+const SUBSCRIBERS: usize = 3;
+
+fn sample_frame() -> String {
+    let side = |base: f64, step: f64| {
+        (0..LEVELS)
+            .map(|i| {
+                format!(
+                    r#"{{"price":{:.1},"size":{:.3}}}"#,
+                    base + step * i as f64,
+                    1.0 + i as f64 * 0.125
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(",")
+    };
+    format!(
+        r#"{{"delta":{{"e":"delta","last_update_id":1000,"update_id":1001,"market_symbol":"BTCUSD","asks":[{}],"bids":[{}],"timestamp":1700000000000}}}}"#,
+        side(43_568.0, 0.5),
+        side(43_567.5, -0.5)
+    )
+}
+
+fn decode_value(text: &str) -> WebSocketDeltaUpdate {
+    let value: serde_json::Value = serde_json::from_str(text).unwrap();
+    let delta = value.get("delta").unwrap().clone();
+    serde_json::from_value(delta).unwrap()
+}
+
+fn decode_typed(text: &str) -> WebSocketDeltaUpdate {
+    match WebSocketMessage::from_json(text) {
+        Ok(Some(WebSocketMessage::Delta(update))) => update,
+        other => panic!("expected delta, got {other:?}"),
+    }
+}
+
+/// Run `f` `ITERATIONS` times after a warmup and print the time per call.
+fn bench(label: &str, mut f: impl FnMut()) {
+    for _ in 0..10_000 {
+        f();
+    }
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        f();
+    }
+    let per_iter = start.elapsed().as_nanos() as f64 / f64::from(ITERATIONS);
+    println!("  {label:<40} {:>8.2} us", per_iter / 1_000.0);
+}
+
+fn main() {
+    let frame = sample_frame();
+    assert_eq!(
+        decode_value(&frame).asks.len(),
+        decode_typed(&frame).asks.len()
+    );
+
+    println!("Rust delta decode benchmark");
+    println!("  Iterations: {ITERATIONS}, levels per side: {LEVELS}");
+    bench("Value + clone + from_value", || {
+        black_box(decode_value(black_box(&frame)));
+    });
+    bench("WebSocketMessage::from_json", || {
+        black_box(decode_typed(black_box(&frame)));
+    });
+
+    let update = decode_typed(&frame);
+    bench(&format!("fan-out to {SUBSCRIBERS}, deep clone"), || {
+        for _ in 0..SUBSCRIBERS {
+            black_box(black_box(&update).clone());
+        }
+    });
+    let shared = Arc::new(update);
+    bench(&format!("fan-out to {SUBSCRIBERS}, Arc"), || {
+        for _ in 0..SUBSCRIBERS {
+            black_box(Arc::clone(black_box(&shared)));
+        }
+    });
+}
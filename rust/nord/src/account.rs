@@ -1,4 +1,4 @@
-//! Live account order/fill tracking via WebSocket.
+//! Live account order/fill/balance tracking via WebSocket.
 //!
 //! Mirrors the TypeScript `AccountStream` class, ported to Rust following the
 //! same background-task pattern as [`crate::orderbook::OrderbookStream`].
@@ -7,9 +7,37 @@
 //!
 //! A background tokio task owns a `HashMap<u64, TrackedOrder>` and applies
 //! WebSocket `places`, `fills`, and `cancels` events. Fill events are forwarded
-//! to an `mpsc` channel so the caller can react (e.g. update position).
+//! to an `mpsc` channel so the caller can react (e.g. update position), and
+//! broadcast to any number of other subscribers (e.g. a
+//! [`PositionStream`](crate::PositionStream)). Each fill is tagged with our
+//! maker/taker role, its ids, the receive time and its fee from the
+//! account's [`FeeSchedule`], fetched when the task starts and refreshed
+//! hourly. Client order ids of orders we placed are resolved through the
+//! [`Oms`](crate::Oms), when one is attached.
 //! The current orders map is published via a `watch` channel for lock-free
 //! reads.
+//!
+//! Token balances are seeded from REST when the task starts, then overwritten
+//! per token by the `balances` map of each WebSocket update (which carries
+//! the new amount of every token the update touched). The balance map is
+//! published on a second `watch` channel, and every change is broadcast as a
+//! [`BalanceChange`]:
+//!
+//! ```text
+//!   GET /account (start, lag) ---+
+//!                                v
+//!   WS balances {token: amount} --> HashMap<String, TrackedBalance> --> watch
+//!                                         |
+//!                            with fills   +--------------------+
+//!                                         |                    |
+//!                            without fills v                   v
+//!   GET deposit/withdrawal/funding --> classify --> broadcast<BalanceChange>
+//! ```
+//!
+//! The WebSocket does not say why a balance changed. A change in an update
+//! that carried fills is trade settlement; any other change is matched
+//! against the account's recent deposit, withdrawal and funding history,
+//! fetched off the update path, and broadcast once labelled.
 
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -21,12 +49,30 @@ use tokio_util::sync::CancellationToken;
 use tracing::{error, info, warn};
 
 use crate::client::Nord;
-use crate::types::Side;
-use crate::ws::events::WebSocketAccountUpdate;
+use crate::clock::Clock;
+use crate::fees::FeeSchedule;
+use crate::oms::OrderManager;
+use crate::types::{Account, AccountFundingInfo, DepositInfo, FillRole, Side, WithdrawalInfo};
+use crate::ws::events::{AccountFill, WebSocketAccountUpdate};
 
 /// Reconnect delay after the WebSocket feed drops.
 const RECONNECT_DELAY_MS: u64 = 3000;
 
+/// Capacity of the balance change broadcast channel.
+const BALANCE_CHANNEL_CAPACITY: usize = 64;
+
+/// Capacity of the fill broadcast channel.
+const FILL_CHANNEL_CAPACITY: usize = 256;
+
+/// Interval between fee schedule refreshes (tiers follow trading volume).
+const FEE_REFRESH_INTERVAL_MS: u64 = 3_600_000;
+
+/// Most recent history entries fetched to classify a balance change.
+const BALANCE_HISTORY_PAGE_SIZE: u8 = 20;
+
+/// Tolerance when matching a history entry against a balance change.
+const BALANCE_MATCH_EPSILON: f64 = 1e-6;
+
 /// An open order tracked from WebSocket events.
 #[derive(Debug, Clone)]
 pub struct TrackedOrder {
@@ -35,6 +81,8 @@ pub struct TrackedOrder {
     pub price: f64,
     pub size: f64,
     pub market_id: u32,
+    /// Client-assigned id, when known from a REST sync.
+    pub client_order_id: Option<u64>,
 }
 
 /// A fill event emitted when an order is (partially) filled.
@@ -46,18 +94,141 @@ pub struct FillEvent {
     pub price: f64,
     pub remaining: f64,
     pub market_id: u32,
+    /// Our role in the trade.
+    pub role: FillRole,
+    pub maker_id: u32,
+    pub taker_id: u32,
+    /// Sender tracking id of the action that caused the fill, if any.
+    pub tracking_id: Option<u64>,
+    /// Client-assigned id of our order, when known.
+    pub client_order_id: Option<u64>,
+    /// Fee in quote units (negative = rebate), or `None` if the fee
+    /// schedule could not be fetched.
+    pub fee: Option<f64>,
+    /// Unix epoch milliseconds when the fill was received.
+    pub received_at: u64,
+    /// Account `update_id` of the update that carried the fill.
+    pub update_id: u64,
+}
+
+impl FillEvent {
+    /// `size * price` in quote units.
+    pub fn notional(&self) -> f64 {
+        self.size * self.price
+    }
+}
+
+/// A token balance tracked from REST snapshots and WebSocket updates.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrackedBalance {
+    pub token: String,
+    pub amount: f64,
+    /// Account `update_id` at which `amount` was last set.
+    pub update_id: u64,
+}
+
+/// Cause of a balance change.
+///
+/// The WebSocket feed does not label balance changes, so the cause is
+/// inferred from the rest of the update and the account's REST history.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BalanceChangeKind {
+    /// Changed in an update that also carried fills: trade settlement and
+    /// trading fees.
+    Fill,
+    /// Matches a recent deposit of the token.
+    Deposit,
+    /// Matches a recent withdrawal of the token (including its fee).
+    Withdrawal,
+    /// Matches a recent funding payment, received or paid.
+    Funding,
+    /// Increased without a fill or a matching history entry (e.g. a rebate,
+    /// or the history could not be fetched).
+    Credit,
+    /// Decreased without a fill or a matching history entry (e.g. a fee).
+    Debit,
+    /// Corrected by a REST snapshot after the stream lagged.
+    Resync,
+}
+
+/// A change to one token balance.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BalanceChange {
+    pub token: String,
+    pub old_amount: f64,
+    pub new_amount: f64,
+    pub update_id: u64,
+    pub kind: BalanceChangeKind,
+}
+
+impl BalanceChange {
+    /// Signed change (`new_amount - old_amount`).
+    pub fn delta(&self) -> f64 {
+        self.new_amount - self.old_amount
+    }
+}
+
+/// Where fill events go (the single-consumer queue and the broadcast), and
+/// what is needed to enrich them.
+#[derive(Clone)]
+struct FillSink {
+    queue: mpsc::UnboundedSender<FillEvent>,
+    events: broadcast::Sender<FillEvent>,
+    account_id: u32,
+    fees: Option<FeeSchedule>,
+    /// OMS state, for client order ids the WebSocket does not carry.
+    oms: Option<watch::Receiver<OrderManager>>,
+    clock: Clock,
+}
+
+impl FillSink {
+    fn send(&self, fill: FillEvent) {
+        let _ = self.events.send(fill.clone());
+        let _ = self.queue.send(fill);
+    }
+
+    /// Our role in a fill, or `None` if the account is on neither side.
+    fn role(&self, fill: &AccountFill) -> Option<FillRole> {
+        if fill.maker_id == self.account_id {
+            Some(FillRole::Maker)
+        } else if fill.taker_id == self.account_id {
+            Some(FillRole::Taker)
+        } else {
+            None
+        }
+    }
+
+    /// Client order id of one of our orders, if the OMS has mapped it.
+    fn client_order_id(&self, order_id: u64) -> Option<u64> {
+        self.oms
+            .as_ref()?
+            .borrow()
+            .get_by_order_id(order_id)
+            .map(|o| o.client_order_id)
+    }
 }
 
-/// Live account stream — tracks orders/fills/cancels in real-time.
+/// Senders for the balance map and its change events.
+#[derive(Clone)]
+struct BalanceTx {
+    state: watch::Sender<HashMap<String, TrackedBalance>>,
+    changes: broadcast::Sender<BalanceChange>,
+}
+
+/// Live account stream — tracks orders/fills/cancels/balances in real-time.
 ///
 /// Call [`AccountStream::new`] then [`AccountStream::connect`] to start.
 pub struct AccountStream {
     account_id: u32,
-    account_rx: Option<broadcast::Receiver<WebSocketAccountUpdate>>,
+    account_rx: Option<broadcast::Receiver<Arc<WebSocketAccountUpdate>>>,
     orders_rx: watch::Receiver<HashMap<u64, TrackedOrder>>,
     orders_tx: watch::Sender<HashMap<u64, TrackedOrder>>,
     fill_rx: Option<mpsc::UnboundedReceiver<FillEvent>>,
-    fill_tx: mpsc::UnboundedSender<FillEvent>,
+    fill_tx: FillSink,
+    balances_rx: watch::Receiver<HashMap<String, TrackedBalance>>,
+    balance_tx: BalanceTx,
+    /// Markets whose fee rates are fetched (default: every market).
+    fee_markets: Vec<u32>,
     cancel: CancellationToken,
     task_handle: Option<JoinHandle<()>>,
     nord: Option<Arc<Nord>>,
@@ -74,25 +245,55 @@ impl AccountStream {
     /// * `nord` - Shared Nord client for REST re-sync on reconnect.
     pub fn new(
         account_id: u32,
-        account_rx: broadcast::Receiver<WebSocketAccountUpdate>,
+        account_rx: broadcast::Receiver<Arc<WebSocketAccountUpdate>>,
         nord: Arc<Nord>,
     ) -> Self {
         let (orders_tx, orders_rx) = watch::channel(HashMap::new());
-        let (fill_tx, fill_rx) = mpsc::unbounded_channel();
+        let (queue, fill_rx) = mpsc::unbounded_channel();
+        let (events, _) = broadcast::channel(FILL_CHANNEL_CAPACITY);
+        let (state, balances_rx) = watch::channel(HashMap::new());
+        let (changes, _) = broadcast::channel(BALANCE_CHANNEL_CAPACITY);
         Self {
             account_id,
             account_rx: Some(account_rx),
             orders_rx,
             orders_tx,
             fill_rx: Some(fill_rx),
-            fill_tx,
+            fill_tx: FillSink {
+                queue,
+                events,
+                account_id,
+                fees: None,
+                oms: None,
+                clock: nord.clock(),
+            },
+            balances_rx,
+            balance_tx: BalanceTx { state, changes },
+            fee_markets: nord.markets.iter().map(|m| m.market_id).collect(),
             cancel: CancellationToken::new(),
             task_handle: None,
             nord: Some(nord),
         }
     }
 
-    /// Start the background processing task.
+    /// Only fetch market-specific fee rates for `market_ids`; fills in other
+    /// markets are charged the account's tier rates.
+    pub fn with_fee_markets(mut self, market_ids: Vec<u32>) -> Self {
+        self.fee_markets = market_ids;
+        self
+    }
+
+    /// Resolve client order ids of orders placed through `oms` (from
+    /// [`Oms::subscribe`](crate::Oms::subscribe)). The WebSocket feed does
+    /// not carry them, so without this only orders seeded by
+    /// [`sync_initial_orders`](Self::sync_initial_orders) have one.
+    pub fn with_order_ids(mut self, oms: watch::Receiver<OrderManager>) -> Self {
+        self.fill_tx.oms = Some(oms);
+        self
+    }
+
+    /// Start the background processing task. The task seeds balances and
+    /// fetches the fee schedule from REST before applying WebSocket updates.
     pub fn connect(&mut self) {
         let account_rx = self
             .account_rx
@@ -102,6 +303,8 @@ impl AccountStream {
         let account_id = self.account_id;
         let orders_tx = self.orders_tx.clone();
         let fill_tx = self.fill_tx.clone();
+        let balance_tx = self.balance_tx.clone();
+        let fee_markets = self.fee_markets.clone();
         let cancel = self.cancel.clone();
         let nord = self
             .nord
@@ -111,7 +314,17 @@ impl AccountStream {
         info!(account_id, "subscribing to account updates");
 
         let handle = tokio::spawn(async move {
-            run_account_task(account_id, account_rx, orders_tx, fill_tx, cancel, nord).await;
+            run_account_task(
+                account_id,
+                account_rx,
+                orders_tx,
+                fill_tx,
+                balance_tx,
+                fee_markets,
+                cancel,
+                nord,
+            )
+            .await;
         });
         self.task_handle = Some(handle);
     }
@@ -121,6 +334,13 @@ impl AccountStream {
         self.fill_rx.take()
     }
 
+    /// Subscribe to fill events. Unlike [`take_fill_rx`](Self::take_fill_rx)
+    /// this can be called any number of times; only fills after the call are
+    /// received.
+    pub fn subscribe_fills(&self) -> broadcast::Receiver<FillEvent> {
+        self.fill_tx.events.subscribe()
+    }
+
     /// Current orders map (lock-free read).
     pub fn get_orders(&self) -> HashMap<u64, TrackedOrder> {
         self.orders_rx.borrow().clone()
@@ -156,6 +376,7 @@ impl AccountStream {
                         price: o.price,
                         size: o.size,
                         market_id: o.market_id,
+                        client_order_id: o.client_order_id,
                     },
                 )
             })
@@ -163,6 +384,35 @@ impl AccountStream {
         let _ = self.orders_tx.send(map);
     }
 
+    /// Current balances keyed by token symbol (lock-free read).
+    pub fn get_balances(&self) -> HashMap<String, TrackedBalance> {
+        self.balances_rx.borrow().clone()
+    }
+
+    /// Current amount of one token, or `None` if the account holds none.
+    pub fn get_balance(&self, token: &str) -> Option<f64> {
+        self.balances_rx.borrow().get(token).map(|b| b.amount)
+    }
+
+    /// Subscribe to balance map changes.
+    pub fn subscribe_balances(&self) -> watch::Receiver<HashMap<String, TrackedBalance>> {
+        self.balances_rx.clone()
+    }
+
+    /// Subscribe to individual balance changes (deposits, withdrawals,
+    /// funding, fees, fill settlement).
+    pub fn subscribe_balance_changes(&self) -> broadcast::Receiver<BalanceChange> {
+        self.balance_tx.changes.subscribe()
+    }
+
+    /// Seed balances from an account snapshot the caller already holds.
+    ///
+    /// Tokens updated by the WebSocket after the snapshot's `update_id` keep
+    /// their newer amount.
+    pub fn sync_initial_balances(&self, account: &Account) {
+        apply_balance_snapshot(account, &self.balance_tx);
+    }
+
     /// Shut down the background task.
     pub fn close(&self) {
         self.cancel.cancel();
@@ -182,18 +432,41 @@ impl Drop for AccountStream {
 // Background task
 // ---------------------------------------------------------------------------
 
+#[allow(clippy::too_many_arguments)]
 async fn run_account_task(
     account_id: u32,
-    mut account_rx: broadcast::Receiver<WebSocketAccountUpdate>,
+    mut account_rx: broadcast::Receiver<Arc<WebSocketAccountUpdate>>,
     orders_tx: watch::Sender<HashMap<u64, TrackedOrder>>,
-    fill_tx: mpsc::UnboundedSender<FillEvent>,
+    mut fill_tx: FillSink,
+    balance_tx: BalanceTx,
+    fee_markets: Vec<u32>,
     cancel: CancellationToken,
     nord: Arc<Nord>,
 ) {
     info!(account_id, "account stream active");
 
+    // Seed balances. Updates buffered meanwhile are newer and win per token.
+    match nord.get_account(account_id).await {
+        Ok(account) => {
+            apply_balance_snapshot(&account, &balance_tx);
+            info!(
+                account_id,
+                tokens = account.balances.len(),
+                "seeded balances from server"
+            );
+        }
+        Err(e) => error!(account_id, error = %e, "failed to seed balances"),
+    }
+
+    refresh_fees(&nord, account_id, &fee_markets, &mut fill_tx).await;
+    let fee_period = Duration::from_millis(FEE_REFRESH_INTERVAL_MS);
+    let mut fee_interval = time::interval_at(time::Instant::now() + fee_period, fee_period);
+
     loop {
         tokio::select! {
+            _ = fee_interval.tick() => {
+                refresh_fees(&nord, account_id, &fee_markets, &mut fill_tx).await;
+            }
             update = account_rx.recv() => {
                 match update {
                     Ok(data) => {
@@ -201,10 +474,19 @@ async fn run_account_task(
                             continue;
                         }
                         apply_update(&data, &orders_tx, &fill_tx);
+                        let unlabelled = apply_balances(&data, &balance_tx);
+                        if !unlabelled.is_empty() {
+                            tokio::spawn(classify_balance_changes(
+                                Arc::clone(&nord),
+                                account_id,
+                                unlabelled,
+                                balance_tx.changes.clone(),
+                            ));
+                        }
                     }
                     Err(broadcast::error::RecvError::Lagged(n)) => {
                         warn!(account_id, skipped = n, "account stream lagged — re-syncing");
-                        resync_orders(&nord, account_id, &orders_tx).await;
+                        resync_account(&nord, account_id, &orders_tx, &balance_tx).await;
                     }
                     Err(broadcast::error::RecvError::Closed) => {
                         warn!(account_id, "account broadcast channel closed");
@@ -224,16 +506,40 @@ async fn run_account_task(
     }
 }
 
+/// Fetch the fee schedule. On failure the previous schedule, if any, is kept.
+async fn refresh_fees(nord: &Nord, account_id: u32, fee_markets: &[u32], fill_tx: &mut FillSink) {
+    match FeeSchedule::fetch(nord, account_id, fee_markets).await {
+        Ok(fees) => {
+            info!(
+                account_id,
+                tier = fees.tier,
+                maker = fees.maker_rate,
+                taker = fees.taker_rate,
+                "fetched fee schedule"
+            );
+            fill_tx.fees = Some(fees);
+        }
+        Err(e) if fill_tx.fees.is_some() => {
+            warn!(account_id, error = %e, "failed to refresh fees, keeping the previous schedule");
+        }
+        Err(e) => error!(account_id, error = %e, "failed to fetch fees, fills carry no fee"),
+    }
+}
+
 /// Apply a single WebSocket account update to the orders map.
 fn apply_update(
     data: &WebSocketAccountUpdate,
     orders_tx: &watch::Sender<HashMap<u64, TrackedOrder>>,
-    fill_tx: &mpsc::UnboundedSender<FillEvent>,
+    fill_tx: &FillSink,
 ) {
     orders_tx.send_modify(|orders| {
         // Placements
         for (id_str, place) in &data.places {
             if let Ok(order_id) = id_str.parse::<u64>() {
+                let client_order_id = orders
+                    .get(&order_id)
+                    .and_then(|o| o.client_order_id)
+                    .or_else(|| fill_tx.client_order_id(order_id));
                 orders.insert(
                     order_id,
                     TrackedOrder {
@@ -242,6 +548,7 @@ fn apply_update(
                         price: place.price,
                         size: place.current_size,
                         market_id: place.market_id,
+                        client_order_id,
                     },
                 );
             }
@@ -251,13 +558,36 @@ fn apply_update(
         for (id_str, fill) in &data.fills {
             if let Ok(order_id) = id_str.parse::<u64>() {
                 if fill.quantity > 0.0 {
-                    let _ = fill_tx.send(FillEvent {
+                    let Some(role) = fill_tx.role(fill) else {
+                        warn!(
+                            order_id,
+                            maker_id = fill.maker_id,
+                            taker_id = fill.taker_id,
+                            "fill does not involve our account — skipped"
+                        );
+                        continue;
+                    };
+                    fill_tx.send(FillEvent {
                         order_id,
                         side: fill.side,
                         size: fill.quantity,
                         price: fill.price,
                         remaining: fill.remaining,
                         market_id: fill.market_id,
+                        role,
+                        maker_id: fill.maker_id,
+                        taker_id: fill.taker_id,
+                        tracking_id: fill.sender_tracking_id,
+                        client_order_id: orders
+                            .get(&order_id)
+                            .and_then(|o| o.client_order_id)
+                            .or_else(|| fill_tx.client_order_id(order_id)),
+                        fee: fill_tx
+                            .fees
+                            .as_ref()
+                            .map(|fees| fees.fee(fill.market_id, role, fill.quantity, fill.price)),
+                        received_at: fill_tx.clock.now_ms(),
+                        update_id: data.update_id,
                     });
                 }
 
@@ -278,11 +608,215 @@ fn apply_update(
     });
 }
 
-/// Re-fetch orders from the server after a lag event.
-async fn resync_orders(
+/// Overwrite the balance of every token in a WebSocket update that is newer
+/// than the tracked amount. Changes settled by fills are broadcast; the
+/// others are returned as [`BalanceChangeKind::Credit`] or
+/// [`BalanceChangeKind::Debit`] to be classified.
+fn apply_balances(data: &WebSocketAccountUpdate, balance_tx: &BalanceTx) -> Vec<BalanceChange> {
+    let mut changes = Vec::new();
+    balance_tx.state.send_if_modified(|balances| {
+        let mut modified = false;
+        for (token, &amount) in &data.balances {
+            let old_amount = match balances.get(token) {
+                Some(b) if b.update_id >= data.update_id => continue,
+                Some(b) => b.amount,
+                None => 0.0,
+            };
+            balances.insert(
+                token.clone(),
+                TrackedBalance {
+                    token: token.clone(),
+                    amount,
+                    update_id: data.update_id,
+                },
+            );
+            modified = true;
+
+            if amount != old_amount {
+                let kind = if !data.fills.is_empty() {
+                    BalanceChangeKind::Fill
+                } else if amount > old_amount {
+                    BalanceChangeKind::Credit
+                } else {
+                    BalanceChangeKind::Debit
+                };
+                changes.push(BalanceChange {
+                    token: token.clone(),
+                    old_amount,
+                    new_amount: amount,
+                    update_id: data.update_id,
+                    kind,
+                });
+            }
+        }
+        modified
+    });
+    let (fills, unlabelled): (Vec<_>, Vec<_>) = changes
+        .into_iter()
+        .partition(|c| c.kind == BalanceChangeKind::Fill);
+    for change in fills {
+        let _ = balance_tx.changes.send(change);
+    }
+    unlabelled
+}
+
+/// Label fill-less balance changes from the account's REST history and
+/// broadcast them. Changes stay `Credit`/`Debit` if nothing matches.
+async fn classify_balance_changes(
+    nord: Arc<Nord>,
+    account_id: u32,
+    mut changes: Vec<BalanceChange>,
+    tx: broadcast::Sender<BalanceChange>,
+) {
+    let history = BalanceHistory::fetch(&nord, account_id, &changes).await;
+    for change in &mut changes {
+        let token_id = nord
+            .tokens
+            .iter()
+            .find(|t| t.symbol == change.token)
+            .map(|t| t.token_id);
+        change.kind = history.classify(change, token_id);
+        let _ = tx.send(change.clone());
+    }
+}
+
+/// Recent account history that can explain a balance change without fills.
+#[derive(Debug, Default)]
+struct BalanceHistory {
+    deposits: Vec<DepositInfo>,
+    withdrawals: Vec<WithdrawalInfo>,
+    funding: Vec<AccountFundingInfo>,
+}
+
+impl BalanceHistory {
+    /// Fetch the latest page of each history that can explain `changes`
+    /// (deposits for credits, withdrawals for debits, funding for both).
+    /// A failed fetch is logged and leaves that history empty.
+    async fn fetch(nord: &Nord, account_id: u32, changes: &[BalanceChange]) -> Self {
+        let page = Some(BALANCE_HISTORY_PAGE_SIZE);
+        let mut history = Self::default();
+        if changes.iter().any(|c| c.delta() > 0.0) {
+            match nord
+                .get_account_deposit_history(account_id, None, None, None, page)
+                .await
+            {
+                Ok(result) => history.deposits = result.items,
+                Err(e) => warn!(account_id, error = %e, "failed to fetch deposit history"),
+            }
+        }
+        if changes.iter().any(|c| c.delta() < 0.0) {
+            match nord
+                .get_account_withdrawal_history(account_id, None, None, None, page)
+                .await
+            {
+                Ok(result) => history.withdrawals = result.items,
+                Err(e) => warn!(account_id, error = %e, "failed to fetch withdrawal history"),
+            }
+        }
+        match nord
+            .get_account_funding_history(account_id, None, None, None, None, page)
+            .await
+        {
+            Ok(result) => history.funding = result.items,
+            Err(e) => warn!(account_id, error = %e, "failed to fetch funding history"),
+        }
+        history
+    }
+
+    /// Kind of the first history entry matching `change`: a deposit or
+    /// withdrawal of the token that left its balance at the new amount or
+    /// moved it by the same amount, or a funding settlement (all markets
+    /// settled by one action) worth the same amount.
+    fn classify(&self, change: &BalanceChange, token_id: Option<u32>) -> BalanceChangeKind {
+        let delta = change.delta();
+        let near = |a: f64, b: f64| (a - b).abs() <= BALANCE_MATCH_EPSILON;
+        let same_token = |id: u32| token_id == Some(id);
+
+        if delta > 0.0
+            && self.deposits.iter().any(|d| {
+                same_token(d.token_id)
+                    && (near(d.balance, change.new_amount) || near(d.amount, delta))
+            })
+        {
+            return BalanceChangeKind::Deposit;
+        }
+        if delta < 0.0
+            && self.withdrawals.iter().any(|w| {
+                same_token(w.token_id)
+                    && (near(w.balance, change.new_amount)
+                        || near(w.amount.abs(), -delta)
+                        || near(w.amount.abs() + w.fee, -delta))
+            })
+        {
+            return BalanceChangeKind::Withdrawal;
+        }
+
+        let mut settlements: HashMap<u64, f64> = HashMap::new();
+        for payment in &self.funding {
+            *settlements.entry(payment.action_id).or_default() += payment.funding_pnl;
+        }
+        if settlements.values().any(|&pnl| near(pnl, delta)) {
+            return BalanceChangeKind::Funding;
+        }
+
+        if delta > 0.0 {
+            BalanceChangeKind::Credit
+        } else {
+            BalanceChangeKind::Debit
+        }
+    }
+}
+
+/// Replace balances with a REST snapshot, keeping tokens the WebSocket has
+/// updated since. Differences from previously tracked amounts are broadcast
+/// as [`BalanceChangeKind::Resync`]; the first seed broadcasts nothing.
+fn apply_balance_snapshot(account: &Account, balance_tx: &BalanceTx) {
+    let mut changes = Vec::new();
+    balance_tx.state.send_modify(|balances| {
+        let mut seeded: HashMap<String, TrackedBalance> = account
+            .balances
+            .iter()
+            .map(|b| {
+                (
+                    b.token.clone(),
+                    TrackedBalance {
+                        token: b.token.clone(),
+                        amount: b.amount,
+                        update_id: account.update_id,
+                    },
+                )
+            })
+            .collect();
+
+        for (token, old) in balances.drain() {
+            if old.update_id > account.update_id {
+                seeded.insert(token, old);
+                continue;
+            }
+            let new_amount = seeded.get(&token).map_or(0.0, |b| b.amount);
+            if new_amount != old.amount {
+                changes.push(BalanceChange {
+                    token,
+                    old_amount: old.amount,
+                    new_amount,
+                    update_id: account.update_id,
+                    kind: BalanceChangeKind::Resync,
+                });
+            }
+        }
+        *balances = seeded;
+    });
+    for change in changes {
+        let _ = balance_tx.changes.send(change);
+    }
+}
+
+/// Re-fetch orders and balances from the server after a lag event.
+async fn resync_account(
     nord: &Nord,
     account_id: u32,
     orders_tx: &watch::Sender<HashMap<u64, TrackedOrder>>,
+    balance_tx: &BalanceTx,
 ) {
     match nord.get_account(account_id).await {
         Ok(account) => {
@@ -298,6 +832,7 @@ async fn resync_orders(
                             price: o.price,
                             size: o.size,
                             market_id: o.market_id,
+                            client_order_id: o.client_order_id,
                         },
                     )
                 })
@@ -305,12 +840,14 @@ async fn resync_orders(
             info!(
                 account_id,
                 orders = map.len(),
-                "re-synced orders from server"
+                tokens = account.balances.len(),
+                "re-synced orders and balances from server"
             );
             let _ = orders_tx.send(map);
+            apply_balance_snapshot(&account, balance_tx);
         }
         Err(e) => {
-            error!(account_id, error = %e, "failed to re-sync orders");
+            error!(account_id, error = %e, "failed to re-sync account");
         }
     }
 }
@@ -318,7 +855,7 @@ async fn resync_orders(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::ws::events::{AccountCancel, AccountFill, AccountPlace};
+    use crate::ws::events::{AccountCancel, AccountPlace};
 
     /// Helper: build a WebSocketAccountUpdate from places/fills/cancels.
     fn make_update(
@@ -337,15 +874,33 @@ mod tests {
         }
     }
 
-    fn orders_and_fill() -> (
+    type OrdersAndFill = (
         watch::Sender<HashMap<u64, TrackedOrder>>,
         watch::Receiver<HashMap<u64, TrackedOrder>>,
-        mpsc::UnboundedSender<FillEvent>,
+        FillSink,
         mpsc::UnboundedReceiver<FillEvent>,
-    ) {
+    );
+
+    fn orders_and_fill() -> OrdersAndFill {
         let (otx, orx) = watch::channel(HashMap::new());
-        let (ftx, frx) = mpsc::unbounded_channel();
-        (otx, orx, ftx, frx)
+        let (queue, frx) = mpsc::unbounded_channel();
+        let (events, _) = broadcast::channel(16);
+        let sink = FillSink {
+            queue,
+            events,
+            account_id: 1,
+            fees: Some(FeeSchedule::from_tier(
+                1,
+                0,
+                &crate::types::FeeTierConfig {
+                    maker_fee_ppm: 100,
+                    taker_fee_ppm: 500,
+                },
+            )),
+            oms: None,
+            clock: Clock::manual(42),
+        };
+        (otx, orx, sink, frx)
     }
 
     #[test]
@@ -421,6 +976,9 @@ mod tests {
         assert_eq!(fill.order_id, 200);
         assert!((fill.size - 0.5).abs() < 1e-6);
         assert!((fill.remaining - 1.5).abs() < 1e-6);
+        assert_eq!(fill.role, FillRole::Maker);
+        assert!((fill.fee.unwrap() - 0.0001 * 0.5 * 51000.0).abs() < 1e-9);
+        assert_eq!(fill.received_at, 42);
 
         // Check order size was updated.
         let orders = orx.borrow();
@@ -599,4 +1157,240 @@ mod tests {
         assert_eq!(fill.order_id, 500);
         assert!((fill.size - 0.3).abs() < 1e-6);
     }
+
+    fn fill_of(order_id: u64, maker_id: u32, taker_id: u32) -> HashMap<String, AccountFill> {
+        let mut fills = HashMap::new();
+        fills.insert(
+            order_id.to_string(),
+            AccountFill {
+                side: Side::Bid,
+                quantity: 0.5,
+                remaining: 0.5,
+                price: 100.0,
+                order_id: order_id.to_string(),
+                market_id: 1,
+                maker_id,
+                taker_id,
+                sender_tracking_id: Some(9),
+            },
+        );
+        fills
+    }
+
+    #[test]
+    fn test_fill_role_checks_both_sides() {
+        let (otx, _orx, ftx, mut frx) = orders_and_fill();
+        apply_update(
+            &make_update(HashMap::new(), fill_of(600, 2, 1), HashMap::new()),
+            &otx,
+            &ftx,
+        );
+        let fill = frx.try_recv().unwrap();
+        assert_eq!(fill.role, FillRole::Taker);
+        assert!((fill.fee.unwrap() - 0.0005 * 0.5 * 100.0).abs() < 1e-9);
+
+        // Neither side is us: not our fill.
+        apply_update(
+            &make_update(HashMap::new(), fill_of(601, 2, 3), HashMap::new()),
+            &otx,
+            &ftx,
+        );
+        assert!(frx.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_fill_takes_client_order_id_from_oms() {
+        let (otx, _orx, mut ftx, mut frx) = orders_and_fill();
+        let mut oms = OrderManager::new(HashMap::new(), 77);
+        let client_order_id = oms.submit(1, Side::Bid, 100.0, 1.0, 0);
+        oms.apply_open_orders(
+            &[crate::types::OpenOrder {
+                order_id: 700,
+                market_id: 1,
+                side: Side::Bid,
+                size: 1.0,
+                price: 100.0,
+                original_order_size: 1.0,
+                client_order_id: Some(client_order_id),
+            }],
+            0,
+        );
+        ftx.oms = Some(watch::channel(oms).1);
+
+        apply_update(
+            &make_update(HashMap::new(), fill_of(700, 1, 2), HashMap::new()),
+            &otx,
+            &ftx,
+        );
+        let fill = frx.try_recv().unwrap();
+        assert_eq!(fill.client_order_id, Some(77));
+        assert_eq!(fill.tracking_id, Some(9));
+    }
+
+    fn balance_tx() -> (BalanceTx, broadcast::Receiver<BalanceChange>) {
+        let (state, _) = watch::channel(HashMap::new());
+        let (changes, changes_rx) = broadcast::channel(16);
+        (BalanceTx { state, changes }, changes_rx)
+    }
+
+    fn balance_update(update_id: u64, balances: &[(&str, f64)]) -> WebSocketAccountUpdate {
+        let mut update = make_update(HashMap::new(), HashMap::new(), HashMap::new());
+        update.update_id = update_id;
+        update.balances = balances
+            .iter()
+            .map(|&(token, amount)| (token.to_string(), amount))
+            .collect();
+        update
+    }
+
+    fn account(update_id: u64, balances: &[(&str, f64)]) -> Account {
+        Account {
+            update_id,
+            orders: Vec::new(),
+            positions: Vec::new(),
+            balances: balances
+                .iter()
+                .enumerate()
+                .map(|(i, &(token, amount))| crate::types::Balance {
+                    token_id: i as u32,
+                    token: token.to_string(),
+                    amount,
+                })
+                .collect(),
+            margins: crate::types::AccountMarginsView {
+                omf: 0.0,
+                mf: 0.0,
+                imf: 0.0,
+                cmf: 0.0,
+                mmf: 0.0,
+                pon: 0.0,
+                pn: 0.0,
+                bankruptcy: false,
+            },
+        }
+    }
+
+    #[test]
+    fn test_balance_updates_classify_changes() {
+        let (btx, mut changes) = balance_tx();
+        apply_balance_snapshot(&account(10, &[("USDC", 1000.0)]), &btx);
+        assert!(changes.try_recv().is_err());
+
+        // Deposit: returned for classification, not broadcast yet.
+        let unlabelled = apply_balances(&balance_update(11, &[("USDC", 1500.0)]), &btx);
+        assert!(changes.try_recv().is_err());
+        assert_eq!(unlabelled.len(), 1);
+        assert_eq!(unlabelled[0].kind, BalanceChangeKind::Credit);
+        assert_eq!(unlabelled[0].delta(), 500.0);
+
+        // Fee charged with a fill.
+        let mut update = balance_update(12, &[("USDC", 1499.5)]);
+        update.fills.insert(
+            "1".to_string(),
+            AccountFill {
+                side: Side::Bid,
+                quantity: 0.1,
+                remaining: 0.0,
+                price: 50000.0,
+                order_id: "1".to_string(),
+                market_id: 1,
+                maker_id: 1,
+                taker_id: 2,
+                sender_tracking_id: None,
+            },
+        );
+        assert!(apply_balances(&update, &btx).is_empty());
+        assert_eq!(changes.try_recv().unwrap().kind, BalanceChangeKind::Fill);
+
+        // Withdrawal; then a replayed older update is ignored.
+        let unlabelled = apply_balances(&balance_update(13, &[("USDC", 499.5)]), &btx);
+        assert_eq!(unlabelled[0].kind, BalanceChangeKind::Debit);
+        assert!(apply_balances(&balance_update(12, &[("USDC", 1499.5)]), &btx).is_empty());
+        assert!(changes.try_recv().is_err());
+
+        let balances = btx.state.borrow();
+        assert_eq!(balances["USDC"].amount, 499.5);
+        assert_eq!(balances["USDC"].update_id, 13);
+    }
+
+    fn balance_change(old_amount: f64, new_amount: f64) -> BalanceChange {
+        BalanceChange {
+            token: "USDC".into(),
+            old_amount,
+            new_amount,
+            update_id: 1,
+            kind: BalanceChangeKind::Credit,
+        }
+    }
+
+    #[test]
+    fn test_history_classifies_fill_less_changes() {
+        let history = BalanceHistory {
+            deposits: vec![DepositInfo {
+                time: String::new(),
+                action_id: 1,
+                account_id: 1,
+                token_id: 0,
+                amount: 500.0,
+                balance: 1500.0,
+                event_index: 0,
+            }],
+            withdrawals: vec![WithdrawalInfo {
+                time: String::new(),
+                action_id: 2,
+                account_id: 1,
+                token_id: 0,
+                amount: 200.0,
+                balance: 1300.0,
+                fee: 1.0,
+                dest_pubkey: None,
+            }],
+            funding: [(3, 0, 1.5), (3, 1, -0.25), (4, 0, -2.0)]
+                .into_iter()
+                .map(|(action_id, market_id, funding_pnl)| AccountFundingInfo {
+                    time: String::new(),
+                    action_id,
+                    market_id,
+                    position_size: 1.0,
+                    funding_pnl,
+                })
+                .collect(),
+        };
+        let kind = |old, new, token_id| history.classify(&balance_change(old, new), token_id);
+
+        assert_eq!(kind(1000.0, 1500.0, Some(0)), BalanceChangeKind::Deposit);
+        // The withdrawal fee is part of the debit.
+        assert_eq!(kind(1500.0, 1299.0, Some(0)), BalanceChangeKind::Withdrawal);
+        // Funding across markets settled by one action.
+        assert_eq!(kind(1500.0, 1501.25, Some(0)), BalanceChangeKind::Funding);
+        assert_eq!(kind(1500.0, 1498.0, Some(0)), BalanceChangeKind::Funding);
+        // Deposits of another token, or no match at all.
+        assert_eq!(kind(1000.0, 1500.0, Some(7)), BalanceChangeKind::Credit);
+        assert_eq!(kind(1500.0, 1499.9, Some(0)), BalanceChangeKind::Debit);
+        assert_eq!(
+            BalanceHistory::default().classify(&balance_change(1.0, 2.0), Some(0)),
+            BalanceChangeKind::Credit
+        );
+    }
+
+    #[test]
+    fn test_snapshot_resync_keeps_newer_ws_balances() {
+        let (btx, mut changes) = balance_tx();
+        apply_balance_snapshot(&account(10, &[("USDC", 1000.0), ("SOL", 2.0)]), &btx);
+        apply_balances(&balance_update(25, &[("SOL", 3.0)]), &btx);
+
+        // Snapshot at 20: USDC corrected, SOL kept from the newer WS update,
+        // and a token the account no longer holds goes to zero.
+        apply_balance_snapshot(&account(20, &[("USDC", 900.0), ("SOL", 2.5)]), &btx);
+        let change = changes.try_recv().unwrap();
+        assert_eq!(change.kind, BalanceChangeKind::Resync);
+        assert_eq!((change.old_amount, change.new_amount), (1000.0, 900.0));
+        assert!(changes.try_recv().is_err());
+        assert_eq!(btx.state.borrow()["SOL"].amount, 3.0);
+
+        apply_balance_snapshot(&account(30, &[("USDC", 900.0)]), &btx);
+        let change = changes.try_recv().unwrap();
+        assert_eq!((change.token.as_str(), change.new_amount), ("SOL", 0.0));
+        assert!(!btx.state.borrow().contains_key("SOL"));
+    }
 }
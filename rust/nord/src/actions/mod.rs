@@ -5,8 +5,10 @@ pub mod signing;
 use prost::Message;
 use std::future::Future;
 use std::pin::Pin;
+use std::time::Instant;
 
 use crate::error::{NordError, Result};
+use crate::latency::ACTION_ROUND_TRIP;
 use crate::proto::nord::{self, Action, Receipt};
 use crate::rest::NordHttpClient;
 
@@ -37,17 +39,23 @@ pub async fn prepare_action(action: &Action, sign_fn: &SignFn) -> Result<Vec<u8>
     Ok(msg)
 }
 
-/// Send a prepared action to the server and decode the receipt.
+/// Send a prepared action to the server and decode the receipt. The
+/// submit-to-response time is recorded in the client's latency telemetry
+/// whatever the outcome, so failed and timed-out submissions count too.
 pub async fn send_action(
     http_client: &NordHttpClient,
     action: &Action,
     sign_fn: &SignFn,
 ) -> Result<Receipt> {
     let payload = prepare_action(action, sign_fn).await?;
-    let response_bytes = http_client.post_action(&payload).await?;
-    let receipt = Receipt::decode_length_delimited(response_bytes.as_slice())
-        .map_err(NordError::ProtobufDecode)?;
-    Ok(receipt)
+    let submitted = Instant::now();
+    let response = http_client.post_action(&payload).await;
+    http_client.latency().record(
+        ACTION_ROUND_TRIP,
+        submitted.elapsed().as_secs_f64() * 1000.0,
+        http_client.clock().now_ms(),
+    );
+    Receipt::decode_length_delimited(response?.as_slice()).map_err(NordError::ProtobufDecode)
 }
 
 /// Format a receipt error into a human-readable string.
@@ -158,6 +166,30 @@ mod tests {
         assert_eq!(decoded.nonce, 7);
     }
 
+    #[tokio::test]
+    async fn test_send_action_stamps_latency_with_client_clock() {
+        // Nothing listens on port 1: the submission fails fast but is timed.
+        let http_client =
+            NordHttpClient::new("http://127.0.0.1:1").with_clock(crate::Clock::manual(5_000_000));
+        let sign_fn: Box<SignFn> = Box::new(|_| Box::pin(async { Ok(vec![0; 64]) }));
+        let action = create_action(
+            0,
+            1,
+            nord::action::Kind::CancelOrderById(nord::action::CancelOrderById {
+                session_id: 0,
+                order_id: 1,
+                delegator_account_id: None,
+                sender_account_id: None,
+            }),
+        );
+        assert!(send_action(&http_client, &action, &sign_fn).await.is_err());
+
+        let latency = http_client.latency();
+        assert_eq!(latency.snapshot(5_000_000)[0].samples, 1);
+        // Stamped by the manual clock, so long expired in (older) system time.
+        assert!(latency.snapshot(1_000_000_000).is_empty());
+    }
+
     #[test]
     fn test_format_receipt_error() {
         let receipt = Receipt {
@@ -0,0 +1,474 @@
+//! Orderbook analytics derived from integer-tick depth.
+//!
+//! [`BookSignals`] bundles the measures the market maker and monitor read on
+//! every book update (microprice, top-N imbalance, depth within bps bands,
+//! fill-cost estimates, spread in ticks). The
+//! [`OrderbookStream`](crate::OrderbookStream) background task computes them
+//! directly from its own levels and publishes them on a `watch` channel, so
+//! consumers no longer need to clone and walk the full depth map.
+//!
+//! The same measures are available on demand from an [`OrderbookDepth`]
+//! snapshot.
+
+use std::collections::BTreeMap;
+
+use crate::audit::AuditConfig;
+use crate::orderbook::{OrderbookDepth, TickScale, BBO, DEFAULT_MAX_LEVELS};
+use crate::types::Side;
+
+// ---------------------------------------------------------------------------
+// Configuration
+// ---------------------------------------------------------------------------
+
+/// What the orderbook stream retains, which signals it publishes and
+/// whether it audits itself against REST snapshots.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OrderbookConfig {
+    /// Price levels kept per side; worse levels are dropped. Use
+    /// `usize::MAX` to retain the full book.
+    pub max_levels: usize,
+    /// Levels per side summed for [`BookSignals::imbalance`].
+    pub imbalance_levels: usize,
+    /// Bands (in bps from mid) for [`BookSignals::depth`].
+    pub depth_bands_bps: Vec<f64>,
+    /// Base sizes for [`BookSignals::fills`] (one buy and one sell each).
+    pub fill_sizes: Vec<f64>,
+    /// Levels per side walked for [`BookSignals::depth`] and
+    /// [`BookSignals::fills`], so that the per-update cost does not grow with
+    /// `max_levels`. Deeper analysis is available from an
+    /// [`OrderbookDepth`] snapshot.
+    pub signal_levels: usize,
+    /// Periodic integrity audit; `None` disables it.
+    pub audit: Option<AuditConfig>,
+    /// How long the book may stay crossed or locked before it is discarded
+    /// and re-snapshotted, in milliseconds.
+    pub unhealthy_grace_ms: u64,
+}
+
+impl Default for OrderbookConfig {
+    fn default() -> Self {
+        Self {
+            max_levels: DEFAULT_MAX_LEVELS,
+            imbalance_levels: 5,
+            depth_bands_bps: vec![5.0, 10.0, 25.0],
+            fill_sizes: Vec::new(),
+            signal_levels: 50,
+            audit: None,
+            unhealthy_grace_ms: 2_000,
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Signal types
+// ---------------------------------------------------------------------------
+
+/// Resting size within a band around mid.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DepthBand {
+    /// Band half-width in basis points from mid.
+    pub bps: f64,
+    /// Bid size with price `>= mid * (1 - bps / 10_000)`.
+    pub bid_size: f64,
+    /// Ask size with price `<= mid * (1 + bps / 10_000)`.
+    pub ask_size: f64,
+    /// Notional (price * size) of `bid_size`.
+    pub bid_notional: f64,
+    /// Notional (price * size) of `ask_size`.
+    pub ask_notional: f64,
+}
+
+/// Estimated cost of an immediate (taker) order of a given size.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FillEstimate {
+    /// Taker side: [`Side::Bid`] buys from the asks, [`Side::Ask`] sells
+    /// into the bids.
+    pub side: Side,
+    /// Requested base size.
+    pub size: f64,
+    /// Size available in the retained book (may be less than `size`).
+    pub filled: f64,
+    /// Volume-weighted average fill price.
+    pub vwap: f64,
+    /// Last (worst) level touched.
+    pub worst_price: f64,
+    /// Adverse distance of `vwap` from mid in basis points (always `>= 0`).
+    pub slippage_bps: f64,
+}
+
+impl FillEstimate {
+    /// Whether the retained book could absorb the full size.
+    pub fn is_complete(&self) -> bool {
+        self.filled >= self.size
+    }
+}
+
+/// Derived top-of-book and depth signals for one book state.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BookSignals {
+    /// Exact best bid and ask.
+    pub bbo: BBO,
+    /// Resting size at the best bid.
+    pub bid_size: f64,
+    /// Resting size at the best ask.
+    pub ask_size: f64,
+    /// Arithmetic mid.
+    pub mid: f64,
+    /// Top-of-book size-weighted price:
+    /// `(bid * ask_size + ask * bid_size) / (bid_size + ask_size)`.
+    pub microprice: f64,
+    /// `(bid_vol - ask_vol) / (bid_vol + ask_vol)` over the top
+    /// `imbalance_levels` of each side, in `[-1, 1]`.
+    pub imbalance: f64,
+    /// Best ask minus best bid, in ticks.
+    pub spread_ticks: i64,
+    /// Cumulative depth for each configured band.
+    pub depth: Vec<DepthBand>,
+    /// Buy and sell estimates for each configured fill size.
+    pub fills: Vec<FillEstimate>,
+    /// Unix epoch milliseconds when the signals were computed.
+    pub timestamp: u64,
+}
+
+impl BookSignals {
+    /// Compute signals from raw levels, or `None` if either side is empty.
+    pub(crate) fn compute(
+        bids: &BTreeMap<i64, u64>,
+        asks: &BTreeMap<i64, u64>,
+        scale: TickScale,
+        config: &OrderbookConfig,
+        timestamp: u64,
+    ) -> Option<Self> {
+        let (&bid_ticks, &bid_lots) = bids.iter().next_back()?;
+        let (&ask_ticks, &ask_lots) = asks.iter().next()?;
+        let mid = mid_price(bid_ticks, ask_ticks, scale);
+
+        Some(Self {
+            bbo: BBO {
+                best_bid: scale.ticks_to_decimal(bid_ticks),
+                best_ask: scale.ticks_to_decimal(ask_ticks),
+            },
+            bid_size: scale.lots_to_size(bid_lots),
+            ask_size: scale.lots_to_size(ask_lots),
+            mid,
+            microprice: microprice(bids, asks, scale)?,
+            imbalance: imbalance(bids, asks, config.imbalance_levels),
+            spread_ticks: ask_ticks - bid_ticks,
+            depth: config
+                .depth_bands_bps
+                .iter()
+                .map(|&bps| depth_band(bids, asks, scale, mid, bps, config.signal_levels))
+                .collect(),
+            fills: config
+                .fill_sizes
+                .iter()
+                .flat_map(|&size| {
+                    [
+                        fill_estimate(
+                            bids,
+                            asks,
+                            scale,
+                            mid,
+                            Side::Bid,
+                            size,
+                            config.signal_levels,
+                        ),
+                        fill_estimate(
+                            bids,
+                            asks,
+                            scale,
+                            mid,
+                            Side::Ask,
+                            size,
+                            config.signal_levels,
+                        ),
+                    ]
+                })
+                .flatten()
+                .collect(),
+            timestamp,
+        })
+    }
+
+    /// Estimate for `side` and `size`, if it was configured.
+    pub fn fill(&self, side: Side, size: f64) -> Option<&FillEstimate> {
+        self.fills.iter().find(|f| f.side == side && f.size == size)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// On-demand analytics on a depth snapshot
+// ---------------------------------------------------------------------------
+
+impl OrderbookDepth {
+    /// Mid price, or `None` if either side is empty.
+    pub fn mid(&self) -> Option<f64> {
+        let bid = *self.bids.keys().next_back()?;
+        let ask = *self.asks.keys().next()?;
+        Some(mid_price(bid, ask, self.scale))
+    }
+
+    /// Top-of-book size-weighted microprice.
+    pub fn microprice(&self) -> Option<f64> {
+        microprice(&self.bids, &self.asks, self.scale)
+    }
+
+    /// Volume imbalance over the top `levels` of each side, in `[-1, 1]`.
+    pub fn imbalance(&self, levels: usize) -> f64 {
+        imbalance(&self.bids, &self.asks, levels)
+    }
+
+    /// Cumulative size within `bps` of mid on each side.
+    pub fn depth_within_bps(&self, bps: f64) -> Option<DepthBand> {
+        let mid = self.mid()?;
+        Some(depth_band(
+            &self.bids,
+            &self.asks,
+            self.scale,
+            mid,
+            bps,
+            usize::MAX,
+        ))
+    }
+
+    /// VWAP and slippage of a taker order of `size` on `side`, or `None` if
+    /// either side is empty (slippage is measured from mid).
+    pub fn fill_estimate(&self, side: Side, size: f64) -> Option<FillEstimate> {
+        let mid = self.mid()?;
+        fill_estimate(
+            &self.bids,
+            &self.asks,
+            self.scale,
+            mid,
+            side,
+            size,
+            usize::MAX,
+        )
+    }
+
+    /// Best ask minus best bid, in ticks.
+    pub fn spread_ticks(&self) -> Option<i64> {
+        Some(self.asks.keys().next()? - self.bids.keys().next_back()?)
+    }
+
+    /// All signals under `config` (its retention setting is ignored).
+    pub fn signals(&self, config: &OrderbookConfig) -> Option<BookSignals> {
+        BookSignals::compute(&self.bids, &self.asks, self.scale, config, 0)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Helpers
+// ---------------------------------------------------------------------------
+
+/// Mid of two tick prices, summed in ticks to avoid double rounding.
+pub(crate) fn mid_price(bid_ticks: i64, ask_ticks: i64, scale: TickScale) -> f64 {
+    (bid_ticks + ask_ticks) as f64 / 2.0 / 10f64.powi(i32::from(scale.price_decimals))
+}
+
+fn microprice(
+    bids: &BTreeMap<i64, u64>,
+    asks: &BTreeMap<i64, u64>,
+    scale: TickScale,
+) -> Option<f64> {
+    let (&bid_ticks, &bid_lots) = bids.iter().next_back()?;
+    let (&ask_ticks, &ask_lots) = asks.iter().next()?;
+    let bid = scale.ticks_to_price(bid_ticks);
+    let ask = scale.ticks_to_price(ask_ticks);
+    Some((bid * ask_lots as f64 + ask * bid_lots as f64) / (bid_lots + ask_lots) as f64)
+}
+
+fn imbalance(bids: &BTreeMap<i64, u64>, asks: &BTreeMap<i64, u64>, levels: usize) -> f64 {
+    let bid_vol: u64 = bids.values().rev().take(levels).sum();
+    let ask_vol: u64 = asks.values().take(levels).sum();
+    let total = bid_vol + ask_vol;
+    if total == 0 {
+        0.0
+    } else {
+        (bid_vol as f64 - ask_vol as f64) / total as f64
+    }
+}
+
+fn depth_band(
+    bids: &BTreeMap<i64, u64>,
+    asks: &BTreeMap<i64, u64>,
+    scale: TickScale,
+    mid: f64,
+    bps: f64,
+    max_levels: usize,
+) -> DepthBand {
+    let lo = scale.price_to_ticks(mid * (1.0 - bps / 10_000.0));
+    let hi = scale.price_to_ticks(mid * (1.0 + bps / 10_000.0));
+    let (bid_size, bid_notional) = sum_levels(bids.range(lo..).rev().take(max_levels), scale);
+    let (ask_size, ask_notional) = sum_levels(asks.range(..=hi).take(max_levels), scale);
+    DepthBand {
+        bps,
+        bid_size,
+        ask_size,
+        bid_notional,
+        ask_notional,
+    }
+}
+
+fn sum_levels<'a>(
+    levels: impl Iterator<Item = (&'a i64, &'a u64)>,
+    scale: TickScale,
+) -> (f64, f64) {
+    let mut lots = 0u64;
+    let mut notional = 0.0;
+    for (&ticks, &l) in levels {
+        lots += l;
+        notional += scale.ticks_to_price(ticks) * scale.lots_to_size(l);
+    }
+    (scale.lots_to_size(lots), notional)
+}
+
+fn fill_estimate(
+    bids: &BTreeMap<i64, u64>,
+    asks: &BTreeMap<i64, u64>,
+    scale: TickScale,
+    mid: f64,
+    side: Side,
+    size: f64,
+    max_levels: usize,
+) -> Option<FillEstimate> {
+    let want = scale.size_to_lots(size);
+    let levels: Box<dyn Iterator<Item = (&i64, &u64)>> = match side {
+        Side::Bid => Box::new(asks.iter().take(max_levels)),
+        Side::Ask => Box::new(bids.iter().rev().take(max_levels)),
+    };
+
+    let mut filled = 0u64;
+    let mut cost = 0.0;
+    let mut worst = None;
+    for (&ticks, &lots) in levels {
+        if filled >= want {
+            break;
+        }
+        let take = lots.min(want - filled);
+        filled += take;
+        cost += scale.ticks_to_price(ticks) * scale.lots_to_size(take);
+        worst = Some(ticks);
+    }
+
+    let worst = worst?;
+    let filled = scale.lots_to_size(filled);
+    let vwap = cost / filled;
+    let slippage_bps = match side {
+        Side::Bid => (vwap - mid) / mid * 10_000.0,
+        Side::Ask => (mid - vwap) / mid * 10_000.0,
+    };
+    Some(FillEstimate {
+        side,
+        size,
+        filled,
+        vwap,
+        worst_price: scale.ticks_to_price(worst),
+        slippage_bps,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCALE: TickScale = TickScale {
+        price_decimals: 2,
+        size_decimals: 2,
+    };
+
+    /// Bids 99.99 x1, 99.98 x2, 99.00 x5; asks 100.01 x3, 100.02 x1, 101.00 x4.
+    fn depth() -> OrderbookDepth {
+        let levels = |l: &[(i64, u64)]| l.iter().copied().collect::<BTreeMap<_, _>>();
+        OrderbookDepth {
+            bids: levels(&[(9_999, 100), (9_998, 200), (9_900, 500)]),
+            asks: levels(&[(10_001, 300), (10_002, 100), (10_100, 400)]),
+            scale: SCALE,
+            seq: 0,
+        }
+    }
+
+    #[test]
+    fn test_microprice_leans_toward_thin_side() {
+        let d = depth();
+        // (99.99 * 3 + 100.01 * 1) / 4 = 99.995
+        assert!((d.microprice().unwrap() - 99.995).abs() < 1e-9);
+        assert_eq!(d.mid(), Some(100.0));
+        assert_eq!(d.spread_ticks(), Some(2));
+    }
+
+    #[test]
+    fn test_imbalance_top_n() {
+        let d = depth();
+        // Top 1: (1 - 3) / 4
+        assert!((d.imbalance(1) + 0.5).abs() < 1e-12);
+        // Top 3: (8 - 8) / 16
+        assert_eq!(d.imbalance(3), 0.0);
+    }
+
+    #[test]
+    fn test_depth_within_bps() {
+        let band = depth().depth_within_bps(5.0).unwrap();
+        // 5 bps of 100 = 0.05: bids >= 99.95, asks <= 100.05.
+        assert!((band.bid_size - 3.0).abs() < 1e-12);
+        assert!((band.ask_size - 4.0).abs() < 1e-12);
+        assert!((band.ask_notional - (100.01 * 3.0 + 100.02)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_fill_estimate_walks_levels() {
+        let d = depth();
+        let buy = d.fill_estimate(Side::Bid, 4.0).unwrap();
+        assert!(buy.is_complete());
+        assert!((buy.vwap - (100.01 * 3.0 + 100.02) / 4.0).abs() < 1e-9);
+        assert_eq!(buy.worst_price, 100.02);
+        assert!(buy.slippage_bps > 0.0);
+
+        let sell = d.fill_estimate(Side::Ask, 100.0).unwrap();
+        assert!(!sell.is_complete());
+        assert!((sell.filled - 8.0).abs() < 1e-12);
+        assert_eq!(sell.worst_price, 99.0);
+        assert!(sell.slippage_bps > 0.0);
+    }
+
+    #[test]
+    fn test_signals_follow_config() {
+        let config = OrderbookConfig {
+            imbalance_levels: 1,
+            depth_bands_bps: vec![1.0, 200.0],
+            fill_sizes: vec![1.0],
+            ..Default::default()
+        };
+        let s = depth().signals(&config).unwrap();
+        assert_eq!((s.bid_size, s.ask_size), (1.0, 3.0));
+        assert_eq!(s.depth.len(), 2);
+        assert!((s.depth[1].bid_size - 8.0).abs() < 1e-12);
+        assert_eq!(s.fills.len(), 2);
+        assert_eq!(s.fill(Side::Bid, 1.0).unwrap().vwap, 100.01);
+        assert!((s.imbalance + 0.5).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_signals_walk_at_most_signal_levels() {
+        let config = OrderbookConfig {
+            depth_bands_bps: vec![200.0],
+            fill_sizes: vec![100.0],
+            signal_levels: 2,
+            ..Default::default()
+        };
+        let s = depth().signals(&config).unwrap();
+        assert!((s.depth[0].bid_size - 3.0).abs() < 1e-12);
+        assert!((s.fill(Side::Ask, 100.0).unwrap().filled - 3.0).abs() < 1e-12);
+        assert_eq!(s.fill(Side::Ask, 100.0).unwrap().worst_price, 99.98);
+    }
+
+    #[test]
+    fn test_empty_side_has_no_signals() {
+        let mut d = depth();
+        d.asks.clear();
+        assert!(d.signals(&OrderbookConfig::default()).is_none());
+        assert!(d.mid().is_none());
+        assert!(d.fill_estimate(Side::Ask, 1.0).is_none());
+        assert!(d.fill_estimate(Side::Bid, 1.0).is_none());
+    }
+}
@@ -0,0 +1,288 @@
+//! Periodic integrity audit of the local orderbook.
+//!
+//! The [`OrderbookStream`](crate::OrderbookStream) background task can
+//! optionally fetch a REST snapshot on a fixed interval and compare it with
+//! the incrementally maintained book *at the same `update_id`*:
+//!
+//! ```text
+//!   audit tick --> GET orderbook (update_id = U)
+//!                      |
+//!        local id > U  |  local id <= U
+//!        (skip) <------+------> hold snapshot, keep applying deltas <= U
+//!                                        |
+//!                      next delta > U (or local id == U)
+//!                                        |
+//!                                        v
+//!                        compare top N levels of each side
+//!                           |                        |
+//!                         clean                  diverged
+//!                                                    |
+//!                                   reload book from the snapshot (heal)
+//! ```
+//!
+//! Results are accumulated in [`AuditStats`] and published on a `watch`
+//! channel alongside price and depth.
+
+use std::collections::BTreeMap;
+
+use crate::orderbook::TickScale;
+use crate::types::Side;
+
+/// Default interval between audits.
+const DEFAULT_AUDIT_INTERVAL_MS: u64 = 30_000;
+
+/// Default number of levels per side compared.
+const DEFAULT_AUDIT_LEVELS: usize = 20;
+
+/// Maximum divergent levels kept on an [`AuditReport`].
+const MAX_REPORTED_DIVERGENCES: usize = 32;
+
+// ---------------------------------------------------------------------------
+// Configuration
+// ---------------------------------------------------------------------------
+
+/// How often to audit the book and how deep to compare.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AuditConfig {
+    /// Interval between REST snapshot fetches in milliseconds.
+    pub interval_ms: u64,
+    /// Best levels per side compared (capped at the book's retention).
+    pub levels: usize,
+}
+
+impl Default for AuditConfig {
+    fn default() -> Self {
+        Self {
+            interval_ms: DEFAULT_AUDIT_INTERVAL_MS,
+            levels: DEFAULT_AUDIT_LEVELS,
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Results
+// ---------------------------------------------------------------------------
+
+/// One price level where the local book disagrees with the exchange.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LevelDivergence {
+    /// Book side of the level.
+    pub side: Side,
+    /// Level price.
+    pub price: f64,
+    /// Size in the local book (`0.0` if the level is missing locally).
+    pub local_size: f64,
+    /// Size in the REST snapshot (`0.0` if the exchange has no such level).
+    pub exchange_size: f64,
+}
+
+/// Outcome of a single audit.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AuditReport {
+    /// `update_id` at which both books were compared.
+    pub update_id: u64,
+    /// Levels compared across both sides.
+    pub levels_checked: usize,
+    /// Total number of divergent levels.
+    pub divergent_levels: usize,
+    /// The first divergent levels (best first, bids then asks).
+    pub divergences: Vec<LevelDivergence>,
+    /// Unix epoch milliseconds when the comparison ran.
+    pub timestamp: u64,
+}
+
+impl AuditReport {
+    /// Whether the local book matched the exchange exactly.
+    pub fn is_clean(&self) -> bool {
+        self.divergent_levels == 0
+    }
+}
+
+/// Running audit counters for one orderbook stream.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AuditStats {
+    /// Audits that compared both books.
+    pub audits: u64,
+    /// Audits where every compared level matched.
+    pub clean: u64,
+    /// Audits that found a divergence (each one re-seeds the book).
+    pub diverged: u64,
+    /// Snapshots that could not be lined up with the local `update_id`
+    /// (older than the book, or superseded by a re-snapshot).
+    pub skipped: u64,
+    /// REST fetches that failed.
+    pub failed: u64,
+    /// Divergent levels summed over all audits.
+    pub divergent_levels: u64,
+    /// Most recent completed audit.
+    pub last: Option<AuditReport>,
+}
+
+impl AuditStats {
+    /// Fold a completed report into the counters.
+    pub(crate) fn record(&mut self, report: AuditReport) {
+        self.audits += 1;
+        if report.is_clean() {
+            self.clean += 1;
+        } else {
+            self.diverged += 1;
+            self.divergent_levels += report.divergent_levels as u64;
+        }
+        self.last = Some(report);
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Comparison
+// ---------------------------------------------------------------------------
+
+/// Compare both sides of a local book with a snapshot at the same
+/// `update_id`, over the best `levels` prices of the union of each side.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn compare_books(
+    local_bids: &BTreeMap<i64, u64>,
+    local_asks: &BTreeMap<i64, u64>,
+    exchange_bids: &BTreeMap<i64, u64>,
+    exchange_asks: &BTreeMap<i64, u64>,
+    scale: TickScale,
+    levels: usize,
+    update_id: u64,
+    timestamp: u64,
+) -> AuditReport {
+    let mut divergences = Vec::new();
+    let bids = compare_side(
+        local_bids,
+        exchange_bids,
+        Side::Bid,
+        scale,
+        levels,
+        &mut divergences,
+    );
+    let asks = compare_side(
+        local_asks,
+        exchange_asks,
+        Side::Ask,
+        scale,
+        levels,
+        &mut divergences,
+    );
+
+    let divergent_levels = divergences.len();
+    divergences.truncate(MAX_REPORTED_DIVERGENCES);
+    AuditReport {
+        update_id,
+        levels_checked: bids + asks,
+        divergent_levels,
+        divergences,
+        timestamp,
+    }
+}
+
+/// Compare one side; returns the number of levels checked.
+fn compare_side(
+    local: &BTreeMap<i64, u64>,
+    exchange: &BTreeMap<i64, u64>,
+    side: Side,
+    scale: TickScale,
+    levels: usize,
+    out: &mut Vec<LevelDivergence>,
+) -> usize {
+    let prices = top_union(local, exchange, side, levels);
+    for &ticks in &prices {
+        let local_lots = local.get(&ticks).copied().unwrap_or(0);
+        let exchange_lots = exchange.get(&ticks).copied().unwrap_or(0);
+        if local_lots != exchange_lots {
+            out.push(LevelDivergence {
+                side,
+                price: scale.ticks_to_price(ticks),
+                local_size: scale.lots_to_size(local_lots),
+                exchange_size: scale.lots_to_size(exchange_lots),
+            });
+        }
+    }
+    prices.len()
+}
+
+/// The best `n` distinct prices present in either book, best first.
+fn top_union(a: &BTreeMap<i64, u64>, b: &BTreeMap<i64, u64>, side: Side, n: usize) -> Vec<i64> {
+    let mut prices: Vec<i64> = match side {
+        Side::Bid => a
+            .keys()
+            .rev()
+            .take(n)
+            .chain(b.keys().rev().take(n))
+            .copied()
+            .collect(),
+        Side::Ask => a.keys().take(n).chain(b.keys().take(n)).copied().collect(),
+    };
+    match side {
+        Side::Bid => prices.sort_unstable_by(|x, y| y.cmp(x)),
+        Side::Ask => prices.sort_unstable(),
+    }
+    prices.dedup();
+    prices.truncate(n);
+    prices
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCALE: TickScale = TickScale {
+        price_decimals: 2,
+        size_decimals: 4,
+    };
+
+    fn book(levels: &[(i64, u64)]) -> BTreeMap<i64, u64> {
+        levels.iter().copied().collect()
+    }
+
+    #[test]
+    fn test_identical_books_are_clean() {
+        let bids = book(&[(9_999, 10_000), (9_998, 20_000)]);
+        let asks = book(&[(10_001, 5_000)]);
+        let report = compare_books(&bids, &asks, &bids, &asks, SCALE, 20, 42, 7);
+        assert!(report.is_clean());
+        assert_eq!(report.levels_checked, 3);
+        assert_eq!(report.update_id, 42);
+    }
+
+    #[test]
+    fn test_reports_size_and_missing_levels() {
+        let local_bids = book(&[(9_999, 10_000), (9_998, 20_000)]);
+        let exchange_bids = book(&[(9_999, 15_000), (9_997, 1_000)]);
+        let asks = book(&[(10_001, 5_000)]);
+        let report = compare_books(&local_bids, &asks, &exchange_bids, &asks, SCALE, 20, 1, 0);
+        assert_eq!(report.divergent_levels, 3);
+        assert_eq!(
+            report.divergences[0],
+            LevelDivergence {
+                side: Side::Bid,
+                price: 99.99,
+                local_size: 1.0,
+                exchange_size: 1.5,
+            }
+        );
+        // Stale local level, then a level only the exchange has.
+        assert_eq!(report.divergences[1].exchange_size, 0.0);
+        assert_eq!(report.divergences[2].local_size, 0.0);
+    }
+
+    #[test]
+    fn test_only_top_levels_are_compared() {
+        let local = book(&[(10_001, 1), (10_002, 1), (10_003, 1)]);
+        let exchange = book(&[(10_001, 1), (10_002, 1), (10_003, 9)]);
+        let empty = BTreeMap::new();
+        let report = compare_books(&empty, &local, &empty, &exchange, SCALE, 2, 1, 0);
+        assert!(report.is_clean());
+        assert_eq!(report.levels_checked, 2);
+
+        let mut stats = AuditStats::default();
+        stats.record(report);
+        stats.record(compare_books(
+            &empty, &local, &empty, &exchange, SCALE, 3, 2, 0,
+        ));
+        assert_eq!((stats.audits, stats.clean, stats.diverged), (2, 1, 1));
+        assert_eq!(stats.divergent_levels, 1);
+    }
+}
@@ -1,7 +1,11 @@
 use std::collections::HashMap;
 
+use crate::clock::Clock;
 use crate::config::NordConfig;
 use crate::error::{NordError, Result};
+use crate::latency::LatencyTracker;
+use crate::recorder::{RecordSource, Recorder};
+use crate::replay::ReplaySnapshots;
 use crate::rest::NordHttpClient;
 use crate::types::*;
 use crate::ws::NordWebSocketClient;
@@ -23,6 +27,10 @@ pub struct Nord {
     pub tokens: Vec<TokenInfo>,
     /// Symbol -> market_id mapping.
     symbol_to_market_id: HashMap<String, u32>,
+    /// Optional tap that records REST snapshots.
+    recorder: Option<Recorder>,
+    /// Recorded snapshots served instead of REST calls during replay.
+    replay: Option<ReplaySnapshots>,
 }
 
 impl Nord {
@@ -45,9 +53,67 @@ impl Nord {
             markets: info.markets,
             tokens: info.tokens,
             symbol_to_market_id,
+            recorder: None,
+            replay: None,
         })
     }
 
+    /// Build an offline client for replay. Orderbook snapshots are served
+    /// from `snapshots`; other REST calls are not available.
+    pub(crate) fn from_replay(info: MarketsInfo, snapshots: ReplaySnapshots) -> Self {
+        let symbol_to_market_id = info
+            .markets
+            .iter()
+            .map(|m| (m.symbol.clone(), m.market_id))
+            .collect();
+        Self {
+            web_server_url: "replay://".into(),
+            solana_rpc_url: String::new(),
+            app: String::new(),
+            http_client: NordHttpClient::new("replay://").with_clock(snapshots.clock().clone()),
+            markets: info.markets,
+            tokens: info.tokens,
+            symbol_to_market_id,
+            recorder: None,
+            replay: Some(snapshots),
+        }
+    }
+
+    /// Time source for timestamps derived from this client's data: the
+    /// system clock when live, the replay clock when built from a recording.
+    pub fn clock(&self) -> Clock {
+        self.http_client.clock().clone()
+    }
+
+    /// Latency telemetry shared by this client's actions and WebSocket
+    /// clients, and by any feed the caller records into it.
+    pub fn latency(&self) -> &LatencyTracker {
+        self.http_client.latency()
+    }
+
+    /// Record REST orderbook snapshots (and the current market info) to
+    /// `recorder`. Clones made afterwards share the same tap.
+    pub fn set_recorder(&mut self, recorder: Recorder) {
+        let info = MarketsInfo {
+            markets: self.markets.clone(),
+            tokens: self.tokens.clone(),
+        };
+        if let Ok(json) = serde_json::to_string(&info) {
+            recorder.record(RecordSource::NordRest, "info", &json);
+        }
+        self.recorder = Some(recorder);
+    }
+
+    /// Record a REST response under `key` if a recorder is attached.
+    fn record_rest<T: serde::Serialize>(&self, key: &str, value: &T) {
+        if let Some(recorder) = &self.recorder {
+            match serde_json::to_string(value) {
+                Ok(json) => recorder.record(RecordSource::NordRest, key, &json),
+                Err(e) => tracing::warn!(key, error = %e, "failed to record REST response"),
+            }
+        }
+    }
+
     /// Refresh market/token info from the server.
     pub async fn fetch_info(&mut self) -> Result<()> {
         let info = self.http_client.get_info().await?;
@@ -190,15 +256,65 @@ impl Nord {
             .await
     }
 
+    /// Get paginated deposit history for an account.
+    pub async fn get_account_deposit_history(
+        &self,
+        account_id: u32,
+        since: Option<&str>,
+        until: Option<&str>,
+        start_inclusive: Option<u64>,
+        page_size: Option<u8>,
+    ) -> Result<PageResult<DepositInfo>> {
+        self.http_client
+            .get_account_deposit_history(account_id, since, until, start_inclusive, page_size)
+            .await
+    }
+
+    /// Get paginated funding payment history for an account.
+    pub async fn get_account_funding_history(
+        &self,
+        account_id: u32,
+        market_id: Option<u32>,
+        since: Option<&str>,
+        until: Option<&str>,
+        start_inclusive: Option<&str>,
+        page_size: Option<u8>,
+    ) -> Result<PageResult<AccountFundingInfo>> {
+        self.http_client
+            .get_account_funding_history(
+                account_id,
+                market_id,
+                since,
+                until,
+                start_inclusive,
+                page_size,
+            )
+            .await
+    }
+
     /// Get the orderbook for a market by symbol name.
     pub async fn get_orderbook_by_symbol(&self, symbol: &str) -> Result<OrderbookInfo> {
+        if let Some(replay) = &self.replay {
+            return replay.orderbook(symbol);
+        }
         let market_id = self.resolve_market_id(symbol)?;
-        self.http_client.get_orderbook(market_id).await
+        let info = self.http_client.get_orderbook(market_id).await?;
+        self.record_rest(&format!("orderbook@{symbol}"), &info);
+        Ok(info)
     }
 
     /// Get the orderbook for a market by ID.
     pub async fn get_orderbook(&self, market_id: u32) -> Result<OrderbookInfo> {
-        self.http_client.get_orderbook(market_id).await
+        if let Some(replay) = &self.replay {
+            return replay.orderbook(&self.find_market(market_id)?.symbol);
+        }
+        let info = self.http_client.get_orderbook(market_id).await?;
+        if self.recorder.is_some() {
+            if let Ok(market) = self.find_market(market_id) {
+                self.record_rest(&format!("orderbook@{}", market.symbol), &info);
+            }
+        }
+        Ok(info)
     }
 
     /// Get exchange-wide markets and tokens configuration.
@@ -314,7 +430,8 @@ impl Nord {
 
     // --- WebSocket ---
 
-    /// Create a WebSocket client with the given subscriptions.
+    /// Create a WebSocket client with the given subscriptions. Delta latency
+    /// is recorded into [`latency`](Self::latency).
     pub fn create_websocket_client(
         &self,
         trades: &[String],
@@ -345,6 +462,8 @@ impl Nord {
             streams.join("&")
         );
 
-        NordWebSocketClient::new(ws_url)
+        let mut ws = NordWebSocketClient::new(ws_url);
+        ws.set_latency(self.latency().clone(), self.clock());
+        ws
     }
 }
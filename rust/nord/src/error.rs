@@ -45,6 +45,9 @@ pub enum NordError {
     #[error("overflow: {0}")]
     Overflow(String),
 
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
     #[cfg(feature = "solana")]
     #[error("solana error: {0}")]
     Solana(String),
@@ -0,0 +1,513 @@
+//! Orderbook with our own resting orders removed ("ex-own-orders" view).
+//!
+//! The exchange book includes our quotes, so its BBO is often our own
+//! order. [`ExOwnBookStream`] joins a mirrored orderbook depth with the orders
+//! tracked by an [`AccountStream`](crate::AccountStream) and publishes an
+//! [`ExOwnBook`]: the depth with our size subtracted per level, the BBO of
+//! everyone else, and how much of each top level is ours.
+//!
+//! # Architecture
+//!
+//! ```text
+//!   DepthMirror                 watch::Receiver<HashMap<u64, TrackedOrder>>
+//!   (OrderbookHandle)           (AccountStream)
+//!              \                    /
+//!               v                  v
+//!   +-------- background task (update touched ticks) --------+
+//!   |  - buckets our orders for the market by tick              |
+//!   |  - subtracts our lots from each book level (floored at 0) |
+//!   |  - measures our share of each top level                   |
+//!   +-----------------------------------------------------------+
+//!                            |
+//!                 watch::Sender<ExOwnBook>
+//! ```
+//!
+//! The task edits the published book in place: a level batch only re-derives
+//! the ticks it changed, and an order change only the ticks where we had or
+//! now have orders. The full subtraction runs once at start and again when
+//! the mirror re-fetches its depth.
+
+use std::collections::{BTreeMap, HashMap};
+
+use tokio::sync::{oneshot, watch};
+use tokio::task::JoinHandle;
+use tracing::debug;
+
+use crate::account::TrackedOrder;
+use crate::levels::{DepthMirror, LevelChanges};
+use crate::orderbook::{OrderbookDepth, OrderbookHandle, TickScale, BBO};
+use crate::types::Side;
+
+/// Our share of the best level on one side of the full book.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TopLevelShare {
+    /// Best price on this side of the full book.
+    pub price: f64,
+    /// Total size resting at `price`.
+    pub size: f64,
+    /// Part of `size` that is ours (never more than `size`).
+    pub own_size: f64,
+}
+
+impl TopLevelShare {
+    /// Fraction of the top level that is ours, in `[0, 1]`.
+    pub fn share(&self) -> f64 {
+        if self.size > 0.0 {
+            self.own_size / self.size
+        } else {
+            0.0
+        }
+    }
+
+    /// Whether we are the only liquidity at the top level.
+    pub fn is_alone(&self) -> bool {
+        self.own_size > 0.0 && self.own_size >= self.size
+    }
+}
+
+/// One market's book as seen by everyone but us.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExOwnBook {
+    /// Depth with our resting size removed; levels that were entirely ours
+    /// are dropped.
+    pub depth: OrderbookDepth,
+    /// Best bid and ask excluding our orders, or `None` if a side has no
+    /// external liquidity.
+    pub external_bbo: Option<BBO>,
+    /// Our share of the full book's best bid level.
+    pub bid_top: Option<TopLevelShare>,
+    /// Our share of the full book's best ask level.
+    pub ask_top: Option<TopLevelShare>,
+    /// Our resting orders in this market.
+    pub own_orders: usize,
+}
+
+impl ExOwnBook {
+    /// Subtract `orders` (already filtered to this market) from `depth`.
+    ///
+    /// Our sizes are floored at the level size: the account and book feeds
+    /// are not synchronised, so our order can briefly be ahead of the book.
+    pub fn compute<'a>(
+        depth: &OrderbookDepth,
+        orders: impl IntoIterator<Item = &'a TrackedOrder>,
+    ) -> Self {
+        Self::from_levels(depth, &OwnLevels::new(depth.scale, orders))
+    }
+
+    /// Full subtraction of `own` from `depth`.
+    fn from_levels(depth: &OrderbookDepth, own: &OwnLevels) -> Self {
+        let mut book = Self {
+            depth: OrderbookDepth {
+                bids: subtract(&depth.bids, &own.bids),
+                asks: subtract(&depth.asks, &own.asks),
+                scale: depth.scale,
+                seq: depth.seq,
+            },
+            external_bbo: None,
+            bid_top: None,
+            ask_top: None,
+            own_orders: 0,
+        };
+        book.finish(depth, own);
+        book
+    }
+
+    /// Re-derive the ticks touched by `batch`, already applied to `depth`.
+    fn apply_levels(&mut self, depth: &OrderbookDepth, own: &OwnLevels, batch: &LevelChanges) {
+        for change in &batch.changes {
+            let ticks = depth.scale.price_to_ticks(change.price);
+            self.refresh(depth, own, change.side, ticks);
+        }
+        self.finish(depth, own);
+    }
+
+    /// Switch from our orders `old` to `new`, re-deriving only the ticks
+    /// where either has size.
+    fn apply_orders(&mut self, depth: &OrderbookDepth, old: &OwnLevels, new: &OwnLevels) {
+        for (side, old_levels, new_levels) in [
+            (Side::Bid, &old.bids, &new.bids),
+            (Side::Ask, &old.asks, &new.asks),
+        ] {
+            for &ticks in old_levels.keys().chain(new_levels.keys()) {
+                self.refresh(depth, new, side, ticks);
+            }
+        }
+        self.finish(depth, new);
+    }
+
+    /// Recompute the external size of one level.
+    fn refresh(&mut self, depth: &OrderbookDepth, own: &OwnLevels, side: Side, ticks: i64) {
+        let (book, own, external) = match side {
+            Side::Bid => (&depth.bids, &own.bids, &mut self.depth.bids),
+            Side::Ask => (&depth.asks, &own.asks, &mut self.depth.asks),
+        };
+        let lots = book.get(&ticks).copied().unwrap_or(0);
+        let rest = lots.saturating_sub(own.get(&ticks).copied().unwrap_or(0));
+        if rest > 0 {
+            external.insert(ticks, rest);
+        } else {
+            external.remove(&ticks);
+        }
+    }
+
+    /// Update the fields read off the best levels.
+    fn finish(&mut self, depth: &OrderbookDepth, own: &OwnLevels) {
+        let scale = depth.scale;
+        let top_share = |level: Option<(&i64, &u64)>, own: &BTreeMap<i64, u64>| {
+            level.map(|(&ticks, &lots)| TopLevelShare {
+                price: scale.ticks_to_price(ticks),
+                size: scale.lots_to_size(lots),
+                own_size: scale.lots_to_size(own.get(&ticks).copied().unwrap_or(0).min(lots)),
+            })
+        };
+        self.bid_top = top_share(depth.bids.iter().next_back(), &own.bids);
+        self.ask_top = top_share(depth.asks.iter().next(), &own.asks);
+        self.depth.seq = depth.seq;
+        self.external_bbo = self.depth.bbo();
+        self.own_orders = own.orders;
+    }
+}
+
+/// Our resting size per tick on each side of one market.
+#[derive(Debug, Default)]
+struct OwnLevels {
+    bids: BTreeMap<i64, u64>,
+    asks: BTreeMap<i64, u64>,
+    orders: usize,
+}
+
+impl OwnLevels {
+    fn new<'a>(scale: TickScale, orders: impl IntoIterator<Item = &'a TrackedOrder>) -> Self {
+        let mut own = Self::default();
+        for order in orders {
+            let levels = match order.side {
+                Side::Bid => &mut own.bids,
+                Side::Ask => &mut own.asks,
+            };
+            *levels.entry(scale.price_to_ticks(order.price)).or_default() +=
+                scale.size_to_lots(order.size);
+            own.orders += 1;
+        }
+        own
+    }
+}
+
+/// Levels of `book` minus `own`, dropping levels that reach zero.
+fn subtract(book: &BTreeMap<i64, u64>, own: &BTreeMap<i64, u64>) -> BTreeMap<i64, u64> {
+    book.iter()
+        .filter_map(|(&ticks, &lots)| {
+            let rest = lots.saturating_sub(own.get(&ticks).copied().unwrap_or(0));
+            (rest > 0).then_some((ticks, rest))
+        })
+        .collect()
+}
+
+// ---------------------------------------------------------------------------
+// ExOwnBookStream
+// ---------------------------------------------------------------------------
+
+/// Keeps an [`ExOwnBook`] up to date for one market.
+///
+/// Call [`ExOwnBookStream::new`] then [`ExOwnBookStream::connect`] to start.
+pub struct ExOwnBookStream {
+    market_id: u32,
+    // Inputs: `Some` before `connect()`, `None` after (moved into the task).
+    book: Option<OrderbookHandle>,
+    orders_rx: Option<watch::Receiver<HashMap<u64, TrackedOrder>>>,
+    book_tx: watch::Sender<Option<ExOwnBook>>,
+    book_rx: watch::Receiver<Option<ExOwnBook>>,
+    task_handle: Option<JoinHandle<()>>,
+    shutdown_tx: Option<oneshot::Sender<()>>,
+}
+
+impl ExOwnBookStream {
+    /// Create a new stream.
+    ///
+    /// # Arguments
+    ///
+    /// * `market_id` - Market whose orders are subtracted.
+    /// * `book` - From [`OrderbookStream::handle`](crate::OrderbookStream::handle).
+    /// * `orders_rx` - From [`AccountStream::subscribe_orders`](crate::AccountStream::subscribe_orders).
+    pub fn new(
+        market_id: u32,
+        book: OrderbookHandle,
+        orders_rx: watch::Receiver<HashMap<u64, TrackedOrder>>,
+    ) -> Self {
+        let (book_tx, book_rx) = watch::channel(None);
+        Self {
+            market_id,
+            book: Some(book),
+            orders_rx: Some(orders_rx),
+            book_tx,
+            book_rx,
+            task_handle: None,
+            shutdown_tx: None,
+        }
+    }
+
+    /// Start the background task.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called twice.
+    pub fn connect(&mut self) {
+        let book = self
+            .book
+            .take()
+            .expect("connect() called twice: book already consumed");
+        let orders_rx = self.orders_rx.take().expect("orders_rx already consumed");
+        let (shutdown_tx, shutdown_rx) = oneshot::channel();
+        self.shutdown_tx = Some(shutdown_tx);
+        self.task_handle = Some(tokio::spawn(run_ex_own_task(
+            self.market_id,
+            book,
+            orders_rx,
+            self.book_tx.clone(),
+            shutdown_rx,
+        )));
+    }
+
+    /// Latest ex-own book, or `None` before the first depth update.
+    pub fn get(&self) -> Option<ExOwnBook> {
+        self.book_rx.borrow().clone()
+    }
+
+    /// Best bid and ask excluding our orders.
+    pub fn get_external_bbo(&self) -> Option<BBO> {
+        self.book_rx.borrow().as_ref()?.external_bbo
+    }
+
+    /// Clone a `watch::Receiver` for async consumption.
+    pub fn subscribe(&self) -> watch::Receiver<Option<ExOwnBook>> {
+        self.book_rx.clone()
+    }
+
+    /// Shut down the background task.
+    pub fn close(&mut self) {
+        if let Some(tx) = self.shutdown_tx.take() {
+            let _ = tx.send(());
+        }
+        if let Some(handle) = self.task_handle.take() {
+            handle.abort();
+        }
+    }
+}
+
+impl Drop for ExOwnBookStream {
+    fn drop(&mut self) {
+        self.close();
+    }
+}
+
+/// Keep the ex-own book current as the depth or our orders change.
+async fn run_ex_own_task(
+    market_id: u32,
+    book: OrderbookHandle,
+    mut orders_rx: watch::Receiver<HashMap<u64, TrackedOrder>>,
+    book_tx: watch::Sender<Option<ExOwnBook>>,
+    mut shutdown_rx: oneshot::Receiver<()>,
+) {
+    let mut mirror = DepthMirror::new(book).await;
+    let mut refetches = mirror.refetches();
+    let mut own = OwnLevels::default();
+    if let Some(depth) = mirror.depth() {
+        own = own_levels(depth, market_id, &mut orders_rx);
+        let _ = book_tx.send(Some(ExOwnBook::from_levels(depth, &own)));
+    }
+
+    loop {
+        tokio::select! {
+            _ = &mut shutdown_rx => break,
+            batch = mirror.next() => {
+                let Some(batch) = batch else { break };
+                let Some(depth) = mirror.depth() else { continue };
+                if mirror.refetches() != refetches || book_tx.borrow().is_none() {
+                    refetches = mirror.refetches();
+                    own = own_levels(depth, market_id, &mut orders_rx);
+                    let _ = book_tx.send(Some(ExOwnBook::from_levels(depth, &own)));
+                } else {
+                    book_tx.send_modify(|book| {
+                        if let Some(book) = book {
+                            book.apply_levels(depth, &own, &batch);
+                        }
+                    });
+                }
+            }
+            result = orders_rx.changed() => {
+                if result.is_err() {
+                    break;
+                }
+                let Some(depth) = mirror.depth() else { continue };
+                let next = own_levels(depth, market_id, &mut orders_rx);
+                book_tx.send_modify(|book| match book {
+                    Some(book) => book.apply_orders(depth, &own, &next),
+                    None => *book = Some(ExOwnBook::from_levels(depth, &next)),
+                });
+                own = next;
+            }
+        }
+    }
+    debug!(market_id, "ex-own book task exiting");
+}
+
+/// Bucket our current orders in `market_id`.
+fn own_levels(
+    depth: &OrderbookDepth,
+    market_id: u32,
+    orders_rx: &mut watch::Receiver<HashMap<u64, TrackedOrder>>,
+) -> OwnLevels {
+    let orders = orders_rx.borrow_and_update();
+    OwnLevels::new(
+        depth.scale,
+        orders.values().filter(|o| o.market_id == market_id),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::levels::LevelChange;
+
+    const SCALE: TickScale = TickScale {
+        price_decimals: 2,
+        size_decimals: 4,
+    };
+
+    fn order(order_id: u64, side: Side, price: f64, size: f64) -> TrackedOrder {
+        TrackedOrder {
+            order_id,
+            side,
+            price,
+            size,
+            market_id: 1,
+            client_order_id: None,
+        }
+    }
+
+    fn depth() -> OrderbookDepth {
+        OrderbookDepth {
+            // 99.99 x 3, 99.98 x 5
+            bids: [(9_999, 30_000), (9_998, 50_000)].into_iter().collect(),
+            // 100.01 x 1, 100.02 x 2
+            asks: [(10_001, 10_000), (10_002, 20_000)].into_iter().collect(),
+            scale: SCALE,
+            seq: 0,
+        }
+    }
+
+    #[test]
+    fn test_subtracts_own_size_and_measures_top_share() {
+        let orders = [
+            order(1, Side::Bid, 99.99, 1.0),
+            order(2, Side::Ask, 100.01, 1.0),
+        ];
+        let book = ExOwnBook::compute(&depth(), &orders);
+
+        assert_eq!(book.own_orders, 2);
+        assert_eq!(book.depth.bids.get(&9_999), Some(&20_000));
+        // Our ask was the whole level: the external best ask is the next one.
+        assert!(!book.depth.asks.contains_key(&10_001));
+        let bbo = book.external_bbo.unwrap();
+        assert_eq!(bbo.best_bid.to_string(), "99.99");
+        assert_eq!(bbo.best_ask.to_string(), "100.02");
+
+        let bid_top = book.bid_top.unwrap();
+        assert!((bid_top.share() - 1.0 / 3.0).abs() < 1e-12);
+        assert!(!bid_top.is_alone());
+        assert!(book.ask_top.unwrap().is_alone());
+    }
+
+    #[test]
+    fn test_own_size_ahead_of_book_is_floored() {
+        let orders = [
+            order(1, Side::Bid, 99.99, 4.0),
+            order(2, Side::Bid, 99.5, 1.0),
+        ];
+        let book = ExOwnBook::compute(&depth(), &orders);
+        assert!(!book.depth.bids.contains_key(&9_999));
+        let top = book.bid_top.unwrap();
+        assert_eq!(top.own_size, top.size);
+        assert_eq!(book.external_bbo.unwrap().best_bid.to_string(), "99.98");
+    }
+
+    #[test]
+    fn test_incremental_updates_match_full_compute() {
+        let mut full = depth();
+        let mut orders = vec![order(1, Side::Bid, 99.99, 1.0)];
+        let mut own = OwnLevels::new(SCALE, &orders);
+        let mut book = ExOwnBook::from_levels(&full, &own);
+
+        // Our ask joins the best ask, then the level and a new one change.
+        orders.push(order(2, Side::Ask, 100.01, 1.0));
+        let next = OwnLevels::new(SCALE, &orders);
+        book.apply_orders(&full, &own, &next);
+        own = next;
+        assert_eq!(book, ExOwnBook::compute(&full, &orders));
+
+        let batch = LevelChanges {
+            seq: 1,
+            update_id: 1,
+            timestamp: 0,
+            changes: vec![
+                LevelChange {
+                    side: Side::Bid,
+                    price: 99.99,
+                    old_size: 3.0,
+                    new_size: 0.5,
+                    update_id: 1,
+                },
+                LevelChange {
+                    side: Side::Ask,
+                    price: 100.01,
+                    old_size: 1.0,
+                    new_size: 4.0,
+                    update_id: 1,
+                },
+            ],
+        };
+        full.apply(&batch);
+        book.apply_levels(&full, &own, &batch);
+        assert_eq!(book, ExOwnBook::compute(&full, &orders));
+        assert_eq!(book.depth.seq, 1);
+
+        // Our bid is cancelled: its level is external again.
+        orders.remove(0);
+        let next = OwnLevels::new(SCALE, &orders);
+        book.apply_orders(&full, &own, &next);
+        assert_eq!(book, ExOwnBook::compute(&full, &orders));
+        assert_eq!(book.depth.bids.get(&9_999), Some(&5_000));
+    }
+
+    #[tokio::test]
+    async fn test_stream_tracks_order_changes() {
+        let (handle, _levels_tx, mut depth_requests) = OrderbookHandle::for_test("T");
+        tokio::spawn(async move {
+            while let Some(reply) = depth_requests.recv().await {
+                let _ = reply.send(depth());
+            }
+        });
+        let (orders_tx, orders_rx) = watch::channel(HashMap::new());
+        let mut stream = ExOwnBookStream::new(1, handle, orders_rx);
+        let mut rx = stream.subscribe();
+        stream.connect();
+
+        rx.changed().await.unwrap();
+        assert_eq!(
+            stream.get_external_bbo().unwrap().best_ask.to_string(),
+            "100.01"
+        );
+
+        // Orders in other markets are ignored.
+        let mut other = order(3, Side::Ask, 100.01, 1.0);
+        other.market_id = 2;
+        orders_tx.send_modify(|orders| {
+            orders.insert(2, order(2, Side::Ask, 100.01, 1.0));
+            orders.insert(3, other);
+        });
+        rx.changed().await.unwrap();
+        let book = stream.get().unwrap();
+        assert_eq!(book.own_orders, 1);
+        assert_eq!(book.external_bbo.unwrap().best_ask.to_string(), "100.02");
+        stream.close();
+    }
+}
@@ -0,0 +1,125 @@
+//! Trading fee rates for one account.
+//!
+//! An account's default maker/taker rates come from its fee tier
+//! (`get_account_fee_tier` + `get_fee_brackets`, in parts per million).
+//! Markets may charge a different rate, which `get_market_fee` reports per
+//! market, role and account; those override the tier rates.
+
+use std::collections::HashMap;
+
+use futures_util::future::join_all;
+use tracing::warn;
+
+use crate::client::Nord;
+use crate::error::{NordError, Result};
+use crate::types::{FeeTierConfig, FeeTierId, FillRole};
+
+/// Fee rates of one account, as fractions of notional.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FeeSchedule {
+    pub account_id: u32,
+    /// Fee tier assigned to the account.
+    pub tier: FeeTierId,
+    /// Maker rate of the tier (negative = rebate).
+    pub maker_rate: f64,
+    /// Taker rate of the tier.
+    pub taker_rate: f64,
+    /// Per-market `(maker, taker)` rates that override the tier.
+    market_rates: HashMap<u32, (f64, f64)>,
+}
+
+impl FeeSchedule {
+    /// Schedule with the tier rates only.
+    pub fn from_tier(account_id: u32, tier: FeeTierId, config: &FeeTierConfig) -> Self {
+        Self {
+            account_id,
+            tier,
+            maker_rate: config.maker_fee_ppm as f64 / 1e6,
+            taker_rate: config.taker_fee_ppm as f64 / 1e6,
+            market_rates: HashMap::new(),
+        }
+    }
+
+    /// Override the rates for one market.
+    pub fn set_market_rates(&mut self, market_id: u32, maker_rate: f64, taker_rate: f64) {
+        self.market_rates
+            .insert(market_id, (maker_rate, taker_rate));
+    }
+
+    /// Rate charged for `role` in `market_id`.
+    pub fn rate(&self, market_id: u32, role: FillRole) -> f64 {
+        let (maker, taker) = self
+            .market_rates
+            .get(&market_id)
+            .copied()
+            .unwrap_or((self.maker_rate, self.taker_rate));
+        match role {
+            FillRole::Maker => maker,
+            FillRole::Taker => taker,
+        }
+    }
+
+    /// Fee in quote units for a fill of `size` at `price` (negative =
+    /// rebate).
+    pub fn fee(&self, market_id: u32, role: FillRole, size: f64, price: f64) -> f64 {
+        self.rate(market_id, role) * size * price
+    }
+
+    /// Fetch the account's tier rates, then the market rates of
+    /// `market_ids` concurrently. A market whose rates cannot be fetched
+    /// keeps the tier rates.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the tier or the brackets cannot be fetched, or
+    /// the account's tier is not among the brackets.
+    pub async fn fetch(nord: &Nord, account_id: u32, market_ids: &[u32]) -> Result<Self> {
+        let tier = nord.get_account_fee_tier(account_id).await?;
+        let brackets = nord.get_fee_brackets().await?;
+        let config = brackets
+            .iter()
+            .find(|(id, _)| *id == tier)
+            .map(|(_, config)| config)
+            .ok_or_else(|| NordError::Validation(format!("unknown fee tier {tier}")))?;
+        let mut schedule = Self::from_tier(account_id, tier, config);
+
+        let rates = join_all(market_ids.iter().map(|&market_id| async move {
+            let maker = nord.get_market_fee(market_id, &FillRole::Maker, account_id);
+            let taker = nord.get_market_fee(market_id, &FillRole::Taker, account_id);
+            (market_id, maker.await, taker.await)
+        }))
+        .await;
+        for (market_id, maker, taker) in rates {
+            match (maker, taker) {
+                (Ok(maker), Ok(taker)) => schedule.set_market_rates(market_id, maker, taker),
+                (Err(e), _) | (_, Err(e)) => {
+                    warn!(market_id, error = %e, "market fee fetch failed, using tier rates");
+                }
+            }
+        }
+        Ok(schedule)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_market_rates_override_tier() {
+        let mut schedule = FeeSchedule::from_tier(
+            7,
+            2,
+            &FeeTierConfig {
+                maker_fee_ppm: 100,
+                taker_fee_ppm: 500,
+            },
+        );
+        assert_eq!(schedule.rate(1, FillRole::Maker), 0.0001);
+        assert!((schedule.fee(1, FillRole::Taker, 2.0, 100.0) - 0.1).abs() < 1e-12);
+
+        schedule.set_market_rates(1, -0.0001, 0.0003);
+        assert!((schedule.fee(1, FillRole::Maker, 2.0, 100.0) + 0.02).abs() < 1e-12);
+        assert_eq!(schedule.rate(2, FillRole::Taker), 0.0005);
+    }
+}
@@ -0,0 +1,218 @@
+//! Rolling latency telemetry.
+//!
+//! Components record one sample per observed delay into a shared
+//! [`LatencyTracker`], keyed by source:
+//!
+//! ```text
+//!   reference feed  venue event time --> local receive   "Binance:btcusdt@bookTicker"
+//!   01 WebSocket    delta timestamp  --> local receive   "nord_ws:deltas"
+//!   actions         submit           --> receipt decoded "action"
+//! ```
+//!
+//! Each source keeps the samples of a trailing window (bounded in count);
+//! [`LatencyTracker::snapshot`] reports percentiles over it for logs, the
+//! monitor and metrics exporters.
+//!
+//! Event-to-receive latencies compare a remote clock with the local one, so
+//! they include clock skew and may be negative. Samples are timestamped by the
+//! caller, which keeps replayed latencies on the replay clock.
+
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use serde::Serialize;
+
+/// Source key of 01 WebSocket orderbook deltas.
+pub const NORD_WS_DELTAS: &str = "nord_ws:deltas";
+
+/// Source key of action submit-to-receipt round trips.
+pub const ACTION_ROUND_TRIP: &str = "action";
+
+/// Default trailing window of the percentiles.
+const DEFAULT_WINDOW_MS: u64 = 60_000;
+
+/// Samples kept per source, whatever the window.
+const MAX_SAMPLES: usize = 4_096;
+
+/// Percentiles of one source over the trailing window.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LatencyStats {
+    pub source: String,
+    /// Samples within the window.
+    pub samples: usize,
+    /// Samples recorded since the tracker was created.
+    pub total: u64,
+    pub p50_ms: f64,
+    pub p90_ms: f64,
+    pub p99_ms: f64,
+    pub max_ms: f64,
+    /// Most recent sample.
+    pub last_ms: f64,
+}
+
+/// `(recorded_at_ms, latency_ms)` samples of one source.
+#[derive(Debug, Default)]
+struct Samples {
+    samples: VecDeque<(u64, f64)>,
+    total: u64,
+}
+
+impl Samples {
+    fn expire(&mut self, cutoff_ms: u64) {
+        while self.samples.front().is_some_and(|&(at, _)| at < cutoff_ms) {
+            self.samples.pop_front();
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Inner {
+    window_ms: u64,
+    sources: BTreeMap<String, Samples>,
+}
+
+/// Shared, cloneable latency recorder. Clones record into the same sources.
+#[derive(Debug, Clone)]
+pub struct LatencyTracker {
+    inner: Arc<Mutex<Inner>>,
+}
+
+impl Default for LatencyTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LatencyTracker {
+    /// Tracker with a 60s window.
+    pub fn new() -> Self {
+        Self::with_window_ms(DEFAULT_WINDOW_MS)
+    }
+
+    /// Tracker reporting percentiles over the trailing `window_ms`.
+    pub fn with_window_ms(window_ms: u64) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(Inner {
+                window_ms,
+                sources: BTreeMap::new(),
+            })),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Inner> {
+        // A panic while holding the lock cannot leave the samples invalid.
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Record a latency of `latency_ms` for `source`, observed at `at_ms`
+    /// (epoch ms).
+    pub fn record(&self, source: &str, latency_ms: f64, at_ms: u64) {
+        let mut inner = self.lock();
+        let cutoff = at_ms.saturating_sub(inner.window_ms);
+        if !inner.sources.contains_key(source) {
+            inner.sources.insert(source.to_string(), Samples::default());
+        }
+        let samples = inner
+            .sources
+            .get_mut(source)
+            .expect("source inserted above");
+        samples.samples.push_back((at_ms, latency_ms));
+        samples.total += 1;
+        samples.expire(cutoff);
+        if samples.samples.len() > MAX_SAMPLES {
+            samples.samples.pop_front();
+        }
+    }
+
+    /// Record the delay between a remote `event_ms` and the local `recv_ms`.
+    /// Ignored when the event time is missing (zero).
+    pub fn record_event(&self, source: &str, event_ms: u64, recv_ms: u64) {
+        if event_ms > 0 {
+            self.record(source, recv_ms as f64 - event_ms as f64, recv_ms);
+        }
+    }
+
+    /// Percentiles of every source with samples in the window ending at
+    /// `now_ms`, sorted by source.
+    pub fn snapshot(&self, now_ms: u64) -> Vec<LatencyStats> {
+        let mut inner = self.lock();
+        let cutoff = now_ms.saturating_sub(inner.window_ms);
+        inner
+            .sources
+            .iter_mut()
+            .filter_map(|(source, samples)| {
+                samples.expire(cutoff);
+                stats(source, samples)
+            })
+            .collect()
+    }
+
+    /// Percentiles of `source` in the window ending at `now_ms`.
+    pub fn get(&self, source: &str, now_ms: u64) -> Option<LatencyStats> {
+        let mut inner = self.lock();
+        let cutoff = now_ms.saturating_sub(inner.window_ms);
+        let samples = inner.sources.get_mut(source)?;
+        samples.expire(cutoff);
+        stats(source, samples)
+    }
+}
+
+/// Nearest-rank percentiles of the samples; `None` if there are none.
+fn stats(source: &str, samples: &Samples) -> Option<LatencyStats> {
+    let &(_, last_ms) = samples.samples.back()?;
+    let mut values: Vec<f64> = samples.samples.iter().map(|&(_, ms)| ms).collect();
+    values.sort_by(f64::total_cmp);
+    let rank =
+        |p: f64| values[((p * values.len() as f64).ceil() as usize).clamp(1, values.len()) - 1];
+    Some(LatencyStats {
+        source: source.to_string(),
+        samples: values.len(),
+        total: samples.total,
+        p50_ms: rank(0.50),
+        p90_ms: rank(0.90),
+        p99_ms: rank(0.99),
+        max_ms: values[values.len() - 1],
+        last_ms,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_percentiles_over_window() {
+        let tracker = LatencyTracker::with_window_ms(1_000);
+        for i in 1..=100 {
+            tracker.record("feed", i as f64, 10_000 + i);
+        }
+        tracker.record_event("feed", 0, 10_100);
+
+        let stats = tracker.get("feed", 10_100).unwrap();
+        assert_eq!((stats.samples, stats.total), (100, 100));
+        assert_eq!(
+            (stats.p50_ms, stats.p90_ms, stats.p99_ms),
+            (50.0, 90.0, 99.0)
+        );
+        assert_eq!((stats.max_ms, stats.last_ms), (100.0, 100.0));
+
+        // Only the samples recorded from 10_050 on are still in the window.
+        let stats = tracker.get("feed", 11_050).unwrap();
+        assert_eq!(stats.samples, 51);
+        assert_eq!(stats.p50_ms, 75.0);
+        assert!(tracker.snapshot(20_000).is_empty());
+    }
+
+    #[test]
+    fn test_record_event_measures_receive_delay() {
+        let tracker = LatencyTracker::new();
+        tracker.clone().record_event(NORD_WS_DELTAS, 1_000, 1_025);
+        tracker.record_event(ACTION_ROUND_TRIP, 2_000, 1_990);
+
+        let snapshot = tracker.snapshot(2_000);
+        assert_eq!(snapshot.len(), 2);
+        assert_eq!(snapshot[0].source, ACTION_ROUND_TRIP);
+        assert_eq!(snapshot[0].last_ms, -10.0);
+        assert_eq!(snapshot[1].p50_ms, 25.0);
+    }
+}
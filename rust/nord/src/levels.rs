@@ -0,0 +1,249 @@
+//! Incremental per-level orderbook changes.
+//!
+//! After every update the [`OrderbookStream`](crate::OrderbookStream)
+//! background task publishes the net change of each touched level as one
+//! [`LevelChanges`] batch. Consumers that need the full book keep their own
+//! copy with [`DepthMirror`] instead of receiving a fresh depth snapshot on
+//! every update:
+//!
+//! ```text
+//!   subscribe_levels()  ------------------+
+//!   get_depth()  (seq = N)                |
+//!        |                                v
+//!        +--> local OrderbookDepth <-- batch N+1, N+2, ...
+//!                    ^                    |
+//!                    |       gap or lag   |
+//!                    +---- get_depth() <--+
+//! ```
+//!
+//! Each change carries the absolute new size, so applying a batch that is
+//! already reflected in a depth copy is harmless.
+
+use tokio::sync::broadcast;
+use tracing::debug;
+
+use crate::orderbook::{OrderbookDepth, OrderbookHandle};
+use crate::types::Side;
+
+/// Net change of one price level between two published updates.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LevelChange {
+    /// Book side of the level.
+    pub side: Side,
+    /// Level price.
+    pub price: f64,
+    /// Size before the change (`0.0` for a new level).
+    pub old_size: f64,
+    /// Size after the change (`0.0` when the level was removed).
+    pub new_size: f64,
+    /// `update_id` of the book after the change.
+    pub update_id: u64,
+}
+
+/// All level changes produced by one book update (or re-snapshot).
+#[derive(Debug, Clone, PartialEq)]
+pub struct LevelChanges {
+    /// Per-book sequence number, incremented by one for every batch.
+    pub seq: u64,
+    /// `update_id` of the book after the batch.
+    pub update_id: u64,
+    /// Unix epoch milliseconds when the batch was published.
+    pub timestamp: u64,
+    /// Changed levels, bids then asks, ascending by price.
+    pub changes: Vec<LevelChange>,
+}
+
+impl OrderbookDepth {
+    /// Apply a batch of level changes and advance [`seq`](Self::seq).
+    pub fn apply(&mut self, batch: &LevelChanges) {
+        for change in &batch.changes {
+            let levels = match change.side {
+                Side::Bid => &mut self.bids,
+                Side::Ask => &mut self.asks,
+            };
+            let ticks = self.scale.price_to_ticks(change.price);
+            let lots = self.scale.size_to_lots(change.new_size);
+            if lots == 0 {
+                levels.remove(&ticks);
+            } else {
+                levels.insert(ticks, lots);
+            }
+        }
+        self.seq = batch.seq;
+    }
+}
+
+// ---------------------------------------------------------------------------
+// DepthMirror
+// ---------------------------------------------------------------------------
+
+/// Full local copy of a live book kept current from level changes.
+///
+/// Subscribes before fetching the initial depth so no batch is missed, and
+/// re-fetches the depth whenever a batch is missing.
+pub struct DepthMirror {
+    handle: OrderbookHandle,
+    levels_rx: broadcast::Receiver<LevelChanges>,
+    depth: Option<OrderbookDepth>,
+    refetches: u64,
+}
+
+impl DepthMirror {
+    /// Subscribe to `handle` and fetch the initial depth.
+    pub async fn new(handle: OrderbookHandle) -> Self {
+        let levels_rx = handle.subscribe_levels();
+        let depth = handle.get_depth().await;
+        Self {
+            handle,
+            levels_rx,
+            depth,
+            refetches: 0,
+        }
+    }
+
+    /// Current copy of the book, or `None` if the book has been closed
+    /// before a depth could be fetched.
+    pub fn depth(&self) -> Option<&OrderbookDepth> {
+        self.depth.as_ref()
+    }
+
+    /// Number of times the depth was re-fetched after a gap or lag. Views
+    /// derived from the depth batch by batch must be rebuilt when it moves.
+    pub fn refetches(&self) -> u64 {
+        self.refetches
+    }
+
+    /// Wait for the next batch and apply it.
+    ///
+    /// Returns the applied batch, or `None` once the book's channel closes.
+    /// Batches already reflected in the copy are skipped; after a gap the
+    /// depth is re-fetched and the triggering batch is still returned.
+    pub async fn next(&mut self) -> Option<LevelChanges> {
+        loop {
+            let batch = match self.levels_rx.recv().await {
+                Ok(batch) => batch,
+                Err(broadcast::error::RecvError::Lagged(n)) => {
+                    debug!(
+                        "{} level changes lagged by {n}, re-fetching depth",
+                        self.handle.symbol()
+                    );
+                    self.depth = self.handle.get_depth().await;
+                    self.refetches += 1;
+                    continue;
+                }
+                Err(broadcast::error::RecvError::Closed) => return None,
+            };
+
+            let seq = self.depth.as_ref().map_or(0, |depth| depth.seq);
+            match &mut self.depth {
+                Some(_) if batch.seq <= seq => continue,
+                Some(depth) if batch.seq == seq + 1 => depth.apply(&batch),
+                _ => {
+                    debug!(
+                        "{} level changes gap ({seq} -> {}), re-fetching depth",
+                        self.handle.symbol(),
+                        batch.seq,
+                    );
+                    self.depth = self.handle.get_depth().await;
+                    self.refetches += 1;
+                }
+            }
+            return Some(batch);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::orderbook::TickScale;
+
+    const SCALE: TickScale = TickScale {
+        price_decimals: 2,
+        size_decimals: 4,
+    };
+
+    fn change(side: Side, price: f64, old_size: f64, new_size: f64) -> LevelChange {
+        LevelChange {
+            side,
+            price,
+            old_size,
+            new_size,
+            update_id: 1,
+        }
+    }
+
+    #[test]
+    fn test_apply_sets_and_removes_levels() {
+        let mut depth = OrderbookDepth {
+            bids: [(9_999, 10_000)].into_iter().collect(),
+            asks: [(10_001, 5_000)].into_iter().collect(),
+            scale: SCALE,
+            seq: 3,
+        };
+        let batch = LevelChanges {
+            seq: 4,
+            update_id: 1,
+            timestamp: 0,
+            changes: vec![
+                change(Side::Bid, 99.99, 1.0, 2.5),
+                change(Side::Bid, 99.98, 0.0, 1.0),
+                change(Side::Ask, 100.01, 0.5, 0.0),
+            ],
+        };
+        depth.apply(&batch);
+        assert_eq!(depth.seq, 4);
+        assert_eq!(
+            depth.bid_levels().collect::<Vec<_>>(),
+            vec![(99.99, 2.5), (99.98, 1.0)]
+        );
+        assert!(depth.asks.is_empty());
+
+        // Re-applying is idempotent.
+        let before = depth.clone();
+        depth.apply(&batch);
+        assert_eq!(depth, before);
+    }
+
+    #[tokio::test]
+    async fn test_mirror_refetches_after_gap() {
+        let (handle, levels_tx, mut depth_requests) = OrderbookHandle::for_test("T");
+        let (seq_tx, seq_rx) = tokio::sync::watch::channel(0u64);
+        tokio::spawn(async move {
+            while let Some(reply) = depth_requests.recv().await {
+                let seq = *seq_rx.borrow();
+                let _ = reply.send(OrderbookDepth {
+                    bids: [(9_999, seq * 10_000)].into_iter().collect(),
+                    asks: Default::default(),
+                    scale: SCALE,
+                    seq,
+                });
+            }
+        });
+        let batch = |seq: u64, size: f64| LevelChanges {
+            seq,
+            update_id: seq,
+            timestamp: 0,
+            changes: vec![change(Side::Bid, 99.99, 0.0, size)],
+        };
+
+        let mut mirror = DepthMirror::new(handle).await;
+        assert_eq!(mirror.depth().unwrap().seq, 0);
+
+        levels_tx.send(batch(1, 1.0)).unwrap();
+        assert_eq!(mirror.next().await.unwrap().seq, 1);
+        assert_eq!(mirror.depth().unwrap().bids.get(&9_999), Some(&10_000));
+        assert_eq!(mirror.refetches(), 0);
+
+        // Batch 2 is lost: the mirror re-fetches the depth at seq 3.
+        seq_tx.send(3).unwrap();
+        levels_tx.send(batch(3, 3.0)).unwrap();
+        assert_eq!(mirror.next().await.unwrap().seq, 3);
+        assert_eq!(mirror.depth().unwrap().seq, 3);
+        assert_eq!(mirror.refetches(), 1);
+        assert_eq!(mirror.depth().unwrap().bids.get(&9_999), Some(&30_000));
+
+        drop(levels_tx);
+        assert!(mirror.next().await.is_none());
+    }
+}
@@ -1,11 +1,23 @@
 pub mod account;
 pub mod actions;
 pub mod admin;
+pub mod analytics;
+pub mod audit;
 pub mod client;
+pub mod clock;
 pub mod config;
 pub mod error;
+pub mod ex_own;
+pub mod fees;
+pub mod latency;
+pub mod levels;
+pub mod oms;
 pub mod orderbook;
+pub mod orderbook_manager;
+pub mod position;
 pub mod proto;
+pub mod recorder;
+pub mod replay;
 pub mod rest;
 pub mod types;
 pub mod user;
@@ -43,10 +55,46 @@ pub use types::{Account, AccountMarginsView, Balance, OpenOrder, PerpPosition, P
 pub use types::{OrderbookInfo, SideSummary};
 
 // Orderbook (live stream)
-pub use orderbook::{MidPrice, OrderbookDepth, OrderbookSide, OrderbookStream, BBO};
+pub use orderbook::{
+    BookHealth, MidPrice, OrderbookDepth, OrderbookHandle, OrderbookSide, OrderbookStream,
+    TickScale, BBO,
+};
+pub use orderbook_manager::OrderbookManager;
+
+// Per-level orderbook changes
+pub use levels::{DepthMirror, LevelChange, LevelChanges};
+
+// Orderbook analytics
+pub use analytics::{BookSignals, DepthBand, FillEstimate, OrderbookConfig};
+pub use audit::{AuditConfig, AuditReport, AuditStats, LevelDivergence};
+
+// Market-data recording
+pub use recorder::{
+    RecordSource, RecordedFrame, Recorder, RecorderConfig, RecorderStats, RecorderWriter,
+};
+
+// Replay
+pub use clock::{epoch_ms, Clock};
+pub use replay::{
+    Recording, ReplayAck, ReplayAckGuard, ReplayDriver, ReplaySink, ReplaySpeed, ReplayStats,
+};
 
 // Account (live stream)
-pub use account::{AccountStream, FillEvent, TrackedOrder};
+pub use account::{
+    AccountStream, BalanceChange, BalanceChangeKind, FillEvent, TrackedBalance, TrackedOrder,
+};
+
+// Latency telemetry
+pub use latency::{LatencyStats, LatencyTracker};
+
+// Order management
+pub use oms::{ManagedOrder, Oms, OrderManager, OrderState};
+
+// Position + PnL (live stream)
+pub use position::{LivePosition, PositionStream, PositionStreamConfig};
+
+// Orderbook excluding our own orders
+pub use ex_own::{ExOwnBook, ExOwnBookStream, TopLevelShare};
 
 // Trade + order history
 pub use types::{OrderInfo, Trade};
@@ -67,6 +115,7 @@ pub use types::{DepositInfo, WithdrawalInfo};
 pub use types::LiquidationInfo;
 
 // Fees
+pub use fees::FeeSchedule;
 pub use types::{AccountFeeTier, FeeTierConfig};
 
 // Admin
@@ -81,8 +130,11 @@ pub use types::{ActionsItem, QuoteSize};
 // User info
 pub use types::{User, UserSession};
 
+// WebSocket client
+pub use ws::{DispatchStats, NordWebSocketClient};
+
 // WebSocket events
 pub use ws::events::{
-    WebSocketAccountUpdate, WebSocketCandleUpdate, WebSocketDeltaUpdate, WebSocketMessage,
-    WebSocketTradeUpdate,
+    StreamTrade, WebSocketAccountUpdate, WebSocketCandleUpdate, WebSocketDeltaUpdate,
+    WebSocketMessage, WebSocketTradeUpdate,
 };
@@ -0,0 +1,1083 @@
+//! Client-side order management: every order we send, from submission to
+//! its final state.
+//!
+//! [`AccountStream`](crate::AccountStream) only learns about an order once the
+//! exchange has placed it, so an order whose atomic action is still in flight
+//! is invisible to it. The OMS records each order when it is submitted, keyed
+//! by a client order id it assigns itself, and moves it through its
+//! lifecycle from action receipts and WebSocket places, fills and cancels:
+//!
+//! ```text
+//!                 track_actions()
+//!                       |
+//!                       v           error receipt
+//!                  PendingNew -------------------------> Rejected
+//!                   |    |
+//!   posted/WS place |    +--- receipt: not posted -----> Filled (Taken)
+//!                   v                                    or Cancelled
+//!              Acknowledged
+//!                |       \  cancel sent
+//!           fill |        +-------------> PendingCancel ---> Cancelled
+//!                v                         |    ^
+//!          PartiallyFilled ----------------+    | cancel rejected or
+//!                |                         |    | timed out: back to
+//!                +-------> Filled <--------+    | Acknowledged or
+//!                                                 PartiallyFilled
+//! ```
+//!
+//! Timeouts are applied by [`OrderManager::prune`], which the background
+//! task runs every second. Events the WebSocket never delivered are
+//! recovered from REST: [`OrderManager::missing_from`] lists the orders a
+//! periodic open-orders snapshot no longer contains, and the caller feeds
+//! each one's [`OrderInfo`] to [`OrderManager::apply_order_info`].
+//!
+//! Once the exchange assigns an order id, the OMS indexes the order by it.
+//! WebSocket events can arrive before the receipt that reveals the id; those
+//! are buffered per order id and replayed when the id is mapped.
+//!
+//! Sizes are absolute: a place carries the resting size and a fill the
+//! remaining size, so an event applied twice (e.g. a fill seen in both the
+//! receipt and the WebSocket feed) is harmless.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use rust_decimal::prelude::ToPrimitive;
+use tokio::sync::{broadcast, oneshot, watch};
+use tokio::task::JoinHandle;
+use tokio::time::{self, Duration};
+use tracing::{debug, info, warn};
+
+use crate::actions::atomic::UserAtomicSubaction;
+use crate::client::Nord;
+use crate::clock::Clock;
+use crate::orderbook::TickScale;
+use crate::proto::nord as proto;
+use crate::types::{FinalizationReason, OpenOrder, OrderInfo, Side};
+use crate::ws::events::WebSocketAccountUpdate;
+
+/// Sizes below this are treated as zero.
+const SIZE_EPSILON: f64 = 1e-9;
+
+/// How long WebSocket events for an unknown order id are kept while waiting
+/// for the receipt that maps it. Events for orders placed by other clients
+/// are never mapped and expire after this.
+const UNMATCHED_TTL_MS: u64 = 30_000;
+
+/// How long an order may stay pending placement before it is considered
+/// rejected.
+pub const PENDING_TIMEOUT_MS: u64 = 30_000;
+
+/// How long a cancel may stay unconfirmed before the order is considered
+/// live again (and the cancel may be retried).
+pub const CANCEL_TIMEOUT_MS: u64 = 10_000;
+
+/// How long finalized orders stay queryable.
+const FINAL_RETENTION_MS: u64 = 60_000;
+
+/// Interval between prunes in the background task.
+const PRUNE_INTERVAL_MS: u64 = 1_000;
+
+type ReceiptInner = proto::receipt::atomic_subaction_result_kind::Inner;
+
+/// Lifecycle state of a managed order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OrderState {
+    /// Submitted, not yet confirmed by the exchange.
+    PendingNew,
+    /// Resting on the book, nothing filled.
+    Acknowledged,
+    /// Resting on the book, partly filled.
+    PartiallyFilled,
+    /// Cancel submitted, not yet confirmed.
+    PendingCancel,
+    /// Removed from the book before filling completely.
+    Cancelled,
+    /// Filled completely.
+    Filled,
+    /// Refused by the exchange; never reached the book.
+    Rejected,
+}
+
+impl OrderState {
+    /// Whether no further transitions are expected.
+    pub fn is_final(self) -> bool {
+        matches!(self, Self::Cancelled | Self::Filled | Self::Rejected)
+    }
+
+    /// Whether the exchange has not yet confirmed the last request.
+    pub fn is_in_flight(self) -> bool {
+        matches!(self, Self::PendingNew | Self::PendingCancel)
+    }
+}
+
+/// One order followed by the OMS.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ManagedOrder {
+    pub client_order_id: u64,
+    /// Exchange order id, once known.
+    pub order_id: Option<u64>,
+    pub market_id: u32,
+    pub side: Side,
+    pub price: f64,
+    /// Submitted size.
+    pub size: f64,
+    pub filled: f64,
+    /// Size still resting (or to rest, while pending).
+    pub remaining: f64,
+    pub state: OrderState,
+    /// Why the order left the book, once it has.
+    pub finalization: Option<FinalizationReason>,
+    /// Error of the last rejected request (placement or cancel).
+    pub error: Option<String>,
+    /// Unix epoch milliseconds of submission.
+    pub created_at: u64,
+    /// Unix epoch milliseconds of the last transition.
+    pub updated_at: u64,
+}
+
+impl ManagedOrder {
+    /// Set the resting size, deriving the filled size from it.
+    fn set_remaining(&mut self, remaining: f64) {
+        self.remaining = remaining.max(0.0);
+        self.filled = self.filled.max(self.size - self.remaining);
+    }
+
+    /// State of a live order given its fills.
+    fn live_state(&self) -> OrderState {
+        if self.filled > SIZE_EPSILON {
+            OrderState::PartiallyFilled
+        } else {
+            OrderState::Acknowledged
+        }
+    }
+
+    fn finalize(&mut self, reason: FinalizationReason) {
+        self.state = match reason {
+            FinalizationReason::Canceled => OrderState::Cancelled,
+            FinalizationReason::Filled | FinalizationReason::Taken => OrderState::Filled,
+        };
+        self.finalization = Some(reason);
+        if self.state == OrderState::Filled {
+            self.filled = self.size;
+        }
+        self.remaining = 0.0;
+    }
+}
+
+/// Order event from the WebSocket feed, applied by order id.
+#[derive(Debug, Clone, Copy)]
+enum OrderEvent {
+    Placed { remaining: f64 },
+    Filled { remaining: f64 },
+    Cancelled,
+}
+
+// ---------------------------------------------------------------------------
+// OrderManager
+// ---------------------------------------------------------------------------
+
+/// Order lifecycle state machine. Pure: every method takes the current time
+/// and performs no I/O.
+#[derive(Debug, Clone, Default)]
+pub struct OrderManager {
+    orders: HashMap<u64, ManagedOrder>,
+    /// Exchange order id -> client order id.
+    by_order_id: HashMap<u64, u64>,
+    /// Events for order ids not yet mapped, with the time first seen.
+    unmatched: HashMap<u64, (u64, Vec<OrderEvent>)>,
+    scales: HashMap<u32, TickScale>,
+    next_client_order_id: u64,
+}
+
+impl OrderManager {
+    /// Create a manager assigning client order ids from
+    /// `first_client_order_id` upward. `scales` converts receipt sizes.
+    pub fn new(scales: HashMap<u32, TickScale>, first_client_order_id: u64) -> Self {
+        Self {
+            scales,
+            next_client_order_id: first_client_order_id,
+            ..Default::default()
+        }
+    }
+
+    /// Record a new order as [`PendingNew`](OrderState::PendingNew) and
+    /// return its client order id.
+    pub fn submit(&mut self, market_id: u32, side: Side, price: f64, size: f64, now: u64) -> u64 {
+        let client_order_id = self.next_client_order_id;
+        self.next_client_order_id += 1;
+        self.insert(client_order_id, market_id, side, price, size, now);
+        client_order_id
+    }
+
+    fn insert(
+        &mut self,
+        client_order_id: u64,
+        market_id: u32,
+        side: Side,
+        price: f64,
+        size: f64,
+        now: u64,
+    ) {
+        self.orders.insert(
+            client_order_id,
+            ManagedOrder {
+                client_order_id,
+                order_id: None,
+                market_id,
+                side,
+                price,
+                size,
+                filled: 0.0,
+                remaining: size,
+                state: OrderState::PendingNew,
+                finalization: None,
+                error: None,
+                created_at: now,
+                updated_at: now,
+            },
+        );
+    }
+
+    /// Record the actions of an atomic call about to be sent.
+    ///
+    /// Places without a client order id get one assigned (written into the
+    /// action) and are recorded as pending. Cancels of known orders move
+    /// them to [`PendingCancel`](OrderState::PendingCancel).
+    pub fn track_actions(&mut self, actions: &mut [UserAtomicSubaction], now: u64) {
+        for action in actions {
+            match action {
+                UserAtomicSubaction::Place {
+                    market_id,
+                    side,
+                    size,
+                    price,
+                    client_order_id,
+                    ..
+                } => {
+                    let price = price.and_then(|p| p.to_f64()).unwrap_or(0.0);
+                    let size = size.and_then(|s| s.to_f64()).unwrap_or(0.0);
+                    match *client_order_id {
+                        Some(id) => self.insert(id, *market_id, *side, price, size, now),
+                        None => {
+                            *client_order_id =
+                                Some(self.submit(*market_id, *side, price, size, now));
+                        }
+                    }
+                }
+                UserAtomicSubaction::Cancel { order_id } => {
+                    self.request_cancel(*order_id, now);
+                }
+            }
+        }
+    }
+
+    /// Move a live order to [`PendingCancel`](OrderState::PendingCancel).
+    /// Returns `false` if the order id is unknown or the order is not live.
+    pub fn request_cancel(&mut self, order_id: u64, now: u64) -> bool {
+        let Some(order) = self.order_by_id_mut(order_id) else {
+            return false;
+        };
+        if !matches!(
+            order.state,
+            OrderState::Acknowledged | OrderState::PartiallyFilled
+        ) {
+            return false;
+        }
+        order.state = OrderState::PendingCancel;
+        order.updated_at = now;
+        true
+    }
+
+    /// Apply the receipt of an atomic call sent with `actions` (after
+    /// [`track_actions`](Self::track_actions)).
+    ///
+    /// Place results are matched by client order id, falling back to their
+    /// position among the place actions.
+    pub fn on_atomic_receipt(
+        &mut self,
+        actions: &[UserAtomicSubaction],
+        results: &[ReceiptInner],
+        now: u64,
+    ) {
+        let mut places = actions.iter().filter_map(|a| match a {
+            UserAtomicSubaction::Place {
+                client_order_id, ..
+            } => Some(*client_order_id),
+            UserAtomicSubaction::Cancel { .. } => None,
+        });
+
+        for result in results {
+            match result {
+                ReceiptInner::PlaceOrderResult(placed) => {
+                    let positional = places.next().flatten();
+                    if let Some(client_order_id) = placed.client_order_id.or(positional) {
+                        self.on_placed(client_order_id, placed, now);
+                    }
+                }
+                ReceiptInner::CancelOrder(cancel) => {
+                    let client_order_id = cancel
+                        .client_order_id
+                        .or_else(|| self.by_order_id.get(&cancel.order_id).copied());
+                    if let Some(order) = client_order_id.and_then(|id| self.orders.get_mut(&id)) {
+                        if !order.state.is_final() {
+                            order.finalize(FinalizationReason::Canceled);
+                            order.updated_at = now;
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    fn on_placed(
+        &mut self,
+        client_order_id: u64,
+        placed: &proto::receipt::PlaceOrderResult,
+        now: u64,
+    ) {
+        let Some(order) = self.orders.get_mut(&client_order_id) else {
+            return;
+        };
+        if order.state.is_final() {
+            return;
+        }
+        let scale = self.scales.get(&order.market_id).copied();
+        let lots_to_size = |lots: u64| scale.map_or(0.0, |s| s.lots_to_size(lots));
+        let taken: f64 = placed.fills.iter().map(|t| lots_to_size(t.size)).sum();
+        order.updated_at = now;
+
+        match &placed.posted {
+            Some(posted) => {
+                order.order_id = Some(posted.order_id);
+                match scale {
+                    Some(_) => order.set_remaining(lots_to_size(posted.size)),
+                    None => order.filled = order.filled.max(taken),
+                }
+                if order.state == OrderState::PendingNew {
+                    order.state = order.live_state();
+                }
+                self.map_order_id(client_order_id, posted.order_id, now);
+            }
+            None if !placed.fills.is_empty()
+                && (scale.is_none() || taken >= order.size - SIZE_EPSILON) =>
+            {
+                order.finalize(FinalizationReason::Taken);
+            }
+            None => {
+                // Not posted: the unfilled rest was cancelled (IOC, or a
+                // post-only order that would have crossed).
+                order.filled = taken;
+                order.finalize(FinalizationReason::Canceled);
+            }
+        }
+    }
+
+    /// Index `client_order_id` by its exchange order id and replay the events
+    /// buffered for it.
+    fn map_order_id(&mut self, client_order_id: u64, order_id: u64, now: u64) {
+        self.by_order_id.insert(order_id, client_order_id);
+        if let Some((_, events)) = self.unmatched.remove(&order_id) {
+            for event in events {
+                self.apply_event(client_order_id, event, now);
+            }
+        }
+    }
+
+    /// Apply an atomic call the exchange refused: pending places become
+    /// [`Rejected`](OrderState::Rejected) and pending cancels revert to the
+    /// order's live state.
+    ///
+    /// Only call this when the exchange answered with an error. When the
+    /// outcome is unknown (e.g. a timeout) leave the orders pending: a REST
+    /// sync ([`apply_open_orders`](Self::apply_open_orders)) recovers the
+    /// ones that were placed, and [`prune`](Self::prune) rejects the rest.
+    pub fn on_atomic_error(&mut self, actions: &[UserAtomicSubaction], error: &str, now: u64) {
+        for action in actions {
+            let order = match action {
+                UserAtomicSubaction::Place {
+                    client_order_id: Some(id),
+                    ..
+                } => self.orders.get_mut(id),
+                UserAtomicSubaction::Place { .. } => None,
+                UserAtomicSubaction::Cancel { order_id } => self.order_by_id_mut(*order_id),
+            };
+            let Some(order) = order else { continue };
+            match order.state {
+                OrderState::PendingNew => order.state = OrderState::Rejected,
+                OrderState::PendingCancel => order.state = order.live_state(),
+                _ => continue,
+            }
+            order.error = Some(error.to_string());
+            order.updated_at = now;
+        }
+    }
+
+    /// Apply the places, fills and cancels of a WebSocket account update.
+    pub fn apply_account_update(&mut self, update: &WebSocketAccountUpdate, now: u64) {
+        let events = update
+            .places
+            .iter()
+            .map(|(id, place)| {
+                let event = OrderEvent::Placed {
+                    remaining: place.current_size,
+                };
+                (id, event)
+            })
+            .chain(update.fills.iter().map(|(id, fill)| {
+                let event = OrderEvent::Filled {
+                    remaining: fill.remaining,
+                };
+                (id, event)
+            }))
+            .chain(update.cancels.keys().map(|id| (id, OrderEvent::Cancelled)));
+
+        for (id_str, event) in events {
+            let Ok(order_id) = id_str.parse::<u64>() else {
+                continue;
+            };
+            match self.by_order_id.get(&order_id) {
+                Some(&client_order_id) => self.apply_event(client_order_id, event, now),
+                None => self
+                    .unmatched
+                    .entry(order_id)
+                    .or_insert_with(|| (now, Vec::new()))
+                    .1
+                    .push(event),
+            }
+        }
+    }
+
+    fn apply_event(&mut self, client_order_id: u64, event: OrderEvent, now: u64) {
+        let Some(order) = self.orders.get_mut(&client_order_id) else {
+            return;
+        };
+        if order.state.is_final() {
+            return;
+        }
+        match event {
+            OrderEvent::Placed { remaining } => {
+                order.set_remaining(remaining);
+                if order.state == OrderState::PendingNew {
+                    order.state = order.live_state();
+                }
+            }
+            OrderEvent::Filled { remaining } => {
+                order.set_remaining(remaining);
+                if order.remaining <= SIZE_EPSILON {
+                    order.finalize(FinalizationReason::Filled);
+                } else if order.state != OrderState::PendingCancel {
+                    order.state = OrderState::PartiallyFilled;
+                }
+            }
+            OrderEvent::Cancelled => order.finalize(FinalizationReason::Canceled),
+        }
+        order.updated_at = now;
+    }
+
+    /// Adopt the exchange's view of an order fetched over REST: its filled
+    /// size and, once finalized, its [`FinalizationReason`].
+    pub fn apply_order_info(&mut self, info: &OrderInfo, now: u64) {
+        let Some(order) = self.order_by_id_mut(info.order_id) else {
+            return;
+        };
+        if let Some(filled) = info.filled_size {
+            order.filled = order.filled.max(filled);
+        }
+        if let Some(reason) = info.finalization_reason {
+            let filled = order.filled;
+            order.finalize(reason);
+            if reason == FinalizationReason::Canceled {
+                order.filled = filled;
+            }
+        }
+        order.updated_at = now;
+    }
+
+    /// Map pending orders found in a REST open-orders snapshot by their
+    /// client order id. Recovers orders whose receipt was lost.
+    pub fn apply_open_orders(&mut self, open_orders: &[OpenOrder], now: u64) {
+        for open in open_orders {
+            let Some(client_order_id) = open.client_order_id else {
+                continue;
+            };
+            let Some(order) = self.orders.get_mut(&client_order_id) else {
+                continue;
+            };
+            if order.state != OrderState::PendingNew {
+                continue;
+            }
+            order.order_id = Some(open.order_id);
+            order.set_remaining(open.size);
+            order.state = order.live_state();
+            order.updated_at = now;
+            self.map_order_id(client_order_id, open.order_id, now);
+        }
+    }
+
+    /// Drop finalized orders last updated more than `retain_ms` ago and
+    /// expired unmatched events. Orders still pending placement after
+    /// [`PENDING_TIMEOUT_MS`] are rejected; cancels unconfirmed after
+    /// [`CANCEL_TIMEOUT_MS`] return the order to its live state.
+    pub fn prune(&mut self, now: u64, retain_ms: u64) {
+        for order in self.orders.values_mut() {
+            if order.state == OrderState::PendingNew
+                && now.saturating_sub(order.created_at) > PENDING_TIMEOUT_MS
+            {
+                order.state = OrderState::Rejected;
+                order.error = Some("placement not confirmed".to_string());
+                order.updated_at = now;
+            } else if order.state == OrderState::PendingCancel
+                && now.saturating_sub(order.updated_at) > CANCEL_TIMEOUT_MS
+            {
+                order.state = order.live_state();
+                order.error = Some("cancel not confirmed".to_string());
+                order.updated_at = now;
+            }
+        }
+        let expired: Vec<u64> = self
+            .orders
+            .values()
+            .filter(|o| o.state.is_final() && now.saturating_sub(o.updated_at) > retain_ms)
+            .map(|o| o.client_order_id)
+            .collect();
+        for client_order_id in expired {
+            if let Some(order_id) = self
+                .orders
+                .remove(&client_order_id)
+                .and_then(|o| o.order_id)
+            {
+                self.by_order_id.remove(&order_id);
+            }
+        }
+        self.unmatched
+            .retain(|_, (seen, _)| now.saturating_sub(*seen) <= UNMATCHED_TTL_MS);
+    }
+
+    fn order_by_id_mut(&mut self, order_id: u64) -> Option<&mut ManagedOrder> {
+        let client_order_id = self.by_order_id.get(&order_id)?;
+        self.orders.get_mut(client_order_id)
+    }
+
+    /// Order by client order id.
+    pub fn get(&self, client_order_id: u64) -> Option<&ManagedOrder> {
+        self.orders.get(&client_order_id)
+    }
+
+    /// Order by exchange order id.
+    pub fn get_by_order_id(&self, order_id: u64) -> Option<&ManagedOrder> {
+        self.orders.get(self.by_order_id.get(&order_id)?)
+    }
+
+    /// All tracked orders, including recently finalized ones.
+    pub fn orders(&self) -> impl Iterator<Item = &ManagedOrder> {
+        self.orders.values()
+    }
+
+    /// Non-final orders in `market_id`, including pending ones.
+    pub fn open_orders(&self, market_id: u32) -> Vec<ManagedOrder> {
+        self.orders
+            .values()
+            .filter(|o| o.market_id == market_id && !o.state.is_final())
+            .cloned()
+            .collect()
+    }
+
+    /// Number of orders in `market_id` awaiting exchange confirmation.
+    pub fn in_flight(&self, market_id: u32) -> usize {
+        self.orders
+            .values()
+            .filter(|o| o.market_id == market_id && o.state.is_in_flight())
+            .count()
+    }
+
+    /// Exchange order ids of orders in `market_id` that are on the book by
+    /// our account (live or pending cancel) but absent from `open_orders`, a
+    /// REST snapshot of that market. Their final state was missed, or they
+    /// were placed after the snapshot; fetching each and applying it with
+    /// [`apply_order_info`](Self::apply_order_info) settles which.
+    pub fn missing_from(&self, open_orders: &[OpenOrder], market_id: u32) -> Vec<u64> {
+        self.orders
+            .values()
+            .filter(|o| {
+                o.market_id == market_id
+                    && matches!(
+                        o.state,
+                        OrderState::Acknowledged
+                            | OrderState::PartiallyFilled
+                            | OrderState::PendingCancel
+                    )
+            })
+            .filter_map(|o| o.order_id)
+            .filter(|id| !open_orders.iter().any(|open| open.order_id == *id))
+            .collect()
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Oms
+// ---------------------------------------------------------------------------
+
+/// Shared OMS: the caller records outgoing actions and their receipts, a
+/// background task applies WebSocket account updates.
+///
+/// The [`OrderManager`] lives in a `watch` channel, so a recorded submission
+/// is visible to readers immediately and subscribers are woken on every
+/// change.
+pub struct Oms {
+    account_id: u32,
+    clock: Clock,
+    // Input: `Some` before `connect()`, `None` after (moved into the task).
+    account_rx: Option<broadcast::Receiver<Arc<WebSocketAccountUpdate>>>,
+    state_tx: watch::Sender<OrderManager>,
+    task_handle: Option<JoinHandle<()>>,
+    shutdown_tx: Option<oneshot::Sender<()>>,
+}
+
+impl Oms {
+    /// Create a new OMS.
+    ///
+    /// Client order ids start at the current time in microseconds so ids
+    /// from a previous run are not reused.
+    ///
+    /// # Arguments
+    ///
+    /// * `account_id` - Account whose orders are managed.
+    /// * `account_rx` - Broadcast receiver from the WebSocket client's
+    ///   `subscribe_accounts()` method.
+    /// * `nord` - Client providing market decimals and the clock.
+    pub fn new(
+        account_id: u32,
+        account_rx: broadcast::Receiver<Arc<WebSocketAccountUpdate>>,
+        nord: &Nord,
+    ) -> Self {
+        let clock = nord.clock();
+        let scales = nord
+            .markets
+            .iter()
+            .map(|m| (m.market_id, TickScale::from_market(m)))
+            .collect();
+        let manager = OrderManager::new(scales, clock.now_ms() * 1000);
+        Self {
+            account_id,
+            clock,
+            account_rx: Some(account_rx),
+            state_tx: watch::channel(manager).0,
+            task_handle: None,
+            shutdown_tx: None,
+        }
+    }
+
+    /// Start applying WebSocket account updates.
+    pub fn connect(&mut self) {
+        let account_rx = self
+            .account_rx
+            .take()
+            .expect("connect() called twice: account_rx already consumed");
+        let (shutdown_tx, shutdown_rx) = oneshot::channel();
+        self.shutdown_tx = Some(shutdown_tx);
+        self.task_handle = Some(tokio::spawn(run_oms_task(
+            self.account_id,
+            account_rx,
+            self.state_tx.clone(),
+            self.clock.clone(),
+            shutdown_rx,
+        )));
+    }
+
+    /// Record the actions of an atomic call about to be sent. See
+    /// [`OrderManager::track_actions`].
+    pub fn track_actions(&self, actions: &mut [UserAtomicSubaction]) {
+        let now = self.clock.now_ms();
+        self.state_tx
+            .send_modify(|oms| oms.track_actions(actions, now));
+    }
+
+    /// Apply the receipt of an atomic call.
+    pub fn on_atomic_receipt(&self, actions: &[UserAtomicSubaction], results: &[ReceiptInner]) {
+        let now = self.clock.now_ms();
+        self.state_tx
+            .send_modify(|oms| oms.on_atomic_receipt(actions, results, now));
+    }
+
+    /// Apply an atomic call the exchange refused. See
+    /// [`OrderManager::on_atomic_error`].
+    pub fn on_atomic_error(&self, actions: &[UserAtomicSubaction], error: &str) {
+        let now = self.clock.now_ms();
+        self.state_tx
+            .send_modify(|oms| oms.on_atomic_error(actions, error, now));
+    }
+
+    /// Map pending orders found in a REST open-orders snapshot.
+    pub fn apply_open_orders(&self, open_orders: &[OpenOrder]) {
+        let now = self.clock.now_ms();
+        self.state_tx
+            .send_modify(|oms| oms.apply_open_orders(open_orders, now));
+    }
+
+    /// Adopt an order fetched over REST.
+    pub fn apply_order_info(&self, info: &OrderInfo) {
+        let now = self.clock.now_ms();
+        self.state_tx
+            .send_modify(|oms| oms.apply_order_info(info, now));
+    }
+
+    /// Order by client order id.
+    pub fn get(&self, client_order_id: u64) -> Option<ManagedOrder> {
+        self.state_tx.borrow().get(client_order_id).cloned()
+    }
+
+    /// Non-final orders in `market_id`, including pending ones.
+    pub fn open_orders(&self, market_id: u32) -> Vec<ManagedOrder> {
+        self.state_tx.borrow().open_orders(market_id)
+    }
+
+    /// Number of orders in `market_id` awaiting exchange confirmation.
+    pub fn in_flight(&self, market_id: u32) -> usize {
+        self.state_tx.borrow().in_flight(market_id)
+    }
+
+    /// Live orders absent from a REST open-orders snapshot. See
+    /// [`OrderManager::missing_from`].
+    pub fn missing_from(&self, open_orders: &[OpenOrder], market_id: u32) -> Vec<u64> {
+        self.state_tx.borrow().missing_from(open_orders, market_id)
+    }
+
+    /// Subscribe to every change of the order state.
+    pub fn subscribe(&self) -> watch::Receiver<OrderManager> {
+        self.state_tx.subscribe()
+    }
+
+    /// Shut down the background task.
+    pub fn close(&mut self) {
+        if let Some(tx) = self.shutdown_tx.take() {
+            let _ = tx.send(());
+        }
+        if let Some(handle) = self.task_handle.take() {
+            handle.abort();
+        }
+    }
+}
+
+impl Drop for Oms {
+    fn drop(&mut self) {
+        self.close();
+    }
+}
+
+/// Apply account updates; prune on a timer so timeouts fire even when the
+/// account is quiet.
+async fn run_oms_task(
+    account_id: u32,
+    mut account_rx: broadcast::Receiver<Arc<WebSocketAccountUpdate>>,
+    state_tx: watch::Sender<OrderManager>,
+    clock: Clock,
+    mut shutdown_rx: oneshot::Receiver<()>,
+) {
+    info!(account_id, "oms active");
+    let mut prune_interval = time::interval(Duration::from_millis(PRUNE_INTERVAL_MS));
+    loop {
+        tokio::select! {
+            update = account_rx.recv() => match update {
+                Ok(update) if update.account_id == account_id => {
+                    let now = clock.now_ms();
+                    state_tx.send_modify(|oms| oms.apply_account_update(&update, now));
+                }
+                Ok(_) => {}
+                Err(broadcast::error::RecvError::Lagged(n)) => {
+                    warn!(account_id, skipped = n, "oms lagged, order states may be stale");
+                }
+                Err(broadcast::error::RecvError::Closed) => {
+                    debug!(account_id, "account channel closed, oms stopping");
+                    break;
+                }
+            },
+            _ = prune_interval.tick() => {
+                let now = clock.now_ms();
+                state_tx.send_modify(|oms| oms.prune(now, FINAL_RETENTION_MS));
+            }
+            _ = &mut shutdown_rx => break,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ws::events::{AccountCancel, AccountFill, AccountPlace};
+    use rust_decimal::Decimal;
+
+    const SCALE: TickScale = TickScale {
+        price_decimals: 2,
+        size_decimals: 4,
+    };
+
+    fn manager() -> OrderManager {
+        OrderManager::new([(1, SCALE)].into_iter().collect(), 100)
+    }
+
+    fn place(size: &str) -> UserAtomicSubaction {
+        UserAtomicSubaction::Place {
+            market_id: 1,
+            side: Side::Bid,
+            fill_mode: crate::types::FillMode::Limit,
+            is_reduce_only: false,
+            size: Some(size.parse::<Decimal>().unwrap()),
+            price: Some(Decimal::from(100)),
+            quote_size: None,
+            client_order_id: None,
+        }
+    }
+
+    fn placed(order_id: Option<u64>, resting_lots: u64, fill_lots: &[u64]) -> ReceiptInner {
+        ReceiptInner::PlaceOrderResult(proto::receipt::PlaceOrderResult {
+            posted: order_id.map(|order_id| proto::receipt::Posted {
+                side: 0,
+                market_id: 1,
+                price: 10_000,
+                size: resting_lots,
+                order_id,
+                account_id: 1,
+            }),
+            fills: fill_lots
+                .iter()
+                .map(|&size| proto::receipt::Trade {
+                    order_id: 9,
+                    price: 10_000,
+                    size,
+                    account_id: 2,
+                })
+                .collect(),
+            client_order_id: None,
+            sender_tracking_id: None,
+            triggered: None,
+        })
+    }
+
+    fn update(
+        places: &[(u64, f64)],
+        fills: &[(u64, f64, f64)],
+        cancels: &[u64],
+    ) -> WebSocketAccountUpdate {
+        WebSocketAccountUpdate {
+            last_update_id: 0,
+            update_id: 1,
+            account_id: 1,
+            places: places
+                .iter()
+                .map(|&(id, current_size)| {
+                    let place = AccountPlace {
+                        side: Side::Bid,
+                        current_size,
+                        price: 100.0,
+                        market_id: 1,
+                    };
+                    (id.to_string(), place)
+                })
+                .collect(),
+            fills: fills
+                .iter()
+                .map(|&(id, quantity, remaining)| {
+                    let fill = AccountFill {
+                        side: Side::Bid,
+                        quantity,
+                        remaining,
+                        price: 100.0,
+                        order_id: id.to_string(),
+                        market_id: 1,
+                        maker_id: 1,
+                        taker_id: 2,
+                        sender_tracking_id: None,
+                    };
+                    (id.to_string(), fill)
+                })
+                .collect(),
+            cancels: cancels
+                .iter()
+                .map(|&id| {
+                    let cancel = AccountCancel {
+                        side: Side::Bid,
+                        current_size: 0.0,
+                        price: 100.0,
+                        market_id: 1,
+                    };
+                    (id.to_string(), cancel)
+                })
+                .collect(),
+            balances: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_lifecycle_from_receipts_and_ws_events() {
+        let mut oms = manager();
+        let mut actions = vec![place("2"), place("1"), place("1")];
+        oms.track_actions(&mut actions, 10);
+        let ids: Vec<u64> = actions
+            .iter()
+            .map(|a| match a {
+                UserAtomicSubaction::Place {
+                    client_order_id, ..
+                } => client_order_id.unwrap(),
+                UserAtomicSubaction::Cancel { .. } => unreachable!(),
+            })
+            .collect();
+        assert_eq!(ids, vec![100, 101, 102]);
+        assert_eq!(oms.in_flight(1), 3);
+
+        // A fill for order 7 arrives before the receipt that maps it.
+        oms.apply_account_update(&update(&[], &[(7, 0.5, 1.5)], &[]), 11);
+        oms.on_atomic_receipt(
+            &actions,
+            &[
+                placed(Some(7), 20_000, &[]),
+                placed(None, 0, &[10_000]),
+                placed(None, 0, &[]),
+            ],
+            12,
+        );
+        let first = oms.get_by_order_id(7).unwrap();
+        assert_eq!(first.state, OrderState::PartiallyFilled);
+        assert!((first.filled - 0.5).abs() < 1e-9);
+        assert_eq!(
+            oms.get(101).unwrap().finalization,
+            Some(FinalizationReason::Taken)
+        );
+        assert_eq!(oms.get(102).unwrap().state, OrderState::Cancelled);
+        assert_eq!(oms.in_flight(1), 0);
+
+        // Cancel rejected, then cancelled for real.
+        let mut cancel = vec![UserAtomicSubaction::Cancel { order_id: 7 }];
+        oms.track_actions(&mut cancel, 13);
+        assert_eq!(oms.get(100).unwrap().state, OrderState::PendingCancel);
+        oms.on_atomic_error(&cancel, "busy", 14);
+        assert_eq!(oms.get(100).unwrap().state, OrderState::PartiallyFilled);
+        oms.track_actions(&mut cancel, 15);
+        oms.apply_account_update(&update(&[], &[], &[7]), 16);
+        let first = oms.get(100).unwrap();
+        assert_eq!(first.state, OrderState::Cancelled);
+        assert_eq!(first.finalization, Some(FinalizationReason::Canceled));
+        assert!(oms.open_orders(1).is_empty());
+
+        oms.prune(16 + FINAL_RETENTION_MS + 1, FINAL_RETENTION_MS);
+        assert_eq!(oms.orders().count(), 0);
+        assert!(oms.get_by_order_id(7).is_none());
+    }
+
+    #[test]
+    fn test_rejected_and_filled_orders() {
+        let mut oms = manager();
+        let mut rejected = vec![place("1")];
+        oms.track_actions(&mut rejected, 1);
+        oms.on_atomic_error(&rejected, "insufficient margin", 2);
+        let order = oms.get(100).unwrap();
+        assert_eq!(order.state, OrderState::Rejected);
+        assert_eq!(order.error.as_deref(), Some("insufficient margin"));
+
+        let mut actions = vec![place("1")];
+        oms.track_actions(&mut actions, 3);
+        oms.apply_account_update(&update(&[(8, 1.0)], &[], &[]), 4);
+        oms.on_atomic_receipt(&actions, &[placed(Some(8), 10_000, &[])], 5);
+        assert_eq!(oms.get(101).unwrap().state, OrderState::Acknowledged);
+
+        oms.apply_account_update(&update(&[], &[(8, 1.0, 0.0)], &[]), 6);
+        let order = oms.get(101).unwrap();
+        assert_eq!(order.state, OrderState::Filled);
+        assert_eq!(order.finalization, Some(FinalizationReason::Filled));
+        assert_eq!(order.filled, 1.0);
+
+        // Receipt lost: the order is recovered from REST by client id and
+        // the buffered WS place is replayed; an unrecovered one expires.
+        let mut lost = vec![place("1"), place("1")];
+        oms.track_actions(&mut lost, 10);
+        oms.apply_account_update(&update(&[], &[(9, 0.25, 0.75)], &[]), 11);
+        oms.apply_open_orders(
+            &[OpenOrder {
+                order_id: 9,
+                market_id: 1,
+                side: Side::Bid,
+                size: 1.0,
+                price: 100.0,
+                original_order_size: 1.0,
+                client_order_id: Some(102),
+            }],
+            12,
+        );
+        assert_eq!(
+            oms.get_by_order_id(9).unwrap().state,
+            OrderState::PartiallyFilled
+        );
+        oms.prune(11 + PENDING_TIMEOUT_MS, FINAL_RETENTION_MS);
+        assert_eq!(oms.get(103).unwrap().state, OrderState::Rejected);
+        assert_eq!(oms.in_flight(1), 0);
+
+        // Late events for a final order are ignored.
+        oms.apply_account_update(&update(&[], &[], &[8]), 7);
+        assert_eq!(oms.get(101).unwrap().state, OrderState::Filled);
+    }
+    fn order_info(order_id: u64, filled: f64, reason: Option<FinalizationReason>) -> OrderInfo {
+        OrderInfo {
+            added_at: String::new(),
+            updated_at: String::new(),
+            trade_id: 0,
+            trader_id: 1,
+            market_id: 1,
+            order_id,
+            side: Side::Bid,
+            placed_size: 1.0,
+            filled_size: Some(filled),
+            update_action_id: 0,
+            is_reduce_only: false,
+            fill_mode: crate::types::FillMode::Limit,
+            placed_price: 100.0,
+            original_size_limit: None,
+            original_price_limit: None,
+            placement_origin: crate::types::PlacementOrigin::User,
+            finalization_reason: reason,
+            market_symbol: String::new(),
+            token_symbol: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_unconfirmed_cancel_times_out_and_rest_settles_missed_events() {
+        let mut oms = manager();
+        let mut actions = vec![place("1"), place("1")];
+        oms.track_actions(&mut actions, 0);
+        oms.on_atomic_receipt(
+            &actions,
+            &[placed(Some(7), 10_000, &[]), placed(Some(8), 10_000, &[])],
+            1,
+        );
+
+        // The cancel of 7 is never confirmed: it stops counting as in flight
+        // and can be sent again.
+        let mut cancel = vec![UserAtomicSubaction::Cancel { order_id: 7 }];
+        oms.track_actions(&mut cancel, 2);
+        assert_eq!(oms.in_flight(1), 1);
+        oms.prune(3 + CANCEL_TIMEOUT_MS, FINAL_RETENTION_MS);
+        assert_eq!(oms.in_flight(1), 0);
+        let order = oms.get_by_order_id(7).unwrap();
+        assert_eq!(order.state, OrderState::Acknowledged);
+        assert_eq!(order.error.as_deref(), Some("cancel not confirmed"));
+        assert!(oms.request_cancel(7, 3 + CANCEL_TIMEOUT_MS));
+
+        // Neither is in the REST snapshot: 7 was cancelled and 8 filled,
+        // and the WebSocket delivered neither event.
+        let mut missing = oms.missing_from(&[], 1);
+        missing.sort_unstable();
+        assert_eq!(missing, vec![7, 8]);
+        oms.apply_order_info(
+            &order_info(7, 0.0, Some(FinalizationReason::Canceled)),
+            4 + CANCEL_TIMEOUT_MS,
+        );
+        oms.apply_order_info(
+            &order_info(8, 1.0, Some(FinalizationReason::Filled)),
+            4 + CANCEL_TIMEOUT_MS,
+        );
+        assert_eq!(oms.get_by_order_id(7).unwrap().state, OrderState::Cancelled);
+        assert_eq!(oms.get_by_order_id(8).unwrap().state, OrderState::Filled);
+        assert!(oms.missing_from(&[], 1).is_empty());
+        assert!(oms.open_orders(1).is_empty());
+    }
+}
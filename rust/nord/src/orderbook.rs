@@ -2,37 +2,50 @@
 //!
 //! This module ports the TypeScript `ZoOrderbookStream` to Rust.
 //! It spawns a background tokio task that owns all mutable state and pushes
-//! price/depth updates to consumers via `tokio::sync::watch` channels
-//! (lock-free reads).
+//! price/signal updates to consumers via `tokio::sync::watch` channels
+//! (lock-free reads), plus per-level changes on a broadcast channel. Full
+//! depth copies are built only on request.
 //!
 //! # Architecture
 //!
 //! ```text
-//!   broadcast::Receiver<WebSocketDeltaUpdate>
+//!   broadcast::Receiver<Arc<WebSocketDeltaUpdate>>
 //!              |
 //!              v
 //!   +----- background task (owns OrderbookInner) -----+
 //!   |  - fetches REST snapshot on start / lag / stale  |
 //!   |  - applies delta updates to BTreeMap-based sides |
-//!   |  - computes mid-price & depth after each update  |
+//!   |  - computes mid-price & signals after each update |
+//!   |  - optionally audits itself against REST         |
+//!   |  - flags crossed/locked books, re-snapshots      |
+//!   |  - copies the depth on request                   |
 //!   +--------------------------------------------------+
-//!              |                        |
-//!        watch::Sender<MidPrice>  watch::Sender<Depth>
-//!              |                        |
-//!              v                        v
-//!         consumers (zero-cost borrow via watch::Receiver)
+//!              |                        |                          |
+//!   watch::Sender<MidPrice>  watch::Sender<BookSignals>  broadcast::Sender<LevelChanges>
+//!              |                        |                          |
+//!              v                        v                          v
+//!         consumers (zero-cost borrow via watch::Receiver)    DepthMirror
 //! ```
+//!
+//! Prices are keyed by integer ticks (`price * 10^price_decimals`) and sizes
+//! stored as integer lots (`size * 10^size_decimals`), so a delta whose JSON
+//! float differs from the snapshot in the last bit still hits the same level.
 
 use std::collections::BTreeMap;
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::sync::Arc;
 
-use ordered_float::OrderedFloat;
-use tokio::sync::{broadcast, oneshot, watch};
+use rust_decimal::Decimal;
+use tokio::sync::{broadcast, mpsc, oneshot, watch, Notify};
 use tokio::task::JoinHandle;
 use tracing::{debug, error, info, warn};
 
+use crate::analytics::{mid_price, BookSignals, OrderbookConfig};
+use crate::audit::{compare_books, AuditStats};
 use crate::client::Nord;
-use crate::error::Result;
+use crate::clock::Clock;
+use crate::error::{NordError, Result};
+use crate::levels::{LevelChange, LevelChanges};
+use crate::types::{MarketInfo, OrderbookInfo, Side};
 use crate::ws::events::{OrderbookEntry, WebSocketDeltaUpdate};
 
 /// Consider the book stale after 60 s without an update.
@@ -41,18 +54,145 @@ const STALE_THRESHOLD_MS: u64 = 60_000;
 /// How often to check for staleness.
 const STALE_CHECK_INTERVAL_MS: u64 = 10_000;
 
-/// Maximum price levels kept per side to bound memory.
-const MAX_LEVELS: usize = 100;
+/// How often to check whether a crossed or locked book outlived its grace
+/// period while no deltas arrive.
+const HEALTH_CHECK_INTERVAL_MS: u64 = 250;
+
+/// Capacity of the level-change broadcast channel. A subscriber that falls
+/// further behind re-fetches the depth.
+const LEVEL_CHANNEL_CAPACITY: usize = 1024;
+
+/// Default price levels kept per side (see [`OrderbookConfig::max_levels`]).
+pub(crate) const DEFAULT_MAX_LEVELS: usize = 100;
 
 // ---------------------------------------------------------------------------
 // Public types
 // ---------------------------------------------------------------------------
 
+/// Conversion between exchange decimals and integer ticks / lots for one
+/// market.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct TickScale {
+    /// Decimal places of the price (1 tick = `10^-price_decimals`).
+    pub price_decimals: u8,
+    /// Decimal places of the size (1 lot = `10^-size_decimals`).
+    pub size_decimals: u8,
+}
+
+impl TickScale {
+    /// Create a scale from the market's price and size decimals.
+    pub fn new(price_decimals: u8, size_decimals: u8) -> Self {
+        Self {
+            price_decimals,
+            size_decimals,
+        }
+    }
+
+    /// Scale for the given market.
+    pub fn from_market(market: &MarketInfo) -> Self {
+        Self::new(market.price_decimals, market.size_decimals)
+    }
+
+    /// Nearest tick to `price`.
+    pub fn price_to_ticks(&self, price: f64) -> i64 {
+        (price * pow10(self.price_decimals)).round() as i64
+    }
+
+    /// Price of `ticks` as the nearest `f64`.
+    pub fn ticks_to_price(&self, ticks: i64) -> f64 {
+        ticks as f64 / pow10(self.price_decimals)
+    }
+
+    /// Exact decimal price of `ticks`.
+    pub fn ticks_to_decimal(&self, ticks: i64) -> Decimal {
+        Decimal::new(ticks, u32::from(self.price_decimals))
+    }
+
+    /// Exact mid of two tick prices: half-ticks carried as one more decimal.
+    pub fn mid_to_decimal(&self, bid_ticks: i64, ask_ticks: i64) -> Decimal {
+        Decimal::new(
+            (bid_ticks + ask_ticks) * 5,
+            u32::from(self.price_decimals) + 1,
+        )
+        .normalize()
+    }
+
+    /// Nearest lot count to `size` (negative sizes clamp to zero).
+    pub fn size_to_lots(&self, size: f64) -> u64 {
+        (size * pow10(self.size_decimals)).round().max(0.0) as u64
+    }
+
+    /// Size of `lots` as the nearest `f64`.
+    pub fn lots_to_size(&self, lots: u64) -> f64 {
+        lots as f64 / pow10(self.size_decimals)
+    }
+
+    /// Exact decimal size of `lots`.
+    pub fn lots_to_decimal(&self, lots: u64) -> Decimal {
+        Decimal::new(lots as i64, u32::from(self.size_decimals))
+    }
+}
+
+fn pow10(decimals: u8) -> f64 {
+    10f64.powi(i32::from(decimals))
+}
+
+/// Whether the top of book is consistent enough to price against.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum BookHealth {
+    /// No snapshot yet, or one side is empty.
+    #[default]
+    Empty,
+    /// Best bid strictly below best ask.
+    Healthy,
+    /// Best bid equal to best ask since `since_ms`.
+    Locked { since_ms: u64 },
+    /// Best bid above best ask since `since_ms`.
+    Crossed { since_ms: u64 },
+}
+
+impl BookHealth {
+    /// Classify a top of book, keeping the start time of an ongoing
+    /// locked/crossed episode from `prev`.
+    pub(crate) fn assess(bid: Option<i64>, ask: Option<i64>, prev: Self, now_ms: u64) -> Self {
+        let (Some(bid), Some(ask)) = (bid, ask) else {
+            return Self::Empty;
+        };
+        match bid.cmp(&ask) {
+            std::cmp::Ordering::Less => Self::Healthy,
+            std::cmp::Ordering::Equal => Self::Locked {
+                since_ms: prev.unhealthy_since().unwrap_or(now_ms),
+            },
+            std::cmp::Ordering::Greater => Self::Crossed {
+                since_ms: prev.unhealthy_since().unwrap_or(now_ms),
+            },
+        }
+    }
+
+    /// Whether both sides are present and the book is neither crossed nor
+    /// locked. Consumers should not quote against a book that is not.
+    pub fn is_healthy(&self) -> bool {
+        matches!(self, Self::Healthy)
+    }
+
+    /// When the current locked/crossed episode began, if any.
+    pub fn unhealthy_since(&self) -> Option<u64> {
+        match *self {
+            Self::Locked { since_ms } | Self::Crossed { since_ms } => Some(since_ms),
+            Self::Empty | Self::Healthy => None,
+        }
+    }
+}
+
 /// Mid-price derived from best bid and best ask.
 #[derive(Copy, Clone, Debug, PartialEq)]
 pub struct MidPrice {
     /// Arithmetic mean of `bid` and `ask`.
     pub mid: f64,
+    /// Exact mean of `bid` and `ask` (at most one decimal finer than the
+    /// tick) for prices from an 01 orderbook; `None` for sources that only
+    /// publish floats.
+    pub exact_mid: Option<Decimal>,
     /// Best bid price.
     pub bid: f64,
     /// Best ask price.
@@ -61,22 +201,56 @@ pub struct MidPrice {
     pub timestamp: u64,
 }
 
-/// Best bid and best ask prices.
+/// Best bid and best ask prices (exact, on the market's tick grid).
 #[derive(Copy, Clone, Debug, PartialEq)]
 pub struct BBO {
     /// Highest resting bid price.
-    pub best_bid: f64,
+    pub best_bid: Decimal,
     /// Lowest resting ask price.
-    pub best_ask: f64,
+    pub best_ask: Decimal,
 }
 
 /// Snapshot of the full orderbook depth (both sides).
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct OrderbookDepth {
-    /// Bid levels: price -> size, sorted ascending by price.
-    pub bids: BTreeMap<OrderedFloat<f64>, f64>,
-    /// Ask levels: price -> size, sorted ascending by price.
-    pub asks: BTreeMap<OrderedFloat<f64>, f64>,
+    /// Bid levels: price ticks -> size lots, sorted ascending by price.
+    pub bids: BTreeMap<i64, u64>,
+    /// Ask levels: price ticks -> size lots, sorted ascending by price.
+    pub asks: BTreeMap<i64, u64>,
+    /// Tick/lot scale used by `bids` and `asks`.
+    pub scale: TickScale,
+    /// Sequence number of the last [`LevelChanges`] batch reflected in this
+    /// copy (`0` before the first batch).
+    pub seq: u64,
+}
+
+impl OrderbookDepth {
+    /// Exact best bid and ask, or `None` if either side is empty.
+    pub fn bbo(&self) -> Option<BBO> {
+        let bid = *self.bids.keys().next_back()?;
+        let ask = *self.asks.keys().next()?;
+        Some(BBO {
+            best_bid: self.scale.ticks_to_decimal(bid),
+            best_ask: self.scale.ticks_to_decimal(ask),
+        })
+    }
+
+    /// Bid levels as `(price, size)`, best (highest) first.
+    pub fn bid_levels(&self) -> impl Iterator<Item = (f64, f64)> + '_ {
+        self.bids.iter().rev().map(|(&p, &s)| self.level(p, s))
+    }
+
+    /// Ask levels as `(price, size)`, best (lowest) first.
+    pub fn ask_levels(&self) -> impl Iterator<Item = (f64, f64)> + '_ {
+        self.asks.iter().map(|(&p, &s)| self.level(p, s))
+    }
+
+    fn level(&self, ticks: i64, lots: u64) -> (f64, f64) {
+        (
+            self.scale.ticks_to_price(ticks),
+            self.scale.lots_to_size(lots),
+        )
+    }
 }
 
 // ---------------------------------------------------------------------------
@@ -87,12 +261,20 @@ pub struct OrderbookDepth {
 /// insert/remove and free sorted iteration. This replaces the TypeScript
 /// `Map` + `sortedPrices` array approach, eliminating the O(n log n)
 /// rebuild on every structural change.
+///
+/// Levels are keyed by integer price ticks and hold integer size lots.
 #[derive(Clone, Debug)]
 pub struct OrderbookSide {
-    levels: BTreeMap<OrderedFloat<f64>, f64>,
+    levels: BTreeMap<i64, u64>,
     /// `true` for the ask side, `false` for the bid side.
     /// Determines which end is "best" and which end gets trimmed.
     is_ask: bool,
+    scale: TickScale,
+    /// Levels retained; worse levels are trimmed.
+    max_levels: usize,
+    /// Net `(old_lots, new_lots)` per tick since the last
+    /// [`take_changes`](Self::take_changes), when tracking is enabled.
+    changes: Option<BTreeMap<i64, (u64, u64)>>,
 }
 
 impl OrderbookSide {
@@ -102,28 +284,48 @@ impl OrderbookSide {
     ///
     /// * `is_ask` - `true` for the ask side (best = lowest price),
     ///   `false` for the bid side (best = highest price).
-    pub fn new(is_ask: bool) -> Self {
+    /// * `scale` - Tick/lot scale of the market.
+    pub fn new(is_ask: bool, scale: TickScale) -> Self {
         Self {
             levels: BTreeMap::new(),
             is_ask,
+            scale,
+            max_levels: DEFAULT_MAX_LEVELS,
+            changes: None,
         }
     }
 
-    /// Apply incremental delta updates. An entry with `size == 0.0` removes
-    /// that price level; otherwise the level is inserted or updated.
-    /// Trims to [`MAX_LEVELS`] afterwards.
+    /// Record every level change for [`take_changes`](Self::take_changes).
+    pub(crate) fn with_change_tracking(mut self) -> Self {
+        self.changes = Some(BTreeMap::new());
+        self
+    }
+
+    /// Retain at most `max_levels` levels (`usize::MAX` keeps the full
+    /// side). Defaults to 100.
+    pub fn with_max_levels(mut self, max_levels: usize) -> Self {
+        self.max_levels = max_levels;
+        self.trim();
+        self
+    }
+
+    /// Apply incremental delta updates. An entry whose size rounds to zero
+    /// lots removes that price level; otherwise the level is inserted or
+    /// updated. Trims to the retention limit afterwards.
     ///
     /// # Arguments
     ///
     /// * `entries` - Slice of orderbook entries to apply.
     pub fn apply_deltas(&mut self, entries: &[OrderbookEntry]) {
         for entry in entries {
-            let key = OrderedFloat(entry.price);
-            if entry.size == 0.0 {
-                self.levels.remove(&key);
+            let ticks = self.scale.price_to_ticks(entry.price);
+            let lots = self.scale.size_to_lots(entry.size);
+            let old = if lots == 0 {
+                self.levels.remove(&ticks)
             } else {
-                self.levels.insert(key, entry.size);
-            }
+                self.levels.insert(ticks, lots)
+            };
+            self.record(ticks, old.unwrap_or(0), lots);
         }
         self.trim();
     }
@@ -135,32 +337,55 @@ impl OrderbookSide {
     ///
     /// * `entries` - Slice of orderbook entries forming the new snapshot.
     pub fn set_snapshot(&mut self, entries: &[OrderbookEntry]) {
-        self.levels.clear();
+        self.clear();
         for entry in entries {
-            if entry.size > 0.0 {
-                self.levels.insert(OrderedFloat(entry.price), entry.size);
+            let lots = self.scale.size_to_lots(entry.size);
+            if lots > 0 {
+                let ticks = self.scale.price_to_ticks(entry.price);
+                let old = self.levels.insert(ticks, lots);
+                self.record(ticks, old.unwrap_or(0), lots);
             }
         }
         self.trim();
     }
 
-    /// Return the best (top-of-book) price, or `None` if the side is empty.
+    /// Return the best (top-of-book) price in ticks, or `None` if the side
+    /// is empty.
     ///
     /// - **Asks**: lowest price (first key in ascending BTreeMap).
     /// - **Bids**: highest price (last key in ascending BTreeMap).
-    pub fn get_best(&self) -> Option<f64> {
+    pub fn best_ticks(&self) -> Option<i64> {
         if self.is_ask {
             // BTreeMap is ascending; first key = lowest price = best ask.
-            self.levels.keys().next().map(|k| k.0)
+            self.levels.keys().next().copied()
         } else {
             // Last key = highest price = best bid.
-            self.levels.keys().next_back().map(|k| k.0)
+            self.levels.keys().next_back().copied()
         }
     }
 
+    /// Return the best (top-of-book) price, or `None` if the side is empty.
+    pub fn get_best(&self) -> Option<f64> {
+        self.best_ticks().map(|t| self.scale.ticks_to_price(t))
+    }
+
+    /// Size resting at `price`, or `None` if there is no such level.
+    pub fn size_at(&self, price: f64) -> Option<f64> {
+        self.levels
+            .get(&self.scale.price_to_ticks(price))
+            .map(|&lots| self.scale.lots_to_size(lots))
+    }
+
+    /// Tick/lot scale of this side.
+    pub fn scale(&self) -> TickScale {
+        self.scale
+    }
+
     /// Remove all levels.
     pub fn clear(&mut self) {
-        self.levels.clear();
+        for (ticks, lots) in std::mem::take(&mut self.levels) {
+            self.record(ticks, lots, 0);
+        }
     }
 
     /// Number of price levels on this side.
@@ -173,26 +398,49 @@ impl OrderbookSide {
         self.levels.is_empty()
     }
 
-    /// Clone of all levels (price -> size).
-    pub fn get_levels(&self) -> BTreeMap<OrderedFloat<f64>, f64> {
+    /// Clone of all levels (price ticks -> size lots).
+    pub fn get_levels(&self) -> BTreeMap<i64, u64> {
         self.levels.clone()
     }
 
-    /// Trim to `MAX_LEVELS` by removing the worst prices.
+    /// Trim to `max_levels` by removing the worst prices.
     ///
     /// - **Asks**: remove the *highest* prices (worst asks).
     /// - **Bids**: remove the *lowest* prices (worst bids).
     fn trim(&mut self) {
-        while self.levels.len() > MAX_LEVELS {
-            if self.is_ask {
+        while self.levels.len() > self.max_levels {
+            let removed = if self.is_ask {
                 // Remove highest (worst ask).
-                self.levels.pop_last();
+                self.levels.pop_last()
             } else {
                 // Remove lowest (worst bid).
-                self.levels.pop_first();
+                self.levels.pop_first()
+            };
+            if let Some((ticks, lots)) = removed {
+                self.record(ticks, lots, 0);
             }
         }
     }
+
+    /// Fold a change into the pending set, keeping the first `old` and the
+    /// latest `new` for each tick.
+    fn record(&mut self, ticks: i64, old: u64, new: u64) {
+        if let Some(changes) = self.changes.as_mut() {
+            changes.entry(ticks).or_insert((old, old)).1 = new;
+        }
+    }
+
+    /// Drain net changes since the last call as `(ticks, old_lots,
+    /// new_lots)`, skipping levels that ended where they started.
+    pub(crate) fn take_changes(&mut self) -> impl Iterator<Item = (i64, u64, u64)> {
+        self.changes
+            .as_mut()
+            .map(std::mem::take)
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|(_, (old, new))| old != new)
+            .map(|(ticks, (old, new))| (ticks, old, new))
+    }
 }
 
 // ---------------------------------------------------------------------------
@@ -206,18 +454,44 @@ struct OrderbookInner {
     last_update_id: u64,
     last_update_time: u64,
     snapshot_loaded: bool,
-    delta_buffer: Vec<WebSocketDeltaUpdate>,
+    delta_buffer: Vec<Arc<WebSocketDeltaUpdate>>,
+    /// Time source for update and price timestamps (replay-aware).
+    clock: Clock,
+    config: OrderbookConfig,
+    /// Health as of the last emit.
+    health: BookHealth,
+    /// Sequence number of the last published [`LevelChanges`] batch.
+    seq: u64,
 }
 
 impl OrderbookInner {
-    fn new() -> Self {
+    fn new(scale: TickScale, config: OrderbookConfig, clock: Clock) -> Self {
         Self {
-            bids: OrderbookSide::new(false),
-            asks: OrderbookSide::new(true),
+            bids: OrderbookSide::new(false, scale)
+                .with_max_levels(config.max_levels)
+                .with_change_tracking(),
+            asks: OrderbookSide::new(true, scale)
+                .with_max_levels(config.max_levels)
+                .with_change_tracking(),
             last_update_id: 0,
             last_update_time: 0,
             snapshot_loaded: false,
             delta_buffer: Vec::new(),
+            clock,
+            config,
+            health: BookHealth::Empty,
+            seq: 0,
+        }
+    }
+
+    /// Copy of the current book, tagged with the last published sequence
+    /// number.
+    fn depth(&self) -> OrderbookDepth {
+        OrderbookDepth {
+            bids: self.bids.get_levels(),
+            asks: self.asks.get_levels(),
+            scale: self.bids.scale(),
+            seq: self.seq,
         }
     }
 
@@ -229,6 +503,175 @@ impl OrderbookInner {
         self.last_update_time = 0;
         self.snapshot_loaded = false;
         self.delta_buffer.clear();
+        self.health = BookHealth::Empty;
+    }
+
+    /// Describe a locked/crossed episode that has outlived the configured
+    /// grace period.
+    fn health_grace_expired(&self) -> Option<String> {
+        let since = self.health.unhealthy_since()?;
+        let elapsed = self.clock.now_ms().saturating_sub(since);
+        (elapsed >= self.config.unhealthy_grace_ms)
+            .then(|| format!("{:?} for {elapsed}ms", self.health))
+    }
+}
+
+/// Channels the background task publishes to after every update.
+struct Publishers {
+    price_tx: watch::Sender<Option<MidPrice>>,
+    levels_tx: broadcast::Sender<LevelChanges>,
+    signals_tx: watch::Sender<Option<BookSignals>>,
+    audit_tx: watch::Sender<AuditStats>,
+    health_tx: watch::Sender<BookHealth>,
+}
+
+/// Requests for an on-demand [`OrderbookDepth`] copy.
+pub(crate) type DepthRequests = mpsc::UnboundedReceiver<oneshot::Sender<OrderbookDepth>>;
+
+impl Publishers {
+    /// Create every channel, returning the senders, the matching handle and
+    /// the receiving end of depth requests.
+    fn channel(symbol: &str) -> (Self, OrderbookHandle, DepthRequests) {
+        let (price_tx, price_rx) = watch::channel(None);
+        let (levels_tx, _) = broadcast::channel(LEVEL_CHANNEL_CAPACITY);
+        let levels_weak = levels_tx.downgrade();
+        let (depth_req_tx, depth_req_rx) = mpsc::unbounded_channel();
+        let (signals_tx, signals_rx) = watch::channel(None);
+        let (audit_tx, audit_rx) = watch::channel(AuditStats::default());
+        let (health_tx, health_rx) = watch::channel(BookHealth::Empty);
+        let publishers = Self {
+            price_tx,
+            levels_tx,
+            signals_tx,
+            audit_tx,
+            health_tx,
+        };
+        let handle = OrderbookHandle {
+            symbol: symbol.to_string(),
+            price_rx,
+            levels_tx: levels_weak,
+            depth_req_tx,
+            signals_rx,
+            audit_rx,
+            health_rx,
+        };
+        (publishers, handle, depth_req_rx)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// OrderbookHandle
+// ---------------------------------------------------------------------------
+
+/// Cloneable read side of one live book: the latest price, signals, health
+/// and audit counters, plus `watch` receivers for each, level-change
+/// subscriptions and on-demand depth copies.
+///
+/// Obtained from [`OrderbookStream::handle`] or
+/// [`OrderbookManager::add_market`](crate::OrderbookManager::add_market).
+/// Handles stay valid after the book is closed; they simply stop updating.
+#[derive(Clone)]
+pub struct OrderbookHandle {
+    symbol: String,
+    price_rx: watch::Receiver<Option<MidPrice>>,
+    /// Weak so that handles do not keep the channel open after the task
+    /// exits.
+    levels_tx: broadcast::WeakSender<LevelChanges>,
+    depth_req_tx: mpsc::UnboundedSender<oneshot::Sender<OrderbookDepth>>,
+    signals_rx: watch::Receiver<Option<BookSignals>>,
+    audit_rx: watch::Receiver<AuditStats>,
+    health_rx: watch::Receiver<BookHealth>,
+}
+
+impl OrderbookHandle {
+    /// Market symbol of this book.
+    pub fn symbol(&self) -> &str {
+        &self.symbol
+    }
+
+    /// Latest computed mid-price, or `None` while either side is empty.
+    pub fn get_mid_price(&self) -> Option<MidPrice> {
+        *self.price_rx.borrow()
+    }
+
+    /// Latest exact best-bid-offer, or `None` while either side is empty.
+    ///
+    /// Read from the published signals, which are refreshed from the book
+    /// on every emit and cleared as soon as a side empties, so this never
+    /// outlives the levels it was derived from.
+    pub fn get_bbo(&self) -> Option<BBO> {
+        self.signals_rx.borrow().as_ref().map(|signals| signals.bbo)
+    }
+
+    /// Clone a `watch::Receiver` for async price consumption.
+    /// Call `.changed().await` to wait for the next update, then
+    /// `.borrow()` to read the value.
+    pub fn subscribe_price(&self) -> watch::Receiver<Option<MidPrice>> {
+        self.price_rx.clone()
+    }
+
+    /// Latest derived signals (microprice, imbalance, depth bands, fill
+    /// estimates), or `None` while either side is empty.
+    pub fn get_signals(&self) -> Option<BookSignals> {
+        self.signals_rx.borrow().clone()
+    }
+
+    /// Clone a `watch::Receiver` for async signal consumption.
+    pub fn subscribe_signals(&self) -> watch::Receiver<Option<BookSignals>> {
+        self.signals_rx.clone()
+    }
+
+    /// Integrity audit counters (all zero unless
+    /// [`OrderbookConfig::audit`] is set).
+    pub fn get_audit_stats(&self) -> AuditStats {
+        self.audit_rx.borrow().clone()
+    }
+
+    /// Clone a `watch::Receiver` that changes after every audit.
+    pub fn subscribe_audit(&self) -> watch::Receiver<AuditStats> {
+        self.audit_rx.clone()
+    }
+
+    /// Current book health. Do not quote unless
+    /// [`BookHealth::is_healthy`].
+    pub fn get_health(&self) -> BookHealth {
+        *self.health_rx.borrow()
+    }
+
+    /// Clone a `watch::Receiver` that changes when the health changes.
+    pub fn subscribe_health(&self) -> watch::Receiver<BookHealth> {
+        self.health_rx.clone()
+    }
+
+    /// Subscribe to per-level changes, one [`LevelChanges`] batch per book
+    /// update. Pair with [`get_depth`](Self::get_depth) (or use
+    /// [`DepthMirror`](crate::DepthMirror)) to maintain a full copy.
+    ///
+    /// The receiver reports `Closed` once the book has been closed.
+    pub fn subscribe_levels(&self) -> broadcast::Receiver<LevelChanges> {
+        match self.levels_tx.upgrade() {
+            Some(tx) => tx.subscribe(),
+            None => broadcast::channel(1).1,
+        }
+    }
+
+    /// Copy of the full book, built by the background task on request.
+    ///
+    /// Returns `None` if the book has been closed.
+    pub async fn get_depth(&self) -> Option<OrderbookDepth> {
+        let (reply_tx, reply_rx) = oneshot::channel();
+        self.depth_req_tx.send(reply_tx).ok()?;
+        reply_rx.await.ok()
+    }
+}
+
+#[cfg(test)]
+impl OrderbookHandle {
+    /// Handle with no background task: the test publishes level changes and
+    /// answers depth requests itself.
+    pub(crate) fn for_test(symbol: &str) -> (Self, broadcast::Sender<LevelChanges>, DepthRequests) {
+        let (publishers, handle, depth_requests) = Publishers::channel(symbol);
+        (handle, publishers.levels_tx, depth_requests)
     }
 }
 
@@ -240,20 +683,25 @@ impl OrderbookInner {
 ///
 /// Call [`OrderbookStream::new`] then [`OrderbookStream::connect`] to start.
 /// Read the latest price with [`OrderbookStream::get_mid_price`] /
-/// [`OrderbookStream::get_bbo`], or clone a `watch::Receiver` via
-/// [`OrderbookStream::subscribe_price`] / [`OrderbookStream::subscribe_depth`]
-/// for async consumption.
+/// [`OrderbookStream::get_bbo`] / [`OrderbookStream::get_signals`], or clone
+/// a `watch::Receiver` via [`OrderbookStream::subscribe_price`] /
+/// [`OrderbookStream::subscribe_signals`] for async consumption. Per-level
+/// changes are available from [`OrderbookStream::subscribe_levels`].
 pub struct OrderbookStream {
     symbol: String,
-    nord: Nord,
+    nord: Arc<Nord>,
+    config: OrderbookConfig,
     /// Receiver end of the broadcast channel for delta updates.
     /// `Some` before `connect()`, `None` after (moved into the task).
-    delta_rx: Option<broadcast::Receiver<WebSocketDeltaUpdate>>,
-    // Watch channels: background task sends, consumers borrow.
-    price_tx: watch::Sender<Option<MidPrice>>,
-    price_rx: watch::Receiver<Option<MidPrice>>,
-    depth_tx: watch::Sender<Option<OrderbookDepth>>,
-    depth_rx: watch::Receiver<Option<OrderbookDepth>>,
+    delta_rx: Option<broadcast::Receiver<Arc<WebSocketDeltaUpdate>>>,
+    // Channels the background task sends on; consumers read via `handle`.
+    // `Some` before `connect()`, `None` after (moved into the task).
+    publishers: Option<Publishers>,
+    handle: OrderbookHandle,
+    /// `Some` before `connect()`, `None` after (moved into the task).
+    depth_requests: Option<DepthRequests>,
+    /// Wakes the background task to discard the book and re-snapshot.
+    resync: Arc<Notify>,
     task_handle: Option<JoinHandle<()>>,
     shutdown_tx: Option<oneshot::Sender<()>>,
 }
@@ -271,19 +719,41 @@ impl OrderbookStream {
     pub fn new(
         symbol: String,
         nord: Nord,
-        delta_rx: broadcast::Receiver<WebSocketDeltaUpdate>,
+        delta_rx: broadcast::Receiver<Arc<WebSocketDeltaUpdate>>,
     ) -> Self {
-        let (price_tx, price_rx) = watch::channel(None);
-        let (depth_tx, depth_rx) = watch::channel(None);
+        Self::with_config(symbol, nord, delta_rx, OrderbookConfig::default())
+    }
+
+    /// Like [`new`](Self::new), with explicit depth retention and signal
+    /// settings.
+    pub fn with_config(
+        symbol: String,
+        nord: Nord,
+        delta_rx: broadcast::Receiver<Arc<WebSocketDeltaUpdate>>,
+        config: OrderbookConfig,
+    ) -> Self {
+        Self::with_shared(symbol, Arc::new(nord), delta_rx, config)
+    }
+
+    /// Like [`with_config`](Self::with_config), sharing one [`Nord`] client
+    /// between many streams (see [`OrderbookManager`](crate::OrderbookManager)).
+    pub(crate) fn with_shared(
+        symbol: String,
+        nord: Arc<Nord>,
+        delta_rx: broadcast::Receiver<Arc<WebSocketDeltaUpdate>>,
+        config: OrderbookConfig,
+    ) -> Self {
+        let (publishers, handle, depth_requests) = Publishers::channel(&symbol);
 
         Self {
             symbol,
             nord,
+            config,
             delta_rx: Some(delta_rx),
-            price_tx,
-            price_rx,
-            depth_tx,
-            depth_rx,
+            publishers: Some(publishers),
+            handle,
+            depth_requests: Some(depth_requests),
+            resync: Arc::new(Notify::new()),
             task_handle: None,
             shutdown_tx: None,
         }
@@ -299,10 +769,22 @@ impl OrderbookStream {
     /// Returns an error if the initial REST snapshot fetch fails or if
     /// `connect` has already been called (delta_rx consumed).
     pub async fn connect(&mut self) -> Result<()> {
+        let scale = self
+            .nord
+            .markets
+            .iter()
+            .find(|m| m.symbol == self.symbol)
+            .map(TickScale::from_market)
+            .ok_or_else(|| NordError::Validation(format!("unknown market {}", self.symbol)))?;
+
         let delta_rx = self
             .delta_rx
             .take()
             .expect("connect() called twice: delta_rx already consumed");
+        let depth_requests = self
+            .depth_requests
+            .take()
+            .expect("depth_requests already consumed");
 
         let (shutdown_tx, shutdown_rx) = oneshot::channel();
         self.shutdown_tx = Some(shutdown_tx);
@@ -312,18 +794,21 @@ impl OrderbookStream {
         let (ready_tx, ready_rx) = oneshot::channel::<Result<()>>();
 
         let symbol = self.symbol.clone();
-        let nord = self.nord.clone();
-        let price_tx = self.price_tx.clone();
-        let depth_tx = self.depth_tx.clone();
+        let nord = Arc::clone(&self.nord);
+        let resync = Arc::clone(&self.resync);
+        let inner = OrderbookInner::new(scale, self.config.clone(), nord.clock());
+        let publishers = self.publishers.take().expect("publishers already consumed");
 
         let handle = tokio::spawn(async move {
             run_background_task(
                 symbol,
+                inner,
                 nord,
                 delta_rx,
                 shutdown_rx,
-                price_tx,
-                depth_tx,
+                resync,
+                publishers,
+                depth_requests,
                 ready_tx,
             )
             .await;
@@ -335,35 +820,82 @@ impl OrderbookStream {
         match ready_rx.await {
             Ok(result) => result,
             // The task dropped ready_tx without sending -- treat as error.
-            Err(_) => Err(crate::error::NordError::WebSocket(
+            Err(_) => Err(NordError::WebSocket(
                 "orderbook background task exited before ready".into(),
             )),
         }
     }
 
-    /// Latest computed mid-price, or `None` if no valid BBO exists yet.
+    /// Cloneable read-only view of this book for other tasks.
+    pub fn handle(&self) -> OrderbookHandle {
+        self.handle.clone()
+    }
+
+    /// Latest computed mid-price, or `None` while either side is empty.
     pub fn get_mid_price(&self) -> Option<MidPrice> {
-        *self.price_rx.borrow()
+        self.handle.get_mid_price()
     }
 
-    /// Latest best-bid-offer, or `None` if no valid BBO exists yet.
+    /// Latest exact best-bid-offer, or `None` while either side is empty.
     pub fn get_bbo(&self) -> Option<BBO> {
-        self.get_mid_price().map(|p| BBO {
-            best_bid: p.bid,
-            best_ask: p.ask,
-        })
+        self.handle.get_bbo()
     }
 
     /// Clone a `watch::Receiver` for async price consumption.
     /// Call `.changed().await` to wait for the next update, then
     /// `.borrow()` to read the value.
     pub fn subscribe_price(&self) -> watch::Receiver<Option<MidPrice>> {
-        self.price_rx.clone()
+        self.handle.subscribe_price()
+    }
+
+    /// Latest derived signals (microprice, imbalance, depth bands, fill
+    /// estimates), or `None` while either side is empty.
+    pub fn get_signals(&self) -> Option<BookSignals> {
+        self.handle.get_signals()
+    }
+
+    /// Clone a `watch::Receiver` for async signal consumption.
+    pub fn subscribe_signals(&self) -> watch::Receiver<Option<BookSignals>> {
+        self.handle.subscribe_signals()
+    }
+
+    /// Integrity audit counters (all zero unless
+    /// [`OrderbookConfig::audit`] is set).
+    pub fn get_audit_stats(&self) -> AuditStats {
+        self.handle.get_audit_stats()
+    }
+
+    /// Clone a `watch::Receiver` that changes after every audit.
+    pub fn subscribe_audit(&self) -> watch::Receiver<AuditStats> {
+        self.handle.subscribe_audit()
+    }
+
+    /// Current book health. Do not quote unless
+    /// [`BookHealth::is_healthy`].
+    pub fn get_health(&self) -> BookHealth {
+        self.handle.get_health()
     }
 
-    /// Clone a `watch::Receiver` for async depth consumption.
-    pub fn subscribe_depth(&self) -> watch::Receiver<Option<OrderbookDepth>> {
-        self.depth_rx.clone()
+    /// Clone a `watch::Receiver` that changes when the health changes.
+    pub fn subscribe_health(&self) -> watch::Receiver<BookHealth> {
+        self.handle.subscribe_health()
+    }
+
+    /// Subscribe to per-level changes (see
+    /// [`OrderbookHandle::subscribe_levels`]).
+    pub fn subscribe_levels(&self) -> broadcast::Receiver<LevelChanges> {
+        self.handle.subscribe_levels()
+    }
+
+    /// Copy of the full book, built on request.
+    pub async fn get_depth(&self) -> Option<OrderbookDepth> {
+        self.handle.get_depth().await
+    }
+
+    /// Notifier that makes the background task discard the book and
+    /// re-fetch a REST snapshot (e.g. after an upstream router lagged).
+    pub(crate) fn resync_notify(&self) -> Arc<Notify> {
+        Arc::clone(&self.resync)
     }
 
     /// Shut down the background task and release resources.
@@ -388,17 +920,18 @@ impl Drop for OrderbookStream {
 // ---------------------------------------------------------------------------
 
 /// The long-running background task that owns all mutable orderbook state.
+#[allow(clippy::too_many_arguments)]
 async fn run_background_task(
     symbol: String,
-    nord: Nord,
-    mut delta_rx: broadcast::Receiver<WebSocketDeltaUpdate>,
+    mut inner: OrderbookInner,
+    nord: Arc<Nord>,
+    mut delta_rx: broadcast::Receiver<Arc<WebSocketDeltaUpdate>>,
     mut shutdown_rx: oneshot::Receiver<()>,
-    price_tx: watch::Sender<Option<MidPrice>>,
-    depth_tx: watch::Sender<Option<OrderbookDepth>>,
+    resync: Arc<Notify>,
+    publishers: Publishers,
+    mut depth_requests: DepthRequests,
     ready_tx: oneshot::Sender<Result<()>>,
 ) {
-    let mut inner = OrderbookInner::new();
-
     // 1. Fetch initial REST snapshot.
     if let Err(e) = fetch_snapshot(&nord, &symbol, &mut inner).await {
         error!("initial orderbook snapshot failed for {symbol}: {e}");
@@ -410,7 +943,7 @@ async fn run_background_task(
     drain_buffered_deltas(&mut delta_rx, &mut inner);
 
     // 3. Emit initial price.
-    emit_price(&inner, &price_tx, &depth_tx);
+    emit(&mut inner, &publishers);
 
     info!(
         "orderbook active for {symbol} ({} bids, {} asks, update_id={})",
@@ -425,35 +958,96 @@ async fn run_background_task(
     // 4. Main event loop.
     let mut stale_interval =
         tokio::time::interval(std::time::Duration::from_millis(STALE_CHECK_INTERVAL_MS));
+    let mut health_interval =
+        tokio::time::interval(std::time::Duration::from_millis(HEALTH_CHECK_INTERVAL_MS));
+
+    // Optional integrity audit. The first tick fires immediately, right
+    // after the initial snapshot, so skip it.
+    let mut audit_interval = inner
+        .config
+        .audit
+        .map(|audit| tokio::time::interval(std::time::Duration::from_millis(audit.interval_ms)));
+    if let Some(interval) = audit_interval.as_mut() {
+        interval.tick().await;
+    }
+    let mut audit_stats = AuditStats::default();
+    // REST fetch of the next audit snapshot, run off the delta path so that
+    // the round trip does not stall delta processing.
+    let mut audit_fetch: Option<JoinHandle<Result<OrderbookInfo>>> = None;
+    // Snapshot waiting for the local book to reach its update_id.
+    let mut pending_audit: Option<OrderbookInfo> = None;
 
     loop {
-        tokio::select! {
+        // Each arm yields the reason to re-snapshot, if any.
+        let resync: Option<String> = tokio::select! {
             _ = &mut shutdown_rx => {
                 debug!("orderbook shutdown for {symbol}");
                 break;
             }
-            _ = stale_interval.tick() => {
-                let now = epoch_millis();
-                if inner.last_update_time > 0
-                    && now.saturating_sub(inner.last_update_time) > STALE_THRESHOLD_MS
-                {
-                    warn!(
-                        "orderbook stale for {symbol} ({}ms since last update), re-fetching",
-                        now.saturating_sub(inner.last_update_time),
-                    );
-                    inner.reset();
-                    if let Err(e) = fetch_snapshot(&nord, &symbol, &mut inner).await {
-                        error!("snapshot re-fetch failed for {symbol}: {e}");
-                        continue;
+            _ = resync.notified() => Some("resync requested".into()),
+            _ = async {
+                match audit_interval.as_mut() {
+                    Some(interval) => interval.tick().await,
+                    None => std::future::pending().await,
+                }
+            } => {
+                if audit_fetch.is_some() || pending_audit.take().is_some() {
+                    audit_stats.skipped += 1;
+                    let _ = publishers.audit_tx.send(audit_stats.clone());
+                }
+                if audit_fetch.is_none() {
+                    let (nord, symbol) = (nord.clone(), symbol.clone());
+                    audit_fetch = Some(tokio::spawn(async move {
+                        nord.get_orderbook_by_symbol(&symbol).await
+                    }));
+                }
+                None
+            }
+            joined = async {
+                match audit_fetch.as_mut() {
+                    Some(fetch) => fetch.await,
+                    None => std::future::pending().await,
+                }
+            } => {
+                audit_fetch = None;
+                match joined {
+                    Ok(Ok(info)) if info.update_id < inner.last_update_id => {
+                        debug!(
+                            "audit snapshot for {symbol} is behind the book ({} < {}), skipping",
+                            info.update_id, inner.last_update_id,
+                        );
+                        audit_stats.skipped += 1;
+                    }
+                    Ok(Ok(info)) if info.update_id == inner.last_update_id => {
+                        run_audit(&symbol, &mut inner, &info, &mut audit_stats, &publishers);
+                    }
+                    Ok(Ok(info)) => pending_audit = Some(info),
+                    Ok(Err(e)) => {
+                        warn!("audit snapshot fetch failed for {symbol}: {e}");
+                        audit_stats.failed += 1;
+                    }
+                    Err(e) => {
+                        warn!("audit snapshot task failed for {symbol}: {e}");
+                        audit_stats.failed += 1;
                     }
-                    drain_buffered_deltas(&mut delta_rx, &mut inner);
-                    emit_price(&inner, &price_tx, &depth_tx);
                 }
+                let _ = publishers.audit_tx.send(audit_stats.clone());
+                None
+            }
+            _ = health_interval.tick() => None,
+            Some(reply) = depth_requests.recv() => {
+                let _ = reply.send(inner.depth());
+                None
+            }
+            _ = stale_interval.tick() => {
+                let idle = inner.clock.now_ms().saturating_sub(inner.last_update_time);
+                (inner.last_update_time > 0 && idle > STALE_THRESHOLD_MS)
+                    .then(|| format!("stale ({idle}ms since last update)"))
             }
             result = delta_rx.recv() => {
                 match result {
                     Ok(update) => {
-                        inner.last_update_time = epoch_millis();
+                        inner.last_update_time = inner.clock.now_ms();
 
                         if !inner.snapshot_loaded {
                             inner.delta_buffer.push(update);
@@ -470,20 +1064,22 @@ async fn run_background_task(
                             continue;
                         }
 
+                        // The book now reflects every delta up to the
+                        // pending snapshot's update_id: compare before
+                        // moving past it.
+                        if let Some(info) = pending_audit
+                            .take_if(|info| update.update_id > info.update_id)
+                        {
+                            run_audit(&symbol, &mut inner, &info, &mut audit_stats, &publishers);
+                            let _ = publishers.audit_tx.send(audit_stats.clone());
+                        }
+
                         handle_update(&mut inner, &update);
-                        emit_price(&inner, &price_tx, &depth_tx);
+                        emit(&mut inner, &publishers);
+                        None
                     }
                     Err(broadcast::error::RecvError::Lagged(n)) => {
-                        warn!(
-                            "orderbook delta lagged by {n} for {symbol}, re-fetching snapshot"
-                        );
-                        inner.reset();
-                        if let Err(e) = fetch_snapshot(&nord, &symbol, &mut inner).await {
-                            error!("snapshot re-fetch after lag failed for {symbol}: {e}");
-                            continue;
-                        }
-                        drain_buffered_deltas(&mut delta_rx, &mut inner);
-                        emit_price(&inner, &price_tx, &depth_tx);
+                        Some(format!("delta channel lagged by {n}"))
                     }
                     Err(broadcast::error::RecvError::Closed) => {
                         info!("delta channel closed for {symbol}, exiting");
@@ -491,65 +1087,131 @@ async fn run_background_task(
                     }
                 }
             }
+        };
+
+        // A crossed or locked book that does not resolve itself is corrupt.
+        let resync = resync.or_else(|| inner.health_grace_expired());
+
+        if let Some(reason) = resync {
+            warn!("orderbook {reason} for {symbol}, re-fetching snapshot");
+            inner.reset();
+            if pending_audit.take().is_some() {
+                audit_stats.skipped += 1;
+                let _ = publishers.audit_tx.send(audit_stats.clone());
+            }
+            if let Err(e) = fetch_snapshot(&nord, &symbol, &mut inner).await {
+                error!("snapshot re-fetch failed for {symbol}: {e}");
+                continue;
+            }
+            drain_buffered_deltas(&mut delta_rx, &mut inner);
+            emit(&mut inner, &publishers);
         }
     }
+
+    if let Some(fetch) = audit_fetch {
+        fetch.abort();
+    }
 }
 
 // ---------------------------------------------------------------------------
 // Helper functions
 // ---------------------------------------------------------------------------
 
-/// Current Unix epoch in milliseconds.
-fn epoch_millis() -> u64 {
-    SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .unwrap_or_default()
-        .as_millis() as u64
-}
-
 /// Fetch a REST snapshot and populate `inner`.
 async fn fetch_snapshot(nord: &Nord, symbol: &str, inner: &mut OrderbookInner) -> Result<()> {
     debug!("fetching orderbook snapshot for {symbol}");
     let info = nord.get_orderbook_by_symbol(symbol).await?;
+    load_snapshot(inner, &info);
 
-    // Convert `[price, size]` arrays to `OrderbookEntry`.
-    let bid_entries: Vec<OrderbookEntry> = info
-        .bids
-        .iter()
-        .map(|pair| OrderbookEntry {
-            price: pair[0],
-            size: pair[1],
-        })
-        .collect();
-    let ask_entries: Vec<OrderbookEntry> = info
-        .asks
-        .iter()
-        .map(|pair| OrderbookEntry {
-            price: pair[0],
-            size: pair[1],
-        })
-        .collect();
+    info!(
+        "orderbook snapshot loaded for {symbol} (update_id={}, {} bids, {} asks)",
+        info.update_id,
+        inner.bids.len(),
+        inner.asks.len(),
+    );
 
+    Ok(())
+}
+
+/// Replace both sides of `inner` with a REST snapshot.
+fn load_snapshot(inner: &mut OrderbookInner, info: &OrderbookInfo) {
+    let (bid_entries, ask_entries) = snapshot_entries(info);
     inner.bids.set_snapshot(&bid_entries);
     inner.asks.set_snapshot(&ask_entries);
     inner.last_update_id = info.update_id;
-    inner.last_update_time = epoch_millis();
+    inner.last_update_time = inner.clock.now_ms();
     inner.snapshot_loaded = true;
+}
 
-    info!(
-        "orderbook snapshot loaded for {symbol} (update_id={}, {} bids, {} asks)",
+/// Convert `[price, size]` arrays to `OrderbookEntry` (bids, asks).
+fn snapshot_entries(info: &OrderbookInfo) -> (Vec<OrderbookEntry>, Vec<OrderbookEntry>) {
+    let convert = |pairs: &[[f64; 2]]| {
+        pairs
+            .iter()
+            .map(|pair| OrderbookEntry {
+                price: pair[0],
+                size: pair[1],
+            })
+            .collect()
+    };
+    (convert(&info.bids), convert(&info.asks))
+}
+
+/// Compare the local book with a snapshot at the same `update_id`, record
+/// the result, and reload the book from the snapshot if they diverge.
+fn run_audit(
+    symbol: &str,
+    inner: &mut OrderbookInner,
+    info: &OrderbookInfo,
+    stats: &mut AuditStats,
+    publishers: &Publishers,
+) {
+    let Some(audit) = inner.config.audit else {
+        return;
+    };
+    let scale = inner.bids.scale();
+
+    // Build the exchange book at full depth on the same tick grid.
+    let (bid_entries, ask_entries) = snapshot_entries(info);
+    let mut exchange_bids = OrderbookSide::new(false, scale).with_max_levels(usize::MAX);
+    let mut exchange_asks = OrderbookSide::new(true, scale).with_max_levels(usize::MAX);
+    exchange_bids.set_snapshot(&bid_entries);
+    exchange_asks.set_snapshot(&ask_entries);
+
+    let report = compare_books(
+        &inner.bids.levels,
+        &inner.asks.levels,
+        &exchange_bids.levels,
+        &exchange_asks.levels,
+        scale,
+        audit.levels.min(inner.config.max_levels),
         info.update_id,
-        inner.bids.len(),
-        inner.asks.len(),
+        inner.clock.now_ms(),
     );
 
-    Ok(())
+    if report.is_clean() {
+        debug!(
+            "orderbook audit clean for {symbol} (update_id={}, {} levels)",
+            report.update_id, report.levels_checked,
+        );
+    } else {
+        warn!(
+            "orderbook audit for {symbol} found {} divergent levels at update_id={} \
+             (first: {:?}), reloading snapshot",
+            report.divergent_levels,
+            report.update_id,
+            report.divergences.first(),
+        );
+        load_snapshot(inner, info);
+        emit(inner, publishers);
+    }
+    stats.record(report);
 }
 
 /// Drain any buffered deltas from the broadcast channel and apply those
 /// whose `update_id` is newer than the current snapshot.
 fn drain_buffered_deltas(
-    delta_rx: &mut broadcast::Receiver<WebSocketDeltaUpdate>,
+    delta_rx: &mut broadcast::Receiver<Arc<WebSocketDeltaUpdate>>,
     inner: &mut OrderbookInner,
 ) {
     let mut applied = 0u64;
@@ -599,31 +1261,88 @@ fn handle_update(inner: &mut OrderbookInner, update: &WebSocketDeltaUpdate) {
     inner.last_update_id = update.update_id;
 }
 
-/// Compute mid-price from best bid/ask and send to watch channels.
-fn emit_price(
-    inner: &OrderbookInner,
-    price_tx: &watch::Sender<Option<MidPrice>>,
-    depth_tx: &watch::Sender<Option<OrderbookDepth>>,
-) {
-    let best_bid = inner.bids.get_best();
-    let best_ask = inner.asks.get_best();
-
-    if let (Some(bid), Some(ask)) = (best_bid, best_ask) {
-        let mid = (bid + ask) / 2.0;
-        let price = MidPrice {
-            mid,
-            bid,
-            ask,
-            timestamp: epoch_millis(),
-        };
-        let _ = price_tx.send(Some(price));
+/// Assess book health, compute mid-price and signals from best bid/ask and
+/// send them to the watch channels, then publish the level changes since the
+/// previous emit.
+///
+/// Health is published first so a consumer woken by the price sees the
+/// matching health.
+fn emit(inner: &mut OrderbookInner, publishers: &Publishers) {
+    let scale = inner.bids.scale();
+    let now = inner.clock.now_ms();
+
+    let health = BookHealth::assess(
+        inner.bids.best_ticks(),
+        inner.asks.best_ticks(),
+        inner.health,
+        now,
+    );
+    if health != inner.health {
+        if health.unhealthy_since().is_some() && inner.health.unhealthy_since().is_none() {
+            warn!(
+                "orderbook {health:?}: bid {:?} ask {:?}",
+                inner.bids.get_best(),
+                inner.asks.get_best(),
+            );
+        } else if health.is_healthy() && inner.health.unhealthy_since().is_some() {
+            info!("orderbook recovered from {:?}", inner.health);
+        }
+        inner.health = health;
+        let _ = publishers.health_tx.send(health);
     }
 
-    // Always emit depth so consumers see the current state.
-    let _ = depth_tx.send(Some(OrderbookDepth {
-        bids: inner.bids.get_levels(),
-        asks: inner.asks.get_levels(),
-    }));
+    // With a side empty there is no BBO: publish `None` rather than leave
+    // consumers on the last one.
+    let price = match (inner.bids.best_ticks(), inner.asks.best_ticks()) {
+        (Some(bid), Some(ask)) => Some(MidPrice {
+            mid: mid_price(bid, ask, scale),
+            exact_mid: Some(scale.mid_to_decimal(bid, ask)),
+            bid: scale.ticks_to_price(bid),
+            ask: scale.ticks_to_price(ask),
+            timestamp: now,
+        }),
+        _ => None,
+    };
+    publish_latest(&publishers.price_tx, price);
+
+    let signals = BookSignals::compute(
+        &inner.bids.levels,
+        &inner.asks.levels,
+        scale,
+        &inner.config,
+        now,
+    );
+    publish_latest(&publishers.signals_tx, signals);
+
+    let update_id = inner.last_update_id;
+    let mut changes = Vec::new();
+    for (side, book) in [(Side::Bid, &mut inner.bids), (Side::Ask, &mut inner.asks)] {
+        changes.extend(book.take_changes().map(|(ticks, old, new)| LevelChange {
+            side,
+            price: scale.ticks_to_price(ticks),
+            old_size: scale.lots_to_size(old),
+            new_size: scale.lots_to_size(new),
+            update_id,
+        }));
+    }
+    if !changes.is_empty() {
+        inner.seq += 1;
+        let _ = publishers.levels_tx.send(LevelChanges {
+            seq: inner.seq,
+            update_id,
+            timestamp: now,
+            changes,
+        });
+    }
+}
+
+/// Publish `value`, without waking receivers if it stays `None`.
+fn publish_latest<T>(tx: &watch::Sender<Option<T>>, value: Option<T>) {
+    tx.send_if_modified(|current| {
+        let changed = current.is_some() || value.is_some();
+        *current = value;
+        changed
+    });
 }
 
 // ---------------------------------------------------------------------------
@@ -634,11 +1353,17 @@ fn emit_price(
 mod tests {
     use super::*;
 
+    /// Cent ticks, 1e-4 lots.
+    const SCALE: TickScale = TickScale {
+        price_decimals: 2,
+        size_decimals: 4,
+    };
+
     // -- OrderbookSide: ask side -----------------------------------------
 
     #[test]
     fn ask_best_is_lowest_price() {
-        let mut side = OrderbookSide::new(true);
+        let mut side = OrderbookSide::new(true, SCALE);
         side.apply_deltas(&[
             OrderbookEntry {
                 price: 105.0,
@@ -658,7 +1383,7 @@ mod tests {
 
     #[test]
     fn bid_best_is_highest_price() {
-        let mut side = OrderbookSide::new(false);
+        let mut side = OrderbookSide::new(false, SCALE);
         side.apply_deltas(&[
             OrderbookEntry {
                 price: 95.0,
@@ -680,7 +1405,7 @@ mod tests {
 
     #[test]
     fn apply_deltas_inserts_new_levels() {
-        let mut side = OrderbookSide::new(true);
+        let mut side = OrderbookSide::new(true, SCALE);
         assert_eq!(side.len(), 0);
 
         side.apply_deltas(&[
@@ -698,24 +1423,24 @@ mod tests {
 
     #[test]
     fn apply_deltas_updates_existing_level() {
-        let mut side = OrderbookSide::new(true);
+        let mut side = OrderbookSide::new(true, SCALE);
         side.apply_deltas(&[OrderbookEntry {
             price: 100.0,
             size: 5.0,
         }]);
-        assert_eq!(side.levels.get(&OrderedFloat(100.0)), Some(&5.0));
+        assert_eq!(side.size_at(100.0), Some(5.0));
 
         side.apply_deltas(&[OrderbookEntry {
             price: 100.0,
             size: 10.0,
         }]);
-        assert_eq!(side.levels.get(&OrderedFloat(100.0)), Some(&10.0));
+        assert_eq!(side.size_at(100.0), Some(10.0));
         assert_eq!(side.len(), 1);
     }
 
     #[test]
     fn apply_deltas_removes_on_zero_size() {
-        let mut side = OrderbookSide::new(true);
+        let mut side = OrderbookSide::new(true, SCALE);
         side.apply_deltas(&[
             OrderbookEntry {
                 price: 100.0,
@@ -733,14 +1458,14 @@ mod tests {
             size: 0.0,
         }]);
         assert_eq!(side.len(), 1);
-        assert!(side.levels.get(&OrderedFloat(100.0)).is_none());
+        assert_eq!(side.size_at(100.0), None);
     }
 
     // -- set_snapshot ----------------------------------------------------
 
     #[test]
     fn set_snapshot_replaces_all_levels() {
-        let mut side = OrderbookSide::new(true);
+        let mut side = OrderbookSide::new(true, SCALE);
         side.apply_deltas(&[
             OrderbookEntry {
                 price: 100.0,
@@ -763,7 +1488,7 @@ mod tests {
 
     #[test]
     fn set_snapshot_ignores_zero_size_entries() {
-        let mut side = OrderbookSide::new(true);
+        let mut side = OrderbookSide::new(true, SCALE);
         side.set_snapshot(&[
             OrderbookEntry {
                 price: 100.0,
@@ -779,16 +1504,16 @@ mod tests {
             },
         ]);
         assert_eq!(side.len(), 2);
-        assert!(side.levels.get(&OrderedFloat(101.0)).is_none());
+        assert_eq!(side.size_at(101.0), None);
     }
 
     // -- Trimming --------------------------------------------------------
 
     #[test]
     fn asks_trim_removes_highest_prices() {
-        let mut side = OrderbookSide::new(true);
-        // Insert MAX_LEVELS + 5 levels.
-        let entries: Vec<OrderbookEntry> = (0..MAX_LEVELS + 5)
+        let mut side = OrderbookSide::new(true, SCALE);
+        // Insert DEFAULT_MAX_LEVELS + 5 levels.
+        let entries: Vec<OrderbookEntry> = (0..DEFAULT_MAX_LEVELS + 5)
             .map(|i| OrderbookEntry {
                 price: 100.0 + i as f64,
                 size: 1.0,
@@ -796,14 +1521,14 @@ mod tests {
             .collect();
         side.apply_deltas(&entries);
 
-        assert_eq!(side.len(), MAX_LEVELS);
+        assert_eq!(side.len(), DEFAULT_MAX_LEVELS);
         // Best ask = lowest = 100.0 (still present).
         assert_eq!(side.get_best(), Some(100.0));
         // Highest 5 prices should have been removed.
         for i in 0..5 {
-            let price = 100.0 + (MAX_LEVELS + i) as f64;
+            let price = 100.0 + (DEFAULT_MAX_LEVELS + i) as f64;
             assert!(
-                side.levels.get(&OrderedFloat(price)).is_none(),
+                side.size_at(price).is_none(),
                 "price {price} should have been trimmed"
             );
         }
@@ -811,8 +1536,8 @@ mod tests {
 
     #[test]
     fn bids_trim_removes_lowest_prices() {
-        let mut side = OrderbookSide::new(false);
-        let entries: Vec<OrderbookEntry> = (0..MAX_LEVELS + 5)
+        let mut side = OrderbookSide::new(false, SCALE);
+        let entries: Vec<OrderbookEntry> = (0..DEFAULT_MAX_LEVELS + 5)
             .map(|i| OrderbookEntry {
                 price: 100.0 + i as f64,
                 size: 1.0,
@@ -820,15 +1545,15 @@ mod tests {
             .collect();
         side.apply_deltas(&entries);
 
-        assert_eq!(side.len(), MAX_LEVELS);
-        // Best bid = highest = 100.0 + (MAX_LEVELS + 4) (still present).
-        let highest = 100.0 + (MAX_LEVELS + 4) as f64;
+        assert_eq!(side.len(), DEFAULT_MAX_LEVELS);
+        // Best bid = highest = 100.0 + (DEFAULT_MAX_LEVELS + 4) (still present).
+        let highest = 100.0 + (DEFAULT_MAX_LEVELS + 4) as f64;
         assert_eq!(side.get_best(), Some(highest));
         // Lowest 5 prices should have been removed.
         for i in 0..5 {
             let price = 100.0 + i as f64;
             assert!(
-                side.levels.get(&OrderedFloat(price)).is_none(),
+                side.size_at(price).is_none(),
                 "price {price} should have been trimmed"
             );
         }
@@ -838,10 +1563,10 @@ mod tests {
 
     #[test]
     fn get_best_returns_none_when_empty() {
-        let ask_side = OrderbookSide::new(true);
+        let ask_side = OrderbookSide::new(true, SCALE);
         assert_eq!(ask_side.get_best(), None);
 
-        let bid_side = OrderbookSide::new(false);
+        let bid_side = OrderbookSide::new(false, SCALE);
         assert_eq!(bid_side.get_best(), None);
     }
 
@@ -849,7 +1574,7 @@ mod tests {
 
     #[test]
     fn clear_resets_everything() {
-        let mut side = OrderbookSide::new(true);
+        let mut side = OrderbookSide::new(true, SCALE);
         side.apply_deltas(&[
             OrderbookEntry {
                 price: 100.0,
@@ -872,7 +1597,7 @@ mod tests {
 
     #[test]
     fn len_tracks_count() {
-        let mut side = OrderbookSide::new(true);
+        let mut side = OrderbookSide::new(true, SCALE);
         assert_eq!(side.len(), 0);
         assert!(side.is_empty());
 
@@ -901,7 +1626,7 @@ mod tests {
 
     #[test]
     fn get_levels_returns_independent_clone() {
-        let mut side = OrderbookSide::new(true);
+        let mut side = OrderbookSide::new(true, SCALE);
         side.apply_deltas(&[
             OrderbookEntry {
                 price: 100.0,
@@ -915,11 +1640,312 @@ mod tests {
 
         let levels = side.get_levels();
         assert_eq!(levels.len(), 2);
-        assert_eq!(levels[&OrderedFloat(100.0)], 5.0);
-        assert_eq!(levels[&OrderedFloat(101.0)], 3.0);
+        assert_eq!(levels[&10_000], 50_000);
+        assert_eq!(levels[&10_100], 30_000);
 
         // Mutating the side does not affect the returned map.
         side.clear();
         assert_eq!(levels.len(), 2);
     }
+
+    // -- integer ticks ---------------------------------------------------
+
+    #[test]
+    fn float_noise_hits_the_same_level() {
+        let mut side = OrderbookSide::new(false, SCALE);
+        side.set_snapshot(&[OrderbookEntry {
+            price: 0.1 + 0.2,
+            size: 1.0,
+        }]);
+        // 0.30000000000000004 vs 0.3: one level, then removed.
+        side.apply_deltas(&[OrderbookEntry {
+            price: 0.3,
+            size: 2.0,
+        }]);
+        assert_eq!(side.len(), 1);
+        assert_eq!(side.size_at(0.3), Some(2.0));
+
+        side.apply_deltas(&[OrderbookEntry {
+            price: 0.30000000000000004,
+            size: 0.0,
+        }]);
+        assert!(side.is_empty());
+    }
+
+    #[test]
+    fn tick_scale_round_trips() {
+        assert_eq!(SCALE.price_to_ticks(50_000.01 + 1e-9), 5_000_001);
+        assert_eq!(SCALE.ticks_to_price(5_000_001), 50_000.01);
+        assert_eq!(SCALE.ticks_to_decimal(5_000_001).to_string(), "50000.01");
+        assert_eq!(
+            SCALE.mid_to_decimal(5_000_001, 5_000_002).to_string(),
+            "50000.015"
+        );
+        assert_eq!(SCALE.size_to_lots(0.00019999), 2);
+        assert_eq!(SCALE.lots_to_decimal(2).to_string(), "0.0002");
+        assert_eq!(SCALE.size_to_lots(-1.0), 0);
+    }
+
+    #[test]
+    fn depth_exposes_exact_bbo_and_levels() {
+        let mut inner = OrderbookInner::new(SCALE, OrderbookConfig::default(), Clock::manual(7));
+        inner.bids.set_snapshot(&[
+            OrderbookEntry {
+                price: 99.99,
+                size: 1.5,
+            },
+            OrderbookEntry {
+                price: 99.98,
+                size: 2.0,
+            },
+        ]);
+        inner.asks.set_snapshot(&[OrderbookEntry {
+            price: 100.01,
+            size: 0.25,
+        }]);
+
+        let (publishers, handle, _) = Publishers::channel("T");
+        emit(&mut inner, &publishers);
+        assert_eq!(handle.get_health(), BookHealth::Healthy);
+
+        let price = handle.get_mid_price().unwrap();
+        assert_eq!(price.mid, 100.0);
+        assert_eq!(price.exact_mid.unwrap().to_string(), "100");
+        assert_eq!(price.bid, 99.99);
+        assert_eq!(price.timestamp, 7);
+
+        let depth = inner.depth();
+        let bbo = depth.bbo().unwrap();
+        assert_eq!(bbo.best_bid.to_string(), "99.99");
+        assert_eq!(bbo.best_ask.to_string(), "100.01");
+        let bids: Vec<_> = depth.bid_levels().collect();
+        assert_eq!(bids, vec![(99.99, 1.5), (99.98, 2.0)]);
+
+        let signals = handle.get_signals().unwrap();
+        assert_eq!(signals.bbo, bbo);
+        assert_eq!(signals.spread_ticks, 2);
+        assert_eq!(signals.timestamp, 7);
+    }
+
+    #[test]
+    fn emit_clears_price_and_signals_when_a_side_empties() {
+        let mut inner = OrderbookInner::new(SCALE, OrderbookConfig::default(), Clock::manual(7));
+        inner.bids.set_snapshot(&[OrderbookEntry {
+            price: 99.99,
+            size: 1.0,
+        }]);
+        inner.asks.set_snapshot(&[OrderbookEntry {
+            price: 100.01,
+            size: 1.0,
+        }]);
+        let (publishers, handle, _) = Publishers::channel("T");
+        emit(&mut inner, &publishers);
+        assert!(handle.get_signals().is_some());
+
+        let mut price_rx = handle.subscribe_price();
+        inner.asks.clear();
+        emit(&mut inner, &publishers);
+        assert!(price_rx.has_changed().unwrap());
+        assert_eq!(*price_rx.borrow_and_update(), None);
+        assert!(handle.get_signals().is_none());
+        assert_eq!(handle.get_health(), BookHealth::Empty);
+
+        // Still empty: receivers are not woken again.
+        emit(&mut inner, &publishers);
+        assert!(!price_rx.has_changed().unwrap());
+    }
+
+    #[test]
+    fn bbo_follows_the_book_and_clears_when_a_side_empties() {
+        let mut inner = OrderbookInner::new(SCALE, OrderbookConfig::default(), Clock::manual(7));
+        let (publishers, handle, _) = Publishers::channel("T");
+        inner.bids.set_snapshot(&[OrderbookEntry {
+            price: 99.99,
+            size: 1.0,
+        }]);
+        inner.asks.set_snapshot(&[OrderbookEntry {
+            price: 100.01,
+            size: 1.0,
+        }]);
+        emit(&mut inner, &publishers);
+        let bbo = handle.get_bbo().unwrap();
+        assert_eq!(bbo.best_bid, Decimal::new(9999, 2));
+        assert_eq!(bbo.best_ask, Decimal::new(10001, 2));
+
+        inner.bids.set_snapshot(&[OrderbookEntry {
+            price: 99.98,
+            size: 1.0,
+        }]);
+        emit(&mut inner, &publishers);
+        assert_eq!(handle.get_bbo().unwrap().best_bid, Decimal::new(9998, 2));
+
+        inner.bids.clear();
+        emit(&mut inner, &publishers);
+        assert_eq!(handle.get_bbo(), None);
+    }
+
+    #[test]
+    fn emit_publishes_net_level_changes() {
+        let mut inner = OrderbookInner::new(SCALE, OrderbookConfig::default(), Clock::manual(7));
+        let (publishers, handle, _) = Publishers::channel("T");
+        let mut levels_rx = handle.subscribe_levels();
+
+        inner.bids.set_snapshot(&[OrderbookEntry {
+            price: 99.99,
+            size: 1.5,
+        }]);
+        inner.last_update_id = 5;
+        emit(&mut inner, &publishers);
+        let batch = levels_rx.try_recv().unwrap();
+        assert_eq!((batch.seq, batch.update_id, batch.timestamp), (1, 5, 7));
+        assert_eq!(
+            batch.changes,
+            vec![LevelChange {
+                side: Side::Bid,
+                price: 99.99,
+                old_size: 0.0,
+                new_size: 1.5,
+                update_id: 5,
+            }]
+        );
+
+        // Changes between emits collapse to one entry per level; a level
+        // that ends where it started is not reported.
+        inner.bids.apply_deltas(&[OrderbookEntry {
+            price: 99.99,
+            size: 2.0,
+        }]);
+        inner.bids.apply_deltas(&[OrderbookEntry {
+            price: 99.99,
+            size: 3.0,
+        }]);
+        inner.asks.apply_deltas(&[OrderbookEntry {
+            price: 100.01,
+            size: 1.0,
+        }]);
+        inner.asks.apply_deltas(&[OrderbookEntry {
+            price: 100.01,
+            size: 0.0,
+        }]);
+        inner.last_update_id = 6;
+        emit(&mut inner, &publishers);
+        let batch = levels_rx.try_recv().unwrap();
+        assert_eq!(batch.seq, 2);
+        assert_eq!(batch.changes.len(), 1);
+        assert_eq!(
+            (batch.changes[0].old_size, batch.changes[0].new_size),
+            (1.5, 3.0)
+        );
+
+        // Nothing changed: no batch, and the depth copy carries the last seq.
+        emit(&mut inner, &publishers);
+        assert!(levels_rx.try_recv().is_err());
+        assert_eq!(inner.depth().seq, 2);
+    }
+
+    #[test]
+    fn max_levels_is_configurable() {
+        let entries: Vec<OrderbookEntry> = (0..300)
+            .map(|i| OrderbookEntry {
+                price: 100.0 + i as f64,
+                size: 1.0,
+            })
+            .collect();
+        let mut side = OrderbookSide::new(true, SCALE).with_max_levels(usize::MAX);
+        side.apply_deltas(&entries);
+        assert_eq!(side.len(), 300);
+
+        let side = side.with_max_levels(10);
+        assert_eq!(side.len(), 10);
+        assert_eq!(side.get_best(), Some(100.0));
+    }
+
+    #[test]
+    fn audit_reloads_divergent_book() {
+        let config = OrderbookConfig {
+            audit: Some(crate::audit::AuditConfig::default()),
+            ..Default::default()
+        };
+        let mut inner = OrderbookInner::new(SCALE, config, Clock::manual(0));
+        let info = OrderbookInfo {
+            update_id: 10,
+            asks: vec![[100.01, 0.5]],
+            bids: vec![[99.99, 1.0], [99.98, 2.0]],
+            asks_summary: crate::types::SideSummary { sum: 0.5, count: 1 },
+            bids_summary: crate::types::SideSummary { sum: 3.0, count: 2 },
+        };
+        load_snapshot(&mut inner, &info);
+
+        let (publishers, _handle, _) = Publishers::channel("T");
+        let mut stats = AuditStats::default();
+
+        run_audit("T", &mut inner, &info, &mut stats, &publishers);
+        assert_eq!((stats.audits, stats.clean), (1, 1));
+
+        // A missed delta leaves a phantom level behind.
+        inner.bids.apply_deltas(&[OrderbookEntry {
+            price: 99.97,
+            size: 4.0,
+        }]);
+        run_audit("T", &mut inner, &info, &mut stats, &publishers);
+        assert_eq!(
+            (stats.audits, stats.diverged, stats.divergent_levels),
+            (2, 1, 1)
+        );
+        assert_eq!(inner.bids.size_at(99.97), None);
+        assert_eq!(inner.bids.len(), 2);
+    }
+
+    #[test]
+    fn crossed_book_is_unhealthy_until_grace_expires() {
+        let clock = Clock::manual(1_000);
+        let mut inner = OrderbookInner::new(SCALE, OrderbookConfig::default(), clock.clone());
+        let (publishers, handle, _) = Publishers::channel("T");
+
+        emit(&mut inner, &publishers);
+        assert_eq!(handle.get_health(), BookHealth::Empty);
+
+        inner.bids.set_snapshot(&[OrderbookEntry {
+            price: 100.02,
+            size: 1.0,
+        }]);
+        inner.asks.set_snapshot(&[OrderbookEntry {
+            price: 100.01,
+            size: 1.0,
+        }]);
+        emit(&mut inner, &publishers);
+        assert_eq!(handle.get_health(), BookHealth::Crossed { since_ms: 1_000 });
+        assert!(inner.health_grace_expired().is_none());
+
+        // Crossed -> locked keeps the episode start.
+        clock.set_ms(2_500);
+        inner.bids.apply_deltas(&[
+            OrderbookEntry {
+                price: 100.02,
+                size: 0.0,
+            },
+            OrderbookEntry {
+                price: 100.01,
+                size: 1.0,
+            },
+        ]);
+        emit(&mut inner, &publishers);
+        assert_eq!(handle.get_health(), BookHealth::Locked { since_ms: 1_000 });
+        assert!(!handle.get_health().is_healthy());
+
+        clock.set_ms(3_000);
+        assert!(inner.health_grace_expired().is_some());
+
+        inner.bids.apply_deltas(&[OrderbookEntry {
+            price: 100.01,
+            size: 0.0,
+        }]);
+        inner.bids.apply_deltas(&[OrderbookEntry {
+            price: 100.0,
+            size: 1.0,
+        }]);
+        emit(&mut inner, &publishers);
+        assert!(handle.get_health().is_healthy());
+        assert!(inner.health_grace_expired().is_none());
+    }
 }
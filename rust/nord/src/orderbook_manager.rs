@@ -0,0 +1,475 @@
+//! Live orderbooks for many markets fed by a single delta subscription.
+//!
+//! A standalone [`OrderbookStream`] receives every delta on the shared
+//! WebSocket broadcast and discards the ones for other markets. With N books
+//! that is N wake-ups per delta. [`OrderbookManager`] instead runs one router
+//! task that looks each delta up by symbol and forwards it to the owning
+//! book's private channel, and shares a single [`Nord`] client for all REST
+//! snapshots.
+//!
+//! # Architecture
+//!
+//! ```text
+//!   broadcast::Receiver<Arc<WebSocketDeltaUpdate>>   (one WS, all markets)
+//!              |
+//!              v
+//!   +------ router task (symbol -> route) ------+
+//!   |  - forwards each delta to one book        |
+//!   |  - drops deltas for unmanaged markets     |
+//!   |  - on lag, asks every book to re-snapshot |
+//!   +-------------------------------------------+
+//!        |                |                |
+//!        v                v                v
+//!   OrderbookStream  OrderbookStream  OrderbookStream   (per market)
+//!        |                |                |
+//!        v                v                v
+//!   OrderbookHandle  OrderbookHandle  OrderbookHandle   (watch receivers)
+//! ```
+//!
+//! Markets can be added and removed at runtime. The WebSocket subscription
+//! is fixed when the client connects, so it must already carry deltas for
+//! any market that may be added later (e.g. subscribe to every perp).
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use tokio::sync::{broadcast, mpsc, oneshot, Notify};
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+
+use crate::analytics::OrderbookConfig;
+use crate::client::Nord;
+use crate::error::Result;
+use crate::orderbook::{OrderbookHandle, OrderbookStream};
+use crate::ws::events::WebSocketDeltaUpdate;
+
+/// Capacity of each per-market delta channel (matches the WS dispatcher).
+const MARKET_CHANNEL_CAPACITY: usize = 256;
+
+/// Where the router sends deltas for one market.
+struct Route {
+    tx: broadcast::Sender<Arc<WebSocketDeltaUpdate>>,
+    resync: Arc<Notify>,
+}
+
+/// Route table changes sent from the manager to the router task.
+enum RouteCommand {
+    Add(String, Route),
+    Remove(String),
+}
+
+/// Maintains local orderbooks for a dynamic set of markets.
+///
+/// Call [`OrderbookManager::new`], [`OrderbookManager::connect`], then
+/// [`OrderbookManager::add_market`] for each market. Each market gets an
+/// [`OrderbookHandle`] with the same read API as [`OrderbookStream`].
+pub struct OrderbookManager {
+    nord: Arc<Nord>,
+    config: OrderbookConfig,
+    /// `Some` before `connect()`, `None` after (moved into the router).
+    delta_rx: Option<broadcast::Receiver<Arc<WebSocketDeltaUpdate>>>,
+    route_tx: mpsc::UnboundedSender<RouteCommand>,
+    /// `Some` before `connect()`, `None` after (moved into the router).
+    route_rx: Option<mpsc::UnboundedReceiver<RouteCommand>>,
+    books: HashMap<String, OrderbookStream>,
+    task_handle: Option<JoinHandle<()>>,
+    shutdown_tx: Option<oneshot::Sender<()>>,
+}
+
+impl OrderbookManager {
+    /// Create a manager. Call [`connect`](Self::connect) to start routing.
+    ///
+    /// # Arguments
+    ///
+    /// * `nord` - Initialised client shared by every book for REST
+    ///   snapshots.
+    /// * `delta_rx` - Broadcast receiver from the WebSocket client's
+    ///   `subscribe_deltas()` method.
+    /// * `config` - Retention, signal and audit settings applied to every
+    ///   book.
+    pub fn new(
+        nord: Arc<Nord>,
+        delta_rx: broadcast::Receiver<Arc<WebSocketDeltaUpdate>>,
+        config: OrderbookConfig,
+    ) -> Self {
+        let (route_tx, route_rx) = mpsc::unbounded_channel();
+        Self {
+            nord,
+            config,
+            delta_rx: Some(delta_rx),
+            route_tx,
+            route_rx: Some(route_rx),
+            books: HashMap::new(),
+            task_handle: None,
+            shutdown_tx: None,
+        }
+    }
+
+    /// Start the router task.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called twice.
+    pub fn connect(&mut self) {
+        let delta_rx = self
+            .delta_rx
+            .take()
+            .expect("connect() called twice: delta_rx already consumed");
+        let route_rx = self.route_rx.take().expect("route_rx already consumed");
+
+        let (shutdown_tx, shutdown_rx) = oneshot::channel();
+        self.shutdown_tx = Some(shutdown_tx);
+        self.task_handle = Some(tokio::spawn(run_router(delta_rx, route_rx, shutdown_rx)));
+    }
+
+    /// Start maintaining the book for `symbol` and return its handle. If the
+    /// market is already managed, returns the existing handle.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the market is unknown or the initial REST
+    /// snapshot fails; the market is not added in that case.
+    pub async fn add_market(&mut self, symbol: &str) -> Result<OrderbookHandle> {
+        if let Some(book) = self.books.get(symbol) {
+            return Ok(book.handle());
+        }
+
+        // Register the route before the snapshot request so deltas that
+        // arrive while it is in flight are buffered by the book.
+        let (tx, rx) = broadcast::channel(MARKET_CHANNEL_CAPACITY);
+        let mut book = OrderbookStream::with_shared(
+            symbol.to_string(),
+            Arc::clone(&self.nord),
+            rx,
+            self.config.clone(),
+        );
+        let route = Route {
+            tx,
+            resync: book.resync_notify(),
+        };
+        let _ = self
+            .route_tx
+            .send(RouteCommand::Add(symbol.to_string(), route));
+
+        if let Err(e) = book.connect().await {
+            let _ = self.route_tx.send(RouteCommand::Remove(symbol.to_string()));
+            return Err(e);
+        }
+
+        let handle = book.handle();
+        self.books.insert(symbol.to_string(), book);
+        info!(
+            market = symbol,
+            markets = self.books.len(),
+            "orderbook added"
+        );
+        Ok(handle)
+    }
+
+    /// Stop maintaining the book for `symbol`. Existing handles stop
+    /// updating. Returns `false` if the market was not managed.
+    pub fn remove_market(&mut self, symbol: &str) -> bool {
+        let Some(mut book) = self.books.remove(symbol) else {
+            return false;
+        };
+        let _ = self.route_tx.send(RouteCommand::Remove(symbol.to_string()));
+        book.close();
+        info!(
+            market = symbol,
+            markets = self.books.len(),
+            "orderbook removed"
+        );
+        true
+    }
+
+    /// Handle for a managed market.
+    pub fn handle(&self, symbol: &str) -> Option<OrderbookHandle> {
+        self.books.get(symbol).map(OrderbookStream::handle)
+    }
+
+    /// Symbols of all managed markets (unordered).
+    pub fn symbols(&self) -> impl Iterator<Item = &str> {
+        self.books.keys().map(String::as_str)
+    }
+
+    /// Number of managed markets.
+    pub fn len(&self) -> usize {
+        self.books.len()
+    }
+
+    /// Whether no markets are managed.
+    pub fn is_empty(&self) -> bool {
+        self.books.is_empty()
+    }
+
+    /// Stop the router and every book.
+    pub fn close(&mut self) {
+        for book in self.books.values_mut() {
+            book.close();
+        }
+        self.books.clear();
+        if let Some(tx) = self.shutdown_tx.take() {
+            let _ = tx.send(());
+        }
+        if let Some(handle) = self.task_handle.take() {
+            handle.abort();
+        }
+    }
+}
+
+impl Drop for OrderbookManager {
+    fn drop(&mut self) {
+        self.close();
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Router task
+// ---------------------------------------------------------------------------
+
+/// Forward each delta to the book that owns its market.
+async fn run_router(
+    mut delta_rx: broadcast::Receiver<Arc<WebSocketDeltaUpdate>>,
+    mut route_rx: mpsc::UnboundedReceiver<RouteCommand>,
+    mut shutdown_rx: oneshot::Receiver<()>,
+) {
+    let mut routes: HashMap<String, Route> = HashMap::new();
+
+    loop {
+        tokio::select! {
+            // Apply route changes before the next delta so a newly added
+            // market never misses deltas that follow its snapshot request.
+            biased;
+            _ = &mut shutdown_rx => {
+                debug!("orderbook router shutdown");
+                break;
+            }
+            Some(command) = route_rx.recv() => match command {
+                RouteCommand::Add(symbol, route) => {
+                    routes.insert(symbol, route);
+                }
+                RouteCommand::Remove(symbol) => {
+                    routes.remove(&symbol);
+                }
+            },
+            result = delta_rx.recv() => match result {
+                Ok(update) => {
+                    if let Some(route) = routes.get(&update.market_symbol) {
+                        let _ = route.tx.send(update);
+                    }
+                }
+                Err(broadcast::error::RecvError::Lagged(n)) => {
+                    // The lost deltas could belong to any market.
+                    warn!("orderbook router lagged by {n}, re-snapshotting {} books", routes.len());
+                    for route in routes.values() {
+                        route.resync.notify_one();
+                    }
+                }
+                Err(broadcast::error::RecvError::Closed) => {
+                    info!("delta channel closed, orderbook router exiting");
+                    break;
+                }
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::orderbook::MidPrice;
+    use crate::recorder::{RecordSource, RecordedFrame};
+    use crate::replay::{Recording, ReplayDriver};
+    use crate::ws::events::OrderbookEntry;
+    use std::time::Duration;
+    use tokio::sync::watch;
+
+    fn delta(symbol: &str, update_id: u64) -> Arc<WebSocketDeltaUpdate> {
+        Arc::new(WebSocketDeltaUpdate {
+            e: "delta".into(),
+            last_update_id: update_id - 1,
+            update_id,
+            market_symbol: symbol.to_string(),
+            asks: Vec::new(),
+            bids: Vec::new(),
+            timestamp: 0,
+        })
+    }
+
+    #[tokio::test]
+    async fn test_router_forwards_by_symbol_and_resyncs_on_lag() {
+        let (delta_tx, delta_rx) = broadcast::channel(2);
+        let (route_tx, route_rx) = mpsc::unbounded_channel();
+        let (_shutdown_tx, shutdown_rx) = oneshot::channel();
+
+        let (btc_tx, mut btc_rx) = broadcast::channel(8);
+        let btc_resync = Arc::new(Notify::new());
+        route_tx
+            .send(RouteCommand::Add(
+                "BTCUSD".into(),
+                Route {
+                    tx: btc_tx,
+                    resync: Arc::clone(&btc_resync),
+                },
+            ))
+            .unwrap();
+
+        // Overflow the shared channel before the router starts.
+        for id in 1..=3 {
+            delta_tx.send(delta("BTCUSD", id)).unwrap();
+        }
+        let router = tokio::spawn(run_router(delta_rx, route_rx, shutdown_rx));
+
+        btc_resync.notified().await;
+        assert_eq!(btc_rx.recv().await.unwrap().update_id, 2);
+        assert_eq!(btc_rx.recv().await.unwrap().update_id, 3);
+
+        // Unmanaged markets are dropped; removed markets stop receiving.
+        delta_tx.send(delta("ETHUSD", 4)).unwrap();
+        delta_tx.send(delta("BTCUSD", 5)).unwrap();
+        assert_eq!(btc_rx.recv().await.unwrap().update_id, 5);
+        route_tx
+            .send(RouteCommand::Remove("BTCUSD".into()))
+            .unwrap();
+        tokio::task::yield_now().await;
+        delta_tx.send(delta("BTCUSD", 6)).unwrap();
+
+        drop(delta_tx);
+        router.await.unwrap();
+        assert!(matches!(
+            btc_rx.recv().await,
+            Err(broadcast::error::RecvError::Closed)
+        ));
+    }
+
+    fn rest_frame(recv_ms: u64, key: &str, body: String) -> RecordedFrame {
+        RecordedFrame {
+            recv_ms,
+            source: RecordSource::NordRest,
+            key: key.into(),
+            frame: body,
+        }
+    }
+
+    fn snapshot(update_id: u64, bid: f64, ask: f64) -> String {
+        format!(
+            r#"{{"updateId":{update_id},"asks":[[{ask},1.0]],"bids":[[{bid},1.0]],
+                "asksSummary":{{"sum":1.0,"count":1}},"bidsSummary":{{"sum":1.0,"count":1}}}}"#
+        )
+    }
+
+    fn market(id: u32, symbol: &str) -> String {
+        format!(
+            r#"{{"marketId":{id},"symbol":"{symbol}","priceDecimals":1,"sizeDecimals":3,
+                "baseTokenId":{},"quoteTokenId":0,"imf":0.05,"mmf":0.025,"cmf":0.0125}}"#,
+            id + 1
+        )
+    }
+
+    /// A manager for BTCUSD and ETHUSD over a replay client whose REST
+    /// snapshots move from mid 100 (update 10) to mid 200 (update 20) at
+    /// 1000ms.
+    async fn manager() -> (
+        OrderbookManager,
+        broadcast::Sender<Arc<WebSocketDeltaUpdate>>,
+        crate::clock::Clock,
+    ) {
+        let mut frames = vec![rest_frame(
+            0,
+            "info",
+            format!(
+                r#"{{"markets":[{},{}],"tokens":[]}}"#,
+                market(0, "BTCUSD"),
+                market(1, "ETHUSD")
+            ),
+        )];
+        for symbol in ["BTCUSD", "ETHUSD"] {
+            let key = format!("orderbook@{symbol}");
+            frames.push(rest_frame(0, &key, snapshot(10, 99.0, 101.0)));
+            frames.push(rest_frame(1_000, &key, snapshot(20, 199.0, 201.0)));
+        }
+        let driver = ReplayDriver::new(Recording::from_frames(frames)).unwrap();
+        let clock = driver.clock();
+        let nord = Arc::new(driver.nord().unwrap());
+
+        let (delta_tx, delta_rx) = broadcast::channel(4);
+        let mut manager = OrderbookManager::new(nord, delta_rx, OrderbookConfig::default());
+        manager.connect();
+        manager.add_market("BTCUSD").await.unwrap();
+        manager.add_market("ETHUSD").await.unwrap();
+        (manager, delta_tx, clock)
+    }
+
+    fn bid_delta(symbol: &str, update_id: u64, bid: f64) -> Arc<WebSocketDeltaUpdate> {
+        let mut update = (*delta(symbol, update_id)).clone();
+        update.bids = vec![OrderbookEntry {
+            price: bid,
+            size: 1.0,
+        }];
+        Arc::new(update)
+    }
+
+    async fn wait_for_mid(rx: &mut watch::Receiver<Option<MidPrice>>, mid: f64) {
+        tokio::time::timeout(
+            Duration::from_secs(5),
+            rx.wait_for(|p| p.as_ref().is_some_and(|p| p.mid == mid)),
+        )
+        .await
+        .expect("mid price not reached")
+        .unwrap();
+    }
+
+    fn mid(manager: &OrderbookManager, symbol: &str) -> Option<f64> {
+        manager.handle(symbol)?.get_mid_price().map(|p| p.mid)
+    }
+
+    #[tokio::test]
+    async fn test_manager_routes_each_delta_to_its_own_book() {
+        let (manager, delta_tx, _clock) = manager().await;
+        let mut btc = manager.handle("BTCUSD").unwrap().subscribe_price();
+        let mut eth = manager.handle("ETHUSD").unwrap().subscribe_price();
+
+        delta_tx.send(bid_delta("BTCUSD", 11, 100.0)).unwrap();
+        wait_for_mid(&mut btc, 100.5).await;
+        assert_eq!(mid(&manager, "ETHUSD"), Some(100.0));
+
+        delta_tx.send(bid_delta("ETHUSD", 11, 100.6)).unwrap();
+        wait_for_mid(&mut eth, 100.8).await;
+        assert_eq!(mid(&manager, "BTCUSD"), Some(100.5));
+    }
+
+    #[tokio::test]
+    async fn test_remove_market_stops_only_that_book() {
+        let (mut manager, delta_tx, _clock) = manager().await;
+        let btc = manager.handle("BTCUSD").unwrap();
+        let mut eth = manager.handle("ETHUSD").unwrap().subscribe_price();
+
+        assert!(manager.remove_market("BTCUSD"));
+        assert!(!manager.remove_market("BTCUSD"));
+        assert!(manager.handle("BTCUSD").is_none());
+        assert_eq!(manager.symbols().collect::<Vec<_>>(), vec!["ETHUSD"]);
+
+        delta_tx.send(bid_delta("BTCUSD", 11, 100.0)).unwrap();
+        delta_tx.send(bid_delta("ETHUSD", 11, 100.0)).unwrap();
+        wait_for_mid(&mut eth, 100.5).await;
+        // The removed book's last handle stops updating.
+        assert_eq!(btc.get_mid_price().map(|p| p.mid), Some(100.0));
+    }
+
+    #[tokio::test]
+    async fn test_router_lag_resyncs_every_book() {
+        let (manager, delta_tx, clock) = manager().await;
+        let mut btc = manager.handle("BTCUSD").unwrap().subscribe_price();
+        let mut eth = manager.handle("ETHUSD").unwrap().subscribe_price();
+
+        // Newer snapshots are served from now on. Overflow the shared
+        // channel with deltas for an unmanaged market before the router can
+        // drain it: both books must re-snapshot.
+        clock.set_ms(1_000);
+        for id in 1..=8 {
+            delta_tx.send(delta("SOLUSD", id)).unwrap();
+        }
+        wait_for_mid(&mut btc, 200.0).await;
+        wait_for_mid(&mut eth, 200.0).await;
+    }
+}
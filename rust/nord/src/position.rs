@@ -0,0 +1,560 @@
+//! Live position with average entry price and realized/unrealized PnL.
+//!
+//! [`PositionStream`] follows the fills of one account in one market and
+//! keeps a signed size, the average entry price of the open size and the
+//! PnL realized by reducing it. Unrealized PnL is valued against the live
+//! book mid when an [`OrderbookHandle`] is supplied, otherwise against the
+//! exchange mark price fetched at every reconcile.
+//!
+//! # Architecture
+//!
+//! ```text
+//!   broadcast<FillEvent>     OrderbookHandle mid    GET /account (+ /stats)
+//!   (AccountStream)          (optional)             every reconcile_interval
+//!          \                        |                      /
+//!           v                       v                     v
+//!   +-------- background task (owns LivePosition) ---------------+
+//!   |  - average-cost accounting on each fill                     |
+//!   |  - revalues unrealized PnL when the mark moves              |
+//!   |  - adopts the exchange size and entry when they drift       |
+//!   +-------------------------------------------------------------+
+//!                              |
+//!                  watch::Sender<LivePosition>
+//! ```
+//!
+//! Realized PnL is local: the exchange reports only the open position, so it
+//! counts from when the stream started. Fees are not deducted.
+
+use std::sync::Arc;
+
+use tokio::sync::{broadcast, oneshot, watch};
+use tokio::task::JoinHandle;
+use tokio::time::{self, Duration};
+use tracing::{debug, error, warn};
+
+use crate::account::FillEvent;
+use crate::client::Nord;
+use crate::orderbook::{MidPrice, OrderbookHandle};
+use crate::types::{PerpPosition, Side};
+
+/// Default interval between REST reconciles.
+const DEFAULT_RECONCILE_INTERVAL_MS: u64 = 30_000;
+
+/// Default size difference tolerated before the exchange size is adopted.
+const DEFAULT_SIZE_TOLERANCE: f64 = 1e-6;
+
+/// Settings for a [`PositionStream`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PositionStreamConfig {
+    /// Interval between REST reconciles in milliseconds.
+    pub reconcile_interval_ms: u64,
+    /// Size difference (base units) tolerated before the exchange position
+    /// replaces the local one.
+    pub size_tolerance: f64,
+}
+
+impl Default for PositionStreamConfig {
+    fn default() -> Self {
+        Self {
+            reconcile_interval_ms: DEFAULT_RECONCILE_INTERVAL_MS,
+            size_tolerance: DEFAULT_SIZE_TOLERANCE,
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// LivePosition
+// ---------------------------------------------------------------------------
+
+/// Position in one market, updated from fills and reconciled from REST.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LivePosition {
+    pub market_id: u32,
+    /// Signed base size (positive = long, negative = short).
+    pub size: f64,
+    /// Average entry price of the open size (`0.0` when flat).
+    pub avg_entry: f64,
+    /// PnL realized by reducing or flipping the position since the stream
+    /// started.
+    pub realized_pnl: f64,
+    /// `size * (mark - avg_entry)`, or `0.0` before a mark is known.
+    pub unrealized_pnl: f64,
+    /// Price used for `unrealized_pnl`.
+    pub mark: Option<f64>,
+    /// Funding PnL of the open position, as of the last reconcile.
+    pub funding_pnl: f64,
+    /// The exchange's price PnL of the open position, as of the last
+    /// reconcile (compare with `unrealized_pnl`).
+    pub exchange_pnl: Option<f64>,
+    /// Fills applied.
+    pub fills: u64,
+    /// Successful reconciles.
+    pub reconciles: u64,
+    /// Reconciles that replaced the local size or entry.
+    pub corrections: u64,
+    /// Account `update_id` of the last reconciled snapshot. Fills at or
+    /// below it are already part of the exchange position.
+    pub snapshot_update_id: u64,
+    /// Account `update_id` of the last fill applied.
+    pub fill_update_id: u64,
+    /// Unix epoch milliseconds of the last change.
+    pub updated_at: u64,
+}
+
+impl LivePosition {
+    /// A flat position in `market_id`.
+    pub fn new(market_id: u32) -> Self {
+        Self {
+            market_id,
+            ..Default::default()
+        }
+    }
+
+    /// Realized plus unrealized PnL.
+    pub fn total_pnl(&self) -> f64 {
+        self.realized_pnl + self.unrealized_pnl
+    }
+
+    /// Apply a fill with average-cost accounting.
+    ///
+    /// Increasing the position moves the average entry; reducing it realizes
+    /// PnL against the average entry; flipping it realizes the closed part
+    /// and opens the remainder at the fill price.
+    pub fn apply_fill(&mut self, side: Side, size: f64, price: f64) {
+        let signed = match side {
+            Side::Bid => size,
+            Side::Ask => -size,
+        };
+        if self.size == 0.0 || self.size.signum() == signed.signum() {
+            let open = self.size.abs();
+            self.avg_entry = (open * self.avg_entry + size * price) / (open + size);
+            self.size += signed;
+        } else {
+            let closed = size.min(self.size.abs());
+            self.realized_pnl += closed * (price - self.avg_entry) * self.size.signum();
+            let remaining = self.size + signed;
+            if remaining.abs() <= f64::EPSILON * size.max(1.0) {
+                self.size = 0.0;
+                self.avg_entry = 0.0;
+            } else {
+                if remaining.signum() != self.size.signum() {
+                    self.avg_entry = price;
+                }
+                self.size = remaining;
+            }
+        }
+        self.fills += 1;
+        self.revalue();
+    }
+
+    /// Apply a fill unless the last reconciled snapshot already includes it.
+    /// Returns whether it was applied.
+    pub fn apply_fill_event(&mut self, fill: &FillEvent) -> bool {
+        if fill.update_id <= self.snapshot_update_id {
+            return false;
+        }
+        self.apply_fill(fill.side, fill.size, fill.price);
+        self.fill_update_id = self.fill_update_id.max(fill.update_id);
+        true
+    }
+
+    /// Set the mark price and recompute unrealized PnL.
+    pub fn set_mark(&mut self, mark: f64) {
+        self.mark = Some(mark);
+        self.revalue();
+    }
+
+    /// Compare with the exchange position (`None` = flat) taken at account
+    /// `update_id` and adopt its size and entry if they differ. Returns
+    /// whether a correction was made.
+    ///
+    /// A snapshot older than the last applied fill is ignored: adopting it
+    /// would drop that fill, which is never delivered again.
+    pub fn reconcile(
+        &mut self,
+        perp: Option<&PerpPosition>,
+        update_id: u64,
+        size_tolerance: f64,
+    ) -> bool {
+        if update_id < self.fill_update_id {
+            return false;
+        }
+        self.snapshot_update_id = update_id;
+        let (size, entry) = match perp {
+            Some(perp) if perp.is_long => (perp.base_size, perp.price),
+            Some(perp) => (-perp.base_size, perp.price),
+            None => (0.0, 0.0),
+        };
+        self.funding_pnl = perp.map_or(0.0, |p| p.funding_payment_pnl);
+        self.exchange_pnl = perp.map(|p| p.size_price_pnl);
+        self.reconciles += 1;
+
+        let drifted = (self.size - size).abs() > size_tolerance
+            || (size != 0.0 && (self.avg_entry - entry).abs() > entry.abs() * 1e-6);
+        if drifted {
+            self.size = size;
+            self.avg_entry = if size == 0.0 { 0.0 } else { entry };
+            self.corrections += 1;
+            self.revalue();
+        }
+        drifted
+    }
+
+    fn revalue(&mut self) {
+        self.unrealized_pnl = self
+            .mark
+            .map_or(0.0, |mark| self.size * (mark - self.avg_entry));
+    }
+}
+
+// ---------------------------------------------------------------------------
+// PositionStream
+// ---------------------------------------------------------------------------
+
+/// Maintains a [`LivePosition`] for one account and market.
+///
+/// Call [`PositionStream::new`] then [`PositionStream::connect`] to start.
+pub struct PositionStream {
+    account_id: u32,
+    market_id: u32,
+    config: PositionStreamConfig,
+    nord: Arc<Nord>,
+    // Inputs: `Some` before `connect()`, `None` after (moved into the task).
+    fills_rx: Option<broadcast::Receiver<FillEvent>>,
+    book: Option<OrderbookHandle>,
+    position_tx: watch::Sender<LivePosition>,
+    position_rx: watch::Receiver<LivePosition>,
+    task_handle: Option<JoinHandle<()>>,
+    shutdown_tx: Option<oneshot::Sender<()>>,
+}
+
+impl PositionStream {
+    /// Create a new stream.
+    ///
+    /// # Arguments
+    ///
+    /// * `account_id` - Account whose exchange position is reconciled.
+    /// * `market_id` - Market to track; fills in other markets are ignored.
+    /// * `fills_rx` - From [`AccountStream::subscribe_fills`](crate::AccountStream::subscribe_fills).
+    /// * `book` - Book whose mid values the position; `None` to use the
+    ///   exchange mark price.
+    /// * `nord` - Shared client for reconciles.
+    /// * `config` - Reconcile interval and tolerance.
+    pub fn new(
+        account_id: u32,
+        market_id: u32,
+        fills_rx: broadcast::Receiver<FillEvent>,
+        book: Option<OrderbookHandle>,
+        nord: Arc<Nord>,
+        config: PositionStreamConfig,
+    ) -> Self {
+        let (position_tx, position_rx) = watch::channel(LivePosition::new(market_id));
+        Self {
+            account_id,
+            market_id,
+            config,
+            nord,
+            fills_rx: Some(fills_rx),
+            book,
+            position_tx,
+            position_rx,
+            task_handle: None,
+            shutdown_tx: None,
+        }
+    }
+
+    /// Start the background task. The first reconcile runs immediately and
+    /// seeds the position from the exchange.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called twice.
+    pub fn connect(&mut self) {
+        let fills_rx = self
+            .fills_rx
+            .take()
+            .expect("connect() called twice: fills_rx already consumed");
+        let (shutdown_tx, shutdown_rx) = oneshot::channel();
+        self.shutdown_tx = Some(shutdown_tx);
+        self.task_handle = Some(tokio::spawn(run_position_task(
+            self.account_id,
+            self.market_id,
+            self.config,
+            Arc::clone(&self.nord),
+            fills_rx,
+            self.book.take().map(|book| book.subscribe_price()),
+            self.position_tx.clone(),
+            shutdown_rx,
+        )));
+    }
+
+    /// Latest position (lock-free read).
+    pub fn get(&self) -> LivePosition {
+        self.position_rx.borrow().clone()
+    }
+
+    /// Clone a `watch::Receiver` for async consumption.
+    pub fn subscribe(&self) -> watch::Receiver<LivePosition> {
+        self.position_rx.clone()
+    }
+
+    /// Shut down the background task.
+    pub fn close(&mut self) {
+        if let Some(tx) = self.shutdown_tx.take() {
+            let _ = tx.send(());
+        }
+        if let Some(handle) = self.task_handle.take() {
+            handle.abort();
+        }
+    }
+}
+
+impl Drop for PositionStream {
+    fn drop(&mut self) {
+        self.close();
+    }
+}
+
+/// Apply fills and mark changes; reconcile on an interval and after a lag.
+#[allow(clippy::too_many_arguments)]
+async fn run_position_task(
+    account_id: u32,
+    market_id: u32,
+    config: PositionStreamConfig,
+    nord: Arc<Nord>,
+    mut fills_rx: broadcast::Receiver<FillEvent>,
+    mut price_rx: Option<watch::Receiver<Option<MidPrice>>>,
+    position_tx: watch::Sender<LivePosition>,
+    mut shutdown_rx: oneshot::Receiver<()>,
+) {
+    let clock = nord.clock();
+    let mut interval = time::interval(Duration::from_millis(config.reconcile_interval_ms));
+
+    loop {
+        let reconcile = tokio::select! {
+            _ = &mut shutdown_rx => break,
+            _ = interval.tick() => true,
+            result = fills_rx.recv() => match result {
+                Ok(fill) => {
+                    if fill.market_id == market_id {
+                        position_tx.send_if_modified(|p| {
+                            let applied = p.apply_fill_event(&fill);
+                            if applied {
+                                p.updated_at = clock.now_ms();
+                            } else {
+                                debug!(
+                                    market_id,
+                                    update_id = fill.update_id,
+                                    "fill already in reconciled snapshot — skipped"
+                                );
+                            }
+                            applied
+                        });
+                    }
+                    false
+                }
+                Err(broadcast::error::RecvError::Lagged(n)) => {
+                    warn!(market_id, skipped = n, "position fills lagged — reconciling");
+                    true
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            },
+            Some(mid) = async {
+                match price_rx.as_mut() {
+                    Some(rx) => match rx.changed().await {
+                        Ok(()) => *rx.borrow_and_update(),
+                        Err(_) => std::future::pending().await,
+                    },
+                    None => std::future::pending().await,
+                }
+            } => {
+                position_tx.send_if_modified(|p| {
+                    let changed = p.mark != Some(mid.mid);
+                    if changed {
+                        p.set_mark(mid.mid);
+                        p.updated_at = clock.now_ms();
+                    }
+                    changed
+                });
+                false
+            }
+        };
+
+        if reconcile {
+            reconcile_position(
+                &nord,
+                account_id,
+                market_id,
+                &config,
+                price_rx.is_none(),
+                &position_tx,
+            )
+            .await;
+        }
+    }
+    debug!(market_id, "position task exiting");
+}
+
+/// Fetch the exchange position (and mark price, if there is no book) and
+/// reconcile the local one.
+async fn reconcile_position(
+    nord: &Nord,
+    account_id: u32,
+    market_id: u32,
+    config: &PositionStreamConfig,
+    fetch_mark: bool,
+    position_tx: &watch::Sender<LivePosition>,
+) {
+    let account = match nord.get_account(account_id).await {
+        Ok(account) => account,
+        Err(e) => {
+            error!(market_id, error = %e, "position reconcile failed");
+            return;
+        }
+    };
+    let perp = account
+        .positions
+        .iter()
+        .find(|p| p.market_id == market_id)
+        .and_then(|p| p.perp.as_ref());
+
+    let mark = if fetch_mark {
+        match nord.get_market_stats(market_id).await {
+            Ok(stats) => stats.perp_stats.and_then(|s| s.mark_price),
+            Err(e) => {
+                warn!(market_id, error = %e, "mark price fetch failed");
+                None
+            }
+        }
+    } else {
+        None
+    };
+
+    let now = nord.clock().now_ms();
+    position_tx.send_modify(|p| {
+        let (local_size, local_entry) = (p.size, p.avg_entry);
+        if account.update_id < p.fill_update_id {
+            debug!(
+                market_id,
+                snapshot = account.update_id,
+                last_fill = p.fill_update_id,
+                "account snapshot older than the last fill — not reconciled"
+            );
+        }
+        if p.reconcile(perp, account.update_id, config.size_tolerance) {
+            warn!(
+                market_id,
+                local = format!("{local_size:.6}@{local_entry:.4}"),
+                exchange = format!("{:.6}@{:.4}", p.size, p.avg_entry),
+                "position drift detected — adopting exchange position"
+            );
+        }
+        if let Some(mark) = mark {
+            p.set_mark(mark);
+        }
+        p.updated_at = now;
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn perp(size: f64, price: f64) -> PerpPosition {
+        PerpPosition {
+            base_size: size.abs(),
+            price,
+            updated_funding_rate_index: 0.0,
+            funding_payment_pnl: -1.5,
+            size_price_pnl: 12.0,
+            is_long: size > 0.0,
+        }
+    }
+
+    #[test]
+    fn test_average_cost_accounting() {
+        let mut p = LivePosition::new(1);
+        p.apply_fill(Side::Bid, 1.0, 100.0);
+        p.apply_fill(Side::Bid, 1.0, 110.0);
+        assert_eq!((p.size, p.avg_entry), (2.0, 105.0));
+
+        p.set_mark(120.0);
+        assert_eq!(p.unrealized_pnl, 30.0);
+
+        // Reduce: realize against the average, entry unchanged.
+        p.apply_fill(Side::Ask, 0.5, 115.0);
+        assert_eq!((p.size, p.avg_entry), (1.5, 105.0));
+        assert_eq!(p.realized_pnl, 5.0);
+
+        // Flip: close 1.5, open 0.5 short at the fill price.
+        p.apply_fill(Side::Ask, 2.0, 100.0);
+        assert_eq!((p.size, p.avg_entry), (-0.5, 100.0));
+        assert_eq!(p.realized_pnl, 5.0 - 7.5);
+        assert_eq!(p.unrealized_pnl, -10.0);
+
+        // Close: flat with zero entry.
+        p.apply_fill(Side::Bid, 0.5, 90.0);
+        assert_eq!((p.size, p.avg_entry), (0.0, 0.0));
+        assert_eq!(p.realized_pnl, -2.5 + 5.0);
+        assert_eq!(p.total_pnl(), 2.5);
+        assert_eq!(p.fills, 5);
+    }
+
+    #[test]
+    fn test_reconcile_adopts_exchange_position() {
+        let mut p = LivePosition::new(1);
+        p.apply_fill(Side::Bid, 1.0, 100.0);
+
+        assert!(!p.reconcile(Some(&perp(1.0, 100.0)), 1, 1e-6));
+        assert_eq!((p.funding_pnl, p.exchange_pnl), (-1.5, Some(12.0)));
+
+        // A missed fill: the exchange is short.
+        assert!(p.reconcile(Some(&perp(-0.25, 101.0)), 2, 1e-6));
+        assert_eq!((p.size, p.avg_entry), (-0.25, 101.0));
+
+        assert!(p.reconcile(None, 3, 1e-6));
+        assert_eq!((p.size, p.avg_entry, p.exchange_pnl), (0.0, 0.0, None));
+        assert_eq!((p.reconciles, p.corrections), (3, 2));
+    }
+
+    fn fill(update_id: u64, side: Side, size: f64, price: f64) -> FillEvent {
+        FillEvent {
+            order_id: 1,
+            side,
+            size,
+            price,
+            remaining: 0.0,
+            market_id: 1,
+            role: crate::types::FillRole::Maker,
+            maker_id: 7,
+            taker_id: 8,
+            tracking_id: None,
+            client_order_id: None,
+            fee: None,
+            received_at: 0,
+            update_id,
+        }
+    }
+
+    #[test]
+    fn test_fills_in_reconciled_snapshot_are_not_counted_twice() {
+        let mut p = LivePosition::new(1);
+        // The snapshot at update 10 already includes a 1.0 buy.
+        assert!(p.reconcile(Some(&perp(1.0, 100.0)), 10, 1e-6));
+
+        // Its WebSocket fill arrives afterwards and is skipped.
+        assert!(!p.apply_fill_event(&fill(10, Side::Bid, 1.0, 100.0)));
+        assert_eq!(p.size, 1.0);
+
+        // Both fills of a later update are applied.
+        assert!(p.apply_fill_event(&fill(11, Side::Bid, 0.5, 100.0)));
+        assert!(p.apply_fill_event(&fill(11, Side::Bid, 0.5, 100.0)));
+        assert_eq!((p.size, p.fill_update_id), (2.0, 11));
+
+        // A snapshot taken before update 11 would drop those fills.
+        assert!(!p.reconcile(Some(&perp(1.0, 100.0)), 10, 1e-6));
+        assert_eq!(p.size, 2.0);
+        assert!(!p.reconcile(Some(&perp(2.0, 100.0)), 11, 1e-6));
+        assert_eq!(p.snapshot_update_id, 11);
+    }
+}
@@ -0,0 +1,770 @@
+//! Replay of recorded market data into the live consumer channels.
+//!
+//! A [`ReplayDriver`] loads frames written by the
+//! [`Recorder`](crate::recorder::Recorder) and publishes them, in receive
+//! order, into the same `broadcast`/`watch` channels the live sockets feed.
+//! REST orderbook snapshots are served from the recording by the [`Nord`]
+//! client returned from [`ReplayDriver::nord`], so `OrderbookStream` and
+//! the market maker's pricing path run unmodified. Other REST endpoints are
+//! not recorded and fail during replay.
+//!
+//! # Architecture
+//!
+//! ```text
+//!   Recording (frames sorted by recv_ms)
+//!              |
+//!              v
+//!   +------------ ReplayDriver::run ------------+
+//!   |  - advances the shared manual Clock       |
+//!   |  - paces frames (original / Nx / max)     |
+//!   |  - routes by RecordSource (+ stream key)  |
+//!   +-------------------------------------------+
+//!        |                 |                 |
+//!   NordWebSocketClient  BinancePriceFeed   Nord (REST)
+//!   broadcast channels   watch channel      snapshots at or after
+//!                                           the replay clock
+//! ```
+//!
+//! Replay is deterministic with respect to frame order and timestamps: all
+//! time-dependent code that reads [`Nord::clock`] observes the recorded
+//! receive times. At [`ReplaySpeed::Max`] the driver waits for consumers to
+//! drain each sink before publishing the next frame, so broadcast receivers
+//! never lag. A `watch` channel keeps only its latest value, so sinks that
+//! publish into one count their values with a [`ReplayAck`] that the consumer
+//! acknowledges once it has handled each value:
+//!
+//! ```text
+//!   driver --deliver--> sink --publish()--> watch --changed()--> consumer
+//!     ^                   |                                         |
+//!     +-- backlog() == 0 -+<------------ ReplayAck::observe --------+
+//! ```
+//!
+//! The driver yields while a consumer catches up and backs off to short
+//! sleeps if it takes longer, so waiting does not pin a core. A sink that
+//! does not drain within the consumer timeout (see
+//! [`ReplayDriver::with_consumer_timeout`]), e.g. one with a subscribed but
+//! never polled receiver, is counted once in [`ReplayStats::stalled`] and
+//! then no longer waited on until its backlog clears.
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use flate2::read::GzDecoder;
+use tracing::{debug, info, warn};
+
+use crate::client::Nord;
+use crate::clock::Clock;
+use crate::error::{NordError, Result};
+use crate::recorder::{list_recordings, RecordSource, RecordedFrame};
+use crate::types::{MarketsInfo, OrderbookInfo};
+
+/// Recorded key of the market/token info frame.
+const INFO_KEY: &str = "info";
+
+/// Prefix of recorded REST orderbook snapshot keys.
+const ORDERBOOK_KEY_PREFIX: &str = "orderbook@";
+
+/// Default bound on how long [`ReplaySpeed::Max`] waits for a sink to drain.
+const DEFAULT_CONSUMER_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Yields granted to a draining consumer before the driver backs off.
+const CONSUMER_SPIN_YIELDS: u32 = 64;
+
+/// Sleep between backlog checks once the spin yields are exhausted.
+const CONSUMER_BACKOFF: Duration = Duration::from_millis(1);
+
+// ---------------------------------------------------------------------------
+// Public types
+// ---------------------------------------------------------------------------
+
+/// How fast recorded time advances relative to wall-clock time.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ReplaySpeed {
+    /// Reproduce the recorded inter-frame gaps exactly.
+    Original,
+    /// Reproduce the gaps divided by the given factor (e.g. `10.0` = 10x).
+    Accelerated(f64),
+    /// Publish frames as fast as consumers drain them.
+    Max,
+}
+
+impl FromStr for ReplaySpeed {
+    type Err = String;
+
+    /// Parse `"max"`, `"original"`, or a multiplier such as `"10"` / `"10x"`.
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "max" => Ok(Self::Max),
+            "original" | "1" | "1x" => Ok(Self::Original),
+            other => {
+                let factor: f64 = other
+                    .trim_end_matches('x')
+                    .parse()
+                    .map_err(|_| format!("invalid replay speed: {s}"))?;
+                if factor.is_finite() && factor > 0.0 {
+                    Ok(Self::Accelerated(factor))
+                } else {
+                    Err(format!("replay speed must be positive: {s}"))
+                }
+            }
+        }
+    }
+}
+
+/// Counters returned when a replay finishes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ReplayStats {
+    /// Frames handed to at least one sink.
+    pub delivered: u64,
+    /// Streaming frames with no matching sink.
+    pub unrouted: u64,
+    /// Recorded time covered, in milliseconds.
+    pub span_ms: u64,
+    /// Times the driver gave up waiting for a sink to drain.
+    pub stalled: u64,
+}
+
+/// Consumer of replayed frames.
+///
+/// Implemented by the WebSocket client's dispatcher (see
+/// [`NordWebSocketClient::replay_sink`](crate::NordWebSocketClient::replay_sink))
+/// and by reference-price feeds outside this crate.
+pub trait ReplaySink: Send + Sync + 'static {
+    /// Publish one recorded frame.
+    fn deliver(&self, frame: &RecordedFrame);
+
+    /// Number of published values not yet consumed by every receiver.
+    /// Used to apply back-pressure at [`ReplaySpeed::Max`].
+    fn backlog(&self) -> usize {
+        0
+    }
+}
+
+/// Published/consumed counters shared by a `watch`-backed sink and its
+/// consumer.
+///
+/// The sink calls [`publish`](Self::publish) before each send and reports
+/// [`pending`](Self::pending) as its backlog; the consumer holds the guard
+/// from [`observe`](Self::observe) while it handles the value it was woken
+/// for. At [`ReplaySpeed::Max`] at most one value is then outstanding, so
+/// none is overwritten before it is read.
+#[derive(Debug, Clone, Default)]
+pub struct ReplayAck {
+    inner: Arc<AckCounters>,
+}
+
+#[derive(Debug, Default)]
+struct AckCounters {
+    published: AtomicU64,
+    consumed: AtomicU64,
+}
+
+impl ReplayAck {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Count one value about to be sent.
+    pub fn publish(&self) {
+        self.inner.published.fetch_add(1, Ordering::AcqRel);
+    }
+
+    /// Mark every value published so far as consumed when the returned
+    /// guard drops. Take it right after waking, before reading the value.
+    pub fn observe(&self) -> ReplayAckGuard<'_> {
+        ReplayAckGuard {
+            ack: self,
+            seen: self.inner.published.load(Ordering::Acquire),
+        }
+    }
+
+    /// Values published but not yet consumed.
+    pub fn pending(&self) -> usize {
+        let published = self.inner.published.load(Ordering::Acquire);
+        let consumed = self.inner.consumed.load(Ordering::Acquire);
+        published.saturating_sub(consumed) as usize
+    }
+}
+
+/// Acknowledges the values seen by [`ReplayAck::observe`] on drop.
+#[must_use = "the values are acknowledged when the guard drops"]
+pub struct ReplayAckGuard<'a> {
+    ack: &'a ReplayAck,
+    seen: u64,
+}
+
+impl Drop for ReplayAckGuard<'_> {
+    fn drop(&mut self) {
+        self.ack
+            .inner
+            .consumed
+            .fetch_max(self.seen, Ordering::AcqRel);
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Recording
+// ---------------------------------------------------------------------------
+
+/// Recorded frames loaded into memory, sorted by receive time.
+#[derive(Debug, Clone, Default)]
+pub struct Recording {
+    frames: Vec<RecordedFrame>,
+}
+
+impl Recording {
+    /// Build a recording from frames. Frames are stably sorted by `recv_ms`.
+    pub fn from_frames(mut frames: Vec<RecordedFrame>) -> Self {
+        frames.sort_by_key(|f| f.recv_ms);
+        Self { frames }
+    }
+
+    /// Load and merge the given recording files.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a file cannot be opened or a line is not a valid
+    /// [`RecordedFrame`]. A truncated trailing gzip member (e.g. from a
+    /// crashed recorder) ends that file without an error.
+    pub fn load(paths: &[PathBuf]) -> Result<Self> {
+        let mut frames = Vec::new();
+        for path in paths {
+            let reader = BufReader::new(GzDecoder::new(File::open(path)?));
+            for line in reader.lines() {
+                let line = match line {
+                    Ok(l) => l,
+                    Err(e) => {
+                        warn!(path = %path.display(), error = %e, "recording truncated");
+                        break;
+                    }
+                };
+                if line.is_empty() {
+                    continue;
+                }
+                frames.push(serde_json::from_str(&line)?);
+            }
+            debug!(path = %path.display(), total = frames.len(), "loaded recording file");
+        }
+        Ok(Self::from_frames(frames))
+    }
+
+    /// Load every recording file in `dir`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the directory cannot be read or contains no
+    /// recordings, or if any file fails to load.
+    pub fn load_dir(dir: &Path) -> Result<Self> {
+        let paths = list_recordings(dir)?;
+        if paths.is_empty() {
+            return Err(NordError::Validation(format!(
+                "no recordings found in {}",
+                dir.display()
+            )));
+        }
+        Self::load(&paths)
+    }
+
+    /// All frames in receive order.
+    pub fn frames(&self) -> &[RecordedFrame] {
+        &self.frames
+    }
+
+    /// Receive time of the first frame, or `0` if empty.
+    pub fn start_ms(&self) -> u64 {
+        self.frames.first().map_or(0, |f| f.recv_ms)
+    }
+
+    /// Receive time of the last frame, or `0` if empty.
+    pub fn end_ms(&self) -> u64 {
+        self.frames.last().map_or(0, |f| f.recv_ms)
+    }
+
+    /// The most recently recorded market/token info.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the recording holds no info frame.
+    pub fn markets_info(&self) -> Result<MarketsInfo> {
+        let frame = self
+            .frames
+            .iter()
+            .rev()
+            .find(|f| f.source == RecordSource::NordRest && f.key == INFO_KEY)
+            .ok_or_else(|| NordError::Validation("recording has no market info".into()))?;
+        Ok(serde_json::from_str(&frame.frame)?)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// REST snapshots
+// ---------------------------------------------------------------------------
+
+/// Recorded REST orderbook snapshots, indexed by symbol and served relative
+/// to the replay clock.
+#[derive(Debug, Clone)]
+pub(crate) struct ReplaySnapshots {
+    clock: Clock,
+    orderbooks: Arc<HashMap<String, Vec<(u64, OrderbookInfo)>>>,
+}
+
+impl ReplaySnapshots {
+    fn new(recording: &Recording, clock: Clock) -> Result<Self> {
+        let mut orderbooks: HashMap<String, Vec<(u64, OrderbookInfo)>> = HashMap::new();
+        for frame in &recording.frames {
+            if frame.source != RecordSource::NordRest {
+                continue;
+            }
+            if let Some(symbol) = frame.key.strip_prefix(ORDERBOOK_KEY_PREFIX) {
+                let info: OrderbookInfo = serde_json::from_str(&frame.frame)?;
+                orderbooks
+                    .entry(symbol.to_string())
+                    .or_default()
+                    .push((frame.recv_ms, info));
+            }
+        }
+        Ok(Self {
+            clock,
+            orderbooks: Arc::new(orderbooks),
+        })
+    }
+
+    pub(crate) fn clock(&self) -> &Clock {
+        &self.clock
+    }
+
+    /// The snapshot a live request issued now would have received: the first
+    /// one recorded at or after the replay clock, else the latest one.
+    pub(crate) fn orderbook(&self, symbol: &str) -> Result<OrderbookInfo> {
+        let snapshots = self
+            .orderbooks
+            .get(symbol)
+            .filter(|s| !s.is_empty())
+            .ok_or_else(|| {
+                NordError::Validation(format!("no recorded orderbook snapshot for {symbol}"))
+            })?;
+        let now = self.clock.now_ms();
+        let idx = snapshots
+            .partition_point(|(ms, _)| *ms < now)
+            .min(snapshots.len() - 1);
+        Ok(snapshots[idx].1.clone())
+    }
+}
+
+// ---------------------------------------------------------------------------
+// ReplayDriver
+// ---------------------------------------------------------------------------
+
+/// A registered sink plus its routing filter.
+struct Route {
+    source: RecordSource,
+    key: Option<String>,
+    sink: Box<dyn ReplaySink>,
+    /// Set when the sink did not drain within the consumer timeout; cleared
+    /// once its backlog is empty again.
+    stuck: bool,
+}
+
+/// Publishes a [`Recording`] into registered sinks on a manual clock.
+///
+/// Build the consumers from [`ReplayDriver::nord`], register their sinks with
+/// [`ReplayDriver::add_sink`], then spawn [`ReplayDriver::run`].
+pub struct ReplayDriver {
+    recording: Arc<Recording>,
+    clock: Clock,
+    snapshots: ReplaySnapshots,
+    routes: Vec<Route>,
+    consumer_timeout: Duration,
+}
+
+impl ReplayDriver {
+    /// Create a driver whose clock starts at the first recorded frame.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a recorded orderbook snapshot cannot be decoded.
+    pub fn new(recording: Recording) -> Result<Self> {
+        let clock = Clock::manual(recording.start_ms());
+        let snapshots = ReplaySnapshots::new(&recording, clock.clone())?;
+        Ok(Self {
+            recording: Arc::new(recording),
+            clock,
+            snapshots,
+            routes: Vec::new(),
+            consumer_timeout: DEFAULT_CONSUMER_TIMEOUT,
+        })
+    }
+
+    /// How long [`ReplaySpeed::Max`] waits for the sinks to drain before
+    /// publishing the next frame anyway (default 5s of wall time).
+    pub fn with_consumer_timeout(mut self, timeout: Duration) -> Self {
+        self.consumer_timeout = timeout;
+        self
+    }
+
+    /// The replay clock (shared with every [`Nord`] built by this driver).
+    pub fn clock(&self) -> Clock {
+        self.clock.clone()
+    }
+
+    /// The loaded recording.
+    pub fn recording(&self) -> &Recording {
+        &self.recording
+    }
+
+    /// An offline [`Nord`] client that serves market info and orderbook
+    /// snapshots from the recording and reads time from the replay clock.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the recording holds no market info.
+    pub fn nord(&self) -> Result<Nord> {
+        Ok(Nord::from_replay(
+            self.recording.markets_info()?,
+            self.snapshots.clone(),
+        ))
+    }
+
+    /// Route frames from `source` to `sink`. When `key` is set only frames
+    /// recorded under that exact key are delivered (e.g.
+    /// `"btcusdt@bookTicker"`); otherwise every frame from `source` is.
+    pub fn add_sink(&mut self, source: RecordSource, key: Option<String>, sink: impl ReplaySink) {
+        self.routes.push(Route {
+            source,
+            key,
+            sink: Box::new(sink),
+            stuck: false,
+        });
+    }
+
+    /// Publish every streaming frame at the requested speed and return once
+    /// the recording is exhausted. REST frames are served on demand instead.
+    pub async fn run(mut self, speed: ReplaySpeed) -> ReplayStats {
+        let start_ms = self.recording.start_ms();
+        let started = tokio::time::Instant::now();
+        let mut stats = ReplayStats {
+            span_ms: self.recording.end_ms().saturating_sub(start_ms),
+            ..Default::default()
+        };
+
+        info!(
+            frames = self.recording.frames.len(),
+            span_ms = stats.span_ms,
+            ?speed,
+            "replay starting"
+        );
+
+        let recording = Arc::clone(&self.recording);
+        for frame in &recording.frames {
+            if frame.source == RecordSource::NordRest {
+                continue;
+            }
+
+            let offset_ms = frame.recv_ms.saturating_sub(start_ms);
+            match speed {
+                ReplaySpeed::Original => {
+                    tokio::time::sleep_until(started + Duration::from_millis(offset_ms)).await;
+                }
+                ReplaySpeed::Accelerated(factor) => {
+                    let scaled = Duration::from_secs_f64(offset_ms as f64 / 1000.0 / factor);
+                    tokio::time::sleep_until(started + scaled).await;
+                }
+                ReplaySpeed::Max => {
+                    if !self.wait_for_consumers().await {
+                        stats.stalled += 1;
+                    }
+                }
+            }
+
+            self.clock.set_ms(frame.recv_ms);
+
+            let mut routed = false;
+            for route in &self.routes {
+                if route.source == frame.source
+                    && route.key.as_deref().is_none_or(|k| k == frame.key)
+                {
+                    route.sink.deliver(frame);
+                    routed = true;
+                }
+            }
+            if routed {
+                stats.delivered += 1;
+            } else {
+                stats.unrouted += 1;
+            }
+        }
+
+        // Let consumers observe the final frames before the caller tears down.
+        if !self.wait_for_consumers().await {
+            stats.stalled += 1;
+        }
+
+        info!(
+            delivered = stats.delivered,
+            unrouted = stats.unrouted,
+            stalled = stats.stalled,
+            elapsed_ms = started.elapsed().as_millis() as u64,
+            "replay finished"
+        );
+        stats
+    }
+
+    /// Wait until every sink that is still being consumed has been drained,
+    /// for at most the consumer timeout. Returns `false` on timeout, after
+    /// marking the sinks that did not drain as stuck.
+    async fn wait_for_consumers(&mut self) -> bool {
+        for route in &mut self.routes {
+            if route.stuck && route.sink.backlog() == 0 {
+                debug!(source = ?route.source, "replay consumer draining again");
+                route.stuck = false;
+            }
+        }
+
+        let deadline = Instant::now() + self.consumer_timeout;
+        let mut attempts = 0u32;
+        while self.routes.iter().any(|r| !r.stuck && r.sink.backlog() > 0) {
+            if Instant::now() >= deadline {
+                for route in &mut self.routes {
+                    if !route.stuck && route.sink.backlog() > 0 {
+                        warn!(
+                            source = ?route.source,
+                            key = route.key.as_deref().unwrap_or("*"),
+                            timeout_ms = self.consumer_timeout.as_millis() as u64,
+                            "replay consumer not draining, no longer waiting on it"
+                        );
+                        route.stuck = true;
+                    }
+                }
+                return false;
+            }
+            if attempts < CONSUMER_SPIN_YIELDS {
+                attempts += 1;
+                tokio::task::yield_now().await;
+            } else {
+                tokio::time::sleep(CONSUMER_BACKOFF).await;
+            }
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    #[derive(Clone, Default)]
+    struct Collect(Arc<Mutex<Vec<(u64, String)>>>);
+
+    impl ReplaySink for Collect {
+        fn deliver(&self, frame: &RecordedFrame) {
+            self.0
+                .lock()
+                .unwrap()
+                .push((frame.recv_ms, frame.key.clone()));
+        }
+    }
+
+    fn frame(recv_ms: u64, source: RecordSource, key: &str, body: &str) -> RecordedFrame {
+        RecordedFrame {
+            recv_ms,
+            source,
+            key: key.into(),
+            frame: body.into(),
+        }
+    }
+
+    fn snapshot(update_id: u64) -> String {
+        format!(
+            r#"{{"updateId":{update_id},"asks":[[101.0,1.0]],"bids":[[99.0,1.0]],
+                "asksSummary":{{"sum":1.0,"count":1}},"bidsSummary":{{"sum":1.0,"count":1}}}}"#
+        )
+    }
+
+    #[test]
+    fn test_parse_speed() {
+        assert_eq!("max".parse::<ReplaySpeed>(), Ok(ReplaySpeed::Max));
+        assert_eq!("1x".parse::<ReplaySpeed>(), Ok(ReplaySpeed::Original));
+        assert_eq!(
+            "10x".parse::<ReplaySpeed>(),
+            Ok(ReplaySpeed::Accelerated(10.0))
+        );
+        assert!("0".parse::<ReplaySpeed>().is_err());
+        assert!("fast".parse::<ReplaySpeed>().is_err());
+    }
+
+    #[test]
+    fn test_recording_sorts_by_recv_ms() {
+        let rec = Recording::from_frames(vec![
+            frame(30, RecordSource::Binance, "b", "{}"),
+            frame(10, RecordSource::NordWs, "a", "{}"),
+        ]);
+        assert_eq!(rec.start_ms(), 10);
+        assert_eq!(rec.end_ms(), 30);
+        assert!(rec.markets_info().is_err());
+    }
+
+    #[test]
+    fn test_snapshot_served_at_or_after_clock() {
+        let rec = Recording::from_frames(vec![
+            frame(
+                100,
+                RecordSource::NordRest,
+                "orderbook@BTCUSD",
+                &snapshot(1),
+            ),
+            frame(
+                200,
+                RecordSource::NordRest,
+                "orderbook@BTCUSD",
+                &snapshot(2),
+            ),
+        ]);
+        let clock = Clock::manual(0);
+        let snaps = ReplaySnapshots::new(&rec, clock.clone()).unwrap();
+
+        assert_eq!(snaps.orderbook("BTCUSD").unwrap().update_id, 1);
+        clock.set_ms(150);
+        assert_eq!(snaps.orderbook("BTCUSD").unwrap().update_id, 2);
+        clock.set_ms(500);
+        assert_eq!(snaps.orderbook("BTCUSD").unwrap().update_id, 2);
+        assert!(snaps.orderbook("ETHUSD").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_driver_routes_and_advances_clock() {
+        let rec = Recording::from_frames(vec![
+            frame(1_000, RecordSource::NordWs, "deltas@BTCUSD", "{}"),
+            frame(
+                1_001,
+                RecordSource::NordRest,
+                "orderbook@BTCUSD",
+                &snapshot(1),
+            ),
+            frame(1_002, RecordSource::Binance, "btcusdt@bookTicker", "{}"),
+            frame(1_003, RecordSource::Binance, "ethusdt@bookTicker", "{}"),
+        ]);
+        let mut driver = ReplayDriver::new(rec).unwrap();
+        let clock = driver.clock();
+        let ws = Collect::default();
+        let binance = Collect::default();
+        driver.add_sink(RecordSource::NordWs, None, ws.clone());
+        driver.add_sink(
+            RecordSource::Binance,
+            Some("btcusdt@bookTicker".into()),
+            binance.clone(),
+        );
+
+        let stats = driver.run(ReplaySpeed::Max).await;
+
+        assert_eq!(stats.delivered, 2);
+        assert_eq!(stats.unrouted, 1);
+        assert_eq!(stats.span_ms, 3);
+        assert_eq!(clock.now_ms(), 1_003);
+        assert_eq!(*ws.0.lock().unwrap(), vec![(1_000, "deltas@BTCUSD".into())]);
+        assert_eq!(
+            *binance.0.lock().unwrap(),
+            vec![(1_002, "btcusdt@bookTicker".into())]
+        );
+    }
+
+    /// Publishes into a `watch` channel, counted with a [`ReplayAck`].
+    #[derive(Clone)]
+    struct WatchSink {
+        tx: tokio::sync::watch::Sender<u64>,
+        ack: ReplayAck,
+    }
+
+    impl ReplaySink for WatchSink {
+        fn deliver(&self, frame: &RecordedFrame) {
+            self.ack.publish();
+            self.tx.send_replace(frame.recv_ms);
+        }
+
+        fn backlog(&self) -> usize {
+            self.ack.pending()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_max_speed_waits_for_watch_consumer() {
+        let rec = Recording::from_frames(
+            (1..=50)
+                .map(|ms| frame(ms, RecordSource::Binance, "a", "{}"))
+                .collect(),
+        );
+        let mut driver = ReplayDriver::new(rec).unwrap();
+        let (tx, mut rx) = tokio::sync::watch::channel(0);
+        let ack = ReplayAck::new();
+        driver.add_sink(
+            RecordSource::Binance,
+            None,
+            WatchSink {
+                tx,
+                ack: ack.clone(),
+            },
+        );
+        let replay = tokio::spawn(driver.run(ReplaySpeed::Max));
+
+        let mut seen = Vec::new();
+        while rx.changed().await.is_ok() {
+            let _ack = ack.observe();
+            seen.push(*rx.borrow_and_update());
+            // Slow consumer: yield a few times while handling the value.
+            for _ in 0..3 {
+                tokio::task::yield_now().await;
+            }
+        }
+        let stats = replay.await.unwrap();
+        assert_eq!(seen, (1..=50).collect::<Vec<_>>());
+        assert_eq!(stats.stalled, 0);
+    }
+
+    #[tokio::test]
+    async fn test_max_speed_stops_waiting_on_stuck_consumer() {
+        let rec = Recording::from_frames(
+            (1..=20)
+                .map(|ms| frame(ms, RecordSource::Binance, "a", "{}"))
+                .collect(),
+        );
+        let (tx, _rx) = tokio::sync::watch::channel(0);
+        let mut driver = ReplayDriver::new(rec)
+            .unwrap()
+            .with_consumer_timeout(Duration::from_millis(20));
+        driver.add_sink(
+            RecordSource::Binance,
+            None,
+            WatchSink {
+                tx,
+                ack: ReplayAck::new(),
+            },
+        );
+
+        let started = Instant::now();
+        let stats = driver.run(ReplaySpeed::Max).await;
+        assert_eq!(stats.delivered, 20);
+        // Only the second frame waits; the sink is skipped from then on.
+        assert_eq!(stats.stalled, 1);
+        assert!(started.elapsed() < Duration::from_secs(1));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_accelerated_speed_compresses_gaps() {
+        let rec = Recording::from_frames(vec![
+            frame(0, RecordSource::NordWs, "a", "{}"),
+            frame(10_000, RecordSource::NordWs, "a", "{}"),
+        ]);
+        let mut driver = ReplayDriver::new(rec).unwrap();
+        driver.add_sink(RecordSource::NordWs, None, Collect::default());
+
+        let started = tokio::time::Instant::now();
+        driver.run(ReplaySpeed::Accelerated(10.0)).await;
+        assert_eq!(started.elapsed(), Duration::from_secs(1));
+    }
+}
@@ -3,13 +3,19 @@ pub mod endpoints;
 use reqwest::Client;
 use serde::de::DeserializeOwned;
 
+use crate::clock::Clock;
 use crate::error::{NordError, Result};
+use crate::latency::LatencyTracker;
 
 /// HTTP client wrapper for the Nord REST API.
 #[derive(Debug, Clone)]
 pub struct NordHttpClient {
     client: Client,
     base_url: String,
+    /// Records action round trips; shared by clones.
+    latency: LatencyTracker,
+    /// Stamps latency samples (the replay clock when replaying).
+    clock: Clock,
 }
 
 impl NordHttpClient {
@@ -18,9 +24,27 @@ impl NordHttpClient {
         Self {
             client: Client::new(),
             base_url: base_url.trim_end_matches('/').to_string(),
+            latency: LatencyTracker::new(),
+            clock: Clock::System,
         }
     }
 
+    /// Stamp latency samples with `clock` instead of the system clock.
+    pub fn with_clock(mut self, clock: Clock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Time source of this client's latency samples.
+    pub fn clock(&self) -> &Clock {
+        &self.clock
+    }
+
+    /// Latency telemetry of this client and everything built from it.
+    pub fn latency(&self) -> &LatencyTracker {
+        &self.latency
+    }
+
     /// GET a JSON resource.
     pub async fn get<T: DeserializeOwned>(&self, path: &str, query: &[(&str, &str)]) -> Result<T> {
         let url = format!("{}{}", self.base_url, path);
@@ -1,6 +1,8 @@
 use std::collections::HashMap;
+use std::fmt;
 
-use serde::{Deserialize, Serialize};
+use serde::de::{self, IgnoredAny, MapAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize};
 
 use crate::types::{CandleResolution, Side};
 
@@ -106,3 +108,196 @@ pub enum WebSocketMessage {
     Account(WebSocketAccountUpdate),
     Candle(WebSocketCandleUpdate),
 }
+
+impl WebSocketMessage {
+    /// Decode a raw WebSocket text frame.
+    ///
+    /// Wrapped frames (`{"trades": ...}`, `{"delta": ...}`, `{"account": ...}`)
+    /// are deserialised straight into their typed payload in a single pass,
+    /// without building an intermediate `serde_json::Value`. Bare objects
+    /// carrying a candle resolution (`res`) are decoded again as a
+    /// [`WebSocketCandleUpdate`]; candles are low-rate, so the second pass is
+    /// off the hot path.
+    ///
+    /// Returns `Ok(None)` for well-formed JSON objects that match none of the
+    /// known shapes, and `Err` if the text is not valid JSON or a recognised
+    /// payload (including a candle with missing fields) fails to deserialise.
+    pub fn from_json(text: &str) -> serde_json::Result<Option<Self>> {
+        match serde_json::from_str::<Envelope>(text)? {
+            Envelope {
+                message: Some(message),
+                ..
+            } => Ok(Some(message)),
+            Envelope { candle: true, .. } => {
+                serde_json::from_str(text).map(|c| Some(Self::Candle(c)))
+            }
+            Envelope { .. } => Ok(None),
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Single-pass envelope decoding
+// ---------------------------------------------------------------------------
+
+/// Top-level frame keys. Matching on a field identifier avoids allocating a
+/// `String` per key.
+#[derive(Deserialize)]
+#[serde(field_identifier)]
+enum EnvelopeKey {
+    #[serde(rename = "trades")]
+    Trades,
+    #[serde(rename = "delta")]
+    Delta,
+    #[serde(rename = "account")]
+    Account,
+    /// The resolution of a [`WebSocketCandleUpdate`]. The other candle
+    /// fields (`t`, `o`, `c`, ...) are too generic to identify a candle.
+    #[serde(rename = "res")]
+    Candle,
+    #[serde(other)]
+    Other,
+}
+
+/// A frame after the envelope pass.
+struct Envelope {
+    /// The wrapped payload, fully decoded.
+    message: Option<WebSocketMessage>,
+    /// Whether a bare object carried `res`, to be decoded as a candle.
+    candle: bool,
+}
+
+impl<'de> Deserialize<'de> for Envelope {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_map(EnvelopeVisitor)
+    }
+}
+
+struct EnvelopeVisitor;
+
+impl<'de> Visitor<'de> for EnvelopeVisitor {
+    type Value = Envelope;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a WebSocket message object")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Envelope, A::Error> {
+        let mut candle = false;
+
+        while let Some(key) = map.next_key::<EnvelopeKey>()? {
+            let message = match key {
+                EnvelopeKey::Trades => WebSocketMessage::Trade(map.next_value()?),
+                EnvelopeKey::Delta => WebSocketMessage::Delta(map.next_value()?),
+                EnvelopeKey::Account => WebSocketMessage::Account(map.next_value()?),
+                EnvelopeKey::Candle | EnvelopeKey::Other => {
+                    candle |= matches!(key, EnvelopeKey::Candle);
+                    map.next_value::<IgnoredAny>()?;
+                    continue;
+                }
+            };
+
+            // Wrapped payload found; skip whatever else the frame carries.
+            while map.next_entry::<IgnoredAny, IgnoredAny>()?.is_some() {}
+            return Ok(Envelope {
+                message: Some(message),
+                candle: false,
+            });
+        }
+
+        Ok(Envelope {
+            message: None,
+            candle,
+        })
+    }
+
+    fn visit_seq<A: de::SeqAccess<'de>>(self, mut seq: A) -> Result<Envelope, A::Error> {
+        while seq.next_element::<IgnoredAny>()?.is_some() {}
+        Ok(Envelope {
+            message: None,
+            candle: false,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_wrapped_delta() {
+        let json = r#"{"delta":{"e":"delta","last_update_id":10,"update_id":11,
+            "market_symbol":"BTCUSD","asks":[{"price":101.5,"size":2.0}],
+            "bids":[{"price":100.5,"size":0.0}],"timestamp":1700000000000}}"#;
+        match WebSocketMessage::from_json(json).unwrap() {
+            Some(WebSocketMessage::Delta(d)) => {
+                assert_eq!(d.update_id, 11);
+                assert_eq!(d.market_symbol, "BTCUSD");
+                assert_eq!(d.asks.len(), 1);
+                assert_eq!(d.bids[0].size, 0.0);
+            }
+            other => panic!("expected delta, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_decode_wrapped_trades_ignores_trailing_keys() {
+        let json = r#"{"trades":{"last_update_id":1,"update_id":2,"market_symbol":"ETHUSD",
+            "trades":[{"side":"bid","price":3000.0,"size":0.5,"order_id":"7"}]},"extra":[1,2]}"#;
+        match WebSocketMessage::from_json(json).unwrap() {
+            Some(WebSocketMessage::Trade(t)) => {
+                assert_eq!(t.trades.len(), 1);
+                assert_eq!(t.trades[0].side, Side::Bid);
+            }
+            other => panic!("expected trades, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_decode_wrapped_account() {
+        let json = r#"{"account":{"last_update_id":0,"update_id":5,"account_id":42,
+            "fills":{},"places":{"9":{"side":"ask","current_size":1.0,"price":50.0,"market_id":3}},
+            "cancels":{},"balances":{"USDC":1000.0}}}"#;
+        match WebSocketMessage::from_json(json).unwrap() {
+            Some(WebSocketMessage::Account(a)) => {
+                assert_eq!(a.account_id, 42);
+                assert_eq!(a.places["9"].market_id, 3);
+                assert_eq!(a.balances["USDC"], 1000.0);
+            }
+            other => panic!("expected account, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_decode_bare_candle() {
+        let json = r#"{"res":"1","mid":100.0,"t":1700000000,"o":99.0,"h":101.0,"l":98.0,"c":100.5,"v":12.0}"#;
+        match WebSocketMessage::from_json(json).unwrap() {
+            Some(WebSocketMessage::Candle(c)) => {
+                assert_eq!(c.res, CandleResolution::OneMinute);
+                assert_eq!(c.t, 1_700_000_000);
+                assert_eq!(c.c, 100.5);
+            }
+            other => panic!("expected candle, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_unknown_shape_decodes_to_none() {
+        assert!(WebSocketMessage::from_json(r#"{"hello":"world"}"#)
+            .unwrap()
+            .is_none());
+        // Single-letter keys shared with candles do not make a candle.
+        assert!(WebSocketMessage::from_json(r#"{"t":1,"c":2.0,"x":"y"}"#)
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn test_malformed_frames_are_errors() {
+        assert!(WebSocketMessage::from_json("not json").is_err());
+        // Recognised wrapper whose payload does not match the schema.
+        assert!(WebSocketMessage::from_json(r#"{"delta":{"update_id":"x"}}"#).is_err());
+        // Candle with missing fields.
+        assert!(WebSocketMessage::from_json(r#"{"res":"1","mid":1.0}"#).is_err());
+    }
+}
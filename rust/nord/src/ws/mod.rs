@@ -1,67 +1,224 @@
 pub mod events;
 pub mod subscriber;
 
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
 use std::time::Duration;
 
 use futures_util::{SinkExt, StreamExt};
 use tokio::sync::broadcast;
 use tokio_tungstenite::tungstenite::Message;
-use tracing::{debug, error, info, warn};
+use tracing::{debug, info, warn};
 
+use crate::clock::Clock;
 use crate::error::NordError;
+use crate::latency::{LatencyTracker, NORD_WS_DELTAS};
+use crate::recorder::{RecordSource, RecordedFrame, Recorder};
+use crate::replay::ReplaySink;
 
 use events::*;
 
 /// WebSocket client for the Nord exchange.
 ///
 /// Manages a persistent connection with auto-reconnect and heartbeat.
-/// Dispatches typed messages via broadcast channels.
+/// Dispatches typed messages via broadcast channels. Each payload is decoded
+/// once and shared by all subscribers as an `Arc` rather than cloned per
+/// receiver.
 #[derive(Debug)]
 pub struct NordWebSocketClient {
     url: String,
-    trade_tx: broadcast::Sender<WebSocketTradeUpdate>,
-    delta_tx: broadcast::Sender<WebSocketDeltaUpdate>,
-    account_tx: broadcast::Sender<WebSocketAccountUpdate>,
-    candle_tx: broadcast::Sender<WebSocketCandleUpdate>,
+    dispatcher: Dispatcher,
     shutdown_tx: Option<tokio::sync::oneshot::Sender<()>>,
 }
 
-impl NordWebSocketClient {
-    /// Create a new WebSocket client (does not connect yet).
-    pub fn new(url: String) -> Self {
+/// Point-in-time snapshot of the frame counters kept by the dispatcher.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DispatchStats {
+    /// Trade frames published.
+    pub trades: u64,
+    /// Delta (orderbook) frames published.
+    pub deltas: u64,
+    /// Account frames published.
+    pub accounts: u64,
+    /// Candle frames published.
+    pub candles: u64,
+    /// Well-formed frames that matched no known message shape.
+    pub unknown: u64,
+    /// Frames that were not valid JSON or failed typed deserialisation.
+    pub malformed: u64,
+}
+
+/// Lock-free counters shared between the connection task and the client.
+#[derive(Debug, Default)]
+struct DispatchCounters {
+    trades: AtomicU64,
+    deltas: AtomicU64,
+    accounts: AtomicU64,
+    candles: AtomicU64,
+    unknown: AtomicU64,
+    malformed: AtomicU64,
+}
+
+impl DispatchCounters {
+    fn snapshot(&self) -> DispatchStats {
+        DispatchStats {
+            trades: self.trades.load(Ordering::Relaxed),
+            deltas: self.deltas.load(Ordering::Relaxed),
+            accounts: self.accounts.load(Ordering::Relaxed),
+            candles: self.candles.load(Ordering::Relaxed),
+            unknown: self.unknown.load(Ordering::Relaxed),
+            malformed: self.malformed.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Broadcast senders plus counters: decodes each text frame once and routes
+/// the typed payload to the matching channel.
+#[derive(Debug, Clone)]
+struct Dispatcher {
+    trade_tx: broadcast::Sender<Arc<WebSocketTradeUpdate>>,
+    delta_tx: broadcast::Sender<Arc<WebSocketDeltaUpdate>>,
+    account_tx: broadcast::Sender<Arc<WebSocketAccountUpdate>>,
+    candle_tx: broadcast::Sender<Arc<WebSocketCandleUpdate>>,
+    counters: Arc<DispatchCounters>,
+    /// Optional raw-frame tap, keyed by the subscribed stream list.
+    recorder: Option<(Recorder, String)>,
+    /// Optional delta latency telemetry, timed with the clock.
+    latency: Option<(LatencyTracker, Clock)>,
+}
+
+impl Dispatcher {
+    fn new() -> Self {
         let (trade_tx, _) = broadcast::channel(256);
         let (delta_tx, _) = broadcast::channel(256);
         let (account_tx, _) = broadcast::channel(256);
         let (candle_tx, _) = broadcast::channel(256);
 
         Self {
-            url,
             trade_tx,
             delta_tx,
             account_tx,
             candle_tx,
+            counters: Arc::new(DispatchCounters::default()),
+            recorder: None,
+            latency: None,
+        }
+    }
+
+    /// Decode a text frame in a single pass and publish it.
+    fn dispatch(&self, text: &str) {
+        if let Some((recorder, key)) = &self.recorder {
+            recorder.record(RecordSource::NordWs, key, text);
+        }
+        match WebSocketMessage::from_json(text) {
+            Ok(Some(WebSocketMessage::Delta(update))) => {
+                self.counters.deltas.fetch_add(1, Ordering::Relaxed);
+                if let Some((latency, clock)) = &self.latency {
+                    latency.record_event(NORD_WS_DELTAS, update.timestamp, clock.now_ms());
+                }
+                let _ = self.delta_tx.send(Arc::new(update));
+            }
+            Ok(Some(WebSocketMessage::Trade(update))) => {
+                self.counters.trades.fetch_add(1, Ordering::Relaxed);
+                let _ = self.trade_tx.send(Arc::new(update));
+            }
+            Ok(Some(WebSocketMessage::Account(update))) => {
+                self.counters.accounts.fetch_add(1, Ordering::Relaxed);
+                let _ = self.account_tx.send(Arc::new(update));
+            }
+            Ok(Some(WebSocketMessage::Candle(update))) => {
+                self.counters.candles.fetch_add(1, Ordering::Relaxed);
+                let _ = self.candle_tx.send(Arc::new(update));
+            }
+            Ok(None) => {
+                self.counters.unknown.fetch_add(1, Ordering::Relaxed);
+            }
+            Err(e) => {
+                let seen = self.counters.malformed.fetch_add(1, Ordering::Relaxed) + 1;
+                // Warn on the 1st, 10th, 100th, ... malformed frame so a
+                // misbehaving feed cannot flood the log.
+                if is_power_of_ten(seen) {
+                    warn!("failed to decode WebSocket message ({seen} so far): {e}");
+                } else {
+                    debug!("failed to decode WebSocket message: {e}");
+                }
+            }
+        }
+    }
+}
+
+impl ReplaySink for Dispatcher {
+    fn deliver(&self, frame: &RecordedFrame) {
+        self.dispatch(&frame.frame);
+    }
+
+    fn backlog(&self) -> usize {
+        self.trade_tx
+            .len()
+            .max(self.delta_tx.len())
+            .max(self.account_tx.len())
+            .max(self.candle_tx.len())
+    }
+}
+
+impl NordWebSocketClient {
+    /// Create a new WebSocket client (does not connect yet).
+    pub fn new(url: String) -> Self {
+        Self {
+            url,
+            dispatcher: Dispatcher::new(),
             shutdown_tx: None,
         }
     }
 
     /// Subscribe to trade updates.
-    pub fn subscribe_trades(&self) -> broadcast::Receiver<WebSocketTradeUpdate> {
-        self.trade_tx.subscribe()
+    pub fn subscribe_trades(&self) -> broadcast::Receiver<Arc<WebSocketTradeUpdate>> {
+        self.dispatcher.trade_tx.subscribe()
     }
 
     /// Subscribe to delta (orderbook) updates.
-    pub fn subscribe_deltas(&self) -> broadcast::Receiver<WebSocketDeltaUpdate> {
-        self.delta_tx.subscribe()
+    pub fn subscribe_deltas(&self) -> broadcast::Receiver<Arc<WebSocketDeltaUpdate>> {
+        self.dispatcher.delta_tx.subscribe()
     }
 
     /// Subscribe to account updates.
-    pub fn subscribe_accounts(&self) -> broadcast::Receiver<WebSocketAccountUpdate> {
-        self.account_tx.subscribe()
+    pub fn subscribe_accounts(&self) -> broadcast::Receiver<Arc<WebSocketAccountUpdate>> {
+        self.dispatcher.account_tx.subscribe()
     }
 
     /// Subscribe to candle updates.
-    pub fn subscribe_candles(&self) -> broadcast::Receiver<WebSocketCandleUpdate> {
-        self.candle_tx.subscribe()
+    pub fn subscribe_candles(&self) -> broadcast::Receiver<Arc<WebSocketCandleUpdate>> {
+        self.dispatcher.candle_tx.subscribe()
+    }
+
+    /// Record every received text frame (before decoding) to `recorder`.
+    ///
+    /// Must be called before [`connect`](Self::connect) to take effect.
+    pub fn set_recorder(&mut self, recorder: Recorder) {
+        self.dispatcher.recorder = Some((recorder, stream_key(&self.url)));
+    }
+
+    /// Record the latency of every delta (its `timestamp` to the receive
+    /// time on `clock`) into `latency`.
+    ///
+    /// Must be called before [`connect`](Self::connect) to take effect.
+    pub fn set_latency(&mut self, latency: LatencyTracker, clock: Clock) {
+        self.dispatcher.latency = Some((latency, clock));
+    }
+
+    /// A sink that publishes replayed frames into this client's channels, as
+    /// if they had arrived on the socket. Do not [`connect`](Self::connect)
+    /// a client that is being fed by a replay.
+    pub fn replay_sink(&self) -> impl ReplaySink {
+        let mut dispatcher = self.dispatcher.clone();
+        dispatcher.recorder = None;
+        dispatcher
+    }
+
+    /// Frame counters since the client was created, including frames that
+    /// matched no known message type.
+    pub fn dispatch_stats(&self) -> DispatchStats {
+        self.dispatcher.counters.snapshot()
     }
 
     /// Connect and start processing messages in the background.
@@ -70,24 +227,12 @@ impl NordWebSocketClient {
         self.shutdown_tx = Some(shutdown_tx);
 
         let url = self.url.clone();
-        let trade_tx = self.trade_tx.clone();
-        let delta_tx = self.delta_tx.clone();
-        let account_tx = self.account_tx.clone();
-        let candle_tx = self.candle_tx.clone();
+        let dispatcher = self.dispatcher.clone();
 
         tokio::spawn(async move {
             let mut shutdown_rx = shutdown_rx;
             loop {
-                match Self::run_connection(
-                    &url,
-                    &trade_tx,
-                    &delta_tx,
-                    &account_tx,
-                    &candle_tx,
-                    &mut shutdown_rx,
-                )
-                .await
-                {
+                match Self::run_connection(&url, &dispatcher, &mut shutdown_rx).await {
                     Ok(()) => {
                         info!("WebSocket connection closed gracefully");
                         break;
@@ -103,10 +248,7 @@ impl NordWebSocketClient {
 
     async fn run_connection(
         url: &str,
-        trade_tx: &broadcast::Sender<WebSocketTradeUpdate>,
-        delta_tx: &broadcast::Sender<WebSocketDeltaUpdate>,
-        account_tx: &broadcast::Sender<WebSocketAccountUpdate>,
-        candle_tx: &broadcast::Sender<WebSocketCandleUpdate>,
+        dispatcher: &Dispatcher,
         shutdown_rx: &mut tokio::sync::oneshot::Receiver<()>,
     ) -> std::result::Result<(), NordError> {
         let (ws_stream, _) = tokio_tungstenite::connect_async(url)
@@ -139,13 +281,7 @@ impl NordWebSocketClient {
                 msg = read.next() => {
                     match msg {
                         Some(Ok(Message::Text(text))) => {
-                            Self::dispatch_message(
-                                &text,
-                                trade_tx,
-                                delta_tx,
-                                account_tx,
-                                candle_tx,
-                            );
+                            dispatcher.dispatch(&text);
                         }
                         Some(Ok(Message::Pong(_))) => {
                             pong_timeout = None;
@@ -169,50 +305,6 @@ impl NordWebSocketClient {
         }
     }
 
-    fn dispatch_message(
-        text: &str,
-        trade_tx: &broadcast::Sender<WebSocketTradeUpdate>,
-        delta_tx: &broadcast::Sender<WebSocketDeltaUpdate>,
-        account_tx: &broadcast::Sender<WebSocketAccountUpdate>,
-        candle_tx: &broadcast::Sender<WebSocketCandleUpdate>,
-    ) {
-        // Try to determine message type from JSON structure.
-        if let Ok(value) = serde_json::from_str::<serde_json::Value>(text) {
-            // Wrapped messages: { "trades": ... }, { "delta": ... }, { "account": ... }
-            if let Some(trades) = value.get("trades") {
-                if let Ok(update) = serde_json::from_value::<WebSocketTradeUpdate>(trades.clone()) {
-                    let _ = trade_tx.send(update);
-                    return;
-                }
-            }
-            if let Some(delta) = value.get("delta") {
-                if let Ok(update) = serde_json::from_value::<WebSocketDeltaUpdate>(delta.clone()) {
-                    let _ = delta_tx.send(update);
-                    return;
-                }
-            }
-            if let Some(account) = value.get("account") {
-                if let Ok(update) =
-                    serde_json::from_value::<WebSocketAccountUpdate>(account.clone())
-                {
-                    let _ = account_tx.send(update);
-                    return;
-                }
-            }
-            // Candle updates are sent as bare objects with "res" field.
-            if value.get("res").is_some() {
-                if let Ok(update) = serde_json::from_value::<WebSocketCandleUpdate>(value) {
-                    let _ = candle_tx.send(update);
-                    return;
-                }
-            }
-
-            debug!("unrecognized WebSocket message: {text}");
-        } else {
-            error!("failed to parse WebSocket message as JSON: {text}");
-        }
-    }
-
     /// Close the WebSocket connection.
     pub fn close(mut self) {
         if let Some(tx) = self.shutdown_tx.take() {
@@ -220,3 +312,63 @@ impl NordWebSocketClient {
         }
     }
 }
+
+/// The subscribed stream list of a Nord WebSocket URL
+/// (`wss://host/ws/deltas@BTCUSD&trades@BTCUSD` → `deltas@BTCUSD&trades@BTCUSD`).
+fn stream_key(url: &str) -> String {
+    url.split_once("/ws/")
+        .map(|(_, streams)| streams)
+        .unwrap_or(url)
+        .to_string()
+}
+
+/// Whether `n` is 1, 10, 100, ...
+fn is_power_of_ten(mut n: u64) -> bool {
+    while n >= 10 && n.is_multiple_of(10) {
+        n /= 10;
+    }
+    n == 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dispatch_routes_and_counts() {
+        let dispatcher = Dispatcher::new();
+        let mut delta_rx = dispatcher.delta_tx.subscribe();
+
+        dispatcher.dispatch(
+            r#"{"delta":{"e":"delta","last_update_id":1,"update_id":2,"market_symbol":"BTCUSD",
+                "asks":[],"bids":[{"price":100.0,"size":1.0}],"timestamp":0}}"#,
+        );
+        dispatcher.dispatch(r#"{"subscribed":["deltas@BTCUSD"]}"#);
+        dispatcher.dispatch(r#"{"t":1,"o":"pong"}"#);
+        dispatcher.dispatch("garbage");
+
+        let update = delta_rx.try_recv().unwrap();
+        assert_eq!(update.update_id, 2);
+
+        let stats = dispatcher.counters.snapshot();
+        assert_eq!(stats.deltas, 1);
+        assert_eq!(stats.unknown, 2);
+        assert_eq!(stats.malformed, 1);
+        assert_eq!(stats.trades + stats.accounts + stats.candles, 0);
+    }
+
+    #[test]
+    fn test_is_power_of_ten() {
+        let hits: Vec<u64> = (0..=1000).filter(|&n| is_power_of_ten(n)).collect();
+        assert_eq!(hits, vec![1, 10, 100, 1000]);
+    }
+
+    #[test]
+    fn test_stream_key() {
+        assert_eq!(
+            stream_key("wss://zo-mainnet.n1.xyz/ws/deltas@BTCUSD&account@7"),
+            "deltas@BTCUSD&account@7"
+        );
+        assert_eq!(stream_key("ws://localhost:1234"), "ws://localhost:1234");
+    }
+}
@@ -1,20 +1,22 @@
+use std::sync::Arc;
+
 use tokio::sync::broadcast;
 
 use super::events::*;
 
 /// Typed subscription for orderbook delta updates.
 pub struct OrderbookSubscription {
-    rx: broadcast::Receiver<WebSocketDeltaUpdate>,
+    rx: broadcast::Receiver<Arc<WebSocketDeltaUpdate>>,
 }
 
 impl OrderbookSubscription {
     /// Create a new orderbook subscription from a broadcast receiver.
-    pub fn new(rx: broadcast::Receiver<WebSocketDeltaUpdate>) -> Self {
+    pub fn new(rx: broadcast::Receiver<Arc<WebSocketDeltaUpdate>>) -> Self {
         Self { rx }
     }
 
     /// Receive the next update. Returns `None` if the channel is closed.
-    pub async fn next(&mut self) -> Option<WebSocketDeltaUpdate> {
+    pub async fn next(&mut self) -> Option<Arc<WebSocketDeltaUpdate>> {
         loop {
             match self.rx.recv().await {
                 Ok(msg) => return Some(msg),
@@ -30,17 +32,17 @@ impl OrderbookSubscription {
 
 /// Typed subscription for trade updates.
 pub struct TradeSubscription {
-    rx: broadcast::Receiver<WebSocketTradeUpdate>,
+    rx: broadcast::Receiver<Arc<WebSocketTradeUpdate>>,
 }
 
 impl TradeSubscription {
     /// Create a new trade subscription from a broadcast receiver.
-    pub fn new(rx: broadcast::Receiver<WebSocketTradeUpdate>) -> Self {
+    pub fn new(rx: broadcast::Receiver<Arc<WebSocketTradeUpdate>>) -> Self {
         Self { rx }
     }
 
     /// Receive the next update. Returns `None` if the channel is closed.
-    pub async fn next(&mut self) -> Option<WebSocketTradeUpdate> {
+    pub async fn next(&mut self) -> Option<Arc<WebSocketTradeUpdate>> {
         loop {
             match self.rx.recv().await {
                 Ok(msg) => return Some(msg),
@@ -56,17 +58,17 @@ impl TradeSubscription {
 
 /// Typed subscription for account updates.
 pub struct AccountSubscription {
-    rx: broadcast::Receiver<WebSocketAccountUpdate>,
+    rx: broadcast::Receiver<Arc<WebSocketAccountUpdate>>,
 }
 
 impl AccountSubscription {
     /// Create a new account subscription from a broadcast receiver.
-    pub fn new(rx: broadcast::Receiver<WebSocketAccountUpdate>) -> Self {
+    pub fn new(rx: broadcast::Receiver<Arc<WebSocketAccountUpdate>>) -> Self {
         Self { rx }
     }
 
     /// Receive the next update. Returns `None` if the channel is closed.
-    pub async fn next(&mut self) -> Option<WebSocketAccountUpdate> {
+    pub async fn next(&mut self) -> Option<Arc<WebSocketAccountUpdate>> {
         loop {
             match self.rx.recv().await {
                 Ok(msg) => return Some(msg),
@@ -82,17 +84,17 @@ impl AccountSubscription {
 
 /// Typed subscription for candle updates.
 pub struct CandleSubscription {
-    rx: broadcast::Receiver<WebSocketCandleUpdate>,
+    rx: broadcast::Receiver<Arc<WebSocketCandleUpdate>>,
 }
 
 impl CandleSubscription {
     /// Create a new candle subscription from a broadcast receiver.
-    pub fn new(rx: broadcast::Receiver<WebSocketCandleUpdate>) -> Self {
+    pub fn new(rx: broadcast::Receiver<Arc<WebSocketCandleUpdate>>) -> Self {
         Self { rx }
     }
 
     /// Receive the next update. Returns `None` if the channel is closed.
-    pub async fn next(&mut self) -> Option<WebSocketCandleUpdate> {
+    pub async fn next(&mut self) -> Option<Arc<WebSocketCandleUpdate>> {
         loop {
             match self.rx.recv().await {
                 Ok(msg) => return Some(msg),
@@ -1,5 +1,11 @@
+use clap::builder::{PossibleValuesParser, TypedValueParser};
 use clap::{Parser, Subcommand};
 
+use crate::composite::CompositeMethod;
+use crate::fair_price::FairPriceMethod;
+use crate::source::Venue;
+use crate::zo_feed::FeedVenue;
+
 /// zo — unified CLI for the zo market maker project.
 #[derive(Parser, Debug)]
 #[command(name = "zo", version)]
@@ -14,7 +20,8 @@ pub struct Cli {
 
 #[derive(Subcommand, Debug)]
 pub enum Command {
-    /// Stream Binance Futures best bid/ask/mid prices
+    /// Stream best bid/ask/mid prices and trades from Binance Futures and/or
+    /// 01 Exchange
     Feed(FeedArgs),
 
     /// Run the market maker bot
@@ -22,18 +29,48 @@ pub enum Command {
 
     /// Launch the market monitor TUI
     Monitor(MonitorArgs),
+
+    /// Record raw 01 Exchange and Binance market data to disk
+    Record(RecordArgs),
+
+    /// Rerun the market maker (dry run) against recorded market data
+    Replay(ReplayArgs),
 }
 
 /// Arguments for the `feed` subcommand.
 #[derive(Parser, Debug)]
 pub struct FeedArgs {
-    /// Trading pair symbols (e.g. btcusdt ethusdt solusdt)
+    /// Binance trading pairs (e.g. btcusdt ethusdt), or 01 market symbol
+    /// prefixes (e.g. BTC ETH) with --venue zo or both
     #[arg(required = true)]
     pub symbols: Vec<String>,
 
+    /// Venue to stream; `both` interleaves each 01 market with its Binance
+    /// reference
+    #[arg(long, value_enum, default_value_t = FeedVenue::Binance)]
+    pub venue: FeedVenue,
+
     /// Output as JSON instead of TSV
     #[arg(long)]
     pub json: bool,
+
+    /// Also print Binance partial book depth with this many levels (5, 10 or
+    /// 20)
+    #[arg(
+        long,
+        value_parser = PossibleValuesParser::new(["5", "10", "20"])
+            .map(|levels| levels.parse::<u8>().expect("possible values are numeric")),
+    )]
+    pub depth: Option<u8>,
+
+    /// Also print trades with the rolling taker flow
+    #[arg(long)]
+    pub trades: bool,
+
+    /// JSON file of per-market venue symbols, merged over the built-in map
+    /// (used to find each 01 market's Binance instrument)
+    #[arg(long)]
+    pub symbol_map: Option<std::path::PathBuf>,
 }
 
 /// Arguments for the `market-maker` subcommand.
@@ -70,13 +107,100 @@ pub struct MarketMakerArgs {
     #[arg(long, default_value = "3000")]
     pub order_sync_interval_ms: u64,
 
-    /// Fair price sample window (ms)
+    /// Fair price model
+    #[arg(long, value_enum, default_value_t = FairPriceMethod::Median)]
+    pub fair_price_method: FairPriceMethod,
+
+    /// Fair price sample window for median and trimmed-mean (ms)
     #[arg(long, default_value = "300000")]
     pub fair_price_window_ms: u64,
 
+    /// Fraction of samples trimmed from each tail by trimmed-mean
+    #[arg(long, default_value = "0.1")]
+    pub fair_price_trim: f64,
+
+    /// Half-life of the ewma fair price offset (ms)
+    #[arg(long, default_value = "30000")]
+    pub fair_price_half_life_ms: u64,
+
+    /// Kalman process noise: basis drift variance per second (bps²)
+    #[arg(long, default_value = "0.01")]
+    pub fair_price_kalman_process_var: f64,
+
+    /// Kalman measurement noise: variance of one offset sample (bps²)
+    #[arg(long, default_value = "1.0")]
+    pub fair_price_kalman_measurement_var: f64,
+
+    /// Minimum spread as a multiple of reference volatility (0 = off)
+    #[arg(long, default_value = "0")]
+    pub vol_spread_multiplier: f64,
+
+    /// Horizon of the volatility used for the spread (ms)
+    #[arg(long, default_value = "10000")]
+    pub vol_spread_horizon_ms: u64,
+
+    /// Fair price standard errors added to the spread (0 = off)
+    #[arg(long, default_value = "0")]
+    pub confidence_z: f64,
+
+    /// File to save fair price samples to, restored on start to skip the
+    /// warmup after a quick restart
+    #[arg(long)]
+    pub fair_price_snapshot: Option<std::path::PathBuf>,
+
+    /// Interval between fair price snapshots (ms)
+    #[arg(long, default_value = "60000")]
+    pub fair_price_snapshot_interval_ms: u64,
+
+    /// Pull quotes when a sample's offset is this far from the model (bps,
+    /// 0 = off)
+    #[arg(long, default_value = "20")]
+    pub breaker_offset_bps: f64,
+
+    /// Pull quotes when the reference moves this far within
+    /// --breaker-jump-window-ms (bps, 0 = off)
+    #[arg(long, default_value = "50")]
+    pub breaker_jump_bps: f64,
+
+    /// Lookback of the reference jump check (ms)
+    #[arg(long, default_value = "1000")]
+    pub breaker_jump_window_ms: u64,
+
+    /// Pull quotes when the 01 mid is this far from the reference mid (bps,
+    /// 0 = off)
+    #[arg(long, default_value = "100")]
+    pub breaker_divergence_bps: f64,
+
+    /// How long quotes stay pulled after the breaker last tripped (ms)
+    #[arg(long, default_value = "5000")]
+    pub breaker_cooldown_ms: u64,
+
+    /// How long a quiet feed's last mid is carried forward when aligning
+    /// the 01 and reference prices (ms)
+    #[arg(long, default_value = "5000")]
+    pub align_tolerance_ms: u64,
+
+    /// Interpolate between price updates when aligning instead of taking the
+    /// last one
+    #[arg(long)]
+    pub align_interpolate: bool,
+
     /// Interval for position sync from the server (ms)
     #[arg(long, default_value = "5000")]
     pub position_sync_interval_ms: u64,
+
+    /// Interval for auditing the local orderbook against REST (ms, 0 = off)
+    #[arg(long, default_value = "30000")]
+    pub book_audit_interval_ms: u64,
+
+    /// File the latency percentiles are written to as JSON each time they
+    /// are logged, for a metrics agent to pick up
+    #[arg(long)]
+    pub latency_file: Option<std::path::PathBuf>,
+
+    /// Reference price settings
+    #[command(flatten)]
+    pub reference: ReferenceArgs,
 }
 
 /// Arguments for the `monitor` subcommand.
@@ -84,4 +208,128 @@ pub struct MarketMakerArgs {
 pub struct MonitorArgs {
     /// Market symbol prefix (e.g. BTC, ETH, SOL)
     pub symbol: String,
+
+    /// Account whose resting orders are shown apart from everyone else's
+    /// liquidity (ex-own BBO and share of the top level)
+    #[arg(long)]
+    pub account_id: Option<u32>,
+
+    /// Also show the size-weighted mid of this many Binance depth levels (5,
+    /// 10 or 20)
+    #[arg(
+        long,
+        value_parser = PossibleValuesParser::new(["5", "10", "20"])
+            .map(|levels| levels.parse::<u8>().expect("possible values are numeric")),
+    )]
+    pub binance_depth: Option<u8>,
+
+    /// Also show the rolling Binance taker flow
+    #[arg(long)]
+    pub binance_trades: bool,
+
+    /// Reference price settings
+    #[command(flatten)]
+    pub reference: ReferenceArgs,
+}
+
+/// Arguments for the `record` subcommand.
+#[derive(Parser, Debug)]
+pub struct RecordArgs {
+    /// Market symbol prefixes (e.g. BTC ETH SOL)
+    #[arg(required = true)]
+    pub symbols: Vec<String>,
+
+    /// Output directory for recording files
+    #[arg(long, default_value = "recordings")]
+    pub dir: String,
+
+    /// Rotate files after this many uncompressed megabytes
+    #[arg(long, default_value = "256")]
+    pub rotate_mb: u64,
+
+    /// Rotate files after this many minutes
+    #[arg(long, default_value = "60")]
+    pub rotate_minutes: u64,
+
+    /// JSON file of per-market venue symbols, merged over the built-in map
+    /// (used to find each market's Binance instrument)
+    #[arg(long)]
+    pub symbol_map: Option<std::path::PathBuf>,
+}
+
+/// Arguments for the `replay` subcommand.
+#[derive(Parser, Debug)]
+pub struct ReplayArgs {
+    /// Directory containing recording files (from `zo record`)
+    #[arg(long, default_value = "recordings")]
+    pub dir: String,
+
+    /// Replay speed: "max", "original", or a multiplier such as "10x"
+    #[arg(long, default_value = "max")]
+    pub speed: nord::ReplaySpeed,
+
+    /// Score every fair price model on the replayed basis samples
+    #[arg(long)]
+    pub compare_fair_price: bool,
+
+    /// Strategy parameters (same as `market-maker`)
+    #[command(flatten)]
+    pub mm: MarketMakerArgs,
+}
+
+/// Reference price arguments shared by `market-maker` and `monitor`.
+#[derive(Parser, Debug)]
+pub struct ReferenceArgs {
+    /// Venues providing the reference price; several are combined
+    /// (e.g. binance,bybit,okx) [default: the market's venue in the symbol
+    /// map, else binance]
+    #[arg(long, value_enum, value_delimiter = ',')]
+    pub reference_venue: Vec<Venue>,
+
+    /// Reference instrument as VENUE=SYMBOL in the venue's format, without a
+    /// price multiplier (default: from the symbol map, else derived from the
+    /// market symbol, e.g. BTCUSDT on Bybit)
+    #[arg(long, value_parser = parse_venue_pair::<String>)]
+    pub reference_symbol: Vec<(Venue, String)>,
+
+    /// JSON file of per-market venue symbols and price multipliers, merged
+    /// over the built-in map
+    #[arg(long)]
+    pub symbol_map: Option<std::path::PathBuf>,
+
+    /// Maximum distance between the reference and the index price at
+    /// startup (bps)
+    #[arg(long, default_value = "500")]
+    pub reference_max_index_deviation_bps: f64,
+
+    /// How several venues are combined
+    #[arg(long, value_enum, default_value_t = CompositeMethod::Median)]
+    pub reference_method: CompositeMethod,
+
+    /// Weight of a venue as VENUE=WEIGHT for weighted-mean (default 1)
+    #[arg(long, value_parser = parse_venue_pair::<f64>)]
+    pub reference_weight: Vec<(Venue, f64)>,
+
+    /// Age after which a venue's price is excluded (ms)
+    #[arg(long, default_value = "2000")]
+    pub reference_stale_ms: u64,
+
+    /// Distance from the median beyond which a venue is excluded; two
+    /// venues further apart than this are both excluded (bps)
+    #[arg(long, default_value = "50")]
+    pub reference_outlier_bps: f64,
+}
+
+/// Parse `VENUE=VALUE` (e.g. `bybit=BTCUSDT`).
+fn parse_venue_pair<T>(s: &str) -> Result<(Venue, T), String>
+where
+    T: std::str::FromStr,
+    T::Err: std::fmt::Display,
+{
+    let (venue, value) = s
+        .split_once('=')
+        .ok_or_else(|| format!("expected VENUE=VALUE, got \"{s}\""))?;
+    let venue = <Venue as clap::ValueEnum>::from_str(venue, true)?;
+    let value = value.parse().map_err(|e| format!("{value}: {e}"))?;
+    Ok((venue, value))
 }
@@ -30,6 +30,26 @@ pub fn mainnet_config() -> NordConfig {
     }
 }
 
+/// Resolve market symbol prefixes (e.g. `"BTC"`) to the first market whose
+/// symbol starts with each.
+///
+/// # Errors
+///
+/// Returns [`ZoError::MarketNotFound`] naming the first prefix that matches
+/// no market.
+pub fn resolve_markets(nord: &Nord, prefixes: &[String]) -> Result<Vec<String>, ZoError> {
+    prefixes
+        .iter()
+        .map(|prefix| {
+            nord.markets
+                .iter()
+                .find(|m| m.symbol.to_uppercase().starts_with(&prefix.to_uppercase()))
+                .map(|m| m.symbol.clone())
+                .ok_or_else(|| ZoError::MarketNotFound(prefix.clone()))
+        })
+        .collect()
+}
+
 /// Create a fully-initialised exchange client from a bs58-encoded private key.
 ///
 /// This:
@@ -0,0 +1,513 @@
+//! Composite reference price across several venues.
+//!
+//! A single reference venue is a single point of failure: one bad print moves
+//! the fair price, and an outage stalls quoting. [`CompositePriceSource`]
+//! combines several [`PriceSource`]s into one mid and is itself a
+//! [`PriceSource`], so it drops into the fair-price pipeline unchanged:
+//!
+//! ```text
+//!   Binance  --+
+//!   Bybit    --+--> drop stale --> drop outliers --> median / weighted mean
+//!   OKX      --+     (per venue)    (vs. median)            |
+//!                                                            v
+//!                                               watch<Option<MidPrice>>
+//! ```
+//!
+//! The composite is recomputed on every member update and on a timer, so a
+//! venue that disconnects (and therefore stops updating) is excluded once its
+//! last price exceeds its staleness cutoff, even if no other venue updates.
+//! A single reference venue is wrapped in a composite too, for that cutoff.
+//!
+//! Outliers need a majority: with three or more fresh venues, those too far
+//! from the median are dropped. Two venues that disagree cannot be told
+//! apart, so both are dropped and nothing is published until they agree.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use tokio::sync::{mpsc, watch};
+use tokio::time;
+use tokio_util::sync::CancellationToken;
+use tracing::{info, warn};
+
+use crate::source::{build_source, PriceSource, Venue};
+use crate::symbols::{SymbolMapping, SymbolRegistry};
+
+/// Interval at which the composite is recomputed without member updates.
+const RECOMPUTE_INTERVAL: Duration = Duration::from_millis(250);
+
+/// How the fresh, non-outlier venue prices are combined.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum CompositeMethod {
+    /// Median of the venues' bids and asks.
+    #[default]
+    Median,
+    /// Mean weighted by the per-venue weights.
+    WeightedMean,
+}
+
+/// Aggregation parameters.
+#[derive(Debug, Clone)]
+pub struct CompositeConfig {
+    pub method: CompositeMethod,
+    /// Age (ms) after which a venue's price is excluded.
+    pub stale_ms: u64,
+    /// Per-venue overrides of [`stale_ms`](Self::stale_ms).
+    pub stale_ms_by_venue: HashMap<Venue, u64>,
+    /// Distance (bps) from the median mid beyond which a venue is an outlier
+    /// (with three or more fresh venues). Two fresh venues further apart
+    /// than this are both outliers.
+    pub outlier_bps: f64,
+    /// Weights for [`CompositeMethod::WeightedMean`] (missing venues = 1.0).
+    pub weights: HashMap<Venue, f64>,
+    /// Minimum number of venues required to publish a price.
+    pub min_venues: usize,
+}
+
+impl Default for CompositeConfig {
+    fn default() -> Self {
+        Self {
+            method: CompositeMethod::Median,
+            stale_ms: 2_000,
+            stale_ms_by_venue: HashMap::new(),
+            outlier_bps: 50.0,
+            weights: HashMap::new(),
+            min_venues: 1,
+        }
+    }
+}
+
+impl CompositeConfig {
+    fn stale_ms(&self, venue: Venue) -> u64 {
+        self.stale_ms_by_venue
+            .get(&venue)
+            .copied()
+            .unwrap_or(self.stale_ms)
+    }
+
+    fn weight(&self, venue: Venue) -> f64 {
+        self.weights.get(&venue).copied().unwrap_or(1.0)
+    }
+}
+
+/// Venues making up the reference price of a market.
+#[derive(Debug, Clone)]
+pub struct ReferenceConfig {
+    /// Venues combined into the reference price. Empty = the market's
+    /// default venue from the registry.
+    pub venues: Vec<Venue>,
+    /// Instrument overrides in each venue's format, taken without a
+    /// multiplier (default: the registry mapping).
+    pub symbols: HashMap<Venue, String>,
+    /// Symbol mappings and multipliers per market.
+    pub registry: SymbolRegistry,
+    /// Maximum startup distance (bps) between the reference and the
+    /// exchange's index price.
+    pub max_index_deviation_bps: f64,
+    pub composite: CompositeConfig,
+}
+
+impl Default for ReferenceConfig {
+    fn default() -> Self {
+        Self {
+            venues: Vec::new(),
+            symbols: HashMap::new(),
+            registry: SymbolRegistry::builtin(),
+            max_index_deviation_bps: 500.0,
+            composite: CompositeConfig::default(),
+        }
+    }
+}
+
+impl ReferenceConfig {
+    /// Venues used for `market_symbol`.
+    pub fn venues_for(&self, market_symbol: &str) -> Vec<Venue> {
+        if self.venues.is_empty() {
+            vec![self.registry.default_venue(market_symbol)]
+        } else {
+            self.venues.clone()
+        }
+    }
+
+    /// Instrument of `market_symbol` on `venue`, with overrides applied.
+    pub fn mapping(&self, market_symbol: &str, venue: Venue) -> SymbolMapping {
+        match self.symbols.get(&venue) {
+            Some(symbol) => SymbolMapping {
+                venue,
+                symbol: symbol.clone(),
+                multiplier: 1.0,
+            },
+            None => self.registry.mapping(market_symbol, venue),
+        }
+    }
+
+    /// Short label for displays: the venue name, or `"Ref"` for a composite.
+    pub fn label(&self, market_symbol: &str) -> String {
+        match self.venues_for(market_symbol).as_slice() {
+            [venue] => venue.to_string(),
+            _ => "Ref".to_string(),
+        }
+    }
+}
+
+/// Build the (unconnected) reference source for `market_symbol`, recording
+/// each venue's feed latency into `latency`.
+pub fn build_reference(
+    config: &ReferenceConfig,
+    market_symbol: &str,
+    latency: &nord::LatencyTracker,
+) -> Box<dyn PriceSource> {
+    let members: Vec<(Venue, Box<dyn PriceSource>)> = config
+        .venues_for(market_symbol)
+        .into_iter()
+        .map(|venue| {
+            let mapping = config.mapping(market_symbol, venue);
+            (venue, build_source(&mapping, latency))
+        })
+        .collect();
+    Box::new(CompositePriceSource::new(members, config.composite.clone()))
+}
+
+// ---------------------------------------------------------------------------
+// Aggregation (pure)
+// ---------------------------------------------------------------------------
+
+/// Why a venue did not contribute to the composite.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Exclusion {
+    /// No price yet, or the feed disconnected before the first update.
+    NoPrice,
+    /// Last price older than the venue's staleness cutoff.
+    Stale,
+    /// Too far from the median of all fresh venues, or, with only two,
+    /// too far from the other one.
+    Outlier,
+}
+
+/// Combine the latest price of each venue at `now_ms`.
+///
+/// Returns the exclusions alongside `None` when fewer than
+/// [`min_venues`](CompositeConfig::min_venues) venues remain.
+pub fn combine(
+    quotes: &[(Venue, Option<nord::MidPrice>)],
+    now_ms: u64,
+    config: &CompositeConfig,
+) -> (Option<nord::MidPrice>, Vec<(Venue, Exclusion)>) {
+    let mut excluded = Vec::new();
+    let mut fresh: Vec<(Venue, nord::MidPrice)> = Vec::new();
+    for &(venue, price) in quotes {
+        match price {
+            None => excluded.push((venue, Exclusion::NoPrice)),
+            Some(p) if now_ms.saturating_sub(p.timestamp) > config.stale_ms(venue) => {
+                excluded.push((venue, Exclusion::Stale));
+            }
+            Some(p) => fresh.push((venue, p)),
+        }
+    }
+
+    if let [(a, pa), (b, pb)] = fresh[..] {
+        let center = (pa.mid + pb.mid) * 0.5;
+        if ((pa.mid - pb.mid) / center).abs() * 10_000.0 > config.outlier_bps {
+            excluded.extend([(a, Exclusion::Outlier), (b, Exclusion::Outlier)]);
+            fresh.clear();
+        }
+    } else if fresh.len() >= 3 {
+        let center = median(fresh.iter().map(|(_, p)| p.mid).collect());
+        fresh.retain(|&(venue, p)| {
+            let keep = ((p.mid - center) / center).abs() * 10_000.0 <= config.outlier_bps;
+            if !keep {
+                excluded.push((venue, Exclusion::Outlier));
+            }
+            keep
+        });
+    }
+
+    if fresh.len() < config.min_venues.max(1) {
+        return (None, excluded);
+    }
+
+    let (bid, ask) = match config.method {
+        CompositeMethod::Median => (
+            median(fresh.iter().map(|(_, p)| p.bid).collect()),
+            median(fresh.iter().map(|(_, p)| p.ask).collect()),
+        ),
+        CompositeMethod::WeightedMean => {
+            let total: f64 = fresh.iter().map(|&(v, _)| config.weight(v)).sum();
+            let mean = |f: fn(&nord::MidPrice) -> f64| {
+                fresh
+                    .iter()
+                    .map(|(v, p)| config.weight(*v) * f(p))
+                    .sum::<f64>()
+                    / total
+            };
+            (mean(|p| p.bid), mean(|p| p.ask))
+        }
+    };
+    let timestamp = fresh.iter().map(|(_, p)| p.timestamp).max().unwrap_or(0);
+    let price = nord::MidPrice {
+        mid: (bid + ask) * 0.5,
+        exact_mid: None,
+        bid,
+        ask,
+        timestamp,
+    };
+    (Some(price), excluded)
+}
+
+/// Median of a non-empty list (mean of the middle pair for even lengths).
+fn median(mut values: Vec<f64>) -> f64 {
+    values.sort_by(f64::total_cmp);
+    let n = values.len();
+    if n % 2 == 1 {
+        values[n / 2]
+    } else {
+        (values[n / 2 - 1] + values[n / 2]) * 0.5
+    }
+}
+
+// ---------------------------------------------------------------------------
+// CompositePriceSource
+// ---------------------------------------------------------------------------
+
+/// [`PriceSource`] combining several member sources.
+pub struct CompositePriceSource {
+    members: Vec<(Venue, Box<dyn PriceSource>)>,
+    config: CompositeConfig,
+    price_tx: watch::Sender<Option<nord::MidPrice>>,
+    price_rx: watch::Receiver<Option<nord::MidPrice>>,
+    cancel: CancellationToken,
+}
+
+impl CompositePriceSource {
+    /// Combine `members` (unconnected) according to `config`.
+    pub fn new(members: Vec<(Venue, Box<dyn PriceSource>)>, config: CompositeConfig) -> Self {
+        let (price_tx, price_rx) = watch::channel(None);
+        Self {
+            members,
+            config,
+            price_tx,
+            price_rx,
+            cancel: CancellationToken::new(),
+        }
+    }
+}
+
+impl PriceSource for CompositePriceSource {
+    fn name(&self) -> String {
+        let names: Vec<String> = self.members.iter().map(|(_, s)| s.name()).collect();
+        format!("{:?}({})", self.config.method, names.join(","))
+    }
+
+    fn connect(&self) {
+        let (update_tx, update_rx) = mpsc::unbounded_channel();
+        let mut receivers = Vec::with_capacity(self.members.len());
+        for (venue, source) in &self.members {
+            source.connect();
+            let mut rx = source.subscribe_price();
+            receivers.push((*venue, rx.clone()));
+            // Forward member updates as wake-ups for the aggregator.
+            let update_tx = update_tx.clone();
+            let cancel = self.cancel.clone();
+            tokio::spawn(async move {
+                loop {
+                    tokio::select! {
+                        changed = rx.changed() => {
+                            if changed.is_err() || update_tx.send(()).is_err() {
+                                return;
+                            }
+                        }
+                        _ = cancel.cancelled() => return,
+                    }
+                }
+            });
+        }
+        tokio::spawn(run_composite_task(
+            receivers,
+            self.config.clone(),
+            update_rx,
+            self.price_tx.clone(),
+            self.cancel.clone(),
+        ));
+    }
+
+    fn get_mid_price(&self) -> Option<nord::MidPrice> {
+        *self.price_rx.borrow()
+    }
+
+    fn subscribe_price(&self) -> watch::Receiver<Option<nord::MidPrice>> {
+        self.price_rx.clone()
+    }
+
+    fn member_prices(&self) -> Vec<(String, watch::Receiver<Option<nord::MidPrice>>)> {
+        self.members
+            .iter()
+            .flat_map(|(_, source)| source.member_prices())
+            .collect()
+    }
+
+    fn close(&self) {
+        self.cancel.cancel();
+        for (_, source) in &self.members {
+            source.close();
+        }
+    }
+}
+
+/// Recompute on member updates and on a timer; log venue exclusions as they
+/// change.
+async fn run_composite_task(
+    receivers: Vec<(Venue, watch::Receiver<Option<nord::MidPrice>>)>,
+    config: CompositeConfig,
+    mut update_rx: mpsc::UnboundedReceiver<()>,
+    price_tx: watch::Sender<Option<nord::MidPrice>>,
+    cancel: CancellationToken,
+) {
+    let mut recompute = time::interval(RECOMPUTE_INTERVAL);
+    let mut last_excluded: Vec<(Venue, Exclusion)> = Vec::new();
+    loop {
+        tokio::select! {
+            Some(()) = update_rx.recv() => {}
+            _ = recompute.tick() => {}
+            _ = cancel.cancelled() => return,
+        }
+
+        let quotes: Vec<(Venue, Option<nord::MidPrice>)> = receivers
+            .iter()
+            .map(|(venue, rx)| (*venue, *rx.borrow()))
+            .collect();
+        let (price, excluded) = combine(&quotes, nord::epoch_ms(), &config);
+
+        if excluded != last_excluded {
+            for (venue, reason) in &excluded {
+                if !last_excluded.contains(&(*venue, *reason)) {
+                    warn!(%venue, ?reason, "reference venue excluded");
+                }
+            }
+            for (venue, _) in &last_excluded {
+                if !excluded.iter().any(|(v, _)| v == venue) {
+                    info!(%venue, "reference venue included");
+                }
+            }
+            last_excluded = excluded;
+        }
+        price_tx.send_if_modified(|current| {
+            let changed = *current != price;
+            *current = price;
+            changed
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn quote(venue: Venue, mid: f64, timestamp: u64) -> (Venue, Option<nord::MidPrice>) {
+        let price = nord::MidPrice {
+            mid,
+            exact_mid: None,
+            bid: mid - 1.0,
+            ask: mid + 1.0,
+            timestamp,
+        };
+        (venue, Some(price))
+    }
+
+    #[test]
+    fn test_median_rejects_stale_and_outlier_venues() {
+        let config = CompositeConfig {
+            stale_ms_by_venue: [(Venue::Hyperliquid, 500)].into_iter().collect(),
+            ..Default::default()
+        };
+        let quotes = [
+            quote(Venue::Binance, 100.0, 9_000),
+            quote(Venue::Bybit, 100.2, 9_500),
+            quote(Venue::Okx, 110.0, 9_800),         // 1000bps away
+            quote(Venue::Hyperliquid, 100.1, 9_000), // older than its 500ms cutoff
+        ];
+        let (price, excluded) = combine(&quotes, 10_000, &config);
+        let price = price.unwrap();
+        assert!((price.mid - 100.1).abs() < 1e-9);
+        assert_eq!(price.timestamp, 9_500);
+        assert_eq!(
+            excluded,
+            vec![
+                (Venue::Hyperliquid, Exclusion::Stale),
+                (Venue::Okx, Exclusion::Outlier)
+            ]
+        );
+
+        // Every venue stale: no price at all.
+        let (price, excluded) = combine(&quotes, 20_000, &config);
+        assert!(price.is_none());
+        assert_eq!(excluded.len(), 4);
+    }
+
+    #[test]
+    fn test_weighted_mean_and_min_venues() {
+        let config = CompositeConfig {
+            method: CompositeMethod::WeightedMean,
+            weights: [(Venue::Binance, 3.0)].into_iter().collect(),
+            min_venues: 2,
+            outlier_bps: 1_000.0,
+            ..Default::default()
+        };
+        let quotes = [
+            quote(Venue::Binance, 100.0, 1_000),
+            quote(Venue::Bybit, 104.0, 1_000),
+            (Venue::Okx, None),
+        ];
+        let (price, excluded) = combine(&quotes, 1_000, &config);
+        assert!((price.unwrap().mid - 101.0).abs() < 1e-9);
+        assert_eq!(excluded, vec![(Venue::Okx, Exclusion::NoPrice)]);
+
+        let (price, _) = combine(&quotes[1..], 1_000, &config);
+        assert!(price.is_none(), "one venue is below min_venues");
+    }
+
+    #[test]
+    fn test_two_disagreeing_venues_are_both_outliers() {
+        let config = CompositeConfig::default();
+        let agree = [
+            quote(Venue::Binance, 100.0, 1_000),
+            quote(Venue::Bybit, 100.4, 1_000),
+        ];
+        let (price, excluded) = combine(&agree, 1_000, &config);
+        assert!((price.unwrap().mid - 100.2).abs() < 1e-9);
+        assert!(excluded.is_empty());
+
+        let disagree = [
+            quote(Venue::Binance, 100.0, 1_000),
+            quote(Venue::Bybit, 101.0, 1_000), // ~100bps apart
+        ];
+        let (price, excluded) = combine(&disagree, 1_000, &config);
+        assert!(price.is_none());
+        assert_eq!(
+            excluded,
+            vec![
+                (Venue::Binance, Exclusion::Outlier),
+                (Venue::Bybit, Exclusion::Outlier)
+            ]
+        );
+
+        // A single venue still has a staleness cutoff.
+        let (price, excluded) = combine(&agree[..1], 1_000 + config.stale_ms + 1, &config);
+        assert!(price.is_none());
+        assert_eq!(excluded, vec![(Venue::Binance, Exclusion::Stale)]);
+    }
+
+    #[test]
+    fn test_member_prices_list_every_venue() {
+        let config = ReferenceConfig {
+            venues: vec![Venue::Binance, Venue::Bybit],
+            ..Default::default()
+        };
+        let reference = build_reference(&config, "PEPE-PERP", &nord::LatencyTracker::default());
+        let names: Vec<String> = reference
+            .member_prices()
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect();
+        assert_eq!(names, ["Binance:1000pepeusdt", "Bybit:1000PEPEUSDT"]);
+    }
+}
@@ -0,0 +1,206 @@
+//! As-of time alignment of the local and reference mids.
+//!
+//! Each feed publishes only when its price changes, so a feed's last mid
+//! stays its price until the next update. The aligner keeps a short history
+//! per feed and joins them at a common timestamp `t`:
+//!
+//! ```text
+//!   known(feed) = min(latest, feed.last + tolerance_ms)    latest = newest update of either feed
+//!   t           = min(known(local), known(reference))
+//!   mid(feed)   = last mid at or before t                   (as-of)
+//!              or linear between the updates around t       (interpolate)
+//! ```
+//!
+//! A quiet feed is carried forward for up to `tolerance_ms`, so a quiet 01
+//! book keeps producing samples while the reference moves, and both mids are
+//! always taken at the same instant rather than up to a tolerance apart.
+//! Timestamps are the feeds' own (`MidPrice::timestamp`).
+
+use std::collections::VecDeque;
+
+/// Alignment settings.
+#[derive(Debug, Clone)]
+pub struct AlignConfig {
+    /// How long a feed's last mid is carried forward in milliseconds.
+    pub tolerance_ms: u64,
+    /// Interpolate between the updates around the common timestamp instead
+    /// of taking the last one before it.
+    pub interpolate: bool,
+}
+
+impl Default for AlignConfig {
+    fn default() -> Self {
+        Self {
+            tolerance_ms: 5_000,
+            interpolate: false,
+        }
+    }
+}
+
+/// Local and reference mids at a common timestamp.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AlignedMids {
+    /// Common timestamp in epoch milliseconds.
+    pub time_ms: u64,
+    pub local_mid: f64,
+    pub reference_mid: f64,
+}
+
+/// Recent `(timestamp, mid)` updates of one feed, oldest first.
+#[derive(Default)]
+struct FeedHistory {
+    points: VecDeque<(u64, f64)>,
+}
+
+impl FeedHistory {
+    /// Append an update and drop those older than `keep_ms`, keeping the last
+    /// one before the cutoff as the as-of price at the cutoff. An update at
+    /// the newest timestamp replaces it; older ones are ignored.
+    fn push(&mut self, timestamp: u64, mid: f64, keep_ms: u64) {
+        match self.points.back_mut() {
+            Some(last) if timestamp < last.0 => return,
+            Some(last) if timestamp == last.0 => last.1 = mid,
+            _ => self.points.push_back((timestamp, mid)),
+        }
+        let cutoff = timestamp.saturating_sub(keep_ms);
+        while self.points.get(1).is_some_and(|&(t, _)| t <= cutoff) {
+            self.points.pop_front();
+        }
+    }
+
+    fn last(&self) -> Option<u64> {
+        self.points.back().map(|&(t, _)| t)
+    }
+
+    /// Mid as of `t`, or `None` if `t` precedes the history.
+    fn at(&self, t: u64, interpolate: bool) -> Option<f64> {
+        let i = self
+            .points
+            .partition_point(|&(ts, _)| ts <= t)
+            .checked_sub(1)?;
+        let (t0, m0) = self.points[i];
+        match self.points.get(i + 1) {
+            Some(&(t1, m1)) if interpolate => {
+                Some(m0 + (m1 - m0) * (t - t0) as f64 / (t1 - t0) as f64)
+            }
+            _ => Some(m0),
+        }
+    }
+}
+
+/// Joins the local and reference feeds as of a common timestamp.
+pub struct PriceAligner {
+    config: AlignConfig,
+    local: FeedHistory,
+    reference: FeedHistory,
+    /// Common timestamp of the last sample returned.
+    last_time_ms: Option<u64>,
+}
+
+impl PriceAligner {
+    pub fn new(config: AlignConfig) -> Self {
+        Self {
+            config,
+            local: FeedHistory::default(),
+            reference: FeedHistory::default(),
+            last_time_ms: None,
+        }
+    }
+
+    /// Record a local (01) mid.
+    pub fn push_local(&mut self, price: &nord::MidPrice) {
+        self.local
+            .push(price.timestamp, price.mid, self.config.tolerance_ms);
+    }
+
+    /// Record a reference mid.
+    pub fn push_reference(&mut self, price: &nord::MidPrice) {
+        self.reference
+            .push(price.timestamp, price.mid, self.config.tolerance_ms);
+    }
+
+    /// Mids at the newest common timestamp, or `None` if either feed has no
+    /// price then or the timestamp has not advanced since the last sample.
+    pub fn next_sample(&mut self) -> Option<AlignedMids> {
+        let (local_last, reference_last) = (self.local.last()?, self.reference.last()?);
+        let latest = local_last.max(reference_last);
+        let known = |last: u64| (last + self.config.tolerance_ms).min(latest);
+        let time_ms = known(local_last).min(known(reference_last));
+        if self.last_time_ms.is_some_and(|t| time_ms <= t) {
+            return None;
+        }
+        let mids = AlignedMids {
+            time_ms,
+            local_mid: self.local.at(time_ms, self.config.interpolate)?,
+            reference_mid: self.reference.at(time_ms, self.config.interpolate)?,
+        };
+        self.last_time_ms = Some(time_ms);
+        Some(mids)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn price(mid: f64, timestamp: u64) -> nord::MidPrice {
+        nord::MidPrice {
+            mid,
+            exact_mid: None,
+            bid: mid,
+            ask: mid,
+            timestamp,
+        }
+    }
+
+    #[test]
+    fn test_quiet_feed_carried_forward_within_tolerance() {
+        let mut aligner = PriceAligner::new(AlignConfig {
+            tolerance_ms: 3_000,
+            interpolate: false,
+        });
+        assert_eq!(aligner.next_sample(), None);
+        aligner.push_local(&price(101.0, 1_000));
+        aligner.push_reference(&price(100.0, 1_200));
+        let s = aligner.next_sample().unwrap();
+        assert_eq!(
+            (s.time_ms, s.local_mid, s.reference_mid),
+            (1_200, 101.0, 100.0)
+        );
+        // Same timestamp: no new sample.
+        assert_eq!(aligner.next_sample(), None);
+
+        // The local book stays quiet while the reference moves.
+        aligner.push_reference(&price(100.5, 3_500));
+        let s = aligner.next_sample().unwrap();
+        assert_eq!(
+            (s.time_ms, s.local_mid, s.reference_mid),
+            (3_500, 101.0, 100.5)
+        );
+
+        // Beyond the tolerance the local mid is no longer carried: the join
+        // stops at 4_000 and uses the reference as of then.
+        aligner.push_reference(&price(102.0, 6_000));
+        let s = aligner.next_sample().unwrap();
+        assert_eq!((s.time_ms, s.reference_mid), (4_000, 100.5));
+        aligner.push_reference(&price(103.0, 7_000));
+        assert_eq!(aligner.next_sample(), None);
+    }
+
+    #[test]
+    fn test_interpolates_between_updates() {
+        let mut aligner = PriceAligner::new(AlignConfig {
+            tolerance_ms: 1_000,
+            interpolate: true,
+        });
+        aligner.push_local(&price(101.0, 1_000));
+        aligner.push_reference(&price(100.0, 1_500));
+        aligner.push_reference(&price(102.0, 2_500));
+        // Local known until 2_000: reference halfway between its updates.
+        let s = aligner.next_sample().unwrap();
+        assert_eq!(
+            (s.time_ms, s.local_mid, s.reference_mid),
+            (2_000, 101.0, 101.0)
+        );
+    }
+}
@@ -0,0 +1,226 @@
+//! Circuit breaker for the pricing pipeline.
+//!
+//! Three triggers mark the fair price unreliable:
+//!
+//! ```text
+//!   offset jump   |sample offset - model offset|   > max_offset_deviation_bps
+//!   reference     |reference mid - any mid within
+//!   jump           reference_window_ms|            > max_reference_move_bps
+//!   divergence    |local mid - reference mid|      > max_divergence_bps
+//! ```
+//!
+//! `sample offset` is `local mid - reference mid`. All distances are in bps
+//! of the reference mid; a limit of 0 disables its trigger. The pricing stays
+//! unreliable until `cooldown_ms` after the last trip.
+//!
+//! The breaker only flags samples, it never drops them: every sample reaches
+//! the model, so that a genuine basis shift is learned whatever its size. The
+//! offset jump keeps tripping until the model has caught up to within
+//! `max_offset_deviation_bps` of the new basis: up to half the window for
+//! the median, a few half-lives for the EWMA. A market that trades steadily
+//! beyond `max_divergence_bps` from the reference stays unreliable until the
+//! two mids converge again.
+
+use std::collections::VecDeque;
+use std::fmt;
+
+/// Circuit breaker limits.
+#[derive(Debug, Clone)]
+pub struct BreakerConfig {
+    /// Maximum distance of a sample's offset from the model's offset (bps).
+    pub max_offset_deviation_bps: f64,
+    /// Maximum move of the reference mid within `reference_window_ms` (bps).
+    pub max_reference_move_bps: f64,
+    /// Lookback of the reference move check in milliseconds.
+    pub reference_window_ms: u64,
+    /// Maximum distance of the local mid from the reference mid (bps).
+    pub max_divergence_bps: f64,
+    /// How long pricing stays unreliable after the last trip in milliseconds.
+    pub cooldown_ms: u64,
+}
+
+impl Default for BreakerConfig {
+    fn default() -> Self {
+        Self {
+            max_offset_deviation_bps: 20.0,
+            max_reference_move_bps: 50.0,
+            reference_window_ms: 1_000,
+            max_divergence_bps: 100.0,
+            cooldown_ms: 5_000,
+        }
+    }
+}
+
+/// Trigger that tripped the breaker, with the measured distance in bps.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Trip {
+    OffsetJump { deviation_bps: f64 },
+    ReferenceJump { move_bps: f64 },
+    Divergence { divergence_bps: f64 },
+}
+
+impl fmt::Display for Trip {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Trip::OffsetJump { deviation_bps } => write!(f, "offset jump {deviation_bps:.1}bps"),
+            Trip::ReferenceJump { move_bps } => write!(f, "reference jump {move_bps:.1}bps"),
+            Trip::Divergence { divergence_bps } => {
+                write!(f, "divergence {divergence_bps:.1}bps")
+            }
+        }
+    }
+}
+
+/// Whether the fair price can be quoted on.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PricingStatus {
+    Reliable,
+    /// Tripped by `trip`; reliable again at `until_ms` unless it trips again.
+    Unreliable {
+        trip: Trip,
+        until_ms: u64,
+    },
+}
+
+/// Tracks the reference mid and the last trip.
+pub(super) struct CircuitBreaker {
+    config: BreakerConfig,
+    /// `(epoch_ms, mid)` within `reference_window_ms`, oldest first.
+    reference: VecDeque<(u64, f64)>,
+    /// Last trip and when it expires.
+    last_trip: Option<(Trip, u64)>,
+}
+
+impl CircuitBreaker {
+    pub(super) fn new(config: BreakerConfig) -> Self {
+        Self {
+            config,
+            reference: VecDeque::new(),
+            last_trip: None,
+        }
+    }
+
+    /// Check a reference mid against the mids of the lookback window.
+    pub(super) fn observe_reference(&mut self, mid: f64, now_ms: u64) {
+        if self.config.max_reference_move_bps <= 0.0 || !mid.is_finite() || mid <= 0.0 {
+            return;
+        }
+        let cutoff = now_ms.saturating_sub(self.config.reference_window_ms);
+        while self.reference.front().is_some_and(|&(t, _)| t < cutoff) {
+            self.reference.pop_front();
+        }
+        let move_bps = self
+            .reference
+            .iter()
+            .map(|&(_, past)| bps(mid - past, mid))
+            .fold(0.0, f64::max);
+        if move_bps > self.config.max_reference_move_bps {
+            self.trip(Trip::ReferenceJump { move_bps }, now_ms);
+        }
+        self.reference.push_back((now_ms, mid));
+    }
+
+    /// Check a basis sample against the model's offset and the reference.
+    pub(super) fn check_sample(
+        &mut self,
+        local_mid: f64,
+        reference_mid: f64,
+        model_offset: Option<f64>,
+        now_ms: u64,
+    ) {
+        let offset = local_mid - reference_mid;
+        if let Some(model_offset) = model_offset {
+            let deviation_bps = bps(offset - model_offset, reference_mid);
+            if self.config.max_offset_deviation_bps > 0.0
+                && deviation_bps > self.config.max_offset_deviation_bps
+            {
+                self.trip(Trip::OffsetJump { deviation_bps }, now_ms);
+            }
+        }
+        let divergence_bps = bps(offset, reference_mid);
+        if self.config.max_divergence_bps > 0.0 && divergence_bps > self.config.max_divergence_bps {
+            self.trip(Trip::Divergence { divergence_bps }, now_ms);
+        }
+    }
+
+    pub(super) fn status(&self, now_ms: u64) -> PricingStatus {
+        match self.last_trip {
+            Some((trip, until_ms)) if now_ms < until_ms => {
+                PricingStatus::Unreliable { trip, until_ms }
+            }
+            _ => PricingStatus::Reliable,
+        }
+    }
+
+    fn trip(&mut self, trip: Trip, now_ms: u64) {
+        self.last_trip = Some((trip, now_ms + self.config.cooldown_ms));
+    }
+}
+
+/// `|diff|` in bps of `price`.
+fn bps(diff: f64, price: f64) -> f64 {
+    (diff / price).abs() * 10_000.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reference_jump_trips_until_cooldown() {
+        let mut breaker = CircuitBreaker::new(BreakerConfig::default());
+        breaker.observe_reference(10_000.0, 1_000);
+        breaker.observe_reference(10_040.0, 1_500); // 40 bps: within limit
+        assert_eq!(breaker.status(1_500), PricingStatus::Reliable);
+
+        breaker.observe_reference(10_100.0, 1_900); // 100 bps from 1_000
+        let PricingStatus::Unreliable { trip, until_ms } = breaker.status(2_000) else {
+            panic!("breaker should trip");
+        };
+        assert!(matches!(trip, Trip::ReferenceJump { move_bps } if move_bps > 99.0));
+        assert_eq!(until_ms, 6_900);
+        assert_eq!(breaker.status(6_900), PricingStatus::Reliable);
+
+        // Outside the lookback window the same move is fine.
+        breaker.observe_reference(10_200.0, 4_000);
+        assert_eq!(breaker.status(7_000), PricingStatus::Reliable);
+    }
+
+    #[test]
+    fn test_sample_triggers() {
+        let mut breaker = CircuitBreaker::new(BreakerConfig::default());
+        // No model offset yet: only the divergence band applies.
+        breaker.check_sample(10_090.0, 10_000.0, None, 1_000);
+        assert_eq!(breaker.status(1_000), PricingStatus::Reliable);
+        breaker.check_sample(10_200.0, 10_000.0, None, 2_000);
+        assert!(matches!(
+            breaker.status(2_000),
+            PricingStatus::Unreliable {
+                trip: Trip::Divergence { .. },
+                ..
+            }
+        ));
+
+        // 30 bps away from a 10 bps model offset.
+        breaker.check_sample(10_040.0, 10_000.0, Some(10.0), 8_000);
+        assert!(matches!(
+            breaker.status(8_000),
+            PricingStatus::Unreliable {
+                trip: Trip::OffsetJump { .. },
+                ..
+            }
+        ));
+        breaker.check_sample(10_015.0, 10_000.0, Some(10.0), 14_000);
+        assert_eq!(breaker.status(14_000), PricingStatus::Reliable);
+
+        // On a 140 bps basis the model agrees, but the mids are 150 bps apart.
+        breaker.check_sample(10_150.0, 10_000.0, Some(140.0), 20_000);
+        assert!(matches!(
+            breaker.status(20_000),
+            PricingStatus::Unreliable {
+                trip: Trip::Divergence { divergence_bps },
+                ..
+            } if (divergence_bps - 150.0).abs() < 1e-9
+        ));
+    }
+}
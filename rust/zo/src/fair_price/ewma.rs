@@ -0,0 +1,146 @@
+//! EWMA-offset fair price model.
+//!
+//! Computes `fair_price = reference_mid + ewma(offsets)`, where each
+//! per-second sample moves the estimate by
+//!
+//! ```text
+//!   alpha = 1 - 0.5 ^ (dt / half_life)        dt = time since the last sample
+//!   offset += alpha * (sample - offset)
+//! ```
+//!
+//! so a sample's weight halves every `ewma_half_life_ms` regardless of gaps in
+//! the feed. After a step change in the basis the estimate covers half the
+//! step within one half-life. The variance of the samples around the estimate
+//! is weighted the same way.
+
+use super::window::SampleTimes;
+use super::{FairPriceConfig, FairPriceMethod, FairPriceModel, FairPriceState};
+
+/// Fair price model using an exponentially weighted offset.
+pub struct EwmaOffset {
+    config: FairPriceConfig,
+    /// Current estimate, `None` before the first sample.
+    offset: Option<f64>,
+    /// Exponentially weighted variance of the samples around `offset`.
+    variance: f64,
+    /// Time of the last sample in epoch milliseconds.
+    last_ms: u64,
+    /// Samples of the last `window_ms`.
+    samples: SampleTimes,
+}
+
+impl EwmaOffset {
+    /// Create a new model with the given configuration.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `config.ewma_half_life_ms` is zero.
+    pub fn new(config: FairPriceConfig) -> Self {
+        assert!(config.ewma_half_life_ms > 0, "EWMA half-life must be > 0");
+        Self {
+            samples: SampleTimes::new(config.window_ms),
+            config,
+            offset: None,
+            variance: 0.0,
+            last_ms: 0,
+        }
+    }
+}
+
+impl EwmaOffset {
+    /// Weight of a sample taken `dt_ms` after the previous one.
+    fn alpha(&self, dt_ms: f64) -> f64 {
+        1.0 - 0.5f64.powf(dt_ms / self.config.ewma_half_life_ms as f64)
+    }
+}
+
+impl FairPriceModel for EwmaOffset {
+    fn method(&self) -> FairPriceMethod {
+        FairPriceMethod::Ewma
+    }
+
+    fn add_sample(&mut self, local_mid: f64, reference_mid: f64, now_ms: u64) -> bool {
+        let Some(restart) = self.samples.add(now_ms) else {
+            return false;
+        };
+        let sample = local_mid - reference_mid;
+        self.offset = Some(match self.offset.filter(|_| !restart) {
+            None => {
+                self.variance = 0.0;
+                sample
+            }
+            Some(offset) => {
+                let dt = now_ms.saturating_sub(self.last_ms) as f64;
+                let alpha = self.alpha(dt);
+                let diff = sample - offset;
+                self.variance = (1.0 - alpha) * (self.variance + alpha * diff * diff);
+                offset + alpha * diff
+            }
+        });
+        self.last_ms = now_ms;
+        true
+    }
+
+    fn get_state(&self, now_ms: u64) -> FairPriceState {
+        let samples = self.samples.len(now_ms);
+        let dispersion = (samples >= 2).then(|| self.variance.sqrt());
+        // Variance of an EWMA of independent samples taken once per second:
+        // sigma^2 * alpha / (2 - alpha).
+        let alpha = self.alpha(1000.0);
+        FairPriceState {
+            offset: self.offset.filter(|_| samples > 0),
+            samples,
+            dispersion,
+            std_error: dispersion.map(|d| d * (alpha / (2.0 - alpha)).sqrt()),
+            volatility: Vec::new(),
+        }
+    }
+
+    fn min_samples(&self) -> usize {
+        self.config.min_samples
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_half_life_decay() {
+        let mut model = EwmaOffset::new(FairPriceConfig {
+            min_samples: 1,
+            ewma_half_life_ms: 10_000,
+            ..Default::default()
+        });
+        model.add_sample(110.0, 100.0, 1_000);
+        model.add_sample(130.0, 100.0, 1_500); // same second: ignored
+        assert_eq!(model.get_state(2_000).offset, Some(10.0));
+
+        // One half-life later the estimate is halfway to the new offset.
+        model.add_sample(130.0, 100.0, 11_000);
+        assert!((model.get_state(11_000).offset.unwrap() - 20.0).abs() < 1e-9);
+        assert_eq!(model.get_state(11_000).samples, 2);
+    }
+
+    #[test]
+    fn test_samples_expire_after_a_gap() {
+        let mut model = EwmaOffset::new(FairPriceConfig {
+            window_ms: 60_000,
+            min_samples: 2,
+            ..Default::default()
+        });
+        model.add_sample(110.0, 100.0, 1_000);
+        model.add_sample(110.0, 100.0, 2_000);
+        assert_eq!(model.get_fair_price(100.0, 2_000), Some(110.0));
+
+        // Nothing for over a window: no offset, and warm up again before
+        // quoting.
+        assert_eq!(model.get_state(62_000).offset, None);
+        assert_eq!(model.get_fair_price(100.0, 62_000), None);
+        model.add_sample(120.0, 100.0, 70_000);
+        assert_eq!(model.get_state(70_000).samples, 1);
+        assert_eq!(model.get_fair_price(100.0, 70_000), None);
+        model.add_sample(120.0, 100.0, 71_000);
+        assert_eq!(model.get_fair_price(100.0, 71_000), Some(120.0));
+    }
+}
@@ -0,0 +1,138 @@
+//! Offline comparison of fair price models.
+//!
+//! Replays a sequence of basis samples through each model and scores its
+//! predictions: at every sample the model's fair price (from the samples
+//! before it) is compared with the local mid that actually followed.
+//!
+//! ```text
+//!   samples --> for each (t, local, reference):
+//!                 predicted = model.get_fair_price(reference, t)   (if warm)
+//!                 error_bps = (predicted - local) / local * 10_000
+//!                 model.add_sample(local, reference, t)
+//! ```
+//!
+//! `zo replay --compare-fair-price` collects the samples from a recording;
+//! the unit tests use synthetic series.
+
+use serde::{Deserialize, Serialize};
+
+use super::{build_model, FairPriceConfig, FairPriceMethod};
+
+/// Every method, in comparison order.
+pub const METHODS: [FairPriceMethod; 4] = [
+    FairPriceMethod::Median,
+    FairPriceMethod::TrimmedMean,
+    FairPriceMethod::Ewma,
+    FairPriceMethod::Kalman,
+];
+
+/// One aligned pair of mids, as fed to a model.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct BasisSample {
+    /// Sample time in epoch milliseconds.
+    pub time_ms: u64,
+    pub local_mid: f64,
+    pub reference_mid: f64,
+}
+
+/// Prediction error of one model over a sample series.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModelScore {
+    pub method: FairPriceMethod,
+    /// Samples with a prediction (after warmup).
+    pub scored: usize,
+    /// Mean absolute error in bps.
+    pub mae_bps: f64,
+    /// Root mean squared error in bps.
+    pub rmse_bps: f64,
+    /// Largest absolute error in bps.
+    pub max_bps: f64,
+}
+
+/// Score the model built from `config` on `samples` (sorted by time).
+pub fn evaluate(config: &FairPriceConfig, samples: &[BasisSample]) -> ModelScore {
+    let mut model = build_model(config);
+    let (mut scored, mut abs_sum, mut sq_sum, mut max_bps) = (0usize, 0.0, 0.0, 0.0f64);
+    for s in samples {
+        if let Some(fair) = model.get_fair_price(s.reference_mid, s.time_ms) {
+            let error_bps = ((fair - s.local_mid) / s.local_mid * 10_000.0).abs();
+            scored += 1;
+            abs_sum += error_bps;
+            sq_sum += error_bps * error_bps;
+            max_bps = max_bps.max(error_bps);
+        }
+        model.add_sample(s.local_mid, s.reference_mid, s.time_ms);
+    }
+    let n = scored.max(1) as f64;
+    ModelScore {
+        method: model.method(),
+        scored,
+        mae_bps: abs_sum / n,
+        rmse_bps: (sq_sum / n).sqrt(),
+        max_bps,
+    }
+}
+
+/// Score every method on `samples`, with the other settings of `config`.
+pub fn compare(config: &FairPriceConfig, samples: &[BasisSample]) -> Vec<ModelScore> {
+    METHODS
+        .iter()
+        .map(|&method| {
+            let config = FairPriceConfig {
+                method,
+                ..config.clone()
+            };
+            evaluate(&config, samples)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Ten minutes at 1/s: the local book trades 2 bps over the reference,
+    /// then 10 bps over after a funding-like shift at minute five. Offsets
+    /// carry ±0.5 bps of alternating noise.
+    fn basis_shift() -> Vec<BasisSample> {
+        (1..=600u64)
+            .map(|i| {
+                let reference_mid = 10_000.0;
+                let basis = if i <= 300 { 2.0 } else { 10.0 };
+                let noise = if i % 2 == 0 { 0.5 } else { -0.5 };
+                BasisSample {
+                    time_ms: i * 1_000,
+                    local_mid: reference_mid + basis + noise,
+                    reference_mid,
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_decaying_models_beat_window_after_basis_shift() {
+        let samples = basis_shift();
+        let scores = compare(&FairPriceConfig::default(), &samples);
+        assert_eq!(scores.len(), METHODS.len());
+        let score = |method| scores.iter().find(|s| s.method == method).unwrap();
+
+        for s in &scores {
+            // Warmup: the first 10 samples are not scored.
+            assert_eq!(s.scored, 590, "{:?}", s.method);
+        }
+        // The windowed models stay closer to the old basis for half the
+        // window; the decaying ones converge within a minute.
+        let median = score(FairPriceMethod::Median);
+        assert!(score(FairPriceMethod::Ewma).mae_bps < median.mae_bps / 2.0);
+        assert!(score(FairPriceMethod::Kalman).mae_bps < median.mae_bps / 2.0);
+        assert!(score(FairPriceMethod::TrimmedMean).mae_bps <= median.mae_bps * 1.5);
+    }
+
+    #[test]
+    fn test_evaluate_skips_warmup() {
+        let samples = &basis_shift()[..5];
+        let score = evaluate(&FairPriceConfig::default(), samples);
+        assert_eq!(score.scored, 0);
+        assert_eq!(score.mae_bps, 0.0);
+    }
+}
@@ -0,0 +1,167 @@
+//! Kalman-filter basis fair price model.
+//!
+//! Models the basis as a random walk observed through noisy per-second
+//! offset samples:
+//!
+//! ```text
+//!   basis(t)  = basis(t - dt) + w      w ~ N(0, q * dt)   process noise
+//!   sample(t) = basis(t) + v           v ~ N(0, r)        measurement noise
+//!
+//!   predict:  P += q * dt
+//!   update:   K = P / (P + r);  basis += K * (sample - basis);  P *= 1 - K
+//! ```
+//!
+//! The gain adapts to the feed: after a gap `P` has grown and the next sample
+//! moves the estimate further. In steady state `K ≈ sqrt(q / r)` per second.
+//! Noise variances are configured in bps² and scaled to price units with the
+//! sample's reference mid. `P` is the variance of the estimate (its standard
+//! error squared); the dispersion is tracked separately as a gain-weighted
+//! variance of the samples around the estimate.
+
+use super::window::SampleTimes;
+use super::{FairPriceConfig, FairPriceMethod, FairPriceModel, FairPriceState};
+
+/// Filter state after the first sample.
+#[derive(Debug, Clone, Copy)]
+struct Estimate {
+    /// Basis (offset) estimate in price units.
+    basis: f64,
+    /// Variance of `basis` in price units squared.
+    variance: f64,
+    /// Gain-weighted variance of the samples around `basis`.
+    dispersion: f64,
+}
+
+/// Fair price model using a one-dimensional Kalman filter on the basis.
+pub struct KalmanBasis {
+    config: FairPriceConfig,
+    estimate: Option<Estimate>,
+    /// Time of the last sample in epoch milliseconds.
+    last_ms: u64,
+    /// Samples of the last `window_ms`.
+    samples: SampleTimes,
+}
+
+impl KalmanBasis {
+    /// Create a new model with the given configuration.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the measurement variance is not positive or the process
+    /// variance is negative.
+    pub fn new(config: FairPriceConfig) -> Self {
+        assert!(
+            config.kalman_measurement_var_bps2 > 0.0 && config.kalman_process_var_bps2 >= 0.0,
+            "Kalman noise variances must be positive"
+        );
+        Self {
+            samples: SampleTimes::new(config.window_ms),
+            config,
+            estimate: None,
+            last_ms: 0,
+        }
+    }
+}
+
+impl FairPriceModel for KalmanBasis {
+    fn method(&self) -> FairPriceMethod {
+        FairPriceMethod::Kalman
+    }
+
+    fn add_sample(&mut self, local_mid: f64, reference_mid: f64, now_ms: u64) -> bool {
+        let Some(restart) = self.samples.add(now_ms) else {
+            return false;
+        };
+        let sample = local_mid - reference_mid;
+        // Variance of 1 bps of the reference price, in price units squared.
+        let bps2 = (reference_mid / 10_000.0).powi(2);
+        let r = self.config.kalman_measurement_var_bps2 * bps2;
+
+        self.estimate = Some(match self.estimate.filter(|_| !restart) {
+            None => Estimate {
+                basis: sample,
+                variance: r,
+                dispersion: 0.0,
+            },
+            Some(Estimate {
+                basis,
+                variance,
+                dispersion,
+            }) => {
+                let dt_s = now_ms.saturating_sub(self.last_ms) as f64 / 1000.0;
+                let predicted = variance + self.config.kalman_process_var_bps2 * bps2 * dt_s;
+                let gain = predicted / (predicted + r);
+                let basis = basis + gain * (sample - basis);
+                Estimate {
+                    basis,
+                    variance: predicted * (1.0 - gain),
+                    dispersion: dispersion + gain * ((sample - basis).powi(2) - dispersion),
+                }
+            }
+        });
+        self.last_ms = now_ms;
+        true
+    }
+
+    fn get_state(&self, now_ms: u64) -> FairPriceState {
+        let samples = self.samples.len(now_ms);
+        let estimate = self.estimate.filter(|_| samples >= 2);
+        FairPriceState {
+            offset: self.estimate.filter(|_| samples > 0).map(|e| e.basis),
+            samples,
+            dispersion: estimate.map(|e| e.dispersion.sqrt()),
+            std_error: estimate.map(|e| e.variance.sqrt()),
+            volatility: Vec::new(),
+        }
+    }
+
+    fn min_samples(&self) -> usize {
+        self.config.min_samples
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_filter_averages_noise_and_tracks_shift() {
+        let mut model = KalmanBasis::new(FairPriceConfig {
+            min_samples: 1,
+            kalman_process_var_bps2: 0.01,
+            kalman_measurement_var_bps2: 1.0,
+            ..Default::default()
+        });
+        // Offsets alternate ±1 bps around +2 bps of 10_000.
+        for i in 1..=60u64 {
+            let noise = if i % 2 == 0 { 1.0 } else { -1.0 };
+            model.add_sample(10_002.0 + noise, 10_000.0, i * 1_000);
+        }
+        let basis = model.get_state(60_000).offset.unwrap();
+        assert!((basis - 2.0).abs() < 0.3, "basis {basis}");
+
+        // Basis steps to +6 bps: most of the step is absorbed within 30s.
+        for i in 61..=90u64 {
+            model.add_sample(10_006.0, 10_000.0, i * 1_000);
+        }
+        let basis = model.get_state(90_000).offset.unwrap();
+        assert!(basis > 5.0 && basis < 6.0, "basis {basis}");
+    }
+
+    #[test]
+    fn test_gap_longer_than_the_window_starts_over() {
+        let mut model = KalmanBasis::new(FairPriceConfig {
+            window_ms: 60_000,
+            ..Default::default()
+        });
+        model.add_sample(10_002.0, 10_000.0, 1_000);
+        model.add_sample(10_002.0, 10_000.0, 2_000);
+        assert_eq!(model.get_state(2_000).offset, Some(2.0));
+
+        // The basis moved during the gap: the old estimate is not reported
+        // and the first sample after the gap replaces it.
+        assert_eq!(model.get_state(62_000).offset, None);
+        model.add_sample(10_030.0, 10_000.0, 70_000);
+        assert_eq!(model.get_state(70_000).offset, Some(30.0));
+    }
+}
@@ -1,117 +1,44 @@
-//! Offset-median fair price calculator.
+//! Offset-median fair price model.
 //!
 //! Computes `fair_price = reference_mid + median(local_mid - reference_mid)`
 //! using per-second offset samples over a configurable time window.
-//! A circular buffer avoids allocations after warmup.
 
-/// Maximum samples retained in the circular buffer (>5 min at 1/s).
-const MAX_SAMPLES: usize = 500;
+use super::window::OffsetWindow;
+use super::{std_dev, FairPriceConfig, FairPriceMethod, FairPriceModel, FairPriceState};
 
-/// Configuration for the fair price calculator.
-#[derive(Debug, Clone)]
-pub struct FairPriceConfig {
-    /// Time window for valid samples in milliseconds (e.g. 300_000 for 5 min).
-    pub window_ms: u64,
-    /// Minimum samples required before producing a fair price.
-    pub min_samples: usize,
-}
+/// Standard error of the median relative to that of the mean for normal
+/// samples (`sqrt(pi / 2)`).
+const MEDIAN_STD_ERROR_FACTOR: f64 = 1.2533;
 
-/// Snapshot of the calculator's current state (for debugging / display).
-#[derive(Debug, Clone)]
-pub struct FairPriceState {
-    /// Raw median offset (ignores `min_samples`), or `None` if no samples.
-    pub offset: Option<f64>,
-    /// Number of valid (non-expired) samples.
-    pub samples: usize,
-}
-
-/// A single offset sample: `local_mid - reference_mid` at a given second.
-#[derive(Clone, Copy)]
-struct OffsetSample {
-    offset: f64,
-    second: u64,
-}
-
-/// Fair price calculator using a circular buffer of per-second offset samples.
+/// Fair price model using the median of per-second offset samples.
 ///
 /// # Algorithm
 ///
 /// Each second, the caller feeds the local exchange mid-price and a reference
-/// exchange mid-price. The calculator stores `offset = local - reference` in a
+/// exchange mid-price. The model stores `offset = local - reference` in a
 /// circular buffer keyed by Unix second (deduplicating within the same second).
 ///
-/// To produce a fair price the calculator:
+/// To produce a fair price the model:
 /// 1. Collects all samples within `window_ms` of the current time.
 /// 2. Computes the median of those offsets using `select_nth_unstable` (O(n)).
 /// 3. Returns `reference_mid + median_offset`.
-pub struct FairPriceCalculator {
+pub struct OffsetMedian {
     config: FairPriceConfig,
-    /// Pre-allocated ring buffer.
-    samples: Vec<OffsetSample>,
-    /// Next write position (wraps around at `MAX_SAMPLES`).
-    head: usize,
-    /// Number of samples written so far (capped at `MAX_SAMPLES`).
-    count: usize,
-    /// Last recorded Unix second (for dedup).
-    last_second: u64,
+    window: OffsetWindow,
 }
 
-impl FairPriceCalculator {
-    /// Create a new calculator with the given configuration.
+impl OffsetMedian {
+    /// Create a new model with the given configuration.
     pub fn new(config: FairPriceConfig) -> Self {
         Self {
+            window: OffsetWindow::new(config.window_ms),
             config,
-            samples: Vec::with_capacity(MAX_SAMPLES),
-            head: 0,
-            count: 0,
-            last_second: 0,
         }
     }
 
-    /// Record a price sample. Only one sample per second is retained.
-    ///
-    /// # Arguments
-    ///
-    /// * `local_mid` - Mid-price from the local exchange (e.g. 01).
-    /// * `reference_mid` - Mid-price from the reference exchange (e.g. Binance).
-    /// * `now_ms` - Current wall-clock time in epoch milliseconds.
-    pub fn add_sample(&mut self, local_mid: f64, reference_mid: f64, now_ms: u64) {
-        let current_second = now_ms / 1000;
-
-        // Deduplicate: at most one sample per second.
-        if current_second <= self.last_second {
-            return;
-        }
-        self.last_second = current_second;
-
-        let sample = OffsetSample {
-            offset: local_mid - reference_mid,
-            second: current_second,
-        };
-
-        // Write into the circular buffer.
-        if self.samples.len() < MAX_SAMPLES {
-            self.samples.push(sample);
-        } else {
-            self.samples[self.head] = sample;
-        }
-        self.head = (self.head + 1) % MAX_SAMPLES;
-        if self.count < MAX_SAMPLES {
-            self.count += 1;
-        }
-    }
-
-    /// Get the fair price: `reference_mid + median(offsets)`.
-    ///
-    /// Returns `None` if fewer than `min_samples` valid samples exist.
-    pub fn get_fair_price(&self, reference_mid: f64, now_ms: u64) -> Option<f64> {
-        let offset = self.get_median_offset(now_ms)?;
-        Some(reference_mid + offset)
-    }
-
     /// Get the median offset, respecting the `min_samples` threshold.
     pub fn get_median_offset(&self, now_ms: u64) -> Option<f64> {
-        let mut offsets = self.collect_valid_offsets(now_ms);
+        let mut offsets = self.window.offsets(now_ms);
         if offsets.len() < self.config.min_samples {
             return None;
         }
@@ -120,7 +47,7 @@ impl FairPriceCalculator {
 
     /// Get the raw median offset (ignores `min_samples`; useful during warmup display).
     pub fn get_raw_median_offset(&self, now_ms: u64) -> Option<f64> {
-        let mut offsets = self.collect_valid_offsets(now_ms);
+        let mut offsets = self.window.offsets(now_ms);
         if offsets.is_empty() {
             return None;
         }
@@ -129,42 +56,48 @@ impl FairPriceCalculator {
 
     /// Number of valid (non-expired) samples.
     pub fn get_sample_count(&self, now_ms: u64) -> usize {
-        let cutoff = cutoff_second(now_ms, self.config.window_ms);
-        self.samples[..self.count]
-            .iter()
-            .filter(|s| s.second > cutoff)
-            .count()
+        self.window.len(now_ms)
+    }
+}
+
+impl FairPriceModel for OffsetMedian {
+    fn method(&self) -> FairPriceMethod {
+        FairPriceMethod::Median
+    }
+
+    /// Record a price sample. Only one sample per second is retained.
+    fn add_sample(&mut self, local_mid: f64, reference_mid: f64, now_ms: u64) -> bool {
+        self.window.add(local_mid - reference_mid, now_ms)
     }
 
-    /// Snapshot of the current state for debugging / display.
-    pub fn get_state(&self, now_ms: u64) -> FairPriceState {
+    fn get_state(&self, now_ms: u64) -> FairPriceState {
+        let samples = self.get_sample_count(now_ms);
+        let dispersion = std_dev(&self.window.offsets(now_ms));
         FairPriceState {
             offset: self.get_raw_median_offset(now_ms),
-            samples: self.get_sample_count(now_ms),
+            samples,
+            dispersion,
+            std_error: dispersion.map(|d| MEDIAN_STD_ERROR_FACTOR * d / (samples as f64).sqrt()),
+            volatility: Vec::new(),
         }
     }
 
-    /// Collect offsets from samples within the time window.
-    fn collect_valid_offsets(&self, now_ms: u64) -> Vec<f64> {
-        let cutoff = cutoff_second(now_ms, self.config.window_ms);
-        self.samples[..self.count]
-            .iter()
-            .filter(|s| s.second > cutoff)
-            .map(|s| s.offset)
-            .collect()
+    fn min_samples(&self) -> usize {
+        self.config.min_samples
     }
-}
 
-/// The cutoff second: samples at or before this second are expired.
-fn cutoff_second(now_ms: u64, window_ms: u64) -> u64 {
-    now_ms.saturating_sub(window_ms) / 1000
+    /// Get the fair price: `reference_mid + median(offsets)`.
+    fn get_fair_price(&self, reference_mid: f64, now_ms: u64) -> Option<f64> {
+        let offset = self.get_median_offset(now_ms)?;
+        Some(reference_mid + offset)
+    }
 }
 
 /// O(n) median via `select_nth_unstable` (introselect).
 ///
 /// For even-length slices, returns the average of the two middle elements.
 /// The input slice is partially reordered (acceptable since we own it).
-fn compute_median(values: &mut [f64]) -> f64 {
+pub(super) fn compute_median(values: &mut [f64]) -> f64 {
     let n = values.len();
     debug_assert!(n > 0);
 
@@ -189,17 +122,19 @@ fn compute_median(values: &mut [f64]) -> f64 {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::fair_price::window::MAX_SAMPLES;
 
     fn cfg(min_samples: usize) -> FairPriceConfig {
         FairPriceConfig {
             window_ms: 300_000, // 5 min
             min_samples,
+            ..Default::default()
         }
     }
 
     #[test]
     fn test_one_sample_per_second_dedup() {
-        let mut calc = FairPriceCalculator::new(cfg(1));
+        let mut calc = OffsetMedian::new(cfg(1));
         // Two samples in the same second — only the first is kept.
         calc.add_sample(100.0, 99.0, 5_000);
         calc.add_sample(200.0, 99.0, 5_500); // same second (5)
@@ -208,7 +143,7 @@ mod tests {
 
     #[test]
     fn test_returns_none_below_min_samples() {
-        let mut calc = FairPriceCalculator::new(cfg(3));
+        let mut calc = OffsetMedian::new(cfg(3));
         calc.add_sample(100.0, 99.0, 1_000);
         calc.add_sample(101.0, 99.0, 2_000);
         // Only 2 samples, need 3.
@@ -217,7 +152,7 @@ mod tests {
 
     #[test]
     fn test_median_odd_count() {
-        let mut calc = FairPriceCalculator::new(cfg(1));
+        let mut calc = OffsetMedian::new(cfg(1));
         // offsets: 1.0, 2.0, 3.0 → median = 2.0
         calc.add_sample(100.0, 99.0, 1_000); // offset 1.0
         calc.add_sample(101.0, 99.0, 2_000); // offset 2.0
@@ -228,7 +163,7 @@ mod tests {
 
     #[test]
     fn test_median_even_count() {
-        let mut calc = FairPriceCalculator::new(cfg(1));
+        let mut calc = OffsetMedian::new(cfg(1));
         // offsets: 1.0, 2.0, 3.0, 4.0 → median = (2+3)/2 = 2.5
         calc.add_sample(100.0, 99.0, 1_000); // 1.0
         calc.add_sample(101.0, 99.0, 2_000); // 2.0
@@ -240,9 +175,10 @@ mod tests {
 
     #[test]
     fn test_window_expiry() {
-        let mut calc = FairPriceCalculator::new(FairPriceConfig {
+        let mut calc = OffsetMedian::new(FairPriceConfig {
             window_ms: 5_000, // 5 s window
             min_samples: 1,
+            ..Default::default()
         });
         calc.add_sample(110.0, 100.0, 1_000); // offset 10, second 1
         calc.add_sample(120.0, 100.0, 2_000); // offset 20, second 2
@@ -259,7 +195,7 @@ mod tests {
 
     #[test]
     fn test_fair_price_equals_reference_plus_offset() {
-        let mut calc = FairPriceCalculator::new(cfg(1));
+        let mut calc = OffsetMedian::new(cfg(1));
         // offset = 105.0 - 100.0 = 5.0
         calc.add_sample(105.0, 100.0, 1_000);
         let fair = calc.get_fair_price(100.0, 2_000).unwrap();
@@ -269,9 +205,10 @@ mod tests {
 
     #[test]
     fn test_circular_buffer_wraparound() {
-        let mut calc = FairPriceCalculator::new(FairPriceConfig {
+        let mut calc = OffsetMedian::new(FairPriceConfig {
             window_ms: 1_000_000, // large window so nothing expires
             min_samples: 1,
+            ..Default::default()
         });
         // Write MAX_SAMPLES + 100 samples to force wraparound.
         for i in 0..(MAX_SAMPLES + 100) {
@@ -279,8 +216,11 @@ mod tests {
             calc.add_sample(100.0 + i as f64, 100.0, t);
         }
         // count should be capped at MAX_SAMPLES
-        assert_eq!(calc.count, MAX_SAMPLES);
-        assert_eq!(calc.samples.len(), MAX_SAMPLES);
+        assert_eq!(calc.window.count, MAX_SAMPLES);
+        assert_eq!(
+            calc.get_sample_count(((MAX_SAMPLES + 101) * 1000) as u64),
+            MAX_SAMPLES
+        );
 
         // The oldest surviving offset is (MAX_SAMPLES+100) - MAX_SAMPLES = 100
         // (i.e. offsets 100..599). Median of 100..599 = (349+350)/2 = 349.5
@@ -291,7 +231,7 @@ mod tests {
 
     #[test]
     fn test_raw_median_ignores_min_samples() {
-        let mut calc = FairPriceCalculator::new(cfg(100)); // unreachably high min
+        let mut calc = OffsetMedian::new(cfg(100)); // unreachably high min
         calc.add_sample(105.0, 100.0, 1_000); // offset 5.0
                                               // get_median_offset returns None (only 1 sample, need 100)
         assert!(calc.get_median_offset(2_000).is_none());
@@ -302,12 +242,18 @@ mod tests {
 
     #[test]
     fn test_get_state() {
-        let mut calc = FairPriceCalculator::new(cfg(1));
+        let mut calc = OffsetMedian::new(cfg(1));
         calc.add_sample(101.0, 100.0, 1_000);
         calc.add_sample(103.0, 100.0, 2_000);
         let state = calc.get_state(3_000);
         assert_eq!(state.samples, 2);
         // offsets: 1.0, 3.0 → median = 2.0
         assert!((state.offset.unwrap() - 2.0).abs() < 1e-12);
+        // sample std = sqrt(2), std error = 1.2533 * sqrt(2) / sqrt(2)
+        assert!((state.dispersion.unwrap() - 2f64.sqrt()).abs() < 1e-12);
+        assert!((state.std_error.unwrap() - MEDIAN_STD_ERROR_FACTOR).abs() < 1e-12);
+        let (low, high) = state.confidence_interval(100.0, 2.0).unwrap();
+        assert!((low - (102.0 - 2.0 * MEDIAN_STD_ERROR_FACTOR)).abs() < 1e-9);
+        assert!((high - (102.0 + 2.0 * MEDIAN_STD_ERROR_FACTOR)).abs() < 1e-9);
     }
 }
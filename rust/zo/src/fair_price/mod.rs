@@ -0,0 +1,457 @@
+//! Fair price models.
+//!
+//! Every model estimates the basis between the local (01) book and the
+//! reference venue from `(local_mid, reference_mid)` samples, taken at a
+//! common timestamp by the [`align`]er, and prices
+//! `fair = reference_mid + offset`:
+//!
+//! ```text
+//!   local_mid, reference_mid --> FairPriceModel --> offset --> reference_mid + offset
+//!   (at most one sample/s)        |
+//!                                 +-- median        median over the window
+//!                                 +-- trimmed-mean  mean over the window, tails dropped
+//!                                 +-- ewma          exponential decay, half-life
+//!                                 +-- kalman        random-walk basis filter
+//! ```
+//!
+//! The windowed estimators weight a five-minute-old sample like a fresh one,
+//! so they lag a basis shift (e.g. after funding) by half the window. The
+//! EWMA and the Kalman filter forget geometrically and catch up within a few
+//! half-lives. [`harness`] scores the models against each other on recorded
+//! samples.
+//!
+//! Besides the offset, every model reports how much to trust it: the
+//! dispersion of the offset samples and the standard error of the estimate,
+//! from which [`FairPriceState::confidence_interval`] is derived. [`FairPrice`]
+//! wraps the selected model and adds the realized volatility of the reference
+//! mid over several horizons, from every reference update, for display and
+//! spread sizing. It also keeps
+//! the samples of the last window, which [`snapshot`] persists across
+//! restarts, and runs the [`breaker`] that flags the pricing unreliable on
+//! jumps in the offset or the reference and when the two mids diverge.
+
+pub mod align;
+pub mod breaker;
+pub mod ewma;
+pub mod harness;
+pub mod kalman;
+pub mod median;
+pub mod snapshot;
+pub mod trimmed;
+pub mod volatility;
+mod window;
+
+use crate::error::ZoError;
+use breaker::CircuitBreaker;
+use harness::BasisSample;
+use snapshot::SampleHistory;
+
+pub use align::{AlignConfig, PriceAligner};
+pub use breaker::{BreakerConfig, PricingStatus};
+pub use ewma::EwmaOffset;
+pub use kalman::KalmanBasis;
+pub use median::OffsetMedian;
+pub use trimmed::TrimmedMean;
+pub use volatility::Volatility;
+
+use volatility::RealizedVol;
+
+/// Estimator used for the fair price offset.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum FairPriceMethod {
+    /// Median offset over the window.
+    #[default]
+    Median,
+    /// Mean offset over the window without the tails.
+    TrimmedMean,
+    /// Exponentially weighted offset.
+    Ewma,
+    /// Kalman filter on a random-walk basis.
+    Kalman,
+}
+
+/// Configuration for the fair price models.
+#[derive(Debug, Clone)]
+pub struct FairPriceConfig {
+    /// Estimator to build.
+    pub method: FairPriceMethod,
+    /// Time window for valid samples in milliseconds (e.g. 300_000 for 5 min).
+    /// The median and trimmed mean estimate over it; every model counts only
+    /// its samples towards `min_samples`.
+    pub window_ms: u64,
+    /// Minimum samples required before producing a fair price.
+    pub min_samples: usize,
+    /// Fraction of samples dropped from each tail by the trimmed mean
+    /// (`0.0..0.5`).
+    pub trim_fraction: f64,
+    /// Age at which a sample has half its weight in the EWMA, in
+    /// milliseconds.
+    pub ewma_half_life_ms: u64,
+    /// Kalman process noise: variance the basis drifts by per second, in
+    /// bps².
+    pub kalman_process_var_bps2: f64,
+    /// Kalman measurement noise: variance of one offset sample around the
+    /// basis, in bps².
+    pub kalman_measurement_var_bps2: f64,
+    /// Horizons of the reference mid's realized volatility, in milliseconds.
+    pub volatility_horizons_ms: Vec<u64>,
+    /// Limits of the pricing circuit breaker.
+    pub breaker: BreakerConfig,
+    /// Time alignment of the local and reference mids fed to the model.
+    pub align: AlignConfig,
+}
+
+impl FairPriceConfig {
+    /// Check the settings of the selected method.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ZoError::Config`] naming the first invalid setting.
+    pub fn validate(&self) -> Result<(), ZoError> {
+        let invalid = |e: String| Err(ZoError::Config(format!("fair price: {e}")));
+        match self.method {
+            FairPriceMethod::TrimmedMean if !(0.0..0.5).contains(&self.trim_fraction) => {
+                invalid(format!(
+                    "trim fraction {} is not within [0, 0.5)",
+                    self.trim_fraction
+                ))
+            }
+            FairPriceMethod::Ewma if self.ewma_half_life_ms == 0 => {
+                invalid("EWMA half-life must be > 0".to_string())
+            }
+            FairPriceMethod::Kalman
+                if !(self.kalman_measurement_var_bps2 > 0.0
+                    && self.kalman_process_var_bps2 >= 0.0) =>
+            {
+                invalid("Kalman noise variances must be positive".to_string())
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+impl Default for FairPriceConfig {
+    fn default() -> Self {
+        Self {
+            method: FairPriceMethod::default(),
+            window_ms: 5 * 60 * 1000, // 5 minutes
+            min_samples: 10,
+            trim_fraction: 0.1,
+            ewma_half_life_ms: 30_000,
+            kalman_process_var_bps2: 0.01,
+            kalman_measurement_var_bps2: 1.0,
+            volatility_horizons_ms: vec![10_000, 60_000, 300_000],
+            breaker: BreakerConfig::default(),
+            align: AlignConfig::default(),
+        }
+    }
+}
+
+/// Snapshot of a model's current state (for debugging / display).
+#[derive(Debug, Clone)]
+pub struct FairPriceState {
+    /// Raw offset estimate (ignores `min_samples`), or `None` if no samples.
+    pub offset: Option<f64>,
+    /// Number of samples within the window.
+    pub samples: usize,
+    /// Standard deviation of the offset samples around the estimate, or
+    /// `None` with fewer than two samples.
+    pub dispersion: Option<f64>,
+    /// Standard error of `offset`, or `None` with fewer than two samples.
+    pub std_error: Option<f64>,
+    /// Realized volatility of the reference mid per configured horizon
+    /// (filled by [`FairPrice`]; empty from a bare model).
+    pub volatility: Vec<Volatility>,
+}
+
+impl FairPriceState {
+    /// Fair price interval `reference_mid + offset ± z * std_error`.
+    pub fn confidence_interval(&self, reference_mid: f64, z: f64) -> Option<(f64, f64)> {
+        let fair = reference_mid + self.offset?;
+        let half_width = z * self.std_error?;
+        Some((fair - half_width, fair + half_width))
+    }
+
+    /// Volatility over `horizon_ms`, if it is one of the configured horizons
+    /// and has samples.
+    pub fn volatility_bps(&self, horizon_ms: u64) -> Option<f64> {
+        self.volatility
+            .iter()
+            .find(|v| v.horizon_ms == horizon_ms)
+            .map(|v| v.vol_bps)
+    }
+}
+
+/// Basis estimator behind the fair price.
+///
+/// Callers may feed every price update; models keep at most one sample per
+/// second so that sample counts and decay do not depend on the feed rate.
+pub trait FairPriceModel: Send {
+    /// Estimator implemented by the model.
+    fn method(&self) -> FairPriceMethod;
+
+    /// Record a price sample. Returns whether it was admitted (at most one
+    /// per second, and not rejected by a check).
+    ///
+    /// # Arguments
+    ///
+    /// * `local_mid` - Mid-price from the local exchange (e.g. 01).
+    /// * `reference_mid` - Mid-price from the reference exchange (e.g. Binance).
+    /// * `now_ms` - Current time in epoch milliseconds.
+    fn add_sample(&mut self, local_mid: f64, reference_mid: f64, now_ms: u64) -> bool;
+
+    /// Snapshot of the current estimate, ignoring `min_samples`.
+    fn get_state(&self, now_ms: u64) -> FairPriceState;
+
+    /// Minimum samples required before [`get_fair_price`](Self::get_fair_price)
+    /// returns a price.
+    fn min_samples(&self) -> usize;
+
+    /// Get the fair price: `reference_mid + offset`.
+    ///
+    /// Returns `None` if fewer than `min_samples` samples exist.
+    fn get_fair_price(&self, reference_mid: f64, now_ms: u64) -> Option<f64> {
+        let state = self.get_state(now_ms);
+        if state.samples < self.min_samples() {
+            return None;
+        }
+        Some(reference_mid + state.offset?)
+    }
+}
+
+/// Build the model selected by `config.method`.
+pub fn build_model(config: &FairPriceConfig) -> Box<dyn FairPriceModel> {
+    match config.method {
+        FairPriceMethod::Median => Box::new(OffsetMedian::new(config.clone())),
+        FairPriceMethod::TrimmedMean => Box::new(TrimmedMean::new(config.clone())),
+        FairPriceMethod::Ewma => Box::new(EwmaOffset::new(config.clone())),
+        FairPriceMethod::Kalman => Box::new(KalmanBasis::new(config.clone())),
+    }
+}
+
+/// The selected model plus the realized volatility of the reference mid and
+/// the pricing circuit breaker.
+pub struct FairPrice {
+    model: Box<dyn FairPriceModel>,
+    volatility: RealizedVol,
+    /// Samples of the last `window_ms`, for snapshots.
+    history: SampleHistory,
+    breaker: CircuitBreaker,
+    gate: SecondGate,
+}
+
+impl FairPrice {
+    /// Build the model selected by `config.method`.
+    pub fn new(config: &FairPriceConfig) -> Self {
+        Self {
+            model: build_model(config),
+            volatility: RealizedVol::new(config.volatility_horizons_ms.clone()),
+            history: SampleHistory::new(config.window_ms),
+            breaker: CircuitBreaker::new(config.breaker.clone()),
+            gate: SecondGate::default(),
+        }
+    }
+
+    /// Check a reference mid for jumps and track its volatility; call on
+    /// every reference update, whether or not it yields a basis sample.
+    pub fn observe_reference(&mut self, reference_mid: f64, now_ms: u64) {
+        self.breaker.observe_reference(reference_mid, now_ms);
+        self.volatility.add(reference_mid, now_ms);
+    }
+
+    /// Whether the fair price can be quoted on at `now_ms`.
+    pub fn pricing_status(&self, now_ms: u64) -> PricingStatus {
+        self.breaker.status(now_ms)
+    }
+
+    /// Per-second samples of the last `window_ms`, oldest first.
+    pub fn samples(&self) -> Vec<BasisSample> {
+        self.history.to_vec()
+    }
+
+    /// Replay saved samples that are still within `window_ms` of `now_ms`,
+    /// oldest first. Returns the number of samples replayed.
+    pub fn restore(&mut self, samples: &[BasisSample], now_ms: u64) -> usize {
+        let mut current: Vec<_> = samples
+            .iter()
+            .filter(|s| self.history.is_current(s, now_ms))
+            .copied()
+            .collect();
+        current.sort_by_key(|s| s.time_ms);
+        for s in &current {
+            self.volatility.add(s.reference_mid, s.time_ms);
+            self.add_sample(s.local_mid, s.reference_mid, s.time_ms);
+        }
+        current.len()
+    }
+}
+
+impl FairPriceModel for FairPrice {
+    fn method(&self) -> FairPriceMethod {
+        self.model.method()
+    }
+
+    fn add_sample(&mut self, local_mid: f64, reference_mid: f64, now_ms: u64) -> bool {
+        if self.gate.admit(now_ms).is_none() {
+            return false;
+        }
+        let model_offset = self.model.get_state(now_ms).offset;
+        self.breaker
+            .check_sample(local_mid, reference_mid, model_offset, now_ms);
+        self.model.add_sample(local_mid, reference_mid, now_ms);
+        self.history.add(BasisSample {
+            time_ms: now_ms,
+            local_mid,
+            reference_mid,
+        });
+        true
+    }
+
+    fn get_state(&self, now_ms: u64) -> FairPriceState {
+        FairPriceState {
+            volatility: self.volatility.estimates(now_ms),
+            ..self.model.get_state(now_ms)
+        }
+    }
+
+    fn min_samples(&self) -> usize {
+        self.model.min_samples()
+    }
+
+    fn get_fair_price(&self, reference_mid: f64, now_ms: u64) -> Option<f64> {
+        self.model.get_fair_price(reference_mid, now_ms)
+    }
+}
+
+/// Sample standard deviation, or `None` with fewer than two values.
+fn std_dev(values: &[f64]) -> Option<f64> {
+    if values.len() < 2 {
+        return None;
+    }
+    let n = values.len() as f64;
+    let mean = values.iter().sum::<f64>() / n;
+    let var = values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (n - 1.0);
+    Some(var.sqrt())
+}
+
+/// Admits at most one sample per Unix second.
+#[derive(Debug, Default)]
+struct SecondGate {
+    /// Last admitted Unix second.
+    last_second: u64,
+}
+
+impl SecondGate {
+    /// The second of `now_ms` if no sample was admitted in it yet.
+    fn admit(&mut self, now_ms: u64) -> Option<u64> {
+        let second = now_ms / 1000;
+        if second <= self.last_second {
+            return None;
+        }
+        self.last_second = second;
+        Some(second)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_restore_skips_warmup_and_expired_samples() {
+        let config = FairPriceConfig {
+            window_ms: 60_000,
+            min_samples: 10,
+            ..Default::default()
+        };
+        let mut before = FairPrice::new(&config);
+        for i in 1..=90u64 {
+            before.add_sample(10_002.0, 10_000.0, i * 1_000);
+            before.add_sample(10_050.0, 10_000.0, i * 1_000 + 500); // same second: ignored
+        }
+        let samples = before.samples();
+        // 31s..=90s are within the last minute.
+        assert_eq!(samples.len(), 60);
+        assert_eq!(samples[0].time_ms, 31_000);
+
+        // Restart 20s later: samples older than a minute are dropped.
+        let mut after = FairPrice::new(&config);
+        assert_eq!(after.restore(&samples, 110_000), 40);
+        assert_eq!(after.get_fair_price(10_000.0, 110_000), Some(10_002.0));
+        assert_eq!(after.samples(), samples[20..]);
+        assert!(after.get_state(110_000).volatility_bps(60_000).is_some());
+    }
+
+    #[test]
+    fn test_breaker_lingers_until_the_model_learns_a_basis_shift() {
+        // Basis 2 bps for five minutes, then 30 bps: a 28 bps offset jump.
+        // Returns how long after the shift the pricing stays unreliable, in
+        // seconds.
+        let unreliable_for = |method| {
+            let config = FairPriceConfig {
+                method,
+                ..Default::default()
+            };
+            let mut calc = FairPrice::new(&config);
+            let mut last_unreliable = 0;
+            for i in 1..=900u64 {
+                let basis = if i <= 300 { 2.0 } else { 30.0 };
+                calc.add_sample(10_000.0 + basis, 10_000.0, i * 1_000);
+                if calc.pricing_status(i * 1_000) != PricingStatus::Reliable {
+                    last_unreliable = i;
+                }
+            }
+            last_unreliable - 300
+        };
+        // The median moves once the new basis fills half the window.
+        let median = unreliable_for(FairPriceMethod::Median);
+        assert!((140..=160).contains(&median), "median: {median}s");
+        let ewma = unreliable_for(FairPriceMethod::Ewma);
+        assert!(ewma < 30, "ewma: {ewma}s");
+    }
+
+    #[test]
+    fn test_pricing_recovers_from_a_basis_step_beyond_the_divergence_band() {
+        // Basis -60 bps, then +60 bps: a 120 bps step, while the mids stay
+        // within the 100 bps divergence band.
+        let mut calc = FairPrice::new(&FairPriceConfig {
+            method: FairPriceMethod::Ewma,
+            ..Default::default()
+        });
+        for i in 1..=300u64 {
+            calc.add_sample(9_940.0, 10_000.0, i * 1_000);
+        }
+        calc.add_sample(10_060.0, 10_000.0, 301_000);
+        assert!(matches!(
+            calc.pricing_status(301_000),
+            PricingStatus::Unreliable {
+                trip: breaker::Trip::OffsetJump { .. },
+                ..
+            }
+        ));
+
+        for i in 302..=600u64 {
+            calc.add_sample(10_060.0, 10_000.0, i * 1_000);
+        }
+        assert_eq!(calc.pricing_status(600_000), PricingStatus::Reliable);
+        let fair = calc.get_fair_price(10_000.0, 600_000).unwrap();
+        assert!((fair - 10_060.0).abs() < 1.0, "fair: {fair}");
+    }
+
+    #[test]
+    fn test_volatility_follows_reference_without_basis_samples() {
+        let config = FairPriceConfig {
+            volatility_horizons_ms: vec![10_000],
+            ..Default::default()
+        };
+        let mut calc = FairPrice::new(&config);
+        // The local book is unhealthy: reference updates only.
+        for i in 1..=10u64 {
+            let mid = if i % 2 == 0 { 10_001.0 } else { 10_000.0 };
+            calc.observe_reference(mid, i * 1_000);
+        }
+        let state = calc.get_state(10_000);
+        assert_eq!(state.samples, 0);
+        assert!(state.volatility_bps(10_000).unwrap() > 0.0);
+    }
+}
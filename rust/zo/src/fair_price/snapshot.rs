@@ -0,0 +1,134 @@
+//! Fair price samples persisted across restarts.
+//!
+//! [`FairPrice`](super::FairPrice) keeps the per-second samples of the last
+//! `window_ms`. The bot writes them to a JSON file periodically and on
+//! shutdown, and replays the ones still inside the window on start, so a
+//! quick restart does not repeat the warmup:
+//!
+//! ```text
+//!   run:    FairPrice::samples --> FairPriceSnapshot --> file.tmp --> rename(file)
+//!   start:  file --> FairPriceSnapshot --> FairPrice::restore  (within window_ms)
+//! ```
+
+use std::collections::VecDeque;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use super::harness::BasisSample;
+use super::SecondGate;
+use crate::error::ZoError;
+
+/// Samples of one market, as written to disk.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FairPriceSnapshot {
+    /// Market the samples were taken on; a snapshot of another market is
+    /// ignored.
+    pub symbol: String,
+    /// Time of the snapshot in epoch milliseconds.
+    pub saved_ms: u64,
+    /// Per-second samples, oldest first.
+    pub samples: Vec<BasisSample>,
+}
+
+impl FairPriceSnapshot {
+    /// Write the snapshot to `path`, replacing it atomically.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ZoError::Config`] if the file cannot be written.
+    pub fn save(&self, path: &Path) -> Result<(), ZoError> {
+        let io_err = |e: std::io::Error| {
+            ZoError::Config(format!("fair price snapshot {}: {e}", path.display()))
+        };
+        let json = serde_json::to_vec(self)?;
+        let tmp = path.with_extension("tmp");
+        std::fs::write(&tmp, json).map_err(io_err)?;
+        std::fs::rename(&tmp, path).map_err(io_err)
+    }
+
+    /// Read the snapshot at `path`, or `None` if there is none yet.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ZoError::Config`] if the file cannot be read, or
+    /// [`ZoError::Json`] if it is not a valid snapshot.
+    pub fn load(path: &Path) -> Result<Option<Self>, ZoError> {
+        let text = match std::fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => {
+                return Err(ZoError::Config(format!(
+                    "fair price snapshot {}: {e}",
+                    path.display()
+                )))
+            }
+        };
+        Ok(Some(serde_json::from_str(&text)?))
+    }
+}
+
+/// Per-second samples within the window, oldest first.
+pub(super) struct SampleHistory {
+    window_ms: u64,
+    samples: VecDeque<BasisSample>,
+    gate: SecondGate,
+}
+
+impl SampleHistory {
+    pub(super) fn new(window_ms: u64) -> Self {
+        Self {
+            window_ms,
+            samples: VecDeque::new(),
+            gate: SecondGate::default(),
+        }
+    }
+
+    /// Record a sample, unless one was already recorded this second.
+    pub(super) fn add(&mut self, sample: BasisSample) {
+        if self.gate.admit(sample.time_ms).is_none() {
+            return;
+        }
+        self.samples.push_back(sample);
+        while self
+            .samples
+            .front()
+            .is_some_and(|s| !self.is_current(s, sample.time_ms))
+        {
+            self.samples.pop_front();
+        }
+    }
+
+    /// Whether `sample` is still inside the window at `now_ms`.
+    pub(super) fn is_current(&self, sample: &BasisSample, now_ms: u64) -> bool {
+        sample.time_ms <= now_ms && now_ms - sample.time_ms < self.window_ms
+    }
+
+    pub(super) fn to_vec(&self) -> Vec<BasisSample> {
+        self.samples.iter().copied().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_save_load_roundtrip() {
+        let path = std::env::temp_dir().join(format!("zo-fair-{}.json", std::process::id()));
+        assert_eq!(FairPriceSnapshot::load(&path).unwrap(), None);
+
+        let snapshot = FairPriceSnapshot {
+            symbol: "BTC-PERP".to_string(),
+            saved_ms: 5_000,
+            samples: vec![BasisSample {
+                time_ms: 4_000,
+                local_mid: 100.5,
+                reference_mid: 100.0,
+            }],
+        };
+        snapshot.save(&path).unwrap();
+        assert_eq!(FairPriceSnapshot::load(&path).unwrap(), Some(snapshot));
+        std::fs::remove_file(&path).unwrap();
+    }
+}
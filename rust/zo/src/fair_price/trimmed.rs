@@ -0,0 +1,102 @@
+//! Trimmed-mean fair price model.
+//!
+//! Computes `fair_price = reference_mid + trimmed_mean(offsets)` over the same
+//! per-second window as the median, dropping `trim_fraction` of the samples
+//! from each tail. Averages more samples than the median (less noise) while
+//! still ignoring spikes from a briefly dislocated book.
+
+use super::window::OffsetWindow;
+use super::{std_dev, FairPriceConfig, FairPriceMethod, FairPriceModel, FairPriceState};
+
+/// Fair price model using the trimmed mean of per-second offset samples.
+pub struct TrimmedMean {
+    config: FairPriceConfig,
+    window: OffsetWindow,
+}
+
+impl TrimmedMean {
+    /// Create a new model with the given configuration.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `config.trim_fraction` is not within `0.0..0.5`.
+    pub fn new(config: FairPriceConfig) -> Self {
+        assert!(
+            (0.0..0.5).contains(&config.trim_fraction),
+            "trim fraction must be within [0, 0.5), got {}",
+            config.trim_fraction
+        );
+        Self {
+            window: OffsetWindow::new(config.window_ms),
+            config,
+        }
+    }
+}
+
+impl FairPriceModel for TrimmedMean {
+    fn method(&self) -> FairPriceMethod {
+        FairPriceMethod::TrimmedMean
+    }
+
+    fn add_sample(&mut self, local_mid: f64, reference_mid: f64, now_ms: u64) -> bool {
+        self.window.add(local_mid - reference_mid, now_ms)
+    }
+
+    fn get_state(&self, now_ms: u64) -> FairPriceState {
+        let mut offsets = self.window.offsets(now_ms);
+        let offset = trimmed_mean(&mut offsets, self.config.trim_fraction);
+        let dispersion = std_dev(&offsets);
+        // The kept samples are less dispersed than all of them; scale by the
+        // kept fraction as a conservative standard error.
+        let kept = 1.0 - 2.0 * self.config.trim_fraction;
+        FairPriceState {
+            offset,
+            samples: offsets.len(),
+            dispersion,
+            std_error: dispersion.map(|d| d / (offsets.len() as f64 * kept).sqrt()),
+            volatility: Vec::new(),
+        }
+    }
+
+    fn min_samples(&self) -> usize {
+        self.config.min_samples
+    }
+}
+
+/// Mean of `values` without the `trim` fraction at each end, or `None` if
+/// empty. The slice is sorted in place.
+fn trimmed_mean(values: &mut [f64], trim: f64) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+    values.sort_unstable_by(f64::total_cmp);
+    let cut = (values.len() as f64 * trim).floor() as usize;
+    let kept = &values[cut..values.len() - cut];
+    Some(kept.iter().sum::<f64>() / kept.len() as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trimmed_mean_drops_tails() {
+        let mut model = TrimmedMean::new(FairPriceConfig {
+            min_samples: 10,
+            trim_fraction: 0.1,
+            ..Default::default()
+        });
+        // offsets 1..=9 plus one spike of 100: the spike and the 1 are cut.
+        for i in 1..=9 {
+            model.add_sample(100.0 + i as f64, 100.0, i * 1_000);
+        }
+        assert!(model.get_fair_price(100.0, 10_000).is_none());
+        model.add_sample(200.0, 100.0, 10_000);
+
+        let state = model.get_state(11_000);
+        assert_eq!(state.samples, 10);
+        // mean(2..=9) = 5.5
+        assert!((state.offset.unwrap() - 5.5).abs() < 1e-12);
+        assert!((model.get_fair_price(100.0, 11_000).unwrap() - 105.5).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,109 @@
+//! Realized volatility of the reference mid.
+//!
+//! Keeps one log price per second for the longest horizon and estimates the
+//! variance rate from the squared returns between consecutive samples:
+//!
+//! ```text
+//!   rate       = sum(ln(p[i] / p[i-1])^2) / (t[n] - t[0])      per second
+//!   vol(H)     = sqrt(rate * H) * 10_000                       bps over H
+//! ```
+//!
+//! Scaling the rate rather than summing returns keeps horizons comparable
+//! while the buffer is still shorter than the horizon, and across gaps in the
+//! feed.
+
+use std::collections::VecDeque;
+
+use super::SecondGate;
+
+/// Realized volatility over one horizon.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Volatility {
+    pub horizon_ms: u64,
+    /// Expected move of the reference mid over the horizon (one standard
+    /// deviation), in bps.
+    pub vol_bps: f64,
+}
+
+/// Per-second log prices of the reference mid.
+pub(super) struct RealizedVol {
+    horizons_ms: Vec<u64>,
+    /// Longest horizon; older samples are dropped.
+    span_ms: u64,
+    /// `(epoch_ms, ln(mid))`, oldest first.
+    samples: VecDeque<(u64, f64)>,
+    gate: SecondGate,
+}
+
+impl RealizedVol {
+    pub(super) fn new(horizons_ms: Vec<u64>) -> Self {
+        let span_ms = horizons_ms.iter().copied().max().unwrap_or(0);
+        Self {
+            horizons_ms,
+            span_ms,
+            samples: VecDeque::new(),
+            gate: SecondGate::default(),
+        }
+    }
+
+    /// Record the reference mid, at most once per second.
+    pub(super) fn add(&mut self, mid: f64, now_ms: u64) {
+        if !mid.is_finite() || mid <= 0.0 || self.gate.admit(now_ms).is_none() {
+            return;
+        }
+        self.samples.push_back((now_ms, mid.ln()));
+        let cutoff = now_ms.saturating_sub(self.span_ms);
+        while self.samples.front().is_some_and(|&(t, _)| t < cutoff) {
+            self.samples.pop_front();
+        }
+    }
+
+    /// Volatility per configured horizon, from the returns within each
+    /// horizon; horizons with fewer than two samples are omitted.
+    pub(super) fn estimates(&self, now_ms: u64) -> Vec<Volatility> {
+        self.horizons_ms
+            .iter()
+            .filter_map(|&horizon_ms| {
+                let cutoff = now_ms.saturating_sub(horizon_ms);
+                let start = self.samples.partition_point(|&(t, _)| t < cutoff);
+                let window = self.samples.range(start..);
+                let (first, last) = (window.clone().next()?, window.clone().next_back()?);
+                let span_s = last.0.saturating_sub(first.0) as f64 / 1000.0;
+                if span_s <= 0.0 {
+                    return None;
+                }
+                let sum_sq: f64 = window
+                    .clone()
+                    .zip(window.skip(1))
+                    .map(|(a, b)| (b.1 - a.1).powi(2))
+                    .sum();
+                let rate = sum_sq / span_s;
+                Some(Volatility {
+                    horizon_ms,
+                    vol_bps: (rate * horizon_ms as f64 / 1000.0).sqrt() * 10_000.0,
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vol_scales_with_horizon() {
+        let mut vol = RealizedVol::new(vec![4_000, 16_000]);
+        // Mid alternates by ±1 bps (log) every second.
+        for i in 1..=20u64 {
+            let step = if i % 2 == 0 { 1e-4 } else { 0.0 };
+            vol.add(100.0 * f64::exp(step), i * 1_000);
+        }
+        let estimates = vol.estimates(20_000);
+        assert_eq!(estimates.len(), 2);
+        // 1 bps per second: 2 bps over 4s, 4 bps over 16s.
+        assert!((estimates[0].vol_bps - 2.0).abs() < 1e-6);
+        assert!((estimates[1].vol_bps - 4.0).abs() < 1e-6);
+        assert!(vol.estimates(60_000).is_empty());
+    }
+}
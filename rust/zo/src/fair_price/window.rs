@@ -0,0 +1,127 @@
+//! Time-windowed offset samples shared by the windowed models.
+//!
+//! A circular buffer of per-second samples avoids allocations after warmup.
+//! The decaying models (EWMA, Kalman) keep only the sample times, so that
+//! they too count the samples of the last window. With no sample left in it
+//! they report no offset, and after such a gap they start over from the next
+//! sample and warm up again.
+
+use std::collections::VecDeque;
+
+use super::SecondGate;
+
+/// Maximum samples retained in the circular buffer (>5 min at 1/s).
+pub(super) const MAX_SAMPLES: usize = 500;
+
+/// A single offset sample: `local_mid - reference_mid` at a given second.
+#[derive(Clone, Copy)]
+struct OffsetSample {
+    offset: f64,
+    second: u64,
+}
+
+/// Circular buffer of per-second offset samples.
+pub(super) struct OffsetWindow {
+    window_ms: u64,
+    /// Pre-allocated ring buffer.
+    samples: Vec<OffsetSample>,
+    /// Next write position (wraps around at `MAX_SAMPLES`).
+    head: usize,
+    /// Number of samples written so far (capped at `MAX_SAMPLES`).
+    pub(super) count: usize,
+    gate: SecondGate,
+}
+
+impl OffsetWindow {
+    pub(super) fn new(window_ms: u64) -> Self {
+        Self {
+            window_ms,
+            samples: Vec::with_capacity(MAX_SAMPLES),
+            head: 0,
+            count: 0,
+            gate: SecondGate::default(),
+        }
+    }
+
+    /// Store `offset`, unless a sample was already stored this second.
+    /// Returns whether it was stored.
+    pub(super) fn add(&mut self, offset: f64, now_ms: u64) -> bool {
+        let Some(second) = self.gate.admit(now_ms) else {
+            return false;
+        };
+        let sample = OffsetSample { offset, second };
+
+        // Write into the circular buffer.
+        if self.samples.len() < MAX_SAMPLES {
+            self.samples.push(sample);
+        } else {
+            self.samples[self.head] = sample;
+        }
+        self.head = (self.head + 1) % MAX_SAMPLES;
+        if self.count < MAX_SAMPLES {
+            self.count += 1;
+        }
+        true
+    }
+
+    /// Number of valid (non-expired) samples.
+    pub(super) fn len(&self, now_ms: u64) -> usize {
+        let cutoff = cutoff_second(now_ms, self.window_ms);
+        self.samples[..self.count]
+            .iter()
+            .filter(|s| s.second > cutoff)
+            .count()
+    }
+
+    /// Offsets of the samples within the time window.
+    pub(super) fn offsets(&self, now_ms: u64) -> Vec<f64> {
+        let cutoff = cutoff_second(now_ms, self.window_ms);
+        self.samples[..self.count]
+            .iter()
+            .filter(|s| s.second > cutoff)
+            .map(|s| s.offset)
+            .collect()
+    }
+}
+
+/// Seconds of the per-second samples within the time window.
+pub(super) struct SampleTimes {
+    window_ms: u64,
+    seconds: VecDeque<u64>,
+    gate: SecondGate,
+}
+
+impl SampleTimes {
+    pub(super) fn new(window_ms: u64) -> Self {
+        Self {
+            window_ms,
+            seconds: VecDeque::new(),
+            gate: SecondGate::default(),
+        }
+    }
+
+    /// Record a sample at `now_ms`, unless one was already recorded this
+    /// second. Returns `None` if it was not recorded, else whether it starts
+    /// over: no earlier sample is within the window.
+    pub(super) fn add(&mut self, now_ms: u64) -> Option<bool> {
+        let second = self.gate.admit(now_ms)?;
+        let cutoff = cutoff_second(now_ms, self.window_ms);
+        while self.seconds.front().is_some_and(|&s| s <= cutoff) {
+            self.seconds.pop_front();
+        }
+        let restart = self.seconds.is_empty();
+        self.seconds.push_back(second);
+        Some(restart)
+    }
+
+    /// Number of samples within the window ending at `now_ms`.
+    pub(super) fn len(&self, now_ms: u64) -> usize {
+        let cutoff = cutoff_second(now_ms, self.window_ms);
+        self.seconds.iter().filter(|&&s| s > cutoff).count()
+    }
+}
+
+/// The cutoff second: samples at or before this second are expired.
+fn cutoff_second(now_ms: u64, window_ms: u64) -> u64 {
+    now_ms.saturating_sub(window_ms) / 1000
+}
@@ -3,12 +3,28 @@
 //! Provides two interfaces:
 //! - [`run_feed`]: CLI mode — streams prices to stdout (used by `zo feed`).
 //! - [`BinancePriceFeed`]: Struct mode — publishes [`MidPrice`] via a `watch`
-//!   channel for consumption by the market maker and monitor.
+//!   channel for consumption by the market maker and monitor, as the Binance
+//!   [`PriceSource`](crate::source::PriceSource). Optionally also consumes
+//!   `@depth<N>` and `@aggTrade` and publishes them as typed channels (shown
+//!   by the monitor).
+//!
+//! Both modes subscribe to `@bookTicker` and, when enabled, the extra streams
+//! over one combined-stream connection:
+//!
+//! ```text
+//! @bookTicker --> BookTop   --> MidPrice (watch), BookTop (watch)
+//! @depth<N>   --> DepthBook --> DepthBook (watch), weighted_mid()
+//! @aggTrade   --> AggTrade  --> TradeFlowWindow --> TradeFlow (watch)
+//! ```
 
-use std::io::{self, BufWriter, Write};
+use std::collections::{HashMap, VecDeque};
+use std::io::{self, Write};
+use std::num::ParseFloatError;
+use std::sync::Mutex;
 use std::time::Duration;
 
 use futures_util::{SinkExt, StreamExt};
+use nord::{RecordSource, RecordedFrame, Recorder, ReplaySink};
 use tokio::sync::watch;
 use tokio::time::{self, Instant};
 use tokio_tungstenite::tungstenite::Message;
@@ -16,8 +32,9 @@ use tokio_util::sync::CancellationToken;
 use tracing::{debug, error, info, warn};
 
 use crate::error::ZoError;
-use crate::output;
-use crate::types::BookTickerMsg;
+use crate::output::{self, PriceMultipliers};
+use crate::source::{scale_price, Venue};
+use crate::types::{AggTradeMsg, BookTickerMsg, DepthMsg, StreamEnvelope};
 
 const PING_INTERVAL: Duration = Duration::from_secs(30);
 const PONG_TIMEOUT: Duration = Duration::from_secs(10);
@@ -25,21 +42,386 @@ const STALE_THRESHOLD: Duration = Duration::from_secs(60);
 const STALE_CHECK_INTERVAL: Duration = Duration::from_secs(10);
 const RECONNECT_DELAY: Duration = Duration::from_secs(3);
 const BINANCE_FUTURES_WS: &str = "wss://fstream.binance.com/ws";
+const BINANCE_FUTURES_STREAM: &str = "wss://fstream.binance.com/stream";
+/// Partial book depths Binance Futures publishes.
+const DEPTH_LEVELS: [u8; 3] = [5, 10, 20];
+/// Window of the published and printed [`TradeFlow`].
+pub(crate) const TRADE_FLOW_WINDOW_MS: u64 = 10_000;
+
+// ---------------------------------------------------------------------------
+// Stream selection and typed payloads
+// ---------------------------------------------------------------------------
+
+/// Optional Binance streams consumed alongside `@bookTicker`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BinanceStreams {
+    /// Partial book depth levels (`@depth5`, `@depth10` or `@depth20`).
+    pub depth: Option<u8>,
+    /// Subscribe to `@aggTrade`.
+    pub agg_trades: bool,
+}
+
+impl BinanceStreams {
+    /// Stream names for `symbol` (lowercase), bookTicker first.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `depth` is not one of the levels Binance publishes.
+    pub fn names(&self, symbol: &str) -> Vec<String> {
+        let mut names = vec![format!("{symbol}@bookTicker")];
+        if let Some(levels) = self.depth {
+            assert!(
+                DEPTH_LEVELS.contains(&levels),
+                "unsupported depth levels {levels}, expected one of {DEPTH_LEVELS:?}"
+            );
+            names.push(format!("{symbol}@depth{levels}"));
+        }
+        if self.agg_trades {
+            names.push(format!("{symbol}@aggTrade"));
+        }
+        names
+    }
+
+    /// Whether any stream beyond `@bookTicker` is enabled.
+    pub fn is_extended(&self) -> bool {
+        self.depth.is_some() || self.agg_trades
+    }
+}
+
+/// Best bid and ask with their resting quantities, from `@bookTicker`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BookTop {
+    pub bid: f64,
+    pub bid_qty: f64,
+    pub ask: f64,
+    pub ask_qty: f64,
+    /// Binance event time (epoch ms, 0 if absent).
+    pub event_time: u64,
+    /// Receive time (epoch ms).
+    pub timestamp: u64,
+}
+
+impl BookTop {
+    /// Arithmetic mid of the top of book.
+    pub fn mid_price(&self) -> nord::MidPrice {
+        nord::MidPrice {
+            mid: (self.bid + self.ask) * 0.5,
+            exact_mid: None,
+            bid: self.bid,
+            ask: self.ask,
+            timestamp: self.timestamp,
+        }
+    }
+
+    /// Quantity-weighted mid (microprice): leans toward the side with less
+    /// resting size. Falls back to the plain mid when both sides are empty.
+    pub fn microprice(&self) -> f64 {
+        weighted_mid(self.bid, self.bid_qty, self.ask, self.ask_qty)
+    }
+}
+
+/// Partial order book from `@depth<N>`, best level first.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DepthBook {
+    /// `(price, qty)` bid levels, highest first.
+    pub bids: Vec<(f64, f64)>,
+    /// `(price, qty)` ask levels, lowest first.
+    pub asks: Vec<(f64, f64)>,
+    /// Binance event time (epoch ms).
+    pub event_time: u64,
+    /// Receive time (epoch ms).
+    pub timestamp: u64,
+}
+
+impl DepthBook {
+    /// Convert a depth payload; `None` if a level does not parse or a side
+    /// is empty.
+    pub(crate) fn from_msg(msg: &DepthMsg, timestamp: u64) -> Option<Self> {
+        let levels = |side: &[[String; 2]]| -> Option<Vec<(f64, f64)>> {
+            side.iter()
+                .map(|[price, qty]| Some((price.parse().ok()?, qty.parse().ok()?)))
+                .collect()
+        };
+        let bids = levels(&msg.b)?;
+        let asks = levels(&msg.a)?;
+        if bids.is_empty() || asks.is_empty() {
+            return None;
+        }
+        Some(Self {
+            bids,
+            asks,
+            event_time: msg.event_time,
+            timestamp,
+        })
+    }
+
+    /// Total bid quantity across the levels.
+    pub fn bid_qty(&self) -> f64 {
+        self.bids.iter().map(|(_, qty)| qty).sum()
+    }
+
+    /// Total ask quantity across the levels.
+    pub fn ask_qty(&self) -> f64 {
+        self.asks.iter().map(|(_, qty)| qty).sum()
+    }
+
+    /// Size-weighted reference mid: the volume-weighted price of each side,
+    /// combined like a microprice using the total size on each side. `None`
+    /// if a side has no levels.
+    pub fn weighted_mid(&self) -> Option<f64> {
+        let (bid_qty, ask_qty) = (self.bid_qty(), self.ask_qty());
+        let vwap = |levels: &[(f64, f64)], total: f64| {
+            if total > 0.0 {
+                Some(levels.iter().map(|(price, qty)| price * qty).sum::<f64>() / total)
+            } else {
+                levels.first().map(|level| level.0)
+            }
+        };
+        Some(weighted_mid(
+            vwap(&self.bids, bid_qty)?,
+            bid_qty,
+            vwap(&self.asks, ask_qty)?,
+            ask_qty,
+        ))
+    }
+}
+
+/// One aggregated trade from `@aggTrade`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AggTrade {
+    pub price: f64,
+    pub qty: f64,
+    /// Side of the taker: [`nord::Side::Bid`] for an aggressive buy.
+    pub aggressor: nord::Side,
+    /// Binance trade time (epoch ms).
+    pub trade_time: u64,
+    /// Receive time (epoch ms).
+    pub timestamp: u64,
+}
+
+impl AggTrade {
+    /// Convert an aggTrade payload.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the price or quantity does not parse.
+    pub(crate) fn from_msg(msg: &AggTradeMsg, timestamp: u64) -> Result<Self, ParseFloatError> {
+        Ok(Self {
+            price: msg.p.parse()?,
+            qty: msg.q.parse()?,
+            aggressor: if msg.m {
+                nord::Side::Ask
+            } else {
+                nord::Side::Bid
+            },
+            trade_time: msg.trade_time,
+            timestamp,
+        })
+    }
+}
+
+/// Aggressor volume over the trailing window, as of the last trade.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct TradeFlow {
+    /// Quantity bought by takers within the window.
+    pub buy_volume: f64,
+    /// Quantity sold by takers within the window.
+    pub sell_volume: f64,
+    /// Number of trades within the window.
+    pub trades: usize,
+    /// Price of the last trade.
+    pub last_price: Option<f64>,
+    /// Binance trade time of the last trade (epoch ms).
+    pub last_trade_time: u64,
+}
+
+impl TradeFlow {
+    /// Net taker flow in `[-1, 1]`: positive when buyers dominate.
+    pub fn imbalance(&self) -> f64 {
+        let total = self.buy_volume + self.sell_volume;
+        if total > 0.0 {
+            (self.buy_volume - self.sell_volume) / total
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Rolling window of trades that [`TradeFlow`] is computed over.
+pub(crate) struct TradeFlowWindow {
+    window_ms: u64,
+    trades: VecDeque<AggTrade>,
+}
+
+impl TradeFlowWindow {
+    pub(crate) fn new(window_ms: u64) -> Self {
+        Self {
+            window_ms,
+            trades: VecDeque::new(),
+        }
+    }
+
+    /// Add `trade`, expire trades older than the window (by trade time) and
+    /// return the resulting flow.
+    pub(crate) fn push(&mut self, trade: AggTrade) -> TradeFlow {
+        self.trades.push_back(trade);
+        let cutoff = trade.trade_time.saturating_sub(self.window_ms);
+        while self.trades.front().is_some_and(|t| t.trade_time < cutoff) {
+            self.trades.pop_front();
+        }
+
+        let mut flow = TradeFlow {
+            trades: self.trades.len(),
+            last_price: Some(trade.price),
+            last_trade_time: trade.trade_time,
+            ..TradeFlow::default()
+        };
+        for t in &self.trades {
+            match t.aggressor {
+                nord::Side::Bid => flow.buy_volume += t.qty,
+                nord::Side::Ask => flow.sell_volume += t.qty,
+            }
+        }
+        flow
+    }
+}
+
+/// Mid weighted by the opposite side's size: `(bid * ask_qty + ask *
+/// bid_qty) / (bid_qty + ask_qty)`.
+fn weighted_mid(bid: f64, bid_qty: f64, ask: f64, ask_qty: f64) -> f64 {
+    let total = bid_qty + ask_qty;
+    if total > 0.0 {
+        (bid * ask_qty + ask * bid_qty) / total
+    } else {
+        (bid + ask) * 0.5
+    }
+}
+
+/// Typed payload decoded from one stream frame.
+enum StreamEvent {
+    Top(BookTop),
+    Depth(DepthBook),
+    Trade(AggTrade),
+}
+
+impl StreamEvent {
+    /// Binance event (or trade) time and local receive time, in epoch ms.
+    fn times(&self) -> (u64, u64) {
+        match self {
+            Self::Top(top) => (top.event_time, top.timestamp),
+            Self::Depth(depth) => (depth.event_time, depth.timestamp),
+            Self::Trade(trade) => (trade.trade_time, trade.timestamp),
+        }
+    }
+}
+
+/// Decode the payload of `stream`; the stream name's suffix selects the
+/// payload type. Returns `None` on parse failure (logged at debug level).
+fn parse_stream(stream: &str, data: &str, timestamp: u64) -> Option<StreamEvent> {
+    if stream.ends_with("@bookTicker") {
+        parse_book_top(data, timestamp).map(StreamEvent::Top)
+    } else if stream.contains("@depth") {
+        match serde_json::from_str::<DepthMsg>(data) {
+            Ok(msg) => DepthBook::from_msg(&msg, timestamp).map(StreamEvent::Depth),
+            Err(e) => {
+                debug!(error = %e, "failed to parse depth");
+                None
+            }
+        }
+    } else if stream.ends_with("@aggTrade") {
+        match serde_json::from_str::<AggTradeMsg>(data) {
+            Ok(msg) => AggTrade::from_msg(&msg, timestamp)
+                .ok()
+                .map(StreamEvent::Trade),
+            Err(e) => {
+                debug!(error = %e, "failed to parse agg trade");
+                None
+            }
+        }
+    } else {
+        debug!(stream, "unknown binance stream");
+        None
+    }
+}
 
 // ---------------------------------------------------------------------------
 // BinancePriceFeed (struct mode for bot / monitor)
 // ---------------------------------------------------------------------------
 
+/// Senders for every typed channel of a [`BinancePriceFeed`].
+#[derive(Clone)]
+struct FeedChannels {
+    price: watch::Sender<Option<nord::MidPrice>>,
+    top: watch::Sender<Option<BookTop>>,
+    depth: watch::Sender<Option<DepthBook>>,
+    flow: watch::Sender<TradeFlow>,
+    /// Symbol multiplier applied to the published [`nord::MidPrice`].
+    multiplier: f64,
+    /// Event-to-receive latency per stream.
+    latency: Option<nord::LatencyTracker>,
+    /// `(stream, latency source key)` of each consumed stream, set by
+    /// [`BinancePriceFeed::bound_channels`].
+    latency_keys: Vec<(String, String)>,
+}
+
+impl FeedChannels {
+    fn new() -> Self {
+        Self {
+            multiplier: 1.0,
+            latency: None,
+            latency_keys: Vec::new(),
+            price: watch::channel(None).0,
+            top: watch::channel(None).0,
+            depth: watch::channel(None).0,
+            flow: watch::channel(TradeFlow::default()).0,
+        }
+    }
+
+    /// Publish one decoded event of `stream`; trades also update `window`.
+    fn publish(&self, stream: &str, event: StreamEvent, window: &mut TradeFlowWindow) {
+        if let Some(latency) = &self.latency {
+            if let Some((_, key)) = self.latency_keys.iter().find(|(s, _)| s == stream) {
+                let (event_ms, recv_ms) = event.times();
+                latency.record_event(key, event_ms, recv_ms);
+            }
+        }
+        match event {
+            // `send_replace` keeps the latest value even while no receiver
+            // is subscribed yet.
+            StreamEvent::Top(top) => {
+                self.price
+                    .send_replace(Some(scale_price(top.mid_price(), self.multiplier)));
+                self.top.send_replace(Some(top));
+            }
+            StreamEvent::Depth(depth) => {
+                self.depth.send_replace(Some(depth));
+            }
+            StreamEvent::Trade(trade) => {
+                self.flow.send_replace(window.push(trade));
+            }
+        }
+    }
+}
+
 /// Live Binance Futures mid-price, published via `watch` channel.
 ///
 /// Spawns a background WebSocket task with auto-reconnect, heartbeat, and
 /// stale connection detection. Consumers call [`subscribe_price`] to get a
 /// `watch::Receiver` that is updated on every book ticker message.
+///
+/// With [`with_streams`] the feed also consumes partial depth and/or
+/// aggregated trades over one combined-stream connection and publishes them
+/// as typed channels: [`BookTop`], [`DepthBook`] and the rolling
+/// [`TradeFlow`].
 pub struct BinancePriceFeed {
-    price_tx: watch::Sender<Option<nord::MidPrice>>,
+    channels: FeedChannels,
     price_rx: watch::Receiver<Option<nord::MidPrice>>,
     cancel: CancellationToken,
-    ws_url: String,
+    symbol: String,
+    /// bookTicker stream name, the key of frames without an envelope.
+    stream: String,
+    streams: BinanceStreams,
+    recorder: Option<Recorder>,
 }
 
 impl BinancePriceFeed {
@@ -47,26 +429,75 @@ impl BinancePriceFeed {
     ///
     /// Does **not** connect yet — call [`connect`] to start.
     pub fn new(symbol: &str) -> Self {
-        let (price_tx, price_rx) = watch::channel(None);
-        let ws_url = format!("{BINANCE_FUTURES_WS}/{symbol}@bookTicker");
+        let channels = FeedChannels::new();
+        let price_rx = channels.price.subscribe();
         Self {
-            price_tx,
+            channels,
             price_rx,
             cancel: CancellationToken::new(),
-            ws_url,
+            symbol: symbol.to_string(),
+            stream: format!("{symbol}@bookTicker"),
+            streams: BinanceStreams::default(),
+            recorder: None,
         }
     }
 
+    /// Also consume the given depth / aggTrade streams.
+    ///
+    /// Must be called before [`connect`] to take effect.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `streams.depth` is not 5, 10 or 20.
+    pub fn with_streams(mut self, streams: BinanceStreams) -> Self {
+        // Validate eagerly rather than at connect time.
+        streams.names(&self.symbol);
+        self.streams = streams;
+        self
+    }
+
+    /// Multiply the published mid-price by `multiplier` (see
+    /// [`SymbolMapping::multiplier`](crate::symbols::SymbolMapping::multiplier)).
+    /// The other typed channels stay in Binance units.
+    ///
+    /// Must be called before [`connect`] or [`replay_sink`] to take effect.
+    pub fn with_multiplier(mut self, multiplier: f64) -> Self {
+        self.channels.multiplier = multiplier;
+        self
+    }
+
+    /// Record each stream's event-to-receive latency into `latency`, as
+    /// `"Binance:<stream>"`.
+    ///
+    /// Must be called before [`connect`] or [`replay_sink`] to take effect.
+    pub fn with_latency(mut self, latency: nord::LatencyTracker) -> Self {
+        self.channels.latency = Some(latency);
+        self
+    }
+
+    /// Record every raw message to `recorder`, keyed by its stream name.
+    ///
+    /// Must be called before [`connect`] to take effect.
+    pub fn set_recorder(&mut self, recorder: Recorder) {
+        self.recorder = Some(recorder);
+    }
+
     /// Start the background WebSocket connection.
     pub fn connect(&self) {
-        let url = self.ws_url.clone();
-        let tx = self.price_tx.clone();
+        let url = self.url();
+        let mut state = ConnectionState {
+            channels: self.bound_channels(),
+            window: TradeFlowWindow::new(TRADE_FLOW_WINDOW_MS),
+            combined: self.streams.is_extended(),
+            stream: self.stream.clone(),
+            recorder: self.recorder.clone(),
+        };
         let cancel = self.cancel.clone();
 
         tokio::spawn(async move {
             info!(url = %url, "binance feed starting");
             loop {
-                match run_price_connection(&url, &tx, &cancel).await {
+                match run_price_connection(&url, &mut state, &cancel).await {
                     Ok(()) => {
                         info!("binance feed stopped gracefully");
                         return;
@@ -87,6 +518,43 @@ impl BinancePriceFeed {
         });
     }
 
+    /// URL to connect to: the raw bookTicker stream, or a combined stream
+    /// when extra streams are enabled.
+    fn url(&self) -> String {
+        if self.streams.is_extended() {
+            format!(
+                "{BINANCE_FUTURES_STREAM}?streams={}",
+                self.streams.names(&self.symbol).join("/")
+            )
+        } else {
+            format!("{BINANCE_FUTURES_WS}/{}", self.stream)
+        }
+    }
+
+    /// Symbol this feed tracks (e.g. `"btcusdt"`).
+    pub fn symbol(&self) -> &str {
+        &self.symbol
+    }
+
+    /// A sink that publishes replayed frames into this feed's channels,
+    /// timestamped with their recorded receive time. The frame key (the
+    /// stream name) selects the stream type; frames of streams this feed
+    /// does not consume, such as other symbols', are skipped, so the sink can
+    /// be routed every Binance frame. Do not [`connect`] a feed that is being
+    /// fed by a replay.
+    ///
+    /// Every published price is counted in `ack`; the price consumer
+    /// acknowledges it with [`nord::ReplayAck::observe`], so that a max-speed
+    /// replay does not overwrite a price before it has been read.
+    pub fn replay_sink(&self, ack: nord::ReplayAck) -> BinanceReplaySink {
+        BinanceReplaySink {
+            channels: self.bound_channels(),
+            window: Mutex::new(TradeFlowWindow::new(TRADE_FLOW_WINDOW_MS)),
+            streams: self.streams.names(&self.symbol),
+            ack,
+        }
+    }
+
     /// Latest mid-price snapshot (lock-free read).
     pub fn get_mid_price(&self) -> Option<nord::MidPrice> {
         *self.price_rx.borrow()
@@ -97,17 +565,113 @@ impl BinancePriceFeed {
         self.price_rx.clone()
     }
 
+    /// Channels of one connection or replay, with the latency key of every
+    /// consumed stream formatted once rather than per frame.
+    fn bound_channels(&self) -> FeedChannels {
+        let mut channels = self.channels.clone();
+        channels.latency_keys = self
+            .streams
+            .names(&self.symbol)
+            .into_iter()
+            .map(|stream| {
+                let key = format!("{}:{stream}", Venue::Binance);
+                (stream, key)
+            })
+            .collect();
+        channels
+    }
+
+    /// Subscribe to top-of-book updates including quantities.
+    pub fn subscribe_book_top(&self) -> watch::Receiver<Option<BookTop>> {
+        self.channels.top.subscribe()
+    }
+
+    /// Subscribe to partial depth snapshots (requires a depth stream).
+    pub fn subscribe_depth(&self) -> watch::Receiver<Option<DepthBook>> {
+        self.channels.depth.subscribe()
+    }
+
+    /// Subscribe to the rolling trade flow (requires the aggTrade stream).
+    pub fn subscribe_trade_flow(&self) -> watch::Receiver<TradeFlow> {
+        self.channels.flow.subscribe()
+    }
+
     /// Gracefully shut down the background task.
     pub fn close(&self) {
         self.cancel.cancel();
     }
 }
 
-/// Single WebSocket connection that parses book tickers into [`MidPrice`] and
-/// sends them via the `watch` channel.
+/// Replay sink returned by [`BinancePriceFeed::replay_sink`].
+pub struct BinanceReplaySink {
+    channels: FeedChannels,
+    window: Mutex<TradeFlowWindow>,
+    /// Stream names the feed consumes.
+    streams: Vec<String>,
+    /// Published prices not yet handled by the price consumer.
+    ack: nord::ReplayAck,
+}
+
+impl ReplaySink for BinanceReplaySink {
+    fn deliver(&self, frame: &RecordedFrame) {
+        if !self.streams.contains(&frame.key) {
+            return;
+        }
+        if let Some(event) = parse_stream(&frame.key, &frame.frame, frame.recv_ms) {
+            if matches!(event, StreamEvent::Top(_)) {
+                self.ack.publish();
+            }
+            let mut window = self.window.lock().unwrap_or_else(|e| e.into_inner());
+            self.channels.publish(&frame.key, event, &mut window);
+        }
+    }
+
+    fn backlog(&self) -> usize {
+        self.ack.pending()
+    }
+}
+
+/// State carried across reconnects of one [`BinancePriceFeed`].
+struct ConnectionState {
+    channels: FeedChannels,
+    window: TradeFlowWindow,
+    /// Connected to the combined endpoint: every frame is an envelope.
+    combined: bool,
+    /// bookTicker stream name, the key of frames on the raw endpoint.
+    stream: String,
+    recorder: Option<Recorder>,
+}
+
+impl ConnectionState {
+    /// Record and publish one text frame, unwrapping the combined-stream
+    /// envelope on the combined endpoint.
+    fn on_text(&mut self, text: &str) {
+        let (stream, data) = if self.combined {
+            match serde_json::from_str::<StreamEnvelope>(text) {
+                Ok(envelope) => (envelope.stream, envelope.data.get()),
+                Err(e) => {
+                    warn!(error = %e, "binance frame without a stream envelope — dropped");
+                    return;
+                }
+            }
+        } else {
+            (self.stream.as_str(), text)
+        };
+        if let Some(recorder) = &self.recorder {
+            recorder.record(RecordSource::Binance, stream, data);
+        }
+        if let Some(event) = parse_stream(stream, data, nord::epoch_ms()) {
+            self.channels.publish(stream, event, &mut self.window);
+        }
+    }
+}
+
+/// Single WebSocket connection that decodes frames and publishes them via
+/// the feed's channels. Raw messages are passed to the recorder (if any)
+/// before parsing.
 async fn run_price_connection(
     url: &str,
-    tx: &watch::Sender<Option<nord::MidPrice>>,
+    state: &mut ConnectionState,
     cancel: &CancellationToken,
 ) -> Result<(), ZoError> {
     let (ws_stream, _) = tokio_tungstenite::connect_async(url).await?;
@@ -133,9 +697,7 @@ async fn run_price_connection(
                 match msg {
                     Some(Ok(Message::Text(text))) => {
                         last_message_time = Instant::now();
-                        if let Some(mid) = parse_book_ticker(&text) {
-                            let _ = tx.send(Some(mid));
-                        }
+                        state.on_text(&text);
                     }
                     Some(Ok(Message::Ping(data))) => {
                         last_message_time = Instant::now();
@@ -172,10 +734,11 @@ async fn run_price_connection(
     }
 }
 
-/// Parse a Binance bookTicker JSON into a [`MidPrice`].
+/// Parse a Binance bookTicker JSON into a [`BookTop`] stamped with
+/// `timestamp` (epoch ms).
 ///
 /// Returns `None` on parse failure (logged at debug level).
-fn parse_book_ticker(text: &str) -> Option<nord::MidPrice> {
+fn parse_book_top(text: &str, timestamp: u64) -> Option<BookTop> {
     let msg: BookTickerMsg = match serde_json::from_str(text) {
         Ok(m) => m,
         Err(e) => {
@@ -183,49 +746,51 @@ fn parse_book_ticker(text: &str) -> Option<nord::MidPrice> {
             return None;
         }
     };
-    let bid: f64 = msg.b.parse().ok()?;
-    let ask: f64 = msg.a.parse().ok()?;
-    let mid = (bid + ask) * 0.5;
-    let timestamp = epoch_ms();
-    Some(nord::MidPrice {
-        mid,
-        bid,
-        ask,
+    Some(BookTop {
+        bid: msg.b.parse().ok()?,
+        bid_qty: msg.bid_qty.parse().ok()?,
+        ask: msg.a.parse().ok()?,
+        ask_qty: msg.ask_qty.parse().ok()?,
+        event_time: msg.event_time,
         timestamp,
     })
 }
 
-/// Current wall-clock time in epoch milliseconds.
-fn epoch_ms() -> u64 {
-    std::time::SystemTime::now()
-        .duration_since(std::time::UNIX_EPOCH)
-        .unwrap_or_default()
-        .as_millis() as u64
-}
-
 // ---------------------------------------------------------------------------
 // run_feed (CLI stdout mode, used by `zo feed`)
 // ---------------------------------------------------------------------------
 
 /// Build the combined stream URL for the given symbols.
-fn build_url(symbols: &[String]) -> String {
-    let streams: Vec<String> = symbols
+fn build_url(symbols: &[String], streams: &BinanceStreams) -> String {
+    let names: Vec<String> = symbols
         .iter()
-        .map(|s| format!("{}@bookTicker", s.to_lowercase()))
+        .flat_map(|s| streams.names(&s.to_lowercase()))
         .collect();
-    format!(
-        "wss://fstream.binance.com/stream?streams={}",
-        streams.join("/")
-    )
+    format!("{BINANCE_FUTURES_STREAM}?streams={}", names.join("/"))
 }
 
 /// Outer reconnection loop. Runs until cancelled. Streams to stdout.
-pub async fn run_feed(symbols: &[String], json_mode: bool, cancel: CancellationToken) {
-    let url = build_url(symbols);
+///
+/// # Arguments
+///
+/// * `symbols` - Binance symbols (e.g. `btcusdt`).
+/// * `multipliers` - Conversion of listed symbols into 01 prices (empty to
+///   print Binance prices).
+/// * `json_mode` - Print JSON lines instead of TSV.
+/// * `streams` - Depth / aggTrade streams to print besides the book ticker.
+/// * `cancel` - Stops the feed.
+pub async fn run_feed(
+    symbols: &[String],
+    multipliers: &PriceMultipliers,
+    json_mode: bool,
+    streams: &BinanceStreams,
+    cancel: CancellationToken,
+) {
+    let url = build_url(symbols, streams);
     info!(url = %url, "starting feed");
 
     loop {
-        match run_single_connection(&url, json_mode, &cancel).await {
+        match run_single_connection(&url, multipliers, json_mode, &cancel).await {
             Ok(()) => {
                 info!("feed stopped gracefully");
                 return;
@@ -251,6 +816,7 @@ pub async fn run_feed(symbols: &[String], json_mode: bool, cancel: CancellationT
 /// Single WebSocket connection lifetime (stdout mode).
 async fn run_single_connection(
     url: &str,
+    multipliers: &PriceMultipliers,
     json_mode: bool,
     cancel: &CancellationToken,
 ) -> Result<(), ZoError> {
@@ -261,9 +827,11 @@ async fn run_single_connection(
 
     info!("connected");
 
-    let stdout = io::stdout().lock();
-    let mut writer = BufWriter::new(stdout);
+    // Every line is written in one call and flushed, so stdout is locked per
+    // line and a concurrent 01 feed (`--venue both`) interleaves whole lines.
+    let mut writer = io::stdout();
     let mut buf = String::with_capacity(512);
+    let mut flows: HashMap<String, TradeFlowWindow> = HashMap::new();
 
     let mut last_message_time = Instant::now();
     let mut ping_interval = time::interval(PING_INTERVAL);
@@ -286,7 +854,7 @@ async fn run_single_connection(
                 match msg {
                     Some(Ok(Message::Text(text))) => {
                         last_message_time = Instant::now();
-                        if let Err(e) = output::handle_message(&text, json_mode, &mut buf, &mut writer) {
+                        if let Err(e) = output::handle_message(&text, json_mode, multipliers, &mut flows, &mut buf, &mut writer) {
                             debug!(error = %e, "failed to handle message");
                         }
                     }
@@ -344,7 +912,7 @@ mod tests {
     #[test]
     fn test_parse_book_ticker_to_mid_price() {
         let json = r#"{"s":"BTCUSDT","b":"50000.00","a":"50010.00","B":"1.5","A":"2.0"}"#;
-        let mid = parse_book_ticker(json).unwrap();
+        let mid = parse_book_top(json, nord::epoch_ms()).unwrap().mid_price();
         assert!((mid.bid - 50000.0).abs() < 1e-6);
         assert!((mid.ask - 50010.0).abs() < 1e-6);
         assert!((mid.mid - 50005.0).abs() < 1e-6);
@@ -354,19 +922,162 @@ mod tests {
     #[test]
     fn test_mid_price_calculation() {
         let json = r#"{"s":"ETHUSDT","b":"3000.50","a":"3001.50","B":"10","A":"10"}"#;
-        let mid = parse_book_ticker(json).unwrap();
+        let mid = parse_book_top(json, 0).unwrap().mid_price();
         // (3000.50 + 3001.50) / 2 = 3001.0
         assert!((mid.mid - 3001.0).abs() < 1e-6);
     }
 
+    #[test]
+    fn test_replay_sink_uses_recorded_time() {
+        let latency = nord::LatencyTracker::new();
+        let feed = BinancePriceFeed::new("btcusdt").with_latency(latency.clone());
+        let ack = nord::ReplayAck::new();
+        let sink = feed.replay_sink(ack.clone());
+        sink.deliver(&RecordedFrame {
+            recv_ms: 1_234,
+            source: RecordSource::Binance,
+            key: feed.stream.clone(),
+            frame: r#"{"E":1200,"s":"BTCUSDT","b":"100","a":"102","B":"1","A":"1"}"#.into(),
+        });
+        let stats = latency.get("Binance:btcusdt@bookTicker", 1_234).unwrap();
+        assert_eq!(stats.last_ms, 34.0);
+        // The price is outstanding until the consumer has handled it.
+        assert_eq!(sink.backlog(), 1);
+        let guard = ack.observe();
+        let mid = feed.get_mid_price().unwrap();
+        drop(guard);
+        assert_eq!(sink.backlog(), 0);
+        assert_eq!(mid.timestamp, 1_234);
+        assert!((mid.mid - 101.0).abs() < 1e-9);
+    }
+
     #[test]
     fn test_parse_invalid_json_returns_none() {
-        assert!(parse_book_ticker("not json").is_none());
+        assert!(parse_book_top("not json", 0).is_none());
     }
 
     #[test]
     fn test_parse_missing_fields_returns_none() {
         let json = r#"{"s":"BTCUSDT","b":"invalid","a":"50010.00"}"#;
-        assert!(parse_book_ticker(json).is_none());
+        assert!(parse_book_top(json, 0).is_none());
+    }
+
+    #[test]
+    fn test_depth_weighted_mid_and_microprice() {
+        let data = r#"{"e":"depthUpdate","E":7,"s":"BTCUSDT","b":[["100","1"],["99","3"]],"a":[["101","2"],["102","2"]]}"#;
+        let Some(StreamEvent::Depth(depth)) = parse_stream("btcusdt@depth5", data, 1) else {
+            panic!("expected depth");
+        };
+        assert_eq!(depth.event_time, 7);
+        // Bid VWAP 99.25 over 4, ask VWAP 101.5 over 4.
+        assert!((depth.weighted_mid().unwrap() - 100.375).abs() < 1e-9);
+        let one_sided = DepthBook {
+            asks: Vec::new(),
+            ..depth
+        };
+        assert_eq!(one_sided.weighted_mid(), None);
+
+        let top =
+            parse_book_top(r#"{"s":"BTCUSDT","b":"100","a":"101","B":"3","A":"1"}"#, 0).unwrap();
+        // Heavy bid pulls the microprice toward the ask.
+        assert!((top.microprice() - 100.75).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_trade_flow_window_expires_old_trades() {
+        let trade = |qty: f64, buyer_maker: bool, trade_time: u64| {
+            let data = format!(
+                r#"{{"e":"aggTrade","a":1,"s":"BTCUSDT","p":"100","q":"{qty}","T":{trade_time},"m":{buyer_maker}}}"#
+            );
+            match parse_stream("btcusdt@aggTrade", &data, 0) {
+                Some(StreamEvent::Trade(trade)) => trade,
+                _ => panic!("expected trade"),
+            }
+        };
+        let mut window = TradeFlowWindow::new(1_000);
+        window.push(trade(2.0, false, 0));
+        let flow = window.push(trade(1.0, true, 500));
+        assert_eq!(
+            (flow.buy_volume, flow.sell_volume, flow.trades),
+            (2.0, 1.0, 2)
+        );
+
+        let flow = window.push(trade(1.0, true, 1_200));
+        assert_eq!(
+            (flow.buy_volume, flow.sell_volume, flow.trades),
+            (0.0, 2.0, 2)
+        );
+        assert_eq!(flow.last_price, Some(100.0));
+        assert_eq!(flow.imbalance(), -1.0);
+    }
+
+    #[test]
+    fn test_combined_stream_frames_reach_typed_channels() {
+        let feed = BinancePriceFeed::new("btcusdt").with_streams(BinanceStreams {
+            depth: Some(5),
+            agg_trades: true,
+        });
+        assert_eq!(
+            feed.url(),
+            "wss://fstream.binance.com/stream?streams=btcusdt@bookTicker/btcusdt@depth5/btcusdt@aggTrade"
+        );
+
+        let mut state = ConnectionState {
+            channels: feed.channels.clone(),
+            window: TradeFlowWindow::new(TRADE_FLOW_WINDOW_MS),
+            combined: true,
+            stream: feed.stream.clone(),
+            recorder: None,
+        };
+        state.on_text(
+            r#"{"stream":"btcusdt@bookTicker","data":{"s":"BTCUSDT","b":"100","a":"102","B":"1","A":"1"}}"#,
+        );
+        state.on_text(
+            r#"{"stream":"btcusdt@aggTrade","data":{"s":"BTCUSDT","p":"101","q":"0.5","T":9,"m":false}}"#,
+        );
+
+        assert!((feed.get_mid_price().unwrap().mid - 101.0).abs() < 1e-9);
+        assert_eq!(feed.subscribe_book_top().borrow().unwrap().ask_qty, 1.0);
+        assert_eq!(feed.subscribe_trade_flow().borrow().buy_volume, 0.5);
+        assert!(feed.subscribe_depth().borrow().is_none());
+
+        // A frame without an envelope is dropped, not read as bookTicker.
+        state.on_text(r#"{"s":"BTCUSDT","b":"200","a":"202","B":"1","A":"1"}"#);
+        assert!((feed.get_mid_price().unwrap().mid - 101.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_replay_sink_routes_every_consumed_stream() {
+        let feed = BinancePriceFeed::new("btcusdt").with_streams(BinanceStreams {
+            depth: Some(5),
+            agg_trades: true,
+        });
+        let sink = feed.replay_sink(nord::ReplayAck::new());
+        let frame = |key: &str, frame: &str| RecordedFrame {
+            recv_ms: 1,
+            source: RecordSource::Binance,
+            key: key.to_string(),
+            frame: frame.into(),
+        };
+        sink.deliver(&frame(
+            "btcusdt@depth5",
+            r#"{"E":7,"s":"BTCUSDT","b":[["100","1"]],"a":[["101","1"]]}"#,
+        ));
+        sink.deliver(&frame(
+            "btcusdt@aggTrade",
+            r#"{"s":"BTCUSDT","p":"101","q":"0.5","T":9,"m":true}"#,
+        ));
+        // Another symbol's frames in the same recording are skipped.
+        sink.deliver(&frame(
+            "ethusdt@bookTicker",
+            r#"{"s":"ETHUSDT","b":"100","a":"102","B":"1","A":"1"}"#,
+        ));
+
+        assert_eq!(
+            feed.subscribe_depth().borrow().as_ref().unwrap().event_time,
+            7
+        );
+        assert_eq!(feed.subscribe_trade_flow().borrow().sell_volume, 0.5);
+        assert!(feed.get_mid_price().is_none());
     }
 }
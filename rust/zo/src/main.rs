@@ -1,5 +1,6 @@
 mod cli;
 mod client;
+mod composite;
 mod error;
 mod fair_price;
 mod feed;
@@ -7,7 +8,11 @@ mod mm;
 mod monitor;
 mod orders;
 mod output;
+mod record;
+mod source;
+mod symbols;
 mod types;
+mod zo_feed;
 
 use clap::Parser;
 use cli::Command;
@@ -39,8 +44,46 @@ async fn main() {
 
     match cli.command {
         Command::Feed(args) => {
-            info!(symbols = ?args.symbols, json = args.json, "feed starting");
-            feed::run_feed(&args.symbols, args.json, cancel).await;
+            info!(
+                symbols = ?args.symbols,
+                venue = ?args.venue,
+                json = args.json,
+                depth = ?args.depth,
+                trades = args.trades,
+                "feed starting"
+            );
+            let streams = feed::BinanceStreams {
+                depth: args.depth,
+                agg_trades: args.trades,
+            };
+            match args.venue {
+                zo_feed::FeedVenue::Binance => {
+                    feed::run_feed(
+                        &args.symbols,
+                        &Default::default(),
+                        args.json,
+                        &streams,
+                        cancel,
+                    )
+                    .await;
+                }
+                venue => {
+                    let registry = or_exit(symbol_registry(args.symbol_map.as_deref()));
+                    let reference = (venue == zo_feed::FeedVenue::Both).then_some(&streams);
+                    let feed = zo_feed::run_zo_feed(
+                        &args.symbols,
+                        args.json,
+                        args.trades,
+                        reference,
+                        &registry,
+                        cancel,
+                    );
+                    if let Err(e) = feed.await {
+                        tracing::error!(error = %e, "feed error");
+                        std::process::exit(1);
+                    }
+                }
+            }
         }
 
         Command::MarketMaker(args) => {
@@ -54,21 +97,7 @@ async fn main() {
                 }
             };
 
-            let config = mm::config::MarketMakerConfig {
-                symbol: args.symbol.to_uppercase(),
-                spread_bps: args.spread_bps,
-                take_profit_bps: args.take_profit_bps,
-                order_size_usd: args.order_size_usd,
-                close_threshold_usd: args.close_threshold_usd,
-                warmup_seconds: args.warmup_seconds,
-                update_throttle_ms: args.update_throttle_ms,
-                order_sync_interval_ms: args.order_sync_interval_ms,
-                fair_price_window_ms: args.fair_price_window_ms,
-                position_sync_interval_ms: args.position_sync_interval_ms,
-                ..Default::default()
-            };
-
-            let bot = mm::bot::MarketMaker::new(config, private_key);
+            let bot = mm::bot::MarketMaker::new(or_exit(mm_config(&args)), private_key);
             if let Err(e) = bot.run(cancel).await {
                 tracing::error!(error = %e, "market maker fatal error");
                 std::process::exit(1);
@@ -77,12 +106,170 @@ async fn main() {
 
         Command::Monitor(args) => {
             let _ = dotenvy::dotenv();
-            if let Err(e) = monitor::run_monitor(&args.symbol, cancel).await {
+            let reference = or_exit(reference_config(&args.reference));
+            let binance = feed::BinanceStreams {
+                depth: args.binance_depth,
+                agg_trades: args.binance_trades,
+            };
+            let monitor =
+                monitor::run_monitor(&args.symbol, args.account_id, &reference, &binance, cancel);
+            if let Err(e) = monitor.await {
                 tracing::error!(error = %e, "monitor error");
                 std::process::exit(1);
             }
         }
+
+        Command::Record(args) => {
+            let registry = or_exit(symbol_registry(args.symbol_map.as_deref()));
+            let config = nord::RecorderConfig {
+                dir: args.dir.into(),
+                rotate_bytes: args.rotate_mb * 1024 * 1024,
+                rotate_interval: std::time::Duration::from_secs(args.rotate_minutes * 60),
+                ..Default::default()
+            };
+            if let Err(e) = record::run_record(&args.symbols, &registry, config, cancel).await {
+                tracing::error!(error = %e, "record error");
+                std::process::exit(1);
+            }
+        }
+
+        Command::Replay(args) => {
+            let recording = match nord::Recording::load_dir(std::path::Path::new(&args.dir)) {
+                Ok(r) => r,
+                Err(e) => {
+                    tracing::error!(error = %e, dir = %args.dir, "failed to load recording");
+                    std::process::exit(1);
+                }
+            };
+            let bot = mm::bot::MarketMaker::new(or_exit(mm_config(&args.mm)), String::new());
+            match bot
+                .replay(recording, args.speed, args.compare_fair_price, cancel)
+                .await
+            {
+                Ok(stats) => info!(
+                    delivered = stats.delivered,
+                    stalled = stats.stalled,
+                    span_ms = stats.span_ms,
+                    "replay complete"
+                ),
+                Err(e) => {
+                    tracing::error!(error = %e, "replay error");
+                    std::process::exit(1);
+                }
+            }
+        }
+    }
+}
+
+/// Build the market maker configuration from CLI arguments.
+///
+/// # Errors
+///
+/// Returns an error if the fair price or reference configuration is invalid.
+fn mm_config(args: &cli::MarketMakerArgs) -> Result<mm::config::MarketMakerConfig, error::ZoError> {
+    let mut fair_price = fair_price::FairPriceConfig {
+        method: args.fair_price_method,
+        window_ms: args.fair_price_window_ms,
+        min_samples: args.warmup_seconds,
+        trim_fraction: args.fair_price_trim,
+        ewma_half_life_ms: args.fair_price_half_life_ms,
+        kalman_process_var_bps2: args.fair_price_kalman_process_var,
+        kalman_measurement_var_bps2: args.fair_price_kalman_measurement_var,
+        breaker: fair_price::BreakerConfig {
+            max_offset_deviation_bps: args.breaker_offset_bps,
+            max_reference_move_bps: args.breaker_jump_bps,
+            reference_window_ms: args.breaker_jump_window_ms,
+            max_divergence_bps: args.breaker_divergence_bps,
+            cooldown_ms: args.breaker_cooldown_ms,
+        },
+        align: fair_price::AlignConfig {
+            tolerance_ms: args.align_tolerance_ms,
+            interpolate: args.align_interpolate,
+        },
+        ..Default::default()
+    };
+    // The quoter reads the volatility at its spread horizon.
+    if !fair_price
+        .volatility_horizons_ms
+        .contains(&args.vol_spread_horizon_ms)
+    {
+        fair_price
+            .volatility_horizons_ms
+            .push(args.vol_spread_horizon_ms);
+        fair_price.volatility_horizons_ms.sort_unstable();
     }
+    fair_price.validate()?;
+    if args.fair_price_snapshot_interval_ms == 0 {
+        return Err(error::ZoError::Config(
+            "fair price snapshot interval must be > 0".to_string(),
+        ));
+    }
+    Ok(mm::config::MarketMakerConfig {
+        symbol: args.symbol.to_uppercase(),
+        spread_bps: args.spread_bps,
+        take_profit_bps: args.take_profit_bps,
+        order_size_usd: args.order_size_usd,
+        close_threshold_usd: args.close_threshold_usd,
+        update_throttle_ms: args.update_throttle_ms,
+        order_sync_interval_ms: args.order_sync_interval_ms,
+        fair_price,
+        vol_spread_multiplier: args.vol_spread_multiplier,
+        vol_spread_horizon_ms: args.vol_spread_horizon_ms,
+        confidence_z: args.confidence_z,
+        fair_price_snapshot: args.fair_price_snapshot.clone(),
+        fair_price_snapshot_interval_ms: args.fair_price_snapshot_interval_ms,
+        position_sync_interval_ms: args.position_sync_interval_ms,
+        book_audit_interval_ms: args.book_audit_interval_ms,
+        latency_file: args.latency_file.clone(),
+        reference: reference_config(&args.reference)?,
+        ..Default::default()
+    })
+}
+
+/// Build the reference price configuration from CLI arguments.
+///
+/// # Errors
+///
+/// Returns an error if the symbol map cannot be loaded.
+fn reference_config(
+    args: &cli::ReferenceArgs,
+) -> Result<composite::ReferenceConfig, error::ZoError> {
+    Ok(composite::ReferenceConfig {
+        venues: args.reference_venue.clone(),
+        symbols: args.reference_symbol.iter().cloned().collect(),
+        registry: symbol_registry(args.symbol_map.as_deref())?,
+        max_index_deviation_bps: args.reference_max_index_deviation_bps,
+        composite: composite::CompositeConfig {
+            method: args.reference_method,
+            stale_ms: args.reference_stale_ms,
+            outlier_bps: args.reference_outlier_bps,
+            weights: args.reference_weight.iter().copied().collect(),
+            ..Default::default()
+        },
+    })
+}
+
+/// Load the symbol map from `path` over the built-in one, or the built-in map
+/// alone.
+///
+/// # Errors
+///
+/// Returns an error if the symbol map cannot be loaded.
+fn symbol_registry(
+    path: Option<&std::path::Path>,
+) -> Result<symbols::SymbolRegistry, error::ZoError> {
+    match path {
+        Some(path) => symbols::SymbolRegistry::load(path),
+        None => Ok(symbols::SymbolRegistry::builtin()),
+    }
+}
+
+/// Unwrap a configuration result, exiting with an error log on failure.
+fn or_exit<T>(result: Result<T, error::ZoError>) -> T {
+    result.unwrap_or_else(|e| {
+        tracing::error!(error = %e, "invalid configuration");
+        std::process::exit(1);
+    })
 }
 
 /// Register SIGINT and SIGTERM handlers that trigger the returned token.
@@ -3,23 +3,39 @@
 //! Ports `src/bots/mm/index.ts`. Uses a `tokio::select!` event loop instead
 //! of callbacks + lodash throttle.
 
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::Duration;
 
 use nord::{NordUser, Side};
 use rust_decimal::Decimal;
-use tokio::time::{self, Instant};
+use serde::Serialize;
+use tokio::sync::{mpsc, watch};
+use tokio::time;
 use tokio_util::sync::CancellationToken;
-use tracing::{error, info, warn};
+use tracing::{debug, error, info, warn};
 
 use crate::client::{create_zo_client, ZoClient};
+use crate::composite::build_reference;
 use crate::error::ZoError;
-use crate::fair_price::{FairPriceCalculator, FairPriceConfig};
+use crate::fair_price::align::AlignedMids;
+use crate::fair_price::harness::{self, BasisSample};
+use crate::fair_price::snapshot::FairPriceSnapshot;
+use crate::fair_price::{FairPrice, FairPriceModel, FairPriceState, PriceAligner, PricingStatus};
 use crate::feed::BinancePriceFeed;
 use crate::mm::config::MarketMakerConfig;
 use crate::mm::position::{PositionConfig, PositionTracker};
 use crate::mm::quoter::Quoter;
 use crate::orders::{cancel_orders, update_quotes, CachedOrder};
+use crate::source::{PriceSource, Venue};
+use crate::symbols::check_index_band;
+
+/// How long to wait for the first reference price before the index check.
+const INDEX_CHECK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How often latency percentiles are logged (and exported).
+const LATENCY_LOG_INTERVAL: Duration = Duration::from_secs(60);
 
 /// Top-level market maker.
 pub struct MarketMaker {
@@ -31,19 +47,6 @@ pub struct MarketMaker {
 // Helpers (pure, testable)
 // ---------------------------------------------------------------------------
 
-/// Derive Binance Futures symbol from an exchange market symbol.
-///
-/// `"BTC-PERP"` → `"btcusdt"`, `"ETH-PERP"` → `"ethusdt"`.
-pub fn derive_binance_symbol(market_symbol: &str) -> String {
-    let base = market_symbol
-        .split('-')
-        .next()
-        .unwrap_or(market_symbol)
-        .to_lowercase()
-        .replace("usd", "");
-    format!("{base}usdt")
-}
-
 /// Convert server API orders to [`CachedOrder`]s.
 pub fn map_api_orders_to_cached(orders: &[nord::OpenOrder]) -> Vec<CachedOrder> {
     orders
@@ -69,9 +72,10 @@ impl MarketMaker {
     /// Run the market maker until `cancel` is triggered.
     ///
     /// This is the main entry point. It:
-    /// 1. Connects to the exchange and Binance.
+    /// 1. Connects to the exchange and the reference venue.
     /// 2. Finds the market and initialises components.
-    /// 3. Warms up the fair price calculator.
+    /// 3. Warms up the fair price calculator, starting from the saved
+    ///    snapshot if configured.
     /// 4. Enters the main event loop (quote, fill, sync, status).
     /// 5. On shutdown, cancels all active orders.
     pub async fn run(&self, cancel: CancellationToken) -> Result<(), ZoError> {
@@ -86,54 +90,11 @@ impl MarketMaker {
         } = client;
 
         // --- Find market ---
-        let market = nord
-            .markets
-            .iter()
-            .find(|m| {
-                m.symbol
-                    .to_uppercase()
-                    .starts_with(&self.config.symbol.to_uppercase())
-            })
-            .ok_or_else(|| {
-                let available: Vec<_> = nord.markets.iter().map(|m| m.symbol.as_str()).collect();
-                ZoError::MarketNotFound(format!(
-                    "\"{}\" not found. Available: {}",
-                    self.config.symbol,
-                    available.join(", ")
-                ))
-            })?;
-
+        let market = self.find_market(&nord)?;
         let market_id = market.market_id;
         let market_symbol = market.symbol.clone();
-        let binance_symbol = derive_binance_symbol(&market_symbol);
-
-        info!(
-            market = %market_symbol,
-            binance = %binance_symbol,
-            spread_bps = self.config.spread_bps,
-            order_size_usd = self.config.order_size_usd,
-            close_threshold_usd = self.config.close_threshold_usd,
-            "CONFIG"
-        );
-
-        // --- Build strategy components ---
-        let fair_price_calc = FairPriceCalculator::new(FairPriceConfig {
-            window_ms: self.config.fair_price_window_ms,
-            min_samples: self.config.warmup_seconds,
-        });
-
-        let position_tracker = PositionTracker::new(PositionConfig {
-            close_threshold_usd: self.config.close_threshold_usd,
-            sync_interval_ms: self.config.position_sync_interval_ms,
-        });
-
-        let quoter = Quoter::new(
-            market.price_decimals,
-            market.size_decimals,
-            self.config.spread_bps,
-            self.config.take_profit_bps,
-            self.config.order_size_usd,
-        );
+        let reference = build_reference(&self.config.reference, &market_symbol, nord.latency());
+        self.log_config(&market_symbol, reference.as_ref());
 
         // --- Build streams ---
         let ws = nord.create_websocket_client(
@@ -143,17 +104,20 @@ impl MarketMaker {
             &[],                                  // no candles
         );
 
-        let mut orderbook = nord::OrderbookStream::new(
+        let mut orderbook = nord::OrderbookStream::with_config(
             market_symbol.clone(),
             (*nord).clone(),
             ws.subscribe_deltas(),
+            self.orderbook_config(),
         );
         orderbook.connect().await?;
 
-        let mut account_stream =
-            nord::AccountStream::new(account_id, ws.subscribe_accounts(), Arc::clone(&nord));
+        let mut oms = nord::Oms::new(account_id, ws.subscribe_accounts(), &nord);
 
-        let binance_feed = BinancePriceFeed::new(&binance_symbol);
+        let mut account_stream =
+            nord::AccountStream::new(account_id, ws.subscribe_accounts(), Arc::clone(&nord))
+                .with_fee_markets(vec![market_id])
+                .with_order_ids(oms.subscribe());
 
         // --- Start connections ---
         // The WebSocket client needs to be started for broadcasts to flow.
@@ -162,10 +126,13 @@ impl MarketMaker {
         ws.connect();
 
         account_stream.connect();
-        binance_feed.connect();
+        oms.connect();
+        reference.connect();
+        self.check_index_band(&nord, market_id, reference.as_ref())
+            .await?;
 
         // --- Sync initial state ---
-        let mut active_orders = {
+        let active_orders = {
             let mut u = NordUser::from_private_key(Arc::clone(&nord), &self.private_key)?;
             u.refresh_session().await?;
             u.update_account_id().await?;
@@ -186,19 +153,371 @@ impl MarketMaker {
             cached
         };
 
+        let mut session = self.session(
+            market,
+            nord.clock(),
+            nord.latency().clone(),
+            orderbook,
+            reference,
+            active_orders,
+        );
+        session.trading = Some(Trading {
+            user: &user,
+            account_id,
+        });
+        session.fill_rx = account_stream.take_fill_rx();
+        session.balances_rx = Some(account_stream.subscribe_balances());
+        session.oms = Some(oms);
+        session.snapshot_path = self.config.fair_price_snapshot.clone();
+        session.restore_fair_price();
+
+        let mut position = nord::PositionStream::new(
+            account_id,
+            market_id,
+            account_stream.subscribe_fills(),
+            Some(session.orderbook.handle()),
+            Arc::clone(&nord),
+            nord::PositionStreamConfig {
+                reconcile_interval_ms: self.config.position_sync_interval_ms,
+                ..Default::default()
+            },
+        );
+        position.connect();
+        session.position = Some(position);
+
+        let mut ex_own = nord::ExOwnBookStream::new(
+            market_id,
+            session.orderbook.handle(),
+            account_stream.subscribe_orders(),
+        );
+        ex_own.connect();
+        session.ex_own = Some(ex_own);
+
         // Start position sync.
-        position_tracker.start_sync(Arc::clone(&nord), account_id, market_id, cancel.clone());
+        session.position_tracker.start_sync(
+            Arc::clone(&nord),
+            account_id,
+            market_id,
+            cancel.clone(),
+        );
+
+        self.run_event_loop(&mut session, &cancel).await;
+        session.save_fair_price();
+
+        // --- Shutdown: cancel all active orders ---
+        if !session.active_orders.is_empty() {
+            match cancel_orders(&user, &session.active_orders, session.oms.as_ref()).await {
+                Ok(()) => info!(
+                    count = session.active_orders.len(),
+                    "cancelled orders — goodbye"
+                ),
+                Err(e) => error!(error = %e, "shutdown cancel error"),
+            }
+        } else {
+            info!("no active orders — goodbye");
+        }
+
+        session.reference.close();
+        session.orderbook.close();
+        if let Some(ex_own) = session.ex_own.as_mut() {
+            ex_own.close();
+        }
+        if let Some(position) = session.position.as_mut() {
+            position.close();
+        }
+        if let Some(oms) = session.oms.as_mut() {
+            oms.close();
+        }
+        account_stream.close();
+
+        Ok(())
+    }
+
+    /// Rerun the strategy against a recording instead of live sockets.
+    ///
+    /// Recorded 01 deltas and Binance book tickers are published through the
+    /// same channels the live feeds use, orderbook snapshots are served from
+    /// the recording, and all strategy timestamps follow the replay clock, so
+    /// the quoting decisions are reproducible. Quotes are logged but never
+    /// sent; fills are not simulated. Returns when the recording is exhausted
+    /// or `cancel` fires.
+    ///
+    /// With `compare_fair_price`, every basis sample is kept and each fair
+    /// price model is scored on them at the end (see
+    /// [`harness`](crate::fair_price::harness)).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ZoError`] if the recording lacks market info or an initial
+    /// orderbook snapshot for the configured market.
+    pub async fn replay(
+        &self,
+        recording: nord::Recording,
+        speed: nord::ReplaySpeed,
+        compare_fair_price: bool,
+        cancel: CancellationToken,
+    ) -> Result<nord::ReplayStats, ZoError> {
+        info!("starting market maker replay (dry run)");
+
+        let mut driver = nord::ReplayDriver::new(recording)?;
+        let nord = driver.nord()?;
+
+        let market = self.find_market(&nord)?;
+        let market_symbol = market.symbol.clone();
+        // Recordings only carry Binance frames.
+        let venues = self.config.reference.venues_for(&market_symbol);
+        if venues != [Venue::Binance] {
+            warn!(?venues, "replay always uses the recorded Binance reference");
+        }
+        let mapping = self
+            .config
+            .reference
+            .mapping(&market_symbol, Venue::Binance);
+        let reference = BinancePriceFeed::new(&mapping.symbol)
+            .with_multiplier(mapping.multiplier)
+            .with_latency(nord.latency().clone());
+        self.log_config(&market_symbol, &reference);
+
+        let ws = nord.create_websocket_client(&[], std::slice::from_ref(&market_symbol), &[], &[]);
+        driver.add_sink(nord::RecordSource::NordWs, None, ws.replay_sink());
+
+        let mut orderbook =
+            nord::OrderbookStream::new(market_symbol.clone(), nord.clone(), ws.subscribe_deltas());
+        orderbook.connect().await?;
+
+        // The event loop acknowledges each replayed reference price, so that
+        // a max-speed replay waits for it instead of overwriting it.
+        let reference_ack = nord::ReplayAck::new();
+        driver.add_sink(
+            nord::RecordSource::Binance,
+            None,
+            reference.replay_sink(reference_ack.clone()),
+        );
+
+        let mut session = self.session(
+            market,
+            nord.clock(),
+            nord.latency().clone(),
+            orderbook,
+            Box::new(reference),
+            Vec::new(),
+        );
+        session.reference_ack = Some(reference_ack);
+        if compare_fair_price {
+            session.basis_samples = Some(Vec::new());
+        }
+
+        // Stop the event loop once the driver has published every frame.
+        let stop = cancel.child_token();
+        let replay_done = stop.clone();
+        let handle = tokio::spawn(async move {
+            let stats = driver.run(speed).await;
+            replay_done.cancel();
+            stats
+        });
+
+        self.run_event_loop(&mut session, &stop).await;
+        session.orderbook.close();
+
+        if let Some(samples) = &session.basis_samples {
+            for score in harness::compare(&self.config.fair_price, samples) {
+                info!(
+                    method = ?score.method,
+                    scored = score.scored,
+                    mae_bps = format!("{:.3}", score.mae_bps),
+                    rmse_bps = format!("{:.3}", score.rmse_bps),
+                    max_bps = format!("{:.2}", score.max_bps),
+                    "FAIR_PRICE_MODEL"
+                );
+            }
+        }
+
+        if !handle.is_finished() {
+            handle.abort();
+        }
+        match handle.await {
+            Ok(stats) => Ok(stats),
+            Err(_) => Ok(nord::ReplayStats::default()),
+        }
+    }
+
+    /// Wait for the first price of each reference venue and check it against
+    /// the exchange's index price, to catch a wrong symbol or multiplier
+    /// before quoting. A venue is checked on its own, so a bad mapping is
+    /// caught even while the composite excludes it as an outlier. Skipped
+    /// (with a warning) for a venue without a price, or entirely without an
+    /// index price.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ZoError::Config`] if a venue's price is too far from the
+    /// index price.
+    async fn check_index_band(
+        &self,
+        nord: &nord::Nord,
+        market_id: u32,
+        reference: &dyn PriceSource,
+    ) -> Result<(), ZoError> {
+        let index_price = match nord.get_market_stats(market_id).await {
+            Ok(stats) => stats.index_price,
+            Err(e) => {
+                warn!(error = %e, "market stats unavailable, skipping index check");
+                return Ok(());
+            }
+        };
+        let Some(index_price) = index_price else {
+            warn!("no index price, skipping index check");
+            return Ok(());
+        };
+        let deadline = time::Instant::now() + INDEX_CHECK_TIMEOUT;
+        for (name, mut rx) in reference.member_prices() {
+            let mid = match time::timeout_at(deadline, rx.wait_for(Option::is_some)).await {
+                Ok(Ok(price)) => price.map_or(0.0, |p| p.mid),
+                _ => {
+                    warn!(reference = %name, "no reference price yet, skipping index check");
+                    continue;
+                }
+            };
+            check_index_band(
+                &name,
+                mid,
+                index_price,
+                self.config.reference.max_index_deviation_bps,
+            )?;
+            info!(
+                reference = %name,
+                reference_mid = mid,
+                index_price,
+                "reference matches index price"
+            );
+        }
+        Ok(())
+    }
+
+    /// Find the configured market by symbol prefix.
+    fn find_market<'n>(&self, nord: &'n nord::Nord) -> Result<&'n nord::MarketInfo, ZoError> {
+        nord.markets
+            .iter()
+            .find(|m| {
+                m.symbol
+                    .to_uppercase()
+                    .starts_with(&self.config.symbol.to_uppercase())
+            })
+            .ok_or_else(|| {
+                let available: Vec<_> = nord.markets.iter().map(|m| m.symbol.as_str()).collect();
+                ZoError::MarketNotFound(format!(
+                    "\"{}\" not found. Available: {}",
+                    self.config.symbol,
+                    available.join(", ")
+                ))
+            })
+    }
+
+    fn log_config(&self, market_symbol: &str, reference: &dyn PriceSource) {
+        info!(
+            market = %market_symbol,
+            reference = reference.name(),
+            spread_bps = self.config.spread_bps,
+            fair_price = ?self.config.fair_price.method,
+            order_size_usd = self.config.order_size_usd,
+            close_threshold_usd = self.config.close_threshold_usd,
+            "CONFIG"
+        );
+    }
+
+    /// Orderbook settings for the live book (audited unless disabled).
+    fn orderbook_config(&self) -> nord::OrderbookConfig {
+        let interval_ms = self.config.book_audit_interval_ms;
+        nord::OrderbookConfig {
+            audit: (interval_ms > 0).then(|| nord::AuditConfig {
+                interval_ms,
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    /// Build the strategy components around already-connected streams.
+    fn session<'a>(
+        &self,
+        market: &nord::MarketInfo,
+        clock: nord::Clock,
+        latency: nord::LatencyTracker,
+        orderbook: nord::OrderbookStream,
+        reference: Box<dyn PriceSource>,
+        active_orders: Vec<CachedOrder>,
+    ) -> Session<'a> {
+        Session {
+            market_id: market.market_id,
+            market_symbol: market.symbol.clone(),
+            clock,
+            latency,
+            orderbook,
+            ex_own: None,
+            reference,
+            fill_rx: None,
+            balances_rx: None,
+            position: None,
+            oms: None,
+            trading: None,
+            fair_price_calc: FairPrice::new(&self.config.fair_price),
+            aligner: PriceAligner::new(self.config.fair_price.align.clone()),
+            basis_samples: None,
+            snapshot_path: None,
+            reference_ack: None,
+            position_tracker: PositionTracker::new(PositionConfig {
+                close_threshold_usd: self.config.close_threshold_usd,
+                sync_interval_ms: self.config.position_sync_interval_ms,
+            }),
+            quoter: Quoter::new(
+                market.price_decimals,
+                market.size_decimals,
+                self.config.spread_bps,
+                self.config.take_profit_bps,
+                self.config.order_size_usd,
+            )
+            .with_risk_scaling(self.config.vol_spread_multiplier, self.config.confidence_z),
+            active_orders,
+        }
+    }
+
+    /// Main event loop shared by live trading and replay. Returns when
+    /// `cancel` fires.
+    async fn run_event_loop(&self, session: &mut Session<'_>, cancel: &CancellationToken) {
+        let Session {
+            market_id,
+            market_symbol,
+            clock,
+            latency,
+            orderbook,
+            ex_own,
+            reference,
+            fill_rx,
+            balances_rx,
+            position,
+            oms,
+            trading,
+            fair_price_calc,
+            aligner,
+            basis_samples,
+            snapshot_path,
+            reference_ack,
+            position_tracker,
+            quoter,
+            active_orders,
+        } = session;
+        let market_id = *market_id;
+        let user = trading.as_ref().map(|t| t.user);
+        let oms = oms.as_ref();
 
         // --- Prepare event loop state ---
-        let mut binance_rx = binance_feed.subscribe_price();
+        let mut reference_rx = reference.subscribe_price();
         let mut zo_price_rx = orderbook.subscribe_price();
-        let mut fill_rx = account_stream
-            .take_fill_rx()
-            .expect("fill_rx already taken");
 
-        let mut fair_price_calc = fair_price_calc;
         let mut last_logged_sample_count: isize = -1;
-        let mut last_update_time = Instant::now();
+        let mut pricing_reliable = true;
+        // Throttle on the session clock so replays make the same decisions.
+        let mut last_update_ms: Option<u64> = None;
 
         let mut order_sync_interval =
             time::interval(Duration::from_millis(self.config.order_sync_interval_ms));
@@ -208,49 +527,83 @@ impl MarketMaker {
             time::interval(Duration::from_millis(self.config.status_interval_ms));
         status_interval.tick().await;
 
-        let update_throttle = Duration::from_millis(self.config.update_throttle_ms);
+        let mut latency_interval = time::interval(LATENCY_LOG_INTERVAL);
+        latency_interval.tick().await;
+
+        let mut snapshot_interval = time::interval(Duration::from_millis(
+            self.config.fair_price_snapshot_interval_ms,
+        ));
+        snapshot_interval.tick().await;
+
+        let update_throttle_ms = self.config.update_throttle_ms;
+        let warmup_samples = fair_price_calc.min_samples();
 
         info!("warming up price feeds...");
 
         // --- Main event loop ---
         loop {
             tokio::select! {
-                // Binance price update
-                result = binance_rx.changed() => {
+                // Reference price update
+                result = reference_rx.changed() => {
                     if result.is_err() { continue; }
-                    let now_ms = epoch_ms();
-                    let binance_mid = match *binance_rx.borrow_and_update() {
-                        Some(ref p) => *p,
-                        None => continue,
+                    // Replay: acknowledge the price once it has been handled.
+                    let _ack = reference_ack.as_ref().map(nord::ReplayAck::observe);
+                    let now_ms = clock.now_ms();
+                    let reference = *reference_rx.borrow_and_update();
+                    let Some(reference_mid) = reference else {
+                        reference_lost(user, active_orders, oms).await;
+                        continue;
                     };
+                    fair_price_calc.observe_reference(reference_mid.mid, now_ms);
 
-                    // Sample fair price if both feeds are fresh.
-                    if let Some(zo_mid) = orderbook.get_mid_price() {
-                        if (binance_mid.timestamp as i64 - zo_mid.timestamp as i64).unsigned_abs() < 1000 {
-                            fair_price_calc.add_sample(zo_mid.mid, binance_mid.mid, now_ms);
+                    // Sample fair price at the common timestamp of both feeds
+                    // if the book is neither crossed nor locked.
+                    aligner.push_reference(&reference_mid);
+                    if let Some(zo_mid) = orderbook
+                        .get_mid_price()
+                        .filter(|_| orderbook.get_health().is_healthy())
+                    {
+                        aligner.push_local(&zo_mid);
+                        add_basis_sample(fair_price_calc, basis_samples, aligner);
+                    }
+
+                    // Pull quotes while the circuit breaker is tripped.
+                    if let PricingStatus::Unreliable { trip, until_ms } = fair_price_calc.pricing_status(now_ms) {
+                        if pricing_reliable {
+                            pricing_reliable = false;
+                            warn!(%trip, retry_in_ms = until_ms - now_ms, "pricing unreliable, pulling quotes");
+                        }
+                        if !active_orders.is_empty() {
+                            pull_quotes(user, active_orders, oms, "unreliable pricing").await;
                         }
+                        continue;
+                    }
+                    if !pricing_reliable {
+                        pricing_reliable = true;
+                        info!("pricing reliable again");
                     }
 
-                    let fair = match fair_price_calc.get_fair_price(binance_mid.mid, now_ms) {
+                    let fair = match fair_price_calc.get_fair_price(reference_mid.mid, now_ms) {
                         Some(f) => f,
                         None => {
-                            log_warmup(&fair_price_calc, &binance_mid, orderbook.get_mid_price(), &mut last_logged_sample_count, self.config.warmup_seconds, now_ms);
+                            log_warmup(fair_price_calc, &reference_mid, orderbook.get_mid_price(), &mut last_logged_sample_count, warmup_samples, now_ms);
                             continue;
                         }
                     };
 
                     // Log "ready" on first valid fair price.
-                    if last_logged_sample_count < self.config.warmup_seconds as isize {
-                        last_logged_sample_count = self.config.warmup_seconds as isize;
+                    if last_logged_sample_count < warmup_samples as isize {
+                        last_logged_sample_count = warmup_samples as isize;
                         info!(fair_price = format!("{fair:.2}"), "ready");
                     }
 
                     // Throttled update.
-                    if last_update_time.elapsed() >= update_throttle {
-                        last_update_time = Instant::now();
+                    if last_update_ms.is_none_or(|t| now_ms.saturating_sub(t) >= update_throttle_ms) {
+                        last_update_ms = Some(now_ms);
+                        let fair_state = fair_price_calc.get_state(now_ms);
                         execute_update(
-                            fair, &user, market_id, &position_tracker,
-                            &quoter, &orderbook, &mut active_orders,
+                            fair, &fair_state, reference_mid.mid, user, market_id, position_tracker,
+                            quoter, orderbook, ex_own.as_ref(), oms, active_orders,
                             &self.config,
                         ).await;
                     }
@@ -259,23 +612,25 @@ impl MarketMaker {
                 // Zo orderbook price update — just sample the fair price.
                 result = zo_price_rx.changed() => {
                     if result.is_err() { continue; }
-                    let now_ms = epoch_ms();
-                    if let Some(ref zo_mid) = *zo_price_rx.borrow_and_update() {
-                        if let Some(binance_mid) = binance_feed.get_mid_price() {
-                            if (zo_mid.timestamp as i64 - binance_mid.timestamp as i64).unsigned_abs() < 1000 {
-                                fair_price_calc.add_sample(zo_mid.mid, binance_mid.mid, now_ms);
-                            }
+                    let zo_mid = *zo_price_rx.borrow_and_update();
+                    if let Some(ref zo_mid) = zo_mid.filter(|_| orderbook.get_health().is_healthy()) {
+                        if let Some(reference_mid) = reference.get_mid_price() {
+                            aligner.push_reference(&reference_mid);
                         }
+                        aligner.push_local(zo_mid);
+                        add_basis_sample(fair_price_calc, basis_samples, aligner);
                     }
                 }
 
                 // Fill event → update position, maybe enter close mode.
-                Some(fill) = fill_rx.recv() => {
+                Some(fill) = recv_fill(fill_rx) => {
                     let dir = if fill.side == Side::Bid { "buy" } else { "sell" };
                     info!(
                         side = dir,
                         price = format!("{:.2}", fill.price),
                         size = fill.size,
+                        role = %fill.role,
+                        fee = fill.fee.map_or("-".to_string(), |f| format!("{f:.4}")),
                         "FILL"
                     );
                     position_tracker.apply_fill(fill.side, fill.size);
@@ -284,24 +639,59 @@ impl MarketMaker {
                     if position_tracker.is_close_mode(fill.price)
                         && !active_orders.is_empty()
                     {
-                        if let Err(e) = cancel_orders(&user, &active_orders).await {
-                            error!(error = %e, "failed to cancel on close mode");
+                        if let Some(user) = user {
+                            if let Err(e) = cancel_orders(user, active_orders, oms).await {
+                                error!(error = %e, "failed to cancel on close mode");
+                            }
                         }
                         active_orders.clear();
                     }
                 }
 
                 // Periodic order sync from server.
-                _ = order_sync_interval.tick() => {
-                    match sync_orders_from_server(&user, account_id, market_id).await {
-                        Ok(orders) => { active_orders = orders; }
+                _ = order_sync_interval.tick(), if trading.is_some() => {
+                    let Some(Trading { user, account_id }) = trading.as_ref() else { continue };
+                    match sync_orders_from_server(user, *account_id, market_id).await {
+                        Ok(orders) => {
+                            *active_orders = match oms {
+                                Some(oms) => {
+                                    oms.apply_open_orders(&orders);
+                                    settle_missing_orders(user, oms, &orders, market_id).await;
+                                    merge_synced_orders(&orders, active_orders, oms, market_id)
+                                }
+                                None => map_api_orders_to_cached(&orders),
+                            };
+                        }
                         Err(e) => { error!(error = %e, "order sync error"); }
                     }
                 }
 
                 // Periodic status log.
                 _ = status_interval.tick() => {
-                    log_status(&position_tracker, &active_orders);
+                    let balances = balances_rx.as_ref().map(|rx| rx.borrow().clone());
+                    log_status(
+                        position_tracker,
+                        active_orders,
+                        &orderbook.get_audit_stats(),
+                        balances.as_ref(),
+                        position.as_ref().map(nord::PositionStream::get).as_ref(),
+                    );
+                }
+
+                // Periodic latency percentiles.
+                _ = latency_interval.tick() => {
+                    let now_ms = clock.now_ms();
+                    let stats = latency.snapshot(now_ms);
+                    log_latency(&stats);
+                    if let Some(path) = self.config.latency_file.as_deref() {
+                        export_latency(path, &stats, now_ms);
+                    }
+                }
+
+                // Periodic fair price snapshot.
+                _ = snapshot_interval.tick(), if snapshot_path.is_some() => {
+                    let Some(path) = snapshot_path.as_deref() else { continue };
+                    save_fair_price(path, market_symbol, fair_price_calc, clock.now_ms());
                 }
 
                 // Shutdown.
@@ -311,41 +701,178 @@ impl MarketMaker {
                 }
             }
         }
+    }
+}
 
-        // --- Shutdown: cancel all active orders ---
-        if !active_orders.is_empty() {
-            match cancel_orders(&user, &active_orders).await {
-                Ok(()) => info!(count = active_orders.len(), "cancelled orders — goodbye"),
-                Err(e) => error!(error = %e, "shutdown cancel error"),
+// ---------------------------------------------------------------------------
+// Internal helpers
+// ---------------------------------------------------------------------------
+
+/// Credentials for sending orders. Absent in dry-run (replay) sessions.
+struct Trading<'a> {
+    user: &'a NordUser,
+    account_id: u32,
+}
+
+/// Streams and strategy state driven by [`MarketMaker::run_event_loop`].
+struct Session<'a> {
+    market_id: u32,
+    market_symbol: String,
+    /// Time source for fair-price samples and quote throttling.
+    clock: nord::Clock,
+    /// Feed, WebSocket and action latencies, logged periodically.
+    latency: nord::LatencyTracker,
+    orderbook: nord::OrderbookStream,
+    /// Book excluding our own orders (live trading only).
+    ex_own: Option<nord::ExOwnBookStream>,
+    /// Reference price on an external venue.
+    reference: Box<dyn PriceSource>,
+    fill_rx: Option<mpsc::UnboundedReceiver<nord::FillEvent>>,
+    /// Live token balances (live trading only).
+    balances_rx: Option<watch::Receiver<HashMap<String, nord::TrackedBalance>>>,
+    /// Entry price and PnL (live trading only).
+    position: Option<nord::PositionStream>,
+    /// Lifecycle of every order we send (live trading only).
+    oms: Option<nord::Oms>,
+    trading: Option<Trading<'a>>,
+    fair_price_calc: FairPrice,
+    /// Joins the 01 and reference mids as of a common timestamp.
+    aligner: PriceAligner,
+    /// Every sample fed to the model, kept to compare models after a replay.
+    basis_samples: Option<Vec<BasisSample>>,
+    /// File the fair price samples are persisted to (live trading only).
+    snapshot_path: Option<PathBuf>,
+    /// Acknowledges replayed reference prices (replay only).
+    reference_ack: Option<nord::ReplayAck>,
+    position_tracker: PositionTracker,
+    quoter: Quoter,
+    active_orders: Vec<CachedOrder>,
+}
+
+impl Session<'_> {
+    /// Replay the saved fair price samples of this market, if any.
+    fn restore_fair_price(&mut self) {
+        let Some(path) = self.snapshot_path.as_deref() else {
+            return;
+        };
+        let snapshot = match FairPriceSnapshot::load(path) {
+            Ok(Some(snapshot)) => snapshot,
+            Ok(None) => return,
+            Err(e) => {
+                warn!(error = %e, "failed to load fair price snapshot");
+                return;
             }
-        } else {
-            info!("no active orders — goodbye");
+        };
+        if snapshot.symbol != self.market_symbol {
+            warn!(
+                symbol = %snapshot.symbol,
+                "fair price snapshot is for another market, ignoring"
+            );
+            return;
         }
+        let now_ms = self.clock.now_ms();
+        let restored = self.fair_price_calc.restore(&snapshot.samples, now_ms);
+        info!(
+            restored,
+            saved = snapshot.samples.len(),
+            age_s = now_ms.saturating_sub(snapshot.saved_ms) / 1000,
+            "restored fair price samples"
+        );
+    }
 
-        binance_feed.close();
-        orderbook.close();
-        account_stream.close();
+    /// Write the fair price samples, if a snapshot file is configured.
+    fn save_fair_price(&self) {
+        if let Some(path) = self.snapshot_path.as_deref() {
+            save_fair_price(
+                path,
+                &self.market_symbol,
+                &self.fair_price_calc,
+                self.clock.now_ms(),
+            );
+        }
+    }
+}
 
-        Ok(())
+/// Next fill, or pending forever when the session has no account stream.
+async fn recv_fill(
+    fill_rx: &mut Option<mpsc::UnboundedReceiver<nord::FillEvent>>,
+) -> Option<nord::FillEvent> {
+    match fill_rx {
+        Some(rx) => rx.recv().await,
+        None => std::future::pending().await,
     }
 }
 
-// ---------------------------------------------------------------------------
-// Internal helpers
-// ---------------------------------------------------------------------------
+/// Cancel every active order. The orders are only forgotten once the cancel
+/// succeeds; on failure they stay active, so the next update retries (and the
+/// periodic order sync drops any that are already gone).
+async fn pull_quotes(
+    user: Option<&NordUser>,
+    active_orders: &mut Vec<CachedOrder>,
+    oms: Option<&nord::Oms>,
+    reason: &str,
+) {
+    if let Some(user) = user {
+        if let Err(e) = cancel_orders(user, active_orders, oms).await {
+            error!(error = %e, reason, "failed to cancel, will retry");
+            return;
+        }
+    }
+    active_orders.clear();
+}
+
+/// The reference feed stopped publishing (every venue stale, venues
+/// disagreeing, or too few of them): nothing anchors the fair price, so no
+/// quote may keep resting at the last one.
+async fn reference_lost(
+    user: Option<&NordUser>,
+    active_orders: &mut Vec<CachedOrder>,
+    oms: Option<&nord::Oms>,
+) {
+    if !active_orders.is_empty() {
+        warn!(
+            orders = active_orders.len(),
+            "no reference price, pulling quotes"
+        );
+        pull_quotes(user, active_orders, oms, "no reference price").await;
+    }
+}
 
 #[allow(clippy::too_many_arguments)]
 async fn execute_update(
     fair_price: f64,
-    user: &NordUser,
+    fair_state: &FairPriceState,
+    reference_mid: f64,
+    user: Option<&NordUser>,
     market_id: u32,
     position_tracker: &PositionTracker,
     quoter: &Quoter,
     orderbook: &nord::OrderbookStream,
+    ex_own: Option<&nord::ExOwnBookStream>,
+    oms: Option<&nord::Oms>,
     active_orders: &mut Vec<CachedOrder>,
     config: &MarketMakerConfig,
 ) {
-    let ctx = position_tracker.get_quoting_context(fair_price);
+    // Never quote against a crossed, locked or one-sided book.
+    let health = orderbook.get_health();
+    if !health.is_healthy() {
+        if !active_orders.is_empty() {
+            warn!(health = ?health, "book unhealthy, pulling quotes");
+            pull_quotes(user, active_orders, oms, "unhealthy book").await;
+        }
+        return;
+    }
+
+    // An unconfirmed placement may already be resting; quoting again now
+    // could double it.
+    if let Some(in_flight) = oms.map(|o| o.in_flight(market_id)).filter(|&n| n > 0) {
+        debug!(in_flight, "orders in flight, skipping update");
+        return;
+    }
+
+    let mut ctx = position_tracker.get_quoting_context(fair_price);
+    ctx.volatility_bps = fair_state.volatility_bps(config.vol_spread_horizon_ms);
+    ctx.fair_std_error_bps = fair_state.std_error.map(|e| e / reference_mid * 10_000.0);
     let pos = &ctx.position_state;
 
     if pos.size_base != 0.0 {
@@ -360,7 +887,14 @@ async fn execute_update(
         );
     }
 
-    let bbo = orderbook.get_bbo();
+    let signals = orderbook.get_signals();
+    // Clamp against everyone else's liquidity rather than our own quotes,
+    // which are about to be replaced anyway.
+    let ex_own_book = ex_own.and_then(nord::ExOwnBookStream::get);
+    let bbo = ex_own_book
+        .as_ref()
+        .and_then(|b| b.external_bbo)
+        .or_else(|| signals.as_ref().map(|s| s.bbo));
     let quotes = quoter.get_quotes(&ctx, bbo.as_ref());
 
     if quotes.is_empty() {
@@ -371,11 +905,7 @@ async fn execute_update(
     // Log the quotes.
     let bid = quotes.iter().find(|q| q.side == Side::Bid);
     let ask = quotes.iter().find(|q| q.side == Side::Ask);
-    let spread_bps = if pos.is_close_mode {
-        config.take_profit_bps
-    } else {
-        config.spread_bps
-    };
+    let spread_bps = quoter.half_spread_bps(&ctx);
     let mode = if pos.is_close_mode { "close" } else { "normal" };
     info!(
         bid = bid
@@ -386,11 +916,40 @@ async fn execute_update(
             .unwrap_or_else(|| "--".into()),
         fair = format!("${fair_price:.2}"),
         spread = format!("{spread_bps}bps"),
+        micro = signals
+            .as_ref()
+            .map(|s| format!("${:.2}", s.microprice))
+            .unwrap_or_else(|| "--".into()),
+        imb = signals
+            .as_ref()
+            .map(|s| format!("{:+.2}", s.imbalance))
+            .unwrap_or_else(|| "--".into()),
+        own_top = ex_own_book
+            .as_ref()
+            .map(|b| {
+                let share = |t: Option<nord::TopLevelShare>| t.map_or(0.0, |t| t.share() * 100.0);
+                format!("{:.0}%/{:.0}%", share(b.bid_top), share(b.ask_top))
+            })
+            .unwrap_or_else(|| "--".into()),
         mode,
         "QUOTE"
     );
 
-    match update_quotes(user, market_id, active_orders, &quotes).await {
+    let Some(user) = user else {
+        // Dry run: treat the quotes as resting so status lines show them.
+        *active_orders = quotes
+            .iter()
+            .map(|q| CachedOrder {
+                order_id: 0,
+                side: q.side,
+                price: q.price,
+                size: q.size,
+            })
+            .collect();
+        return;
+    };
+
+    match update_quotes(user, market_id, active_orders, &quotes, oms).await {
         Ok(new_orders) => *active_orders = new_orders,
         Err(e) => {
             error!(error = %e, "update error");
@@ -403,7 +962,7 @@ async fn sync_orders_from_server(
     user: &NordUser,
     account_id: u32,
     market_id: u32,
-) -> Result<Vec<CachedOrder>, ZoError> {
+) -> Result<Vec<nord::OpenOrder>, ZoError> {
     // Re-fetch user info. Since NordUser requires &mut for fetch_info, and
     // we only have &, we use the REST client directly.
     let account = user.nord.get_account(account_id).await?;
@@ -413,12 +972,95 @@ async fn sync_orders_from_server(
         .filter(|o| o.market_id == market_id)
         .cloned()
         .collect();
-    Ok(map_api_orders_to_cached(&market_orders))
+    Ok(market_orders)
+}
+
+/// Fetch the orders the OMS still considers live but a REST snapshot no
+/// longer lists, and apply their exchange state, so fills and cancels the
+/// WebSocket missed do not leave them open (or pending cancel) forever.
+async fn settle_missing_orders(
+    user: &NordUser,
+    oms: &nord::Oms,
+    synced: &[nord::OpenOrder],
+    market_id: u32,
+) {
+    for order_id in oms.missing_from(synced, market_id) {
+        match user.nord.get_order(order_id).await {
+            Ok(info) => oms.apply_order_info(&info),
+            Err(e) => warn!(order_id, error = %e, "failed to fetch missing order"),
+        }
+    }
+}
+
+/// Active orders after a REST sync: the snapshot, plus previously active
+/// orders the OMS still considers open (acknowledged after the snapshot was
+/// taken, so absent from it).
+fn merge_synced_orders(
+    synced: &[nord::OpenOrder],
+    active_orders: &[CachedOrder],
+    oms: &nord::Oms,
+    market_id: u32,
+) -> Vec<CachedOrder> {
+    let mut merged = map_api_orders_to_cached(synced);
+    let open = oms.open_orders(market_id);
+    for order in active_orders {
+        let known = merged.iter().any(|o| o.order_id == order.order_id);
+        if !known && open.iter().any(|o| o.order_id == Some(order.order_id)) {
+            merged.push(order.clone());
+        }
+    }
+    merged
+}
+
+/// Feed the mids aligned at the newest common timestamp, if it advanced, to
+/// the model, and once admitted to the comparison log if the session keeps
+/// one. The sample is stamped with the common timestamp, not the time the
+/// update was handled.
+fn add_basis_sample(
+    model: &mut dyn FairPriceModel,
+    log: &mut Option<Vec<BasisSample>>,
+    aligner: &mut PriceAligner,
+) {
+    let Some(AlignedMids {
+        time_ms,
+        local_mid,
+        reference_mid,
+    }) = aligner.next_sample()
+    else {
+        return;
+    };
+    // Log what the live model used, without the samples the breaker
+    // rejected or the second gate dropped.
+    if !model.add_sample(local_mid, reference_mid, time_ms) {
+        return;
+    }
+    if let Some(log) = log {
+        log.push(BasisSample {
+            time_ms,
+            local_mid,
+            reference_mid,
+        });
+    }
+}
+
+fn save_fair_price(path: &Path, symbol: &str, calc: &FairPrice, now_ms: u64) {
+    let snapshot = FairPriceSnapshot {
+        symbol: symbol.to_string(),
+        saved_ms: now_ms,
+        samples: calc.samples(),
+    };
+    match snapshot.save(path) {
+        Ok(()) => debug!(
+            samples = snapshot.samples.len(),
+            "saved fair price snapshot"
+        ),
+        Err(e) => warn!(error = %e, "failed to save fair price snapshot"),
+    }
 }
 
 fn log_warmup(
-    calc: &FairPriceCalculator,
-    binance: &nord::MidPrice,
+    calc: &dyn FairPriceModel,
+    reference: &nord::MidPrice,
     zo: Option<nord::MidPrice>,
     last_count: &mut isize,
     target: usize,
@@ -431,8 +1073,8 @@ fn log_warmup(
     *last_count = state.samples as isize;
 
     let offset_bps = if let Some(offset) = state.offset {
-        if binance.mid > 0.0 {
-            format!("{:.1}", offset / binance.mid * 10000.0)
+        if reference.mid > 0.0 {
+            format!("{:.1}", offset / reference.mid * 10000.0)
         } else {
             "--".into()
         }
@@ -446,14 +1088,20 @@ fn log_warmup(
 
     info!(
         samples = format!("{}/{target}", state.samples),
-        binance = format!("${:.2}", binance.mid),
+        reference = format!("${:.2}", reference.mid),
         zo = zo_str,
         offset_bps,
         "warming up"
     );
 }
 
-fn log_status(tracker: &PositionTracker, orders: &[CachedOrder]) {
+fn log_status(
+    tracker: &PositionTracker,
+    orders: &[CachedOrder],
+    audit: &nord::AuditStats,
+    balances: Option<&HashMap<String, nord::TrackedBalance>>,
+    position: Option<&nord::LivePosition>,
+) {
     let pos = tracker.get_base_size();
     let bids: Vec<String> = orders
         .iter()
@@ -475,19 +1123,67 @@ fn log_status(tracker: &PositionTracker, orders: &[CachedOrder]) {
     } else {
         asks.join(",")
     };
+    let mut collateral: Vec<String> = balances
+        .into_iter()
+        .flat_map(HashMap::values)
+        .map(|b| format!("{}={:.2}", b.token, b.amount))
+        .collect();
+    collateral.sort();
+    let collateral_str = if collateral.is_empty() {
+        "-".to_string()
+    } else {
+        collateral.join(",")
+    };
     info!(
         pos = format!("{pos:.5}"),
         bid = bid_str,
         ask = ask_str,
+        collateral = collateral_str,
+        entry = position.map_or("-".to_string(), |p| format!("{:.4}", p.avg_entry)),
+        rpnl = position.map_or("-".to_string(), |p| format!("{:.2}", p.realized_pnl)),
+        upnl = position.map_or("-".to_string(), |p| format!("{:.2}", p.unrealized_pnl)),
+        book_audits = format!("{}/{}", audit.clean, audit.audits),
         "STATUS"
     );
 }
 
-fn epoch_ms() -> u64 {
-    std::time::SystemTime::now()
-        .duration_since(std::time::UNIX_EPOCH)
-        .unwrap_or_default()
-        .as_millis() as u64
+/// Log the latency percentiles of every source with recent samples.
+fn log_latency(snapshot: &[nord::LatencyStats]) {
+    for stats in snapshot {
+        info!(
+            source = stats.source,
+            p50_ms = format!("{:.1}", stats.p50_ms),
+            p90_ms = format!("{:.1}", stats.p90_ms),
+            p99_ms = format!("{:.1}", stats.p99_ms),
+            max_ms = format!("{:.1}", stats.max_ms),
+            samples = stats.samples,
+            "LATENCY"
+        );
+    }
+}
+
+/// Latency file contents: the percentiles of every source at `time_ms`.
+#[derive(Serialize)]
+struct LatencyExport<'a> {
+    time_ms: u64,
+    sources: &'a [nord::LatencyStats],
+}
+
+/// Replace `path` with the latency percentiles, via a temporary file so a
+/// reader never sees a partial write.
+fn export_latency(path: &Path, snapshot: &[nord::LatencyStats], now_ms: u64) {
+    let export = LatencyExport {
+        time_ms: now_ms,
+        sources: snapshot,
+    };
+    let write = || -> std::io::Result<()> {
+        let tmp = path.with_extension("tmp");
+        std::fs::write(&tmp, serde_json::to_vec(&export)?)?;
+        std::fs::rename(&tmp, path)
+    };
+    if let Err(e) = write() {
+        warn!(error = %e, path = %path.display(), "failed to export latency");
+    }
 }
 
 #[cfg(test)]
@@ -495,11 +1191,44 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_derive_binance_symbol() {
-        assert_eq!(derive_binance_symbol("BTC-PERP"), "btcusdt");
-        assert_eq!(derive_binance_symbol("ETH-PERP"), "ethusdt");
-        assert_eq!(derive_binance_symbol("SOL-PERP"), "solusdt");
-        assert_eq!(derive_binance_symbol("DOGE-PERP"), "dogeusdt");
+    fn test_basis_sample_takes_the_aligned_time() {
+        let price = |mid: f64, timestamp: u64| nord::MidPrice {
+            mid,
+            exact_mid: None,
+            bid: mid - 0.5,
+            ask: mid + 0.5,
+            timestamp,
+        };
+        let mut model = FairPrice::new(&Default::default());
+        let mut aligner = PriceAligner::new(Default::default());
+        let mut log = Some(Vec::new());
+        aligner.push_reference(&price(100.0, 1_000));
+        aligner.push_local(&price(101.0, 2_400));
+        add_basis_sample(&mut model, &mut log, &mut aligner);
+
+        let log = log.unwrap();
+        assert_eq!(log.len(), 1);
+        assert_eq!(log[0].time_ms, 2_400);
+        assert_eq!(model.samples(), log);
+    }
+
+    #[test]
+    fn test_latency_export_replaces_the_file() {
+        let path = std::env::temp_dir().join(format!("zo-latency-{}.json", std::process::id()));
+        let latency = nord::LatencyTracker::new();
+        latency.record(nord::latency::ACTION_ROUND_TRIP, 12.0, 1_000);
+        export_latency(&path, &latency.snapshot(1_000), 1_000);
+        export_latency(&path, &latency.snapshot(2_000), 2_000);
+
+        let export: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(export["time_ms"], 2_000);
+        assert_eq!(
+            export["sources"][0]["source"],
+            nord::latency::ACTION_ROUND_TRIP
+        );
+        assert_eq!(export["sources"][0]["p50_ms"], 12.0);
     }
 
     #[test]
@@ -518,4 +1247,19 @@ mod tests {
         assert_eq!(cached[0].order_id, 42);
         assert_eq!(cached[0].side, Side::Bid);
     }
+
+    #[tokio::test]
+    async fn test_lost_reference_pulls_quotes() {
+        let mut active_orders = map_api_orders_to_cached(&[nord::OpenOrder {
+            order_id: 42,
+            market_id: 1,
+            side: Side::Ask,
+            size: 0.5,
+            price: 50010.0,
+            original_order_size: 0.5,
+            client_order_id: None,
+        }]);
+        reference_lost(None, &mut active_orders, None).await;
+        assert!(active_orders.is_empty());
+    }
 }
@@ -1,5 +1,10 @@
 //! Market maker configuration.
 
+use std::path::PathBuf;
+
+use crate::composite::ReferenceConfig;
+use crate::fair_price::FairPriceConfig;
+
 /// All tuneable parameters for the market maker bot.
 ///
 /// Use [`Default::default()`] for sensible defaults, then set `symbol` before
@@ -16,18 +21,39 @@ pub struct MarketMakerConfig {
     pub order_size_usd: f64,
     /// Position threshold (USD) that triggers close mode.
     pub close_threshold_usd: f64,
-    /// Seconds of price samples required before quoting.
-    pub warmup_seconds: usize,
     /// Minimum interval between quote updates in milliseconds.
     pub update_throttle_ms: u64,
     /// Interval for syncing open orders from the API in milliseconds.
     pub order_sync_interval_ms: u64,
     /// Interval for status log lines in milliseconds.
     pub status_interval_ms: u64,
-    /// Time window for fair price offset samples in milliseconds.
-    pub fair_price_window_ms: u64,
+    /// Fair price model; its `min_samples` (one per second) is the warmup
+    /// before quoting.
+    pub fair_price: FairPriceConfig,
+    /// Minimum normal-mode spread as a multiple of the reference volatility
+    /// (0 disables).
+    pub vol_spread_multiplier: f64,
+    /// Horizon of the volatility used for the spread in milliseconds; should
+    /// be one of `fair_price.volatility_horizons_ms`.
+    pub vol_spread_horizon_ms: u64,
+    /// Standard errors of the fair price added to the normal-mode spread
+    /// (0 disables).
+    pub confidence_z: f64,
+    /// File the fair price samples are saved to periodically and on
+    /// shutdown, and restored from on start (live trading only).
+    pub fair_price_snapshot: Option<PathBuf>,
+    /// Interval between fair price snapshots in milliseconds.
+    pub fair_price_snapshot_interval_ms: u64,
     /// Interval for position sync from the server in milliseconds.
     pub position_sync_interval_ms: u64,
+    /// Interval for auditing the local orderbook against a REST snapshot in
+    /// milliseconds (0 disables the audit).
+    pub book_audit_interval_ms: u64,
+    /// File the latency percentiles are written to as JSON with every
+    /// latency log line.
+    pub latency_file: Option<PathBuf>,
+    /// Venues providing the reference price for this market.
+    pub reference: ReferenceConfig,
 }
 
 impl Default for MarketMakerConfig {
@@ -38,12 +64,19 @@ impl Default for MarketMakerConfig {
             take_profit_bps: 0.1,
             order_size_usd: 3000.0,
             close_threshold_usd: 10.0,
-            warmup_seconds: 10,
             update_throttle_ms: 100,
             order_sync_interval_ms: 3000,
             status_interval_ms: 1000,
-            fair_price_window_ms: 5 * 60 * 1000, // 5 minutes
+            fair_price: FairPriceConfig::default(),
+            vol_spread_multiplier: 0.0,
+            vol_spread_horizon_ms: 10_000,
+            confidence_z: 0.0,
+            fair_price_snapshot: None,
+            fair_price_snapshot_interval_ms: 60_000,
             position_sync_interval_ms: 5000,
+            book_audit_interval_ms: 30_000,
+            latency_file: None,
+            reference: ReferenceConfig::default(),
         }
     }
 }
@@ -44,6 +44,10 @@ pub struct QuotingContext {
     pub position_state: PositionState,
     /// Which sides the quoter is allowed to quote.
     pub allowed_sides: Vec<Side>,
+    /// Realized volatility of the reference over the quoting horizon, in bps.
+    pub volatility_bps: Option<f64>,
+    /// Standard error of the fair price, in bps.
+    pub fair_std_error_bps: Option<f64>,
 }
 
 /// Lock-free position tracker.
@@ -141,6 +145,8 @@ impl PositionTracker {
             fair_price,
             position_state: state,
             allowed_sides,
+            volatility_bps: None,
+            fair_std_error_bps: None,
         }
     }
 
@@ -2,6 +2,13 @@
 //!
 //! Uses `rust_decimal::Decimal` for deterministic fixed-point arithmetic
 //! so that prices and sizes align exactly to exchange tick and lot sizes.
+//!
+//! With risk scaling enabled the normal-mode half-spread widens with the
+//! reference volatility and the fair price's uncertainty:
+//!
+//! ```text
+//!   half_spread = max(spread_bps, vol_multiplier * vol_bps) + z * std_error_bps
+//! ```
 
 use nord::{Side, BBO};
 use rust_decimal::prelude::*;
@@ -23,6 +30,12 @@ pub struct Quoter {
     take_profit_bps: Decimal,
     /// Notional order size in USD (normal mode).
     order_size_usd: Decimal,
+    /// Minimum half-spread as a multiple of the reference volatility
+    /// (0 disables).
+    vol_multiplier: Decimal,
+    /// Standard errors of the fair price added to the half-spread
+    /// (0 disables).
+    confidence_z: Decimal,
 }
 
 /// Rounding direction for tick alignment.
@@ -54,20 +67,44 @@ impl Quoter {
             spread_bps: Decimal::from_f64(spread_bps).unwrap_or(dec!(8)),
             take_profit_bps: Decimal::from_f64(take_profit_bps).unwrap_or(dec!(0.1)),
             order_size_usd: Decimal::from_f64(order_size_usd).unwrap_or(dec!(3000)),
+            vol_multiplier: Decimal::ZERO,
+            confidence_z: Decimal::ZERO,
+        }
+    }
+
+    /// Widen the normal-mode spread with volatility and fair price
+    /// uncertainty from the quoting context.
+    ///
+    /// # Arguments
+    ///
+    /// * `vol_multiplier` - Minimum half-spread as a multiple of `volatility_bps`.
+    /// * `confidence_z` - Multiple of `fair_std_error_bps` added on top.
+    pub fn with_risk_scaling(mut self, vol_multiplier: f64, confidence_z: f64) -> Self {
+        self.vol_multiplier = Decimal::from_f64(vol_multiplier).unwrap_or_default();
+        self.confidence_z = Decimal::from_f64(confidence_z).unwrap_or_default();
+        self
+    }
+
+    /// Half-spread from fair in basis points for the given context.
+    ///
+    /// Close mode always uses `take_profit_bps`; missing estimates leave
+    /// the normal-mode spread unscaled.
+    pub fn half_spread_bps(&self, ctx: &QuotingContext) -> Decimal {
+        if ctx.position_state.is_close_mode {
+            return self.take_profit_bps;
         }
+        let bps = |v: Option<f64>| v.and_then(Decimal::from_f64).unwrap_or_default();
+        let vol_floor = self.vol_multiplier * bps(ctx.volatility_bps);
+        self.spread_bps.max(vol_floor) + self.confidence_z * bps(ctx.fair_std_error_bps)
     }
 
     /// Generate quotes for the given quoting context, clamped to the BBO.
     ///
-    /// In normal mode: both bid and ask at `spread_bps` from fair.
+    /// In normal mode: both bid and ask at [`Self::half_spread_bps`] from fair.
     /// In close mode: only the reducing side at `take_profit_bps`.
     pub fn get_quotes(&self, ctx: &QuotingContext, bbo: Option<&BBO>) -> Vec<Quote> {
         let fair = Decimal::from_f64(ctx.fair_price).unwrap_or_default();
-        let bps = if ctx.position_state.is_close_mode {
-            self.take_profit_bps
-        } else {
-            self.spread_bps
-        };
+        let bps = self.half_spread_bps(ctx);
         let spread_amount = fair * bps / dec!(10000);
 
         // In close mode: limit size to position size.
@@ -90,7 +127,7 @@ impl Quoter {
 
             // Clamp bid below best ask (don't cross spread).
             if let Some(bbo) = bbo {
-                let best_ask = Decimal::from_f64(bbo.best_ask).unwrap_or_default();
+                let best_ask = bbo.best_ask;
                 if bid_price >= best_ask {
                     bid_price = self.align_price(best_ask - self.tick_size, RoundMode::Floor);
                 }
@@ -110,7 +147,7 @@ impl Quoter {
 
             // Clamp ask above best bid (don't cross spread).
             if let Some(bbo) = bbo {
-                let best_bid = Decimal::from_f64(bbo.best_bid).unwrap_or_default();
+                let best_bid = bbo.best_bid;
                 if ask_price <= best_bid {
                     ask_price = self.align_price(best_bid + self.tick_size, RoundMode::Ceil);
                 }
@@ -170,6 +207,8 @@ mod tests {
                 is_close_mode: false,
             },
             allowed_sides: vec![Side::Bid, Side::Ask],
+            volatility_bps: None,
+            fair_std_error_bps: None,
         }
     }
 
@@ -185,6 +224,8 @@ mod tests {
                 is_close_mode: true,
             },
             allowed_sides: allowed,
+            volatility_bps: None,
+            fair_std_error_bps: None,
         }
     }
 
@@ -216,6 +257,27 @@ mod tests {
         assert!(ask >= fair_d + dec!(40));
     }
 
+    #[test]
+    fn test_risk_scaling_widens_normal_spread() {
+        let q = quoter().with_risk_scaling(2.0, 1.0);
+        let mut ctx = normal_ctx(50000.0);
+        // No estimates yet: plain 8 bps.
+        assert_eq!(q.half_spread_bps(&ctx), dec!(8));
+
+        // max(8, 2 * 6) + 1 * 1.5 = 13.5 bps = $67.50
+        ctx.volatility_bps = Some(6.0);
+        ctx.fair_std_error_bps = Some(1.5);
+        assert_eq!(q.half_spread_bps(&ctx), dec!(13.5));
+        let quotes = q.get_quotes(&ctx, None);
+        assert_eq!(quotes[0].price, dec!(49932.50));
+        assert_eq!(quotes[1].price, dec!(50067.50));
+
+        // Close mode keeps the take-profit spread.
+        let mut close = close_ctx(50000.0, 0.06, vec![Side::Ask]);
+        close.volatility_bps = Some(6.0);
+        assert_eq!(q.half_spread_bps(&close), dec!(0.1));
+    }
+
     #[test]
     fn test_close_mode_uses_tighter_spread() {
         let q = quoter();
@@ -247,30 +309,30 @@ mod tests {
     fn test_bid_clamped_below_best_ask() {
         let q = quoter();
         let bbo = BBO {
-            best_bid: 49950.0,
-            best_ask: 49960.0, // very tight spread; fair - 8bps might cross
+            best_bid: dec!(49950),
+            best_ask: dec!(49960), // very tight spread; fair - 8bps might cross
         };
         let fair = 49960.0; // fair = best_ask exactly
         let ctx = normal_ctx(fair);
         let quotes = q.get_quotes(&ctx, Some(&bbo));
         let bid = quotes.iter().find(|q| q.side == Side::Bid).unwrap();
         // Bid must be strictly below best_ask
-        assert!(bid.price < Decimal::from_f64(bbo.best_ask).unwrap());
+        assert!(bid.price < bbo.best_ask);
     }
 
     #[test]
     fn test_ask_clamped_above_best_bid() {
         let q = quoter();
         let bbo = BBO {
-            best_bid: 50040.0, // very high bid
-            best_ask: 50050.0,
+            best_bid: dec!(50040), // very high bid
+            best_ask: dec!(50050),
         };
         let fair = 50040.0; // fair = best_bid
         let ctx = normal_ctx(fair);
         let quotes = q.get_quotes(&ctx, Some(&bbo));
         let ask = quotes.iter().find(|q| q.side == Side::Ask).unwrap();
         // Ask must be strictly above best_bid
-        assert!(ask.price > Decimal::from_f64(bbo.best_bid).unwrap());
+        assert!(ask.price > bbo.best_bid);
     }
 
     #[test]
@@ -1,30 +1,31 @@
 //! Market monitor TUI using ratatui + crossterm.
 //!
-//! Displays live pricing (Binance vs 01 Exchange), orderbook depth, recent
-//! trades, and a scrollable log panel.
+//! Displays live pricing (reference venue vs 01 Exchange), orderbook depth,
+//! recent trades, and a scrollable log panel. Binance depth and taker flow
+//! are shown in the pricing panel when enabled.
 
-use std::collections::{BTreeMap, VecDeque};
+use std::collections::VecDeque;
 use std::io::{self, Stdout};
 use std::sync::Arc;
-use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use std::time::Duration;
 
 use crossterm::event::{self, Event, KeyCode, KeyEventKind};
 use crossterm::terminal::{
     disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen,
 };
 use crossterm::ExecutableCommand;
-use ordered_float::OrderedFloat;
 use ratatui::prelude::*;
 use ratatui::widgets::{Block, Borders, Paragraph, Wrap};
-use tokio::sync::broadcast;
+use tokio::sync::{broadcast, watch};
 use tokio_util::sync::CancellationToken;
 use tracing::info;
 
 use crate::client::mainnet_config;
+use crate::composite::{build_reference, ReferenceConfig};
 use crate::error::ZoError;
-use crate::fair_price::{FairPriceCalculator, FairPriceConfig};
-use crate::feed::BinancePriceFeed;
-use crate::mm::bot::derive_binance_symbol;
+use crate::fair_price::{FairPrice, FairPriceConfig, FairPriceModel, PriceAligner, PricingStatus};
+use crate::feed::{BinancePriceFeed, BinanceStreams, BookTop, DepthBook, TradeFlow};
+use crate::source::Venue;
 
 /// Fair price sample window (5 minutes).
 const FAIR_PRICE_WINDOW_MS: u64 = 5 * 60 * 1000;
@@ -32,6 +33,9 @@ const FAIR_PRICE_WINDOW_MS: u64 = 5 * 60 * 1000;
 /// Minimum samples before fair price is valid.
 const FAIR_PRICE_MIN_SAMPLES: usize = 10;
 
+/// Standard errors shown either side of the fair price (95% interval).
+const CONFIDENCE_Z: f64 = 1.96;
+
 /// Window for computing updates-per-second.
 const STATS_WINDOW_MS: u64 = 60_000;
 
@@ -95,24 +99,62 @@ impl RateTracker {
     }
 }
 
+/// Binance order book and trade signals, from a feed separate from the
+/// reference. Prices are Binance prices.
+struct BinanceSignals {
+    feed: BinancePriceFeed,
+    /// Factor converting a Binance price into the 01 price.
+    multiplier: f64,
+    top: watch::Receiver<Option<BookTop>>,
+    depth: Option<watch::Receiver<Option<DepthBook>>>,
+    flow: Option<watch::Receiver<TradeFlow>>,
+}
+
+impl BinanceSignals {
+    /// Connect a Binance feed for `market_symbol` with `streams`.
+    fn connect(reference: &ReferenceConfig, market_symbol: &str, streams: &BinanceStreams) -> Self {
+        let mapping = reference.mapping(market_symbol, Venue::Binance);
+        let feed = BinancePriceFeed::new(&mapping.symbol).with_streams(*streams);
+        feed.connect();
+        Self {
+            multiplier: mapping.multiplier,
+            top: feed.subscribe_book_top(),
+            depth: streams.depth.map(|_| feed.subscribe_depth()),
+            flow: streams.agg_trades.then(|| feed.subscribe_trade_flow()),
+            feed,
+        }
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Public entry point
 // ---------------------------------------------------------------------------
 
 /// Run the market monitor TUI.
 ///
-/// Connects to 01 Exchange (via Nord SDK) and Binance to display real-time
-/// pricing, orderbook depth, trades, and fair price data.
+/// Connects to 01 Exchange (via Nord SDK) and a reference venue to display
+/// real-time pricing, orderbook depth, trades, and fair price data.
 ///
 /// # Arguments
 ///
 /// * `symbol` - Market symbol prefix (e.g. "BTC", "ETH", "SOL").
+/// * `account_id` - Account whose resting orders are separated from the
+///   rest of the book (ex-own BBO and top-level share), if any.
+/// * `reference` - Venues providing the reference price.
+/// * `binance` - Binance depth / aggTrade streams to show; none connects no
+///   extra feed.
 /// * `cancel` - Cancellation token for graceful shutdown.
 ///
 /// # Errors
 ///
 /// Returns [`ZoError`] on connection or market-lookup failures.
-pub async fn run_monitor(symbol: &str, cancel: CancellationToken) -> Result<(), ZoError> {
+pub async fn run_monitor(
+    symbol: &str,
+    account_id: Option<u32>,
+    reference: &ReferenceConfig,
+    binance: &BinanceStreams,
+    cancel: CancellationToken,
+) -> Result<(), ZoError> {
     let config = mainnet_config();
     let nord = Arc::new(nord::Nord::new(config).await?);
 
@@ -123,35 +165,47 @@ pub async fn run_monitor(symbol: &str, cancel: CancellationToken) -> Result<(),
         .find(|m| m.symbol.to_uppercase().starts_with(&symbol.to_uppercase()))
         .ok_or_else(|| ZoError::MarketNotFound(symbol.to_string()))?;
 
+    let market_id = market.market_id;
     let market_symbol = market.symbol.clone();
     let price_decimals = market.price_decimals as usize;
     let size_decimals = market.size_decimals as usize;
-    let binance_symbol = derive_binance_symbol(&market_symbol);
+    let reference_label = reference.label(&market_symbol);
+    let latency = nord.latency().clone();
+    let binance = binance
+        .is_extended()
+        .then(|| BinanceSignals::connect(reference, &market_symbol, binance));
+    let reference = build_reference(reference, &market_symbol, &latency);
 
     info!(
         market = %market_symbol,
-        binance = %binance_symbol,
+        reference = reference.name(),
         "starting monitor"
     );
 
     // Fair price calculator.
-    let mut fair_calc = FairPriceCalculator::new(FairPriceConfig {
+    let fair_config = FairPriceConfig {
         window_ms: FAIR_PRICE_WINDOW_MS,
         min_samples: FAIR_PRICE_MIN_SAMPLES,
-    });
+        ..Default::default()
+    };
+    let mut fair_calc = FairPrice::new(&fair_config);
+    let mut aligner = PriceAligner::new(fair_config.align);
 
-    // Binance price feed.
-    let binance_feed = BinancePriceFeed::new(&binance_symbol);
-    binance_feed.connect();
-    let mut binance_rx = binance_feed.subscribe_price();
+    // Reference price feed.
+    reference.connect();
+    let mut reference_rx = reference.subscribe_price();
 
-    // 01 Exchange WebSocket (deltas + trades).
+    // 01 Exchange WebSocket (deltas + trades, plus account events when our
+    // own orders are tracked).
+    let accounts: Vec<u32> = account_id.into_iter().collect();
     let mut ws_client = nord.create_websocket_client(
         std::slice::from_ref(&market_symbol),
         std::slice::from_ref(&market_symbol),
-        &[],
+        &accounts,
         &[],
     );
+    let mut account_stream = account_id
+        .map(|id| nord::AccountStream::new(id, ws_client.subscribe_accounts(), Arc::clone(&nord)));
     ws_client.connect();
 
     // Orderbook stream.
@@ -159,27 +213,49 @@ pub async fn run_monitor(symbol: &str, cancel: CancellationToken) -> Result<(),
     let mut orderbook =
         nord::OrderbookStream::new(market_symbol.clone(), (*nord).clone(), delta_rx);
     orderbook.connect().await?;
-    let mut ob_depth_rx = orderbook.subscribe_depth();
+    let mut depth_mirror = nord::DepthMirror::new(orderbook.handle()).await;
     let mut ob_price_rx = orderbook.subscribe_price();
+    let mut ob_signals_rx = orderbook.subscribe_signals();
+
+    // Book without our own orders.
+    let mut ex_own = None;
+    if let (Some(id), Some(account_stream)) = (account_id, account_stream.as_mut()) {
+        account_stream.connect();
+        let orders: Vec<_> = nord
+            .get_account(id)
+            .await?
+            .orders
+            .into_iter()
+            .filter(|o| o.market_id == market_id)
+            .collect();
+        account_stream.sync_initial_orders(&orders);
+        let mut stream = nord::ExOwnBookStream::new(
+            market_id,
+            orderbook.handle(),
+            account_stream.subscribe_orders(),
+        );
+        stream.connect();
+        ex_own = Some(stream);
+    }
 
     // Trade stream.
-    let mut trade_rx: broadcast::Receiver<nord::WebSocketTradeUpdate> =
+    let mut trade_rx: broadcast::Receiver<Arc<nord::WebSocketTradeUpdate>> =
         ws_client.subscribe_trades();
 
     // Mutable display state.
-    let mut binance_price: Option<nord::MidPrice> = None;
+    let mut reference_price: Option<nord::MidPrice> = None;
     let mut zo_price: Option<nord::MidPrice> = None;
     let mut fair_price_value: Option<f64> = None;
-    let mut ob_bids: BTreeMap<OrderedFloat<f64>, f64> = BTreeMap::new();
-    let mut ob_asks: BTreeMap<OrderedFloat<f64>, f64> = BTreeMap::new();
+    let mut ob_signals: Option<nord::BookSignals> = None;
     let mut recent_trades: VecDeque<DisplayTrade> = VecDeque::with_capacity(MAX_TRADES);
     let mut log_lines: VecDeque<String> = VecDeque::with_capacity(MAX_LOG_LINES);
 
-    let mut binance_rate = RateTracker::new();
+    let mut reference_rate = RateTracker::new();
     let mut zo_rate = RateTracker::new();
 
     log_lines.push_back(format!(
-        "Market: {market_symbol}, Binance: {binance_symbol}"
+        "Market: {market_symbol}, {reference_label}: {}",
+        reference.name()
     ));
     log_lines.push_back("Connecting...".to_string());
 
@@ -203,16 +279,18 @@ pub async fn run_monitor(symbol: &str, cancel: CancellationToken) -> Result<(),
         }
 
         tokio::select! {
-            // Binance price update.
-            Ok(()) = binance_rx.changed() => {
-                let now = epoch_ms();
-                if let Some(p) = *binance_rx.borrow_and_update() {
-                    binance_price = Some(p);
-                    binance_rate.record(now);
+            // Reference price update.
+            Ok(()) = reference_rx.changed() => {
+                let now = nord::epoch_ms();
+                if let Some(p) = *reference_rx.borrow_and_update() {
+                    reference_price = Some(p);
+                    reference_rate.record(now);
+                    fair_calc.observe_reference(p.mid, now);
                     update_fair_price(
                         &mut fair_calc,
+                        &mut aligner,
                         &mut fair_price_value,
-                        binance_price.as_ref(),
+                        reference_price.as_ref(),
                         zo_price.as_ref(),
                         now,
                     );
@@ -221,26 +299,28 @@ pub async fn run_monitor(symbol: &str, cancel: CancellationToken) -> Result<(),
 
             // 01 Exchange price update.
             Ok(()) = ob_price_rx.changed() => {
-                let now = epoch_ms();
-                if let Some(p) = *ob_price_rx.borrow_and_update() {
-                    zo_price = Some(p);
+                let now = nord::epoch_ms();
+                // `None` once a side of the book empties.
+                zo_price = *ob_price_rx.borrow_and_update();
+                if zo_price.is_some() {
                     zo_rate.record(now);
                     update_fair_price(
                         &mut fair_calc,
+                        &mut aligner,
                         &mut fair_price_value,
-                        binance_price.as_ref(),
+                        reference_price.as_ref(),
                         zo_price.as_ref(),
                         now,
                     );
                 }
             }
 
-            // Orderbook depth update.
-            Ok(()) = ob_depth_rx.changed() => {
-                if let Some(depth) = ob_depth_rx.borrow_and_update().clone() {
-                    ob_bids = depth.bids;
-                    ob_asks = depth.asks;
-                }
+            // Orderbook level changes (applied to the local depth copy).
+            Some(_) = depth_mirror.next() => {}
+
+            // Orderbook signals update.
+            Ok(()) = ob_signals_rx.changed() => {
+                ob_signals = ob_signals_rx.borrow_and_update().clone();
             }
 
             // Trade update.
@@ -248,7 +328,7 @@ pub async fn run_monitor(symbol: &str, cancel: CancellationToken) -> Result<(),
                 match result {
                     Ok(update) => {
                         if update.market_symbol == market_symbol {
-                            let now = epoch_ms();
+                            let now = nord::epoch_ms();
                             for t in &update.trades {
                                 recent_trades.push_front(DisplayTrade {
                                     time_ms: now,
@@ -285,19 +365,23 @@ pub async fn run_monitor(symbol: &str, cancel: CancellationToken) -> Result<(),
                 }
 
                 if !quit {
-                    let now = epoch_ms();
+                    let now = nord::epoch_ms();
                     let _ = terminal.draw(|frame| {
                         render_ui(
                             frame,
                             symbol,
-                            binance_price.as_ref(),
+                            &reference_label,
+                            reference_price.as_ref(),
                             zo_price.as_ref(),
                             fair_price_value,
                             &fair_calc,
-                            &binance_rate,
+                            &reference_rate,
                             &zo_rate,
-                            &ob_bids,
-                            &ob_asks,
+                            depth_mirror.depth(),
+                            ob_signals.as_ref(),
+                            ex_own.as_ref().and_then(nord::ExOwnBookStream::get).as_ref(),
+                            binance.as_ref(),
+                            &latency.snapshot(now),
                             &recent_trades,
                             &log_lines,
                             price_decimals,
@@ -318,8 +402,17 @@ pub async fn run_monitor(symbol: &str, cancel: CancellationToken) -> Result<(),
     restore_terminal(&mut terminal);
 
     // Clean up.
-    binance_feed.close();
+    reference.close();
     orderbook.close();
+    if let Some(binance) = &binance {
+        binance.feed.close();
+    }
+    if let Some(ex_own) = ex_own.as_mut() {
+        ex_own.close();
+    }
+    if let Some(account_stream) = account_stream.as_mut() {
+        account_stream.close();
+    }
 
     result
 }
@@ -330,16 +423,19 @@ pub async fn run_monitor(symbol: &str, cancel: CancellationToken) -> Result<(),
 
 /// Update the fair price calculator and cached value.
 fn update_fair_price(
-    calc: &mut FairPriceCalculator,
+    calc: &mut FairPrice,
+    aligner: &mut PriceAligner,
     cached: &mut Option<f64>,
-    binance: Option<&nord::MidPrice>,
+    reference: Option<&nord::MidPrice>,
     zo: Option<&nord::MidPrice>,
     now_ms: u64,
 ) {
-    if let (Some(b), Some(z)) = (binance, zo) {
-        // Only add sample if both prices are within 1 second.
-        if b.timestamp.abs_diff(z.timestamp) < 1000 {
-            calc.add_sample(z.mid, b.mid, now_ms);
+    if let (Some(b), Some(z)) = (reference, zo) {
+        // Sample both prices as of their common timestamp.
+        aligner.push_reference(b);
+        aligner.push_local(z);
+        if let Some(mids) = aligner.next_sample() {
+            calc.add_sample(mids.local_mid, mids.reference_mid, mids.time_ms);
         }
         *cached = calc.get_fair_price(b.mid, now_ms);
     }
@@ -356,14 +452,6 @@ fn restore_terminal(terminal: &mut Terminal<CrosstermBackend<Stdout>>) {
     let _ = io::stdout().execute(LeaveAlternateScreen);
 }
 
-/// Current epoch milliseconds.
-fn epoch_ms() -> u64 {
-    SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .unwrap_or_default()
-        .as_millis() as u64
-}
-
 // ---------------------------------------------------------------------------
 // UI rendering
 // ---------------------------------------------------------------------------
@@ -373,14 +461,18 @@ fn epoch_ms() -> u64 {
 fn render_ui(
     frame: &mut Frame,
     symbol: &str,
-    binance_price: Option<&nord::MidPrice>,
+    reference_label: &str,
+    reference_price: Option<&nord::MidPrice>,
     zo_price: Option<&nord::MidPrice>,
     fair_price: Option<f64>,
-    fair_calc: &FairPriceCalculator,
-    binance_rate: &RateTracker,
+    fair_calc: &FairPrice,
+    reference_rate: &RateTracker,
     zo_rate: &RateTracker,
-    ob_bids: &BTreeMap<OrderedFloat<f64>, f64>,
-    ob_asks: &BTreeMap<OrderedFloat<f64>, f64>,
+    ob_depth: Option<&nord::OrderbookDepth>,
+    ob_signals: Option<&nord::BookSignals>,
+    ex_own: Option<&nord::ExOwnBook>,
+    binance: Option<&BinanceSignals>,
+    latency: &[nord::LatencyStats],
     recent_trades: &VecDeque<DisplayTrade>,
     log_lines: &VecDeque<String>,
     price_decimals: usize,
@@ -423,20 +515,24 @@ fn render_ui(
     render_pricing(
         frame,
         top_layout[0],
-        binance_price,
+        reference_label,
+        reference_price,
         zo_price,
         fair_price,
         fair_calc,
-        binance_rate,
+        reference_rate,
         zo_rate,
+        ob_signals,
+        ex_own,
+        binance,
+        latency,
         price_decimals,
         now_ms,
     );
     render_orderbook(
         frame,
         top_layout[1],
-        ob_bids,
-        ob_asks,
+        ob_depth,
         price_decimals,
         size_decimals,
     );
@@ -455,23 +551,28 @@ fn render_ui(
 fn render_pricing(
     frame: &mut Frame,
     area: Rect,
-    binance_price: Option<&nord::MidPrice>,
+    reference_label: &str,
+    reference_price: Option<&nord::MidPrice>,
     zo_price: Option<&nord::MidPrice>,
     fair_price: Option<f64>,
-    fair_calc: &FairPriceCalculator,
-    binance_rate: &RateTracker,
+    fair_calc: &FairPrice,
+    reference_rate: &RateTracker,
     zo_rate: &RateTracker,
+    ob_signals: Option<&nord::BookSignals>,
+    ex_own: Option<&nord::ExOwnBook>,
+    binance: Option<&BinanceSignals>,
+    latency: &[nord::LatencyStats],
     price_decimals: usize,
     now_ms: u64,
 ) {
-    let mut lines = Vec::with_capacity(8);
+    let mut lines = Vec::with_capacity(16);
 
-    // Binance line.
-    if let Some(p) = binance_price {
-        let rate = binance_rate.per_second(now_ms);
+    // Reference line.
+    if let Some(p) = reference_price {
+        let rate = reference_rate.per_second(now_ms);
         lines.push(Line::from(vec![
             Span::raw(format!(
-                " Binance ${:.prec$} ",
+                " {reference_label:<7} ${:.prec$} ",
                 p.mid,
                 prec = price_decimals,
             )),
@@ -479,7 +580,7 @@ fn render_pricing(
         ]));
     } else {
         lines.push(Line::from(vec![
-            Span::raw(" Binance "),
+            Span::raw(format!(" {reference_label:<7} ")),
             Span::styled("--", Style::default().fg(Color::Yellow)),
         ]));
     }
@@ -503,7 +604,7 @@ fn render_pricing(
     }
 
     // Current offset.
-    if let (Some(b), Some(z)) = (binance_price, zo_price) {
+    if let (Some(b), Some(z)) = (reference_price, zo_price) {
         let offset = z.mid - b.mid;
         let offset_bps = (offset / b.mid) * 10_000.0;
         let sign = if offset >= 0.0 { "+" } else { "" };
@@ -512,7 +613,7 @@ fn render_pricing(
 
     // Median offset.
     let state = fair_calc.get_state(now_ms);
-    if let (Some(offset), Some(b)) = (state.offset, binance_price) {
+    if let (Some(offset), Some(b)) = (state.offset, reference_price) {
         let median_bps = (offset / b.mid) * 10_000.0;
         let sign = if offset >= 0.0 { "+" } else { "" };
         lines.push(Line::from(vec![
@@ -522,14 +623,133 @@ fn render_pricing(
                 Style::default().fg(Color::DarkGray),
             ),
         ]));
+        if let Some(dispersion) = state.dispersion {
+            let dispersion_bps = dispersion / b.mid * 10_000.0;
+            lines.push(Line::from(format!(" Disp    {dispersion_bps:.1}bps")));
+        }
     }
 
     // Fair price.
-    if let (Some(fp), Some(_b)) = (fair_price, binance_price) {
+    if let (Some(fp), Some(b)) = (fair_price, reference_price) {
         lines.push(Line::from(format!(
             " Fair    ${fp:.prec$}",
             prec = price_decimals,
         )));
+        if let Some((low, high)) = state.confidence_interval(b.mid, CONFIDENCE_Z) {
+            let half_width_bps = (high - low) / 2.0 / b.mid * 10_000.0;
+            lines.push(Line::from(vec![
+                Span::raw(format!(" CI95    ±{half_width_bps:.2}bps ")),
+                Span::styled(
+                    format!("${low:.prec$}-${high:.prec$}", prec = price_decimals),
+                    Style::default().fg(Color::DarkGray),
+                ),
+            ]));
+        }
+    }
+
+    // Circuit breaker.
+    match fair_calc.pricing_status(now_ms) {
+        PricingStatus::Reliable => lines.push(Line::from(vec![
+            Span::raw(" Pricing "),
+            Span::styled("OK", Style::default().fg(Color::Green)),
+        ])),
+        PricingStatus::Unreliable { trip, .. } => lines.push(Line::from(vec![
+            Span::raw(" Pricing "),
+            Span::styled(format!("TRIP {trip}"), Style::default().fg(Color::Red)),
+        ])),
+    }
+
+    // Realized volatility of the reference per horizon.
+    for v in &state.volatility {
+        let label = format!("Vol{}s", v.horizon_ms / 1000);
+        lines.push(Line::from(format!(" {label:<7} {:.1}bps", v.vol_bps)));
+    }
+
+    // Orderbook signals.
+    if let Some(sig) = ob_signals {
+        lines.push(Line::from(format!(
+            " Micro   ${:.prec$}",
+            sig.microprice,
+            prec = price_decimals,
+        )));
+        lines.push(Line::from(format!(
+            " Imb     {:+.2} ({}t)",
+            sig.imbalance, sig.spread_ticks,
+        )));
+        for band in &sig.depth {
+            lines.push(Line::from(vec![
+                Span::raw(format!(" {:>3}bps ", band.bps)),
+                Span::styled(
+                    format!("${:.0}", band.bid_notional),
+                    Style::default().fg(Color::Green),
+                ),
+                Span::raw("/"),
+                Span::styled(
+                    format!("${:.0}", band.ask_notional),
+                    Style::default().fg(Color::Red),
+                ),
+            ]));
+        }
+    }
+
+    // Liquidity excluding our own orders.
+    if let Some(book) = ex_own {
+        let (bid, ask) = match book.external_bbo {
+            Some(bbo) => (bbo.best_bid.to_string(), bbo.best_ask.to_string()),
+            None => ("--".to_string(), "--".to_string()),
+        };
+        lines.push(Line::from(vec![
+            Span::raw(format!(" ExOwn   ${bid}/${ask} ")),
+            Span::styled(
+                format!("({} ours)", book.own_orders),
+                Style::default().fg(Color::DarkGray),
+            ),
+        ]));
+        lines.push(Line::from(vec![
+            Span::raw(" Top     "),
+            top_share_span(book.bid_top.as_ref(), Color::Green),
+            Span::raw("/"),
+            top_share_span(book.ask_top.as_ref(), Color::Red),
+        ]));
+    }
+
+    // Binance microprice, weighted depth mid and taker flow, in 01 prices.
+    if let Some(binance) = binance {
+        let price = |value: Option<f64>| match value {
+            Some(value) => format!(
+                "${:.prec$}",
+                value * binance.multiplier,
+                prec = price_decimals
+            ),
+            None => "--".to_string(),
+        };
+        let top = *binance.top.borrow();
+        lines.push(Line::from(format!(
+            " BnMicro {}",
+            price(top.map(|top| top.microprice()))
+        )));
+        if let Some(depth) = &binance.depth {
+            let weighted_mid = depth.borrow().as_ref().and_then(DepthBook::weighted_mid);
+            lines.push(Line::from(format!(" BnWMid  {}", price(weighted_mid))));
+        }
+        if let Some(flow) = &binance.flow {
+            let flow = flow.borrow();
+            lines.push(Line::from(vec![
+                Span::raw(format!(" BnFlow  {:+.2} ", flow.imbalance())),
+                Span::styled(
+                    format!("({}t)", flow.trades),
+                    Style::default().fg(Color::DarkGray),
+                ),
+            ]));
+        }
+    }
+
+    // Latency percentiles (p50/p99) per source.
+    for stats in latency {
+        lines.push(Line::from(vec![
+            Span::raw(format!(" {:.0}/{:.0}ms ", stats.p50_ms, stats.p99_ms)),
+            Span::styled(stats.source.clone(), Style::default().fg(Color::DarkGray)),
+        ]));
     }
 
     let block = Block::default()
@@ -540,12 +760,25 @@ fn render_pricing(
     frame.render_widget(paragraph, area);
 }
 
+/// Our share of one top level, highlighted when we are alone there.
+fn top_share_span(top: Option<&nord::TopLevelShare>, color: Color) -> Span<'static> {
+    match top {
+        Some(top) if top.is_alone() => {
+            Span::styled("100% alone".to_string(), Style::default().fg(color).bold())
+        }
+        Some(top) => Span::styled(
+            format!("{:.0}%", top.share() * 100.0),
+            Style::default().fg(color),
+        ),
+        None => Span::raw("--"),
+    }
+}
+
 /// Render the orderbook panel.
 fn render_orderbook(
     frame: &mut Frame,
     area: Rect,
-    bids: &BTreeMap<OrderedFloat<f64>, f64>,
-    asks: &BTreeMap<OrderedFloat<f64>, f64>,
+    depth: Option<&nord::OrderbookDepth>,
     price_decimals: usize,
     size_decimals: usize,
 ) {
@@ -560,11 +793,9 @@ fn render_orderbook(
     ));
 
     // Asks: take the closest N to the spread (lowest prices), display reversed.
-    let sorted_asks: Vec<(f64, f64)> = asks
-        .iter()
-        .take(ORDERBOOK_DEPTH)
-        .map(|(p, s)| (p.0, *s))
-        .collect();
+    let sorted_asks: Vec<(f64, f64)> = depth
+        .map(|d| d.ask_levels().take(ORDERBOOK_DEPTH).collect())
+        .unwrap_or_default();
 
     // Pad empty lines if fewer than ORDERBOOK_DEPTH asks.
     for _ in sorted_asks.len()..ORDERBOOK_DEPTH {
@@ -584,8 +815,12 @@ fn render_orderbook(
     }
 
     // Spread line.
-    let best_bid = bids.keys().next_back().map(|k| k.0).unwrap_or(0.0);
-    let best_ask = asks.keys().next().map(|k| k.0).unwrap_or(0.0);
+    let best_bid = depth
+        .and_then(|d| d.bid_levels().next())
+        .map_or(0.0, |(p, _)| p);
+    let best_ask = depth
+        .and_then(|d| d.ask_levels().next())
+        .map_or(0.0, |(p, _)| p);
     let spread = best_ask - best_bid;
     let spread_bps = if best_bid > 0.0 {
         (spread / best_bid) * 10_000.0
@@ -598,12 +833,9 @@ fn render_orderbook(
     )));
 
     // Bids: highest first.
-    let sorted_bids: Vec<(f64, f64)> = bids
-        .iter()
-        .rev()
-        .take(ORDERBOOK_DEPTH)
-        .map(|(p, s)| (p.0, *s))
-        .collect();
+    let sorted_bids: Vec<(f64, f64)> = depth
+        .map(|d| d.bid_levels().take(ORDERBOOK_DEPTH).collect())
+        .unwrap_or_default();
 
     for &(price, size) in &sorted_bids {
         let usd = price * size;
@@ -2,10 +2,12 @@
 //!
 //! Ports `src/sdk/orders.ts`. Compares current active orders against new
 //! desired quotes, cancels stale orders and places new ones atomically.
+//! When an [`Oms`] is given, every chunk is recorded in it before it is sent
+//! and its receipt afterwards, so in-flight orders stay visible.
 
 use nord::actions::atomic::UserAtomicSubaction;
 use nord::proto::nord as proto;
-use nord::{FillMode, NordUser, Side};
+use nord::{FillMode, NordError, NordUser, Oms, Side};
 use rust_decimal::Decimal;
 use tracing::{debug, info};
 
@@ -38,6 +40,7 @@ pub async fn update_quotes(
     market_id: u32,
     current_orders: &[CachedOrder],
     new_quotes: &[Quote],
+    oms: Option<&Oms>,
 ) -> Result<Vec<CachedOrder>, ZoError> {
     let (kept, to_cancel, to_place) = diff_orders(current_orders, new_quotes);
 
@@ -55,7 +58,7 @@ pub async fn update_quotes(
         actions.push(build_place_action(market_id, quote));
     }
 
-    let placed = execute_atomic(user, &actions).await?;
+    let placed = execute_atomic(user, &actions, oms).await?;
 
     let mut result = kept;
     result.extend(placed);
@@ -63,7 +66,11 @@ pub async fn update_quotes(
 }
 
 /// Cancel all given orders atomically (in chunks of 4).
-pub async fn cancel_orders(user: &NordUser, orders: &[CachedOrder]) -> Result<(), ZoError> {
+pub async fn cancel_orders(
+    user: &NordUser,
+    orders: &[CachedOrder],
+    oms: Option<&Oms>,
+) -> Result<(), ZoError> {
     if orders.is_empty() {
         return Ok(());
     }
@@ -71,7 +78,7 @@ pub async fn cancel_orders(user: &NordUser, orders: &[CachedOrder]) -> Result<()
         .iter()
         .map(|o| build_cancel_action(o.order_id))
         .collect();
-    execute_atomic(user, &actions).await?;
+    execute_atomic(user, &actions, oms).await?;
     Ok(())
 }
 
@@ -145,6 +152,7 @@ fn build_cancel_action(order_id: u64) -> UserAtomicSubaction {
 async fn execute_atomic(
     user: &NordUser,
     actions: &[UserAtomicSubaction],
+    oms: Option<&Oms>,
 ) -> Result<Vec<CachedOrder>, ZoError> {
     if actions.is_empty() {
         return Ok(Vec::new());
@@ -161,8 +169,25 @@ async fn execute_atomic(
             "ATOMIC"
         );
 
-        let result = user.atomic(chunk, None).await.map_err(ZoError::Nord)?;
-        let placed = extract_placed_orders(&result.results, chunk);
+        let mut chunk = chunk.to_vec();
+        if let Some(oms) = oms {
+            oms.track_actions(&mut chunk);
+        }
+        let result = match user.atomic(&chunk, None).await {
+            Ok(result) => result,
+            Err(e) => {
+                // Only a receipt error means the exchange refused the
+                // chunk; otherwise its orders may still have been placed.
+                if let (Some(oms), NordError::ReceiptError(_)) = (oms, &e) {
+                    oms.on_atomic_error(&chunk, &e.to_string());
+                }
+                return Err(ZoError::Nord(e));
+            }
+        };
+        if let Some(oms) = oms {
+            oms.on_atomic_receipt(&chunk, &result.results);
+        }
+        let placed = extract_placed_orders(&result.results, &chunk);
 
         if !placed.is_empty() {
             debug!(
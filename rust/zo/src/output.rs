@@ -1,30 +1,202 @@
+use std::collections::HashMap;
 use std::io::Write;
 
+use rust_decimal::prelude::ToPrimitive;
+
 use crate::error::ZoError;
-use crate::types::CombinedStreamMsg;
+use crate::feed::{AggTrade, BookTop, DepthBook, TradeFlowWindow, TRADE_FLOW_WINDOW_MS};
+use crate::types::{AggTradeMsg, BookTickerMsg, DepthMsg, StreamEnvelope};
+
+/// Factors converting venue prices into 01 prices, keyed by the venue
+/// symbol as it appears in stream messages (e.g. `"1000PEPEUSDT"` → `0.001`).
+/// Symbols without an entry are printed unchanged.
+pub type PriceMultipliers = HashMap<String, f64>;
 
-/// Parse a raw JSON text message and write formatted output to the writer.
+/// Parse a raw combined-stream message and write formatted output to the
+/// writer. The stream name selects the line format; `flows` keeps the
+/// rolling trade flow per symbol. Prices of symbols in `multipliers` are
+/// converted into 01 prices and their quantities into 01 units, so the
+/// notional is unchanged.
 ///
-/// Returns `Ok(())` on success, or a `ZoError` if parsing fails.
+/// Returns `Ok(())` on success (unknown streams are skipped), or a `ZoError`
+/// if parsing fails.
 pub fn handle_message<W: Write>(
     text: &str,
     json_mode: bool,
+    multipliers: &PriceMultipliers,
+    flows: &mut HashMap<String, TradeFlowWindow>,
     buf: &mut String,
     writer: &mut W,
 ) -> Result<(), ZoError> {
-    let msg: CombinedStreamMsg = serde_json::from_str(text)?;
-    let d = &msg.data;
+    let envelope: StreamEnvelope = serde_json::from_str(text)?;
+    let (stream, data) = (envelope.stream, envelope.data.get());
+    let (mut qty_a, mut qty_b) = (ryu::Buffer::new(), ryu::Buffer::new());
 
-    let bid: f64 = d.b.parse()?;
-    let ask: f64 = d.a.parse()?;
-    let mid = (bid + ask) * 0.5;
+    buf.clear();
+    if stream.ends_with("@bookTicker") {
+        let d: BookTickerMsg = serde_json::from_str(data)?;
+        let mut top = BookTop {
+            bid: d.b.parse()?,
+            bid_qty: d.bid_qty.parse()?,
+            ask: d.a.parse()?,
+            ask_qty: d.ask_qty.parse()?,
+            event_time: d.event_time,
+            timestamp: 0,
+        };
+        match multipliers.get(&d.s) {
+            Some(&m) => {
+                (top.bid, top.ask) = (top.bid * m, top.ask * m);
+                (top.bid_qty, top.ask_qty) = (top.bid_qty / m, top.ask_qty / m);
+                let (bid_qty, ask_qty) = (qty_a.format(top.bid_qty), qty_b.format(top.ask_qty));
+                write_top(&d.s, &top, bid_qty, ask_qty, json_mode, buf);
+            }
+            None => write_top(&d.s, &top, &d.bid_qty, &d.ask_qty, json_mode, buf),
+        }
+    } else if stream.contains("@depth") {
+        let msg: DepthMsg = serde_json::from_str(data)?;
+        let Some(mut depth) = DepthBook::from_msg(&msg, 0) else {
+            return Ok(());
+        };
+        if let Some(&m) = multipliers.get(&msg.s) {
+            for (price, qty) in depth.bids.iter_mut().chain(depth.asks.iter_mut()) {
+                (*price, *qty) = (*price * m, *qty / m);
+            }
+        }
+        let Some(weighted_mid) = depth.weighted_mid() else {
+            return Ok(());
+        };
+        write_depth(&msg.s, &depth, weighted_mid, json_mode, buf);
+    } else if stream.ends_with("@aggTrade") {
+        let msg: AggTradeMsg = serde_json::from_str(data)?;
+        let mut trade = AggTrade::from_msg(&msg, 0)?;
+        let window = flow_window(flows, &msg.s);
+        match multipliers.get(&msg.s) {
+            Some(&m) => {
+                (trade.price, trade.qty) = (trade.price * m, trade.qty / m);
+                let (price, qty) = (qty_a.format(trade.price), qty_b.format(trade.qty));
+                write_trade(&msg.s, price, qty, &trade, window, json_mode, buf);
+            }
+            None => write_trade(&msg.s, &msg.p, &msg.q, &trade, window, json_mode, buf),
+        }
+    } else {
+        return Ok(());
+    }
 
+    emit(buf, writer)
+}
+
+/// Write a 01 Exchange top-of-book line in the bookTicker format, stamped
+/// with the time the signals were computed.
+///
+/// # Errors
+///
+/// Returns [`ZoError::ConnectionClosed`] if the write fails (e.g. a closed
+/// pipe).
+pub fn handle_book_signals<W: Write>(
+    symbol: &str,
+    signals: &nord::BookSignals,
+    json_mode: bool,
+    buf: &mut String,
+    writer: &mut W,
+) -> Result<(), ZoError> {
+    let top = BookTop {
+        bid: signals.bbo.best_bid.to_f64().unwrap_or_default(),
+        bid_qty: signals.bid_size,
+        ask: signals.bbo.best_ask.to_f64().unwrap_or_default(),
+        ask_qty: signals.ask_size,
+        event_time: signals.timestamp,
+        timestamp: signals.timestamp,
+    };
+    let (mut bid_qty, mut ask_qty) = (ryu::Buffer::new(), ryu::Buffer::new());
     buf.clear();
+    write_top(
+        symbol,
+        &top,
+        bid_qty.format(top.bid_qty),
+        ask_qty.format(top.ask_qty),
+        json_mode,
+        buf,
+    );
+    emit(buf, writer)
+}
+
+/// Write a 01 Exchange trade line in the aggTrade format. 01 trades carry no
+/// time, so `time_ms` is the receive time.
+///
+/// # Errors
+///
+/// Returns [`ZoError::ConnectionClosed`] if the write fails (e.g. a closed
+/// pipe).
+pub fn handle_trade<W: Write>(
+    symbol: &str,
+    trade: &nord::StreamTrade,
+    time_ms: u64,
+    flows: &mut HashMap<String, TradeFlowWindow>,
+    json_mode: bool,
+    buf: &mut String,
+    writer: &mut W,
+) -> Result<(), ZoError> {
+    let flow_trade = AggTrade {
+        price: trade.price,
+        qty: trade.size,
+        aggressor: trade.side,
+        trade_time: time_ms,
+        timestamp: time_ms,
+    };
+    let (mut price, mut qty) = (ryu::Buffer::new(), ryu::Buffer::new());
+    buf.clear();
+    write_trade(
+        symbol,
+        price.format(trade.price),
+        qty.format(trade.size),
+        &flow_trade,
+        flow_window(flows, symbol),
+        json_mode,
+        buf,
+    );
+    emit(buf, writer)
+}
+
+/// Terminate the line in `buf` and write it in one call, so concurrent
+/// writers to stdout interleave whole lines.
+fn emit<W: Write>(buf: &mut String, writer: &mut W) -> Result<(), ZoError> {
+    buf.push('\n');
+    writer.write_all(buf.as_bytes())?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// Rolling trade flow of `symbol`, created on its first trade.
+fn flow_window<'a>(
+    flows: &'a mut HashMap<String, TradeFlowWindow>,
+    symbol: &str,
+) -> &'a mut TradeFlowWindow {
+    if !flows.contains_key(symbol) {
+        flows.insert(
+            symbol.to_string(),
+            TradeFlowWindow::new(TRADE_FLOW_WINDOW_MS),
+        );
+    }
+    flows.get_mut(symbol).expect("window inserted above")
+}
+
+/// Top-of-book line. Quantities are passed as text so venue strings are
+/// printed unchanged.
+fn write_top(
+    symbol: &str,
+    top: &BookTop,
+    bid_qty: &str,
+    ask_qty: &str,
+    json_mode: bool,
+    buf: &mut String,
+) {
+    let (bid, ask) = (top.bid, top.ask);
+    let mid = top.mid_price().mid;
 
     if json_mode {
         // Manual JSON construction to avoid serde_json::to_string allocation overhead.
         buf.push_str("{\"symbol\":\"");
-        buf.push_str(&d.s);
+        buf.push_str(symbol);
         buf.push_str("\",\"bid\":");
         format_f64(buf, bid);
         buf.push_str(",\"ask\":");
@@ -32,15 +204,17 @@ pub fn handle_message<W: Write>(
         buf.push_str(",\"mid\":");
         format_f64(buf, mid);
         buf.push_str(",\"bid_qty\":\"");
-        buf.push_str(&d.bid_qty);
+        buf.push_str(bid_qty);
         buf.push_str("\",\"ask_qty\":\"");
-        buf.push_str(&d.ask_qty);
-        buf.push_str("\",\"event_time\":");
-        itoa_u64(buf, d.event_time);
+        buf.push_str(ask_qty);
+        buf.push_str("\",\"microprice\":");
+        format_f64(buf, top.microprice());
+        buf.push_str(",\"event_time\":");
+        itoa_u64(buf, top.event_time);
         buf.push('}');
     } else {
-        // TSV: symbol \t bid \t ask \t mid \t bid_qty \t ask_qty \t event_time
-        buf.push_str(&d.s);
+        // TSV: symbol \t bid \t ask \t mid \t bid_qty \t ask_qty \t event_time \t microprice
+        buf.push_str(symbol);
         buf.push('\t');
         format_f64(buf, bid);
         buf.push('\t');
@@ -48,18 +222,116 @@ pub fn handle_message<W: Write>(
         buf.push('\t');
         format_f64(buf, mid);
         buf.push('\t');
-        buf.push_str(&d.bid_qty);
+        buf.push_str(bid_qty);
+        buf.push('\t');
+        buf.push_str(ask_qty);
         buf.push('\t');
-        buf.push_str(&d.ask_qty);
+        itoa_u64(buf, top.event_time);
         buf.push('\t');
-        itoa_u64(buf, d.event_time);
+        format_f64(buf, top.microprice());
     }
+}
 
-    buf.push('\n');
-    writer.write_all(buf.as_bytes())?;
-    writer.flush()?;
+/// Depth line: best levels, the size-weighted mid and the total size on each
+/// side.
+fn write_depth(
+    symbol: &str,
+    depth: &DepthBook,
+    weighted_mid: f64,
+    json_mode: bool,
+    buf: &mut String,
+) {
+    let (bid, ask) = (depth.bids[0].0, depth.asks[0].0);
+    if json_mode {
+        buf.push_str("{\"symbol\":\"");
+        buf.push_str(symbol);
+        buf.push_str("\",\"type\":\"depth\",\"bid\":");
+        format_f64(buf, bid);
+        buf.push_str(",\"ask\":");
+        format_f64(buf, ask);
+        buf.push_str(",\"weighted_mid\":");
+        format_f64(buf, weighted_mid);
+        buf.push_str(",\"bid_qty\":");
+        format_f64(buf, depth.bid_qty());
+        buf.push_str(",\"ask_qty\":");
+        format_f64(buf, depth.ask_qty());
+        buf.push_str(",\"levels\":");
+        itoa_u64(buf, depth.bids.len().min(depth.asks.len()) as u64);
+        buf.push_str(",\"event_time\":");
+        itoa_u64(buf, depth.event_time);
+        buf.push('}');
+    } else {
+        // TSV: symbol \t depth \t bid \t ask \t weighted_mid \t bid_qty \t ask_qty \t event_time
+        buf.push_str(symbol);
+        buf.push_str("\tdepth\t");
+        format_f64(buf, bid);
+        buf.push('\t');
+        format_f64(buf, ask);
+        buf.push('\t');
+        format_f64(buf, weighted_mid);
+        buf.push('\t');
+        format_f64(buf, depth.bid_qty());
+        buf.push('\t');
+        format_f64(buf, depth.ask_qty());
+        buf.push('\t');
+        itoa_u64(buf, depth.event_time);
+    }
+}
 
-    Ok(())
+/// Trade line: the trade plus the symbol's rolling taker flow. Price and
+/// quantity are passed as text so venue strings are printed unchanged.
+fn write_trade(
+    symbol: &str,
+    price: &str,
+    qty: &str,
+    trade: &AggTrade,
+    window: &mut TradeFlowWindow,
+    json_mode: bool,
+    buf: &mut String,
+) {
+    let flow = window.push(*trade);
+    let side = match trade.aggressor {
+        nord::Side::Bid => "buy",
+        nord::Side::Ask => "sell",
+    };
+    if json_mode {
+        buf.push_str("{\"symbol\":\"");
+        buf.push_str(symbol);
+        buf.push_str("\",\"type\":\"trade\",\"price\":\"");
+        buf.push_str(price);
+        buf.push_str("\",\"qty\":\"");
+        buf.push_str(qty);
+        buf.push_str("\",\"side\":\"");
+        buf.push_str(side);
+        buf.push_str("\",\"buy_volume\":");
+        format_f64(buf, flow.buy_volume);
+        buf.push_str(",\"sell_volume\":");
+        format_f64(buf, flow.sell_volume);
+        buf.push_str(",\"imbalance\":");
+        format_f64(buf, flow.imbalance());
+        buf.push_str(",\"trades\":");
+        itoa_u64(buf, flow.trades as u64);
+        buf.push_str(",\"trade_time\":");
+        itoa_u64(buf, trade.trade_time);
+        buf.push('}');
+    } else {
+        // TSV: symbol \t trade \t price \t qty \t side \t buy_volume \t sell_volume \t imbalance \t trade_time
+        buf.push_str(symbol);
+        buf.push_str("\ttrade\t");
+        buf.push_str(price);
+        buf.push('\t');
+        buf.push_str(qty);
+        buf.push('\t');
+        buf.push_str(side);
+        buf.push('\t');
+        format_f64(buf, flow.buy_volume);
+        buf.push('\t');
+        format_f64(buf, flow.sell_volume);
+        buf.push('\t');
+        format_f64(buf, flow.imbalance());
+        buf.push('\t');
+        itoa_u64(buf, trade.trade_time);
+    }
 }
 
 /// Fast f64 formatting via `ryu`.
@@ -92,3 +364,72 @@ fn write_u64(buf: &mut [u8; 20], mut val: u64) -> &str {
     // Digits 0-9 are always valid single-byte UTF-8, so this cannot fail.
     std::str::from_utf8(&buf[i..]).expect("digits 0-9 are valid UTF-8")
 }
+
+#[cfg(test)]
+mod tests {
+    use rust_decimal_macros::dec;
+
+    use super::*;
+
+    #[test]
+    fn test_zo_lines_match_binance_columns() {
+        let signals = nord::BookSignals {
+            bbo: nord::BBO {
+                best_bid: dec!(99.5),
+                best_ask: dec!(100.5),
+            },
+            bid_size: 3.0,
+            ask_size: 1.0,
+            mid: 100.0,
+            microprice: 100.25,
+            imbalance: 0.5,
+            spread_ticks: 10,
+            depth: Vec::new(),
+            fills: Vec::new(),
+            timestamp: 1_000,
+        };
+        let (mut buf, mut out) = (String::new(), Vec::new());
+        handle_book_signals("BTC-PERP", &signals, false, &mut buf, &mut out).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "BTC-PERP\t99.5\t100.5\t100.0\t3.0\t1.0\t1000\t100.25\n"
+        );
+
+        let trade = nord::StreamTrade {
+            side: nord::Side::Ask,
+            price: 99.5,
+            size: 2.0,
+            order_id: "1".to_string(),
+        };
+        let (mut flows, mut out) = (HashMap::new(), Vec::new());
+        handle_trade(
+            "BTC-PERP", &trade, 2_000, &mut flows, true, &mut buf, &mut out,
+        )
+        .unwrap();
+        let line: serde_json::Value = serde_json::from_slice(&out).unwrap();
+        assert_eq!(line["side"], "sell");
+        assert_eq!(line["sell_volume"], 2.0);
+        assert_eq!(line["trade_time"], 2_000);
+    }
+
+    #[test]
+    fn test_multiplied_listing_is_printed_in_01_units() {
+        let multipliers: PriceMultipliers = [("1000PEPEUSDT".to_string(), 0.001)].into();
+        let ticker = r#"{"stream":"1000pepeusdt@bookTicker","data":{"s":"1000PEPEUSDT",
+            "b":"0.0100","B":"5000","a":"0.0102","A":"3000","E":1}}"#;
+        let (mut flows, mut buf, mut out) = (HashMap::new(), String::new(), Vec::new());
+        handle_message(ticker, true, &multipliers, &mut flows, &mut buf, &mut out).unwrap();
+        let line: serde_json::Value = serde_json::from_slice(&out).unwrap();
+        assert!((line["bid"].as_f64().unwrap() - 0.00001).abs() < 1e-15);
+        assert!((line["mid"].as_f64().unwrap() - 0.0000101).abs() < 1e-15);
+        assert_eq!(line["bid_qty"], "5000000.0");
+
+        // Other symbols are printed as received.
+        let (mut buf, mut out) = (String::new(), Vec::new());
+        let btc = ticker.replace("1000PEPEUSDT", "BTCUSDT");
+        handle_message(&btc, true, &multipliers, &mut flows, &mut buf, &mut out).unwrap();
+        let line: serde_json::Value = serde_json::from_slice(&out).unwrap();
+        assert_eq!(line["bid"], 0.01);
+        assert_eq!(line["bid_qty"], "5000");
+    }
+}
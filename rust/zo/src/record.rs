@@ -0,0 +1,112 @@
+//! Raw market-data capture (`zo record`).
+//!
+//! Subscribes to 01 Exchange deltas and trades plus the matching Binance
+//! bookTicker streams for each requested market, and writes every frame and
+//! REST orderbook snapshot through a [`nord::Recorder`].
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio_util::sync::CancellationToken;
+use tracing::info;
+
+use crate::client::{mainnet_config, resolve_markets};
+use crate::error::ZoError;
+use crate::feed::BinancePriceFeed;
+use crate::source::Venue;
+use crate::symbols::SymbolRegistry;
+
+/// Interval between recorder status log lines.
+const STATUS_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Record market data for the given symbol prefixes until `cancel` fires.
+///
+/// # Arguments
+///
+/// * `symbols` - Market symbol prefixes (e.g. `["BTC", "ETH"]`).
+/// * `registry` - Symbol map giving each market's Binance instrument.
+/// * `config` - Output directory and rotation settings.
+/// * `cancel` - Cancellation token for graceful shutdown.
+///
+/// # Errors
+///
+/// Returns [`ZoError`] if a market is unknown, the output directory cannot be
+/// created, or an initial orderbook snapshot fails.
+pub async fn run_record(
+    symbols: &[String],
+    registry: &SymbolRegistry,
+    config: nord::RecorderConfig,
+    cancel: CancellationToken,
+) -> Result<(), ZoError> {
+    let mut nord = nord::Nord::new(mainnet_config()).await?;
+
+    let market_symbols = resolve_markets(&nord, symbols)?;
+
+    let dir = config.dir.clone();
+    let (recorder, writer) = nord::Recorder::spawn(config)?;
+    nord.set_recorder(recorder.clone());
+
+    info!(markets = ?market_symbols, dir = %dir.display(), "recording");
+
+    // 01 Exchange WebSocket (deltas + trades for every market).
+    let mut ws = nord.create_websocket_client(&market_symbols, &market_symbols, &[], &[]);
+    ws.set_recorder(recorder.clone());
+    ws.connect();
+
+    // Orderbooks drive the REST snapshots (initial, stale, lagged).
+    let mut orderbooks = nord::OrderbookManager::new(
+        Arc::new(nord),
+        ws.subscribe_deltas(),
+        nord::OrderbookConfig::default(),
+    );
+    orderbooks.connect();
+    for symbol in &market_symbols {
+        orderbooks.add_market(symbol).await?;
+    }
+
+    // Binance reference feeds.
+    // Frames are recorded raw; replay applies the multiplier.
+    let feeds: Vec<BinancePriceFeed> = market_symbols
+        .iter()
+        .map(|symbol| {
+            let mapping = registry.mapping(symbol, Venue::Binance);
+            let mut feed = BinancePriceFeed::new(&mapping.symbol);
+            feed.set_recorder(recorder.clone());
+            feed.connect();
+            feed
+        })
+        .collect();
+
+    let mut status_interval = tokio::time::interval(STATUS_INTERVAL);
+    status_interval.tick().await;
+
+    loop {
+        tokio::select! {
+            _ = status_interval.tick() => {
+                let rec = recorder.stats();
+                let ws_stats = ws.dispatch_stats();
+                info!(
+                    recorded = rec.recorded,
+                    dropped = rec.dropped,
+                    deltas = ws_stats.deltas,
+                    trades = ws_stats.trades,
+                    unknown = ws_stats.unknown,
+                    "RECORD"
+                );
+            }
+            _ = cancel.cancelled() => break,
+        }
+    }
+
+    for feed in &feeds {
+        feed.close();
+    }
+    orderbooks.close();
+    ws.close();
+
+    // Joining the writer blocks while the queue drains and gzip finishes.
+    let _ = tokio::task::spawn_blocking(move || writer.shutdown()).await;
+    info!(recorded = recorder.stats().recorded, "recording finished");
+
+    Ok(())
+}
@@ -0,0 +1,658 @@
+//! Reference price sources on external venues.
+//!
+//! A [`PriceSource`] publishes the best bid/ask of one instrument on another
+//! venue as a [`nord::MidPrice`] over a `watch` channel. The market maker and
+//! the monitor only depend on the trait, so the venue can be chosen per market
+//! with [`Venue`]:
+//!
+//! ```text
+//!   Binance     <sym>@bookTicker       --+
+//!   Bybit       orderbook.1.<SYM>        |
+//!   OKX         bbo-tbt <SYM>-USDT-SWAP  +--> watch<Option<MidPrice>>
+//!   Hyperliquid bbo <COIN>             --+
+//! ```
+//!
+//! Several sources can be combined into one with
+//! [`CompositePriceSource`](crate::composite::CompositePriceSource).
+//!
+//! Binance is served by [`BinancePriceFeed`], which also supports recording
+//! and replay. The other venues ([`WsVenue`]) share [`WsPriceSource`]:
+//! connect, send the venue's subscribe message, parse its top-of-book
+//! frames, and keep the connection alive with the venue's application-level
+//! ping. Bybit sends deltas, so its levels are kept per connection; OKX and
+//! Hyperliquid send the whole top of book in every frame.
+
+use std::fmt;
+use std::time::Duration;
+
+use futures_util::{SinkExt, StreamExt};
+use serde::Deserialize;
+use tokio::sync::watch;
+use tokio::time::{self, Instant};
+use tokio_tungstenite::tungstenite::Message;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, error, info};
+
+use crate::error::ZoError;
+use crate::feed::BinancePriceFeed;
+use crate::symbols::SymbolMapping;
+
+/// Interval between keep-alive pings (Bybit drops idle clients after 30s).
+const PING_INTERVAL: Duration = Duration::from_secs(20);
+const STALE_THRESHOLD: Duration = Duration::from_secs(60);
+const STALE_CHECK_INTERVAL: Duration = Duration::from_secs(10);
+const RECONNECT_DELAY: Duration = Duration::from_secs(3);
+
+const BYBIT_LINEAR_WS: &str = "wss://stream.bybit.com/v5/public/linear";
+const OKX_PUBLIC_WS: &str = "wss://ws.okx.com:8443/ws/v5/public";
+const HYPERLIQUID_WS: &str = "wss://api.hyperliquid.xyz/ws";
+
+// ---------------------------------------------------------------------------
+// Venue
+// ---------------------------------------------------------------------------
+
+/// External venue providing a reference price.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum Venue {
+    /// Binance USDⓈ-M futures.
+    #[default]
+    Binance,
+    /// Bybit USDT perpetuals.
+    Bybit,
+    /// OKX USDT swaps.
+    Okx,
+    /// Hyperliquid perpetuals.
+    Hyperliquid,
+}
+
+impl fmt::Display for Venue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Binance => "Binance",
+            Self::Bybit => "Bybit",
+            Self::Okx => "OKX",
+            Self::Hyperliquid => "Hyperliquid",
+        })
+    }
+}
+
+// ---------------------------------------------------------------------------
+// PriceSource
+// ---------------------------------------------------------------------------
+
+/// Live mid-price of one instrument on an external venue.
+///
+/// Implementations spawn a background task on [`connect`](Self::connect) and
+/// publish every top-of-book update, stamped with the local receive time.
+pub trait PriceSource: Send + Sync {
+    /// Venue and instrument, for logs (e.g. `"Bybit:BTCUSDT"`).
+    fn name(&self) -> String;
+
+    /// Start the background connection.
+    fn connect(&self);
+
+    /// Latest mid-price snapshot (lock-free read).
+    fn get_mid_price(&self) -> Option<nord::MidPrice>;
+
+    /// Subscribe to price updates.
+    fn subscribe_price(&self) -> watch::Receiver<Option<nord::MidPrice>>;
+
+    /// Name and price channel of each venue feed this source is built from,
+    /// so every symbol mapping can be checked on its own. Just this source
+    /// unless it combines several.
+    fn member_prices(&self) -> Vec<(String, watch::Receiver<Option<nord::MidPrice>>)> {
+        vec![(self.name(), self.subscribe_price())]
+    }
+
+    /// Shut down the background task.
+    fn close(&self);
+}
+
+/// Build the (unconnected) source for `mapping`, publishing prices
+/// converted with its multiplier and recording feed latency into `latency`.
+pub fn build_source(
+    mapping: &SymbolMapping,
+    latency: &nord::LatencyTracker,
+) -> Box<dyn PriceSource> {
+    let ws = |venue: WsVenue| -> Box<dyn PriceSource> {
+        Box::new(
+            WsPriceSource::new(venue, &mapping.symbol)
+                .with_multiplier(mapping.multiplier)
+                .with_latency(latency.clone()),
+        )
+    };
+    match mapping.venue {
+        Venue::Binance => Box::new(
+            BinancePriceFeed::new(&mapping.symbol)
+                .with_multiplier(mapping.multiplier)
+                .with_latency(latency.clone()),
+        ),
+        Venue::Bybit => ws(WsVenue::Bybit),
+        Venue::Okx => ws(WsVenue::Okx),
+        Venue::Hyperliquid => ws(WsVenue::Hyperliquid),
+    }
+}
+
+/// Convert a venue price into 01 units with a symbol multiplier.
+pub(crate) fn scale_price(price: nord::MidPrice, multiplier: f64) -> nord::MidPrice {
+    nord::MidPrice {
+        mid: price.mid * multiplier,
+        exact_mid: None,
+        bid: price.bid * multiplier,
+        ask: price.ask * multiplier,
+        timestamp: price.timestamp,
+    }
+}
+
+impl PriceSource for BinancePriceFeed {
+    fn name(&self) -> String {
+        format!("{}:{}", Venue::Binance, self.symbol())
+    }
+
+    fn connect(&self) {
+        BinancePriceFeed::connect(self);
+    }
+
+    fn get_mid_price(&self) -> Option<nord::MidPrice> {
+        BinancePriceFeed::get_mid_price(self)
+    }
+
+    fn subscribe_price(&self) -> watch::Receiver<Option<nord::MidPrice>> {
+        BinancePriceFeed::subscribe_price(self)
+    }
+
+    fn close(&self) {
+        BinancePriceFeed::close(self);
+    }
+}
+
+// ---------------------------------------------------------------------------
+// WsPriceSource (Bybit, OKX, Hyperliquid)
+// ---------------------------------------------------------------------------
+
+/// Venue served by [`WsPriceSource`]: every [`Venue`] but Binance.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WsVenue {
+    /// Bybit USDT perpetuals.
+    Bybit,
+    /// OKX USDT swaps.
+    Okx,
+    /// Hyperliquid perpetuals.
+    Hyperliquid,
+}
+
+impl WsVenue {
+    fn url(self) -> &'static str {
+        match self {
+            Self::Bybit => BYBIT_LINEAR_WS,
+            Self::Okx => OKX_PUBLIC_WS,
+            Self::Hyperliquid => HYPERLIQUID_WS,
+        }
+    }
+}
+
+impl From<WsVenue> for Venue {
+    fn from(venue: WsVenue) -> Self {
+        match venue {
+            WsVenue::Bybit => Self::Bybit,
+            WsVenue::Okx => Self::Okx,
+            WsVenue::Hyperliquid => Self::Hyperliquid,
+        }
+    }
+}
+
+impl fmt::Display for WsVenue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Venue::from(*self).fmt(f)
+    }
+}
+
+/// Top-of-book source for venues that subscribe over a shared endpoint.
+pub struct WsPriceSource {
+    venue: WsVenue,
+    symbol: String,
+    url: String,
+    multiplier: f64,
+    latency: Option<nord::LatencyTracker>,
+    price_tx: watch::Sender<Option<nord::MidPrice>>,
+    price_rx: watch::Receiver<Option<nord::MidPrice>>,
+    cancel: CancellationToken,
+}
+
+impl WsPriceSource {
+    /// Create a source for `symbol` (venue format, e.g. `"BTCUSDT"` on
+    /// Bybit) on the venue's public endpoint.
+    pub fn new(venue: WsVenue, symbol: &str) -> Self {
+        let (price_tx, price_rx) = watch::channel(None);
+        Self {
+            venue,
+            symbol: symbol.to_string(),
+            url: venue.url().to_string(),
+            multiplier: 1.0,
+            latency: None,
+            price_tx,
+            price_rx,
+            cancel: CancellationToken::new(),
+        }
+    }
+
+    /// Multiply published prices by `multiplier` (see
+    /// [`SymbolMapping::multiplier`]).
+    pub fn with_multiplier(mut self, multiplier: f64) -> Self {
+        self.multiplier = multiplier;
+        self
+    }
+
+    /// Record event-to-receive latency under [`PriceSource::name`].
+    pub fn with_latency(mut self, latency: nord::LatencyTracker) -> Self {
+        self.latency = Some(latency);
+        self
+    }
+
+    /// Connect to `url` instead of the venue's public endpoint.
+    #[cfg(test)]
+    pub fn with_url(mut self, url: &str) -> Self {
+        self.url = url.to_string();
+        self
+    }
+}
+
+impl PriceSource for WsPriceSource {
+    fn name(&self) -> String {
+        format!("{}:{}", self.venue, self.symbol)
+    }
+
+    fn connect(&self) {
+        let venue = self.venue;
+        let symbol = self.symbol.clone();
+        let url = self.url.clone();
+        let tx = self.price_tx.clone();
+        let cancel = self.cancel.clone();
+        let multiplier = self.multiplier;
+        let latency = self.latency.clone().map(|l| (l, self.name()));
+
+        tokio::spawn(async move {
+            info!(%venue, %symbol, url = %url, "reference feed starting");
+            loop {
+                let mut parser = TopOfBook::new(venue);
+                match run_connection(
+                    &url,
+                    &symbol,
+                    &mut parser,
+                    multiplier,
+                    latency.as_ref(),
+                    &tx,
+                    &cancel,
+                )
+                .await
+                {
+                    Ok(()) => {
+                        info!(%venue, "reference feed stopped gracefully");
+                        return;
+                    }
+                    Err(e) => {
+                        error!(%venue, error = %e, "reference connection error");
+                        if cancel.is_cancelled() {
+                            return;
+                        }
+                        info!(%venue, delay = ?RECONNECT_DELAY, "reconnecting reference feed");
+                        tokio::select! {
+                            _ = time::sleep(RECONNECT_DELAY) => {}
+                            _ = cancel.cancelled() => return,
+                        }
+                    }
+                }
+            }
+        });
+    }
+
+    fn get_mid_price(&self) -> Option<nord::MidPrice> {
+        *self.price_rx.borrow()
+    }
+
+    fn subscribe_price(&self) -> watch::Receiver<Option<nord::MidPrice>> {
+        self.price_rx.clone()
+    }
+
+    fn close(&self) {
+        self.cancel.cancel();
+    }
+}
+
+/// Single connection: subscribe, then publish every parsed top of book
+/// converted with `multiplier`, recording its latency under the given
+/// source name. A book with an empty side publishes `None`.
+async fn run_connection(
+    url: &str,
+    symbol: &str,
+    parser: &mut TopOfBook,
+    multiplier: f64,
+    latency: Option<&(nord::LatencyTracker, String)>,
+    tx: &watch::Sender<Option<nord::MidPrice>>,
+    cancel: &CancellationToken,
+) -> Result<(), ZoError> {
+    let (ws_stream, _) = tokio_tungstenite::connect_async(url).await?;
+    let (mut sink, mut stream) = ws_stream.split();
+    sink.send(Message::Text(parser.subscribe_message(symbol)))
+        .await?;
+    info!(venue = %parser.venue, "reference connected");
+
+    let mut last_message_time = Instant::now();
+    let mut ping_interval = time::interval(PING_INTERVAL);
+    ping_interval.tick().await;
+    let mut stale_interval = time::interval(STALE_CHECK_INTERVAL);
+    stale_interval.tick().await;
+
+    loop {
+        tokio::select! {
+            msg = stream.next() => {
+                match msg {
+                    Some(Ok(Message::Text(text))) => {
+                        last_message_time = Instant::now();
+                        if let Some(top) = parser.parse(&text) {
+                            let recv_ms = nord::epoch_ms();
+                            if let Some((latency, source)) = latency {
+                                latency.record_event(source, parser.event_ms, recv_ms);
+                            }
+                            let price = top.map(|(bid, ask)| {
+                                scale_price(
+                                    nord::MidPrice {
+                                        mid: (bid + ask) * 0.5,
+                                        exact_mid: None,
+                                        bid,
+                                        ask,
+                                        timestamp: recv_ms,
+                                    },
+                                    multiplier,
+                                )
+                            });
+                            tx.send_if_modified(|current| {
+                                let changed = price.is_some() || current.is_some();
+                                *current = price;
+                                changed
+                            });
+                        }
+                    }
+                    Some(Ok(Message::Ping(data))) => {
+                        last_message_time = Instant::now();
+                        sink.send(Message::Pong(data)).await?;
+                    }
+                    Some(Ok(Message::Close(_))) => return Err(ZoError::ConnectionClosed),
+                    Some(Ok(_)) => { last_message_time = Instant::now(); }
+                    Some(Err(e)) => return Err(ZoError::WebSocket(Box::new(e))),
+                    None => return Err(ZoError::ConnectionClosed),
+                }
+            }
+            _ = ping_interval.tick() => {
+                sink.send(Message::Text(parser.ping_message().to_string())).await?;
+            }
+            _ = stale_interval.tick() => {
+                let elapsed = last_message_time.elapsed();
+                if elapsed > STALE_THRESHOLD {
+                    return Err(ZoError::StaleConnection(elapsed.as_millis() as u64));
+                }
+            }
+            _ = cancel.cancelled() => {
+                let _ = sink.send(Message::Close(None)).await;
+                return Ok(());
+            }
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Venue protocols
+// ---------------------------------------------------------------------------
+
+/// Per-venue subscribe/ping messages and top-of-book parsing.
+#[derive(Debug)]
+struct TopOfBook {
+    venue: WsVenue,
+    /// Bybit `(price, size)` levels per side: a delta only carries the
+    /// levels that changed, with size zero for a deleted one.
+    bids: Vec<(f64, f64)>,
+    asks: Vec<(f64, f64)>,
+    /// Venue timestamp of the last parsed frame (epoch ms, 0 if absent).
+    event_ms: u64,
+}
+
+/// `[price, size, ...]` level as sent by Bybit and OKX.
+type RawLevel = Vec<String>;
+
+#[derive(Deserialize)]
+struct BybitMsg {
+    /// `"snapshot"` (replaces the book) or `"delta"`.
+    #[serde(rename = "type")]
+    kind: String,
+    #[serde(default)]
+    ts: u64,
+    data: BybitBook,
+}
+
+#[derive(Deserialize)]
+struct BybitBook {
+    #[serde(default)]
+    b: Vec<RawLevel>,
+    #[serde(default)]
+    a: Vec<RawLevel>,
+}
+
+#[derive(Deserialize)]
+struct OkxMsg {
+    data: Vec<OkxBook>,
+}
+
+#[derive(Deserialize)]
+struct OkxBook {
+    #[serde(default)]
+    ts: String,
+    #[serde(default)]
+    bids: Vec<RawLevel>,
+    #[serde(default)]
+    asks: Vec<RawLevel>,
+}
+
+#[derive(Deserialize)]
+struct HyperliquidMsg {
+    channel: String,
+    data: HyperliquidBbo,
+}
+
+#[derive(Deserialize)]
+struct HyperliquidBbo {
+    #[serde(default)]
+    time: u64,
+    /// `[bid, ask]`, either may be null when that side is empty.
+    bbo: [Option<HyperliquidLevel>; 2],
+}
+
+#[derive(Deserialize)]
+struct HyperliquidLevel {
+    px: String,
+}
+
+impl TopOfBook {
+    fn new(venue: WsVenue) -> Self {
+        Self {
+            venue,
+            bids: Vec::new(),
+            asks: Vec::new(),
+            event_ms: 0,
+        }
+    }
+
+    fn subscribe_message(&self, symbol: &str) -> String {
+        match self.venue {
+            WsVenue::Bybit => {
+                serde_json::json!({"op": "subscribe", "args": [format!("orderbook.1.{symbol}")]})
+                    .to_string()
+            }
+            WsVenue::Okx => serde_json::json!({
+                "op": "subscribe",
+                "args": [{"channel": "bbo-tbt", "instId": symbol}],
+            })
+            .to_string(),
+            WsVenue::Hyperliquid => serde_json::json!({
+                "method": "subscribe",
+                "subscription": {"type": "bbo", "coin": symbol},
+            })
+            .to_string(),
+        }
+    }
+
+    fn ping_message(&self) -> &'static str {
+        match self.venue {
+            WsVenue::Okx => "ping",
+            WsVenue::Bybit => r#"{"op":"ping"}"#,
+            WsVenue::Hyperliquid => r#"{"method":"ping"}"#,
+        }
+    }
+
+    /// Apply one book frame and return its top of book, `None` inside while
+    /// either side is empty. Subscription acks, pongs and unparseable frames
+    /// return `None`.
+    fn parse(&mut self, text: &str) -> Option<Option<(f64, f64)>> {
+        let (bid, ask) = match self.venue {
+            WsVenue::Bybit => {
+                let msg: BybitMsg = serde_json::from_str(text).ok()?;
+                self.event_ms = msg.ts;
+                let snapshot = msg.kind == "snapshot";
+                apply_levels(&mut self.bids, &msg.data.b, snapshot);
+                apply_levels(&mut self.asks, &msg.data.a, snapshot);
+                (
+                    self.bids.iter().map(|l| l.0).reduce(f64::max),
+                    self.asks.iter().map(|l| l.0).reduce(f64::min),
+                )
+            }
+            WsVenue::Okx => {
+                let msg: OkxMsg = serde_json::from_str(text).ok()?;
+                let book = msg.data.first()?;
+                self.event_ms = book.ts.parse().unwrap_or(0);
+                (best(&book.bids), best(&book.asks))
+            }
+            WsVenue::Hyperliquid => {
+                let msg: HyperliquidMsg = serde_json::from_str(text).ok()?;
+                if msg.channel != "bbo" {
+                    return None;
+                }
+                self.event_ms = msg.data.time;
+                let [bid, ask] = msg.data.bbo;
+                let px = |level: Option<HyperliquidLevel>| level?.px.parse().ok();
+                (px(bid), px(ask))
+            }
+        };
+        if bid.is_none() && ask.is_none() {
+            debug!(venue = %self.venue, "frame without top of book");
+        }
+        Some(bid.zip(ask))
+    }
+}
+
+/// Apply Bybit levels to one side: a snapshot replaces it, a delta upserts
+/// each level and deletes those with size zero.
+fn apply_levels(side: &mut Vec<(f64, f64)>, levels: &[RawLevel], snapshot: bool) {
+    if snapshot {
+        side.clear();
+    }
+    for level in levels {
+        let (Some(Ok(price)), Some(Ok(size))) = (
+            level.first().map(|p| p.parse::<f64>()),
+            level.get(1).map(|s| s.parse::<f64>()),
+        ) else {
+            continue;
+        };
+        side.retain(|l| l.0 != price);
+        if size > 0.0 {
+            side.push((price, size));
+        }
+    }
+}
+
+/// Price of the first level with a non-zero size.
+fn best(levels: &[RawLevel]) -> Option<f64> {
+    levels.iter().find_map(|level| {
+        let price: f64 = level.first()?.parse().ok()?;
+        let size: f64 = level.get(1)?.parse().ok()?;
+        (size > 0.0).then_some(price)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::net::TcpListener;
+
+    #[test]
+    fn test_parse_venue_frames() {
+        let mut bybit = TopOfBook::new(WsVenue::Bybit);
+        let snapshot = r#"{"topic":"orderbook.1.BTCUSDT","type":"snapshot","ts":1,"data":{"s":"BTCUSDT","b":[["100.5","2"]],"a":[["101.5","1"]],"u":1,"seq":1}}"#;
+        assert_eq!(bybit.parse(snapshot), Some(Some((100.5, 101.5))));
+        // Delta touching the ask only: the bid carries over.
+        let delta = r#"{"topic":"orderbook.1.BTCUSDT","type":"delta","ts":2,"data":{"s":"BTCUSDT","b":[],"a":[["101.5","0"],["101.0","3"]],"u":2,"seq":2}}"#;
+        assert_eq!(bybit.parse(delta), Some(Some((100.5, 101.0))));
+        // The best bid is deleted: no stale bid is kept.
+        let delta = r#"{"topic":"orderbook.1.BTCUSDT","type":"delta","ts":3,"data":{"s":"BTCUSDT","b":[["100.5","0"]],"a":[],"u":3,"seq":3}}"#;
+        assert_eq!(bybit.parse(delta), Some(None));
+        let delta = r#"{"topic":"orderbook.1.BTCUSDT","type":"delta","ts":4,"data":{"s":"BTCUSDT","b":[["100.2","1"]],"a":[],"u":4,"seq":4}}"#;
+        assert_eq!(bybit.parse(delta), Some(Some((100.2, 101.0))));
+        assert_eq!(bybit.parse(r#"{"success":true,"op":"subscribe"}"#), None);
+
+        let mut okx = TopOfBook::new(WsVenue::Okx);
+        let frame = r#"{"arg":{"channel":"bbo-tbt","instId":"BTC-USDT-SWAP"},"data":[{"asks":[["8476.98","415","0","13"]],"bids":[["8476.97","256","0","12"]],"ts":"1597026383085"}]}"#;
+        assert_eq!(okx.parse(frame), Some(Some((8476.97, 8476.98))));
+        assert_eq!(okx.parse("pong"), None);
+
+        let mut hl = TopOfBook::new(WsVenue::Hyperliquid);
+        let frame = r#"{"channel":"bbo","data":{"coin":"BTC","time":1,"bbo":[{"px":"99","sz":"1","n":2},null]}}"#;
+        assert_eq!(hl.parse(frame), Some(None), "ask side empty");
+        let frame = r#"{"channel":"bbo","data":{"coin":"BTC","time":2,"bbo":[{"px":"99","sz":"1","n":2},{"px":"99.5","sz":"3","n":1}]}}"#;
+        assert_eq!(hl.parse(frame), Some(Some((99.0, 99.5))));
+    }
+
+    #[tokio::test]
+    async fn test_source_against_mock_server() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        let server = tokio::spawn(async move {
+            let (tcp, _) = listener.accept().await.unwrap();
+            let mut ws = tokio_tungstenite::accept_async(tcp).await.unwrap();
+            let subscribe = match ws.next().await {
+                Some(Ok(Message::Text(text))) => text,
+                other => panic!("expected subscribe, got {other:?}"),
+            };
+            ws.send(Message::Text(r#"{"event":"subscribe"}"#.into()))
+                .await
+                .unwrap();
+            let frame = r#"{"arg":{"channel":"bbo-tbt","instId":"ETH-USDT-SWAP"},"data":[{"asks":[["2001","1","0","1"]],"bids":[["1999","1","0","1"]],"ts":"1"}]}"#;
+            ws.send(Message::Text(frame.into())).await.unwrap();
+            // Hold the connection open until the client closes it.
+            while let Some(Ok(msg)) = ws.next().await {
+                if msg.is_close() {
+                    break;
+                }
+            }
+            subscribe
+        });
+
+        let source = WsPriceSource::new(WsVenue::Okx, "ETH-USDT-SWAP").with_url(&url);
+        let mut rx = source.subscribe_price();
+        source.connect();
+        let mid = time::timeout(Duration::from_secs(5), async {
+            loop {
+                rx.changed().await.unwrap();
+                if let Some(mid) = *rx.borrow_and_update() {
+                    return mid;
+                }
+            }
+        })
+        .await
+        .expect("no price from mock server");
+        assert_eq!((mid.bid, mid.ask, mid.mid), (1999.0, 2001.0, 2000.0));
+        assert_eq!(source.get_mid_price(), Some(mid));
+
+        source.close();
+        let subscribe: serde_json::Value = serde_json::from_str(&server.await.unwrap()).unwrap();
+        assert_eq!(subscribe["args"][0]["instId"], "ETH-USDT-SWAP");
+    }
+}
@@ -0,0 +1,260 @@
+//! Mapping from 01 markets to reference-venue instruments.
+//!
+//! The default venue symbol is derived from the market symbol (e.g.
+//! `"BTC-PERP"` → `btcusdt` on Binance). That breaks for assets a venue only
+//! lists in multiples, such as `1000PEPEUSDT` on Binance or `kPEPE` on
+//! Hyperliquid, whose prices differ from the 01 price by a constant factor,
+//! and for assets a venue does not list at all. A [`SymbolRegistry`] holds
+//! explicit [`SymbolMapping`]s per market base asset:
+//!
+//! ```text
+//!   "PEPE-PERP" --base--> "PEPE" --> [ Binance 1000pepeusdt x0.001,
+//!                                      Bybit   1000PEPEUSDT x0.001, ... ]
+//!                                            |
+//!   venue price * multiplier = 01 price  <---+  (applied in the source,
+//!                                                before fair pricing)
+//! ```
+//!
+//! Built-in defaults cover the common multiplied listings; a JSON file can add
+//! markets or replace their mappings:
+//!
+//! ```text
+//! {
+//!   "PEPE": [{"venue": "binance", "symbol": "1000pepeusdt", "multiplier": 0.001}],
+//!   "HYPE": [{"venue": "hyperliquid", "symbol": "HYPE"}]
+//! }
+//! ```
+//!
+//! The first mapping of a market is its default reference venue. Because a
+//! wrong multiplier is off by orders of magnitude, [`check_index_band`]
+//! compares each venue's mapped price against the exchange's index price at
+//! startup.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use serde::Deserialize;
+
+use crate::error::ZoError;
+use crate::source::Venue;
+
+/// Built-in mappings: `(base, venue, symbol, multiplier)`.
+const BUILTIN: &[(&str, Venue, &str, f64)] = &[
+    ("PEPE", Venue::Binance, "1000pepeusdt", 0.001),
+    ("PEPE", Venue::Bybit, "1000PEPEUSDT", 0.001),
+    ("PEPE", Venue::Hyperliquid, "kPEPE", 0.001),
+    ("BONK", Venue::Binance, "1000bonkusdt", 0.001),
+    ("BONK", Venue::Bybit, "1000BONKUSDT", 0.001),
+    ("BONK", Venue::Hyperliquid, "kBONK", 0.001),
+    ("SHIB", Venue::Binance, "1000shibusdt", 0.001),
+    ("SHIB", Venue::Bybit, "SHIB1000USDT", 0.001),
+    ("SHIB", Venue::Hyperliquid, "kSHIB", 0.001),
+    ("FLOKI", Venue::Binance, "1000flokiusdt", 0.001),
+    ("FLOKI", Venue::Bybit, "1000FLOKIUSDT", 0.001),
+    ("FLOKI", Venue::Hyperliquid, "kFLOKI", 0.001),
+];
+
+/// Instrument tracking an 01 market on one venue.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct SymbolMapping {
+    pub venue: Venue,
+    /// Instrument in the venue's format (e.g. `"1000PEPEUSDT"` on Bybit).
+    pub symbol: String,
+    /// Factor converting a venue price into the 01 price (e.g. `0.001` for a
+    /// 1000x listing).
+    #[serde(default = "unit_multiplier")]
+    pub multiplier: f64,
+}
+
+fn unit_multiplier() -> f64 {
+    1.0
+}
+
+impl SymbolMapping {
+    /// Mapping derived from the market symbol, without a multiplier.
+    pub fn derived(venue: Venue, market_symbol: &str) -> Self {
+        Self {
+            venue,
+            symbol: derive_venue_symbol(venue, market_symbol),
+            multiplier: 1.0,
+        }
+    }
+}
+
+/// Symbol mappings keyed by market base asset (e.g. `"PEPE"`).
+#[derive(Debug, Clone, Default)]
+pub struct SymbolRegistry {
+    markets: HashMap<String, Vec<SymbolMapping>>,
+}
+
+impl SymbolRegistry {
+    /// Registry with the built-in mappings only.
+    pub fn builtin() -> Self {
+        let mut markets: HashMap<String, Vec<SymbolMapping>> = HashMap::new();
+        for &(base, venue, symbol, multiplier) in BUILTIN {
+            markets
+                .entry(base.to_string())
+                .or_default()
+                .push(SymbolMapping {
+                    venue,
+                    symbol: symbol.to_string(),
+                    multiplier,
+                });
+        }
+        Self { markets }
+    }
+
+    /// Built-in mappings overridden by the JSON file at `path`. A market in
+    /// the file replaces all built-in mappings of that market.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ZoError::Config`] if the file cannot be read or contains an
+    /// invalid mapping, or [`ZoError::Json`] if it is not valid JSON.
+    pub fn load(path: &Path) -> Result<Self, ZoError> {
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| ZoError::Config(format!("symbol map {}: {e}", path.display())))?;
+        let mut registry = Self::builtin();
+        registry.merge_json(&text)?;
+        Ok(registry)
+    }
+
+    /// Replace the mappings of every market in `json`.
+    fn merge_json(&mut self, json: &str) -> Result<(), ZoError> {
+        let markets: HashMap<String, Vec<SymbolMapping>> = serde_json::from_str(json)?;
+        for (base, mappings) in markets {
+            if mappings.is_empty() {
+                return Err(ZoError::Config(format!(
+                    "symbol map: {base} has no mappings"
+                )));
+            }
+            if let Some(m) = mappings
+                .iter()
+                .find(|m| !(m.multiplier.is_finite() && m.multiplier > 0.0))
+            {
+                return Err(ZoError::Config(format!(
+                    "symbol map: {base} on {} has invalid multiplier {}",
+                    m.venue, m.multiplier
+                )));
+            }
+            self.markets.insert(base.to_uppercase(), mappings);
+        }
+        Ok(())
+    }
+
+    /// Mapping of `market_symbol` on `venue`, derived from the market symbol
+    /// if the registry has none.
+    pub fn mapping(&self, market_symbol: &str, venue: Venue) -> SymbolMapping {
+        self.markets
+            .get(&market_base(market_symbol))
+            .and_then(|mappings| mappings.iter().find(|m| m.venue == venue))
+            .cloned()
+            .unwrap_or_else(|| SymbolMapping::derived(venue, market_symbol))
+    }
+
+    /// Default reference venue of `market_symbol`: its first mapping, or
+    /// Binance.
+    pub fn default_venue(&self, market_symbol: &str) -> Venue {
+        self.markets
+            .get(&market_base(market_symbol))
+            .and_then(|mappings| mappings.first())
+            .map_or(Venue::Binance, |m| m.venue)
+    }
+}
+
+/// Base asset of a market symbol (`"PEPE-PERP"` or `"PEPEUSD"` →
+/// `"PEPE"`).
+pub fn market_base(market_symbol: &str) -> String {
+    let base = market_symbol
+        .split('-')
+        .next()
+        .unwrap_or(market_symbol)
+        .to_uppercase();
+    match base.strip_suffix("USD") {
+        Some(stripped) if !stripped.is_empty() => stripped.to_string(),
+        _ => base,
+    }
+}
+
+/// Venue symbol of the instrument tracking an 01 market, derived from its
+/// base asset (`"BTC-PERP"` → `"btcusdt"` on Binance, `"BTC-USDT-SWAP"` on
+/// OKX).
+pub fn derive_venue_symbol(venue: Venue, market_symbol: &str) -> String {
+    let base = market_base(market_symbol);
+    match venue {
+        Venue::Binance => format!("{}usdt", base.to_lowercase()),
+        Venue::Bybit => format!("{base}USDT"),
+        Venue::Okx => format!("{base}-USDT-SWAP"),
+        Venue::Hyperliquid => base,
+    }
+}
+
+/// Check that the mapped reference mid is within `max_deviation_bps` of the
+/// exchange's index price.
+///
+/// # Errors
+///
+/// Returns [`ZoError::Config`] naming the reference if the prices are too
+/// far apart, which usually means a wrong symbol or multiplier.
+pub fn check_index_band(
+    reference: &str,
+    reference_mid: f64,
+    index_price: f64,
+    max_deviation_bps: f64,
+) -> Result<(), ZoError> {
+    let deviation_bps = ((reference_mid - index_price) / index_price).abs() * 10_000.0;
+    if deviation_bps.is_finite() && deviation_bps <= max_deviation_bps {
+        return Ok(());
+    }
+    Err(ZoError::Config(format!(
+        "reference {reference} price {reference_mid} is {deviation_bps:.0} bps from index \
+         price {index_price} (max {max_deviation_bps}); check the symbol mapping"
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_registry_builtin_file_override_and_fallback() {
+        let mut registry = SymbolRegistry::builtin();
+        let pepe = registry.mapping("PEPE-PERP", Venue::Binance);
+        assert_eq!(pepe.symbol, "1000pepeusdt");
+        assert_eq!(pepe.multiplier, 0.001);
+        assert_eq!(
+            registry.mapping("BTC-PERP", Venue::Okx),
+            SymbolMapping::derived(Venue::Okx, "BTC-PERP")
+        );
+
+        registry
+            .merge_json(r#"{"hype": [{"venue": "hyperliquid", "symbol": "HYPE"}]}"#)
+            .unwrap();
+        assert_eq!(registry.default_venue("HYPE-PERP"), Venue::Hyperliquid);
+        assert_eq!(
+            registry.mapping("HYPE-PERP", Venue::Hyperliquid).multiplier,
+            1.0
+        );
+        assert_eq!(registry.default_venue("PEPE-PERP"), Venue::Binance);
+
+        let bad = r#"{"PEPE": [{"venue": "bybit", "symbol": "X", "multiplier": 0}]}"#;
+        assert!(registry.merge_json(bad).is_err());
+    }
+
+    #[test]
+    fn test_derive_venue_symbol() {
+        assert_eq!(derive_venue_symbol(Venue::Binance, "BTC-PERP"), "btcusdt");
+        assert_eq!(derive_venue_symbol(Venue::Binance, "DOGEUSD"), "dogeusdt");
+        assert_eq!(derive_venue_symbol(Venue::Bybit, "BTC-PERP"), "BTCUSDT");
+        assert_eq!(derive_venue_symbol(Venue::Okx, "eth-perp"), "ETH-USDT-SWAP");
+        assert_eq!(derive_venue_symbol(Venue::Hyperliquid, "SOLUSD"), "SOL");
+        assert_eq!(market_base("PEPEUSD"), market_base("PEPE-PERP"));
+    }
+
+    #[test]
+    fn test_index_band() {
+        assert!(check_index_band("Binance:1000pepeusdt", 0.0101, 0.01, 500.0).is_ok());
+        // Multiplier missing: 1000x off.
+        assert!(check_index_band("Binance:1000pepeusdt", 10.1, 0.01, 500.0).is_err());
+    }
+}
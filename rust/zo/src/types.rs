@@ -1,12 +1,15 @@
 use rust_decimal::Decimal;
 use serde::Deserialize;
+use serde_json::value::RawValue;
 
-/// Binance combined stream envelope: `{"stream":"btcusdt@bookTicker","data":{...}}`
+/// Binance combined-stream envelope `{"stream":"<name>","data":<payload>}`.
+/// The payload is left unparsed until the stream name selects its type.
 #[derive(Debug, Deserialize)]
-#[allow(dead_code)]
-pub struct CombinedStreamMsg {
-    pub stream: String,
-    pub data: BookTickerMsg,
+pub struct StreamEnvelope<'a> {
+    #[serde(borrow)]
+    pub stream: &'a str,
+    #[serde(borrow)]
+    pub data: &'a RawValue,
 }
 
 /// Binance bookTicker payload.
@@ -40,6 +43,61 @@ pub struct BookTickerMsg {
     pub ask_qty: String,
 }
 
+/// Binance partial book depth payload (`@depth5` / `@depth10` / `@depth20`).
+///
+/// Field names match the Binance API:
+///   e  = event type
+///   E  = event time (ms)
+///   T  = transaction time (ms)
+///   s  = symbol
+///   b  = bids, best first, as `[price, qty]` strings
+///   a  = asks, best first, as `[price, qty]` strings
+#[derive(Debug, Deserialize)]
+#[allow(dead_code)]
+pub struct DepthMsg {
+    #[serde(default)]
+    pub e: String,
+    #[serde(default)]
+    #[serde(rename = "E")]
+    pub event_time: u64,
+    #[serde(default)]
+    #[serde(rename = "T")]
+    pub transaction_time: u64,
+    pub s: String,
+    pub b: Vec<[String; 2]>,
+    pub a: Vec<[String; 2]>,
+}
+
+/// Binance aggTrade payload.
+///
+/// Field names match the Binance API:
+///   e  = event type
+///   E  = event time (ms)
+///   a  = aggregate trade id
+///   s  = symbol
+///   p  = price (string)
+///   q  = quantity (string)
+///   T  = trade time (ms)
+///   m  = buyer is the maker (the aggressor sold)
+#[derive(Debug, Deserialize)]
+#[allow(dead_code)]
+pub struct AggTradeMsg {
+    #[serde(default)]
+    pub e: String,
+    #[serde(default)]
+    #[serde(rename = "E")]
+    pub event_time: u64,
+    #[serde(default)]
+    #[serde(rename = "a")]
+    pub trade_id: u64,
+    pub s: String,
+    pub p: String,
+    pub q: String,
+    #[serde(rename = "T")]
+    pub trade_time: u64,
+    pub m: bool,
+}
+
 /// A quote for order placement with side, price, and size.
 #[derive(Debug, Clone)]
 pub struct Quote {
@@ -0,0 +1,206 @@
+//! 01 Exchange top-of-book and trade feed (`zo feed --venue zo|both`).
+//!
+//! Streams each market's best bid/ask from its live [`nord::OrderbookStream`]
+//! and, optionally, its trades, in the same TSV / JSON line formats as the
+//! Binance bookTicker and aggTrade lines of [`run_feed`]:
+//!
+//! ```text
+//!   01 WS deltas --> OrderbookManager --> BookSignals (watch, per market)
+//!                                              |  top changed?
+//!                                              v
+//!   01 WS trades ----------------------> stdout lines <-- Binance feed
+//!                                                         (--venue both)
+//! ```
+//!
+//! With `--venue both` the Binance feed of every market runs alongside on the
+//! same stdout. Lines are written whole, so the two venues interleave in
+//! arrival order and can be told apart by symbol (`BTC-PERP` vs `BTCUSDT`).
+//! Binance instruments listed in multiples (e.g. `1000PEPEUSDT`) are printed
+//! in 01 prices and sizes, using the multiplier of their symbol mapping.
+
+use std::collections::HashMap;
+use std::io;
+use std::sync::Arc;
+
+use futures_util::stream::{self, SelectAll, Stream, StreamExt};
+use tokio::sync::{broadcast, watch};
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, info, warn};
+
+use crate::client::{mainnet_config, resolve_markets};
+use crate::error::ZoError;
+use crate::feed::{run_feed, BinanceStreams, TradeFlowWindow};
+use crate::output::{self, PriceMultipliers};
+use crate::source::Venue;
+use crate::symbols::SymbolRegistry;
+
+/// Venues streamed by `zo feed`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum FeedVenue {
+    /// Binance USDⓈ-M futures; symbols are Binance pairs (e.g. `btcusdt`).
+    #[default]
+    Binance,
+    /// 01 Exchange; symbols are market prefixes (e.g. `BTC`).
+    Zo,
+    /// 01 Exchange interleaved with each market's Binance reference.
+    Both,
+}
+
+/// Top of book last printed for a market.
+type PrintedTop = (nord::BBO, f64, f64);
+
+/// Stream 01 Exchange top-of-book (and trade) lines to stdout until `cancel`
+/// fires.
+///
+/// # Arguments
+///
+/// * `symbols` - Market symbol prefixes (e.g. `["BTC", "ETH"]`).
+/// * `json_mode` - Print JSON lines instead of TSV.
+/// * `trades` - Also print trades with the rolling taker flow.
+/// * `reference` - Also stream each market's Binance instrument (from
+///   `registry`) with these streams.
+/// * `registry` - Symbol map giving each market's Binance instrument.
+/// * `cancel` - Stops the feed.
+///
+/// # Errors
+///
+/// Returns [`ZoError`] if a market is unknown or an initial orderbook
+/// snapshot fails.
+pub async fn run_zo_feed(
+    symbols: &[String],
+    json_mode: bool,
+    trades: bool,
+    reference: Option<&BinanceStreams>,
+    registry: &SymbolRegistry,
+    cancel: CancellationToken,
+) -> Result<(), ZoError> {
+    let nord = nord::Nord::new(mainnet_config()).await?;
+
+    let market_symbols = resolve_markets(&nord, symbols)?;
+
+    let trade_symbols: &[String] = if trades { &market_symbols } else { &[] };
+    let mut ws = nord.create_websocket_client(trade_symbols, &market_symbols, &[], &[]);
+    ws.connect();
+    let trade_rx = ws.subscribe_trades();
+
+    let mut orderbooks = nord::OrderbookManager::new(
+        Arc::new(nord),
+        ws.subscribe_deltas(),
+        nord::OrderbookConfig::default(),
+    );
+    orderbooks.connect();
+    let mut books = SelectAll::new();
+    for symbol in &market_symbols {
+        let handle = orderbooks.add_market(symbol).await?;
+        books.push(Box::pin(signal_updates(
+            symbol.clone(),
+            handle.subscribe_signals(),
+        )));
+    }
+
+    info!(markets = ?market_symbols, trades, "streaming 01 Exchange");
+
+    match reference {
+        Some(streams) => {
+            let mappings: Vec<_> = market_symbols
+                .iter()
+                .map(|symbol| registry.mapping(symbol, Venue::Binance))
+                .collect();
+            let binance_symbols: Vec<String> = mappings.iter().map(|m| m.symbol.clone()).collect();
+            // Print multiplied listings (e.g. 1000PEPEUSDT) in 01 prices.
+            let multipliers: PriceMultipliers = mappings
+                .iter()
+                .filter(|m| m.multiplier != 1.0)
+                .map(|m| (m.symbol.to_uppercase(), m.multiplier))
+                .collect();
+            tokio::join!(
+                stream_zo(books, trade_rx, json_mode, &cancel),
+                run_feed(
+                    &binance_symbols,
+                    &multipliers,
+                    json_mode,
+                    streams,
+                    cancel.clone()
+                ),
+            );
+        }
+        None => stream_zo(books, trade_rx, json_mode, &cancel).await,
+    }
+
+    orderbooks.close();
+    ws.close();
+    Ok(())
+}
+
+/// Print book and trade lines until `cancel` fires or stdout closes.
+async fn stream_zo<S>(
+    mut books: SelectAll<S>,
+    mut trade_rx: broadcast::Receiver<Arc<nord::WebSocketTradeUpdate>>,
+    json_mode: bool,
+    cancel: &CancellationToken,
+) where
+    S: Stream<Item = (String, nord::BookSignals)> + Unpin,
+{
+    let mut writer = io::stdout();
+    let mut buf = String::with_capacity(512);
+    let mut flows: HashMap<String, TradeFlowWindow> = HashMap::new();
+    let mut printed: HashMap<String, PrintedTop> = HashMap::new();
+
+    loop {
+        let written = tokio::select! {
+            Some((symbol, signals)) = books.next() => {
+                // Deltas below the top of book change the signals only.
+                let top = (signals.bbo, signals.bid_size, signals.ask_size);
+                if printed.get(&symbol) == Some(&top) {
+                    continue;
+                }
+                printed.insert(symbol.clone(), top);
+                output::handle_book_signals(&symbol, &signals, json_mode, &mut buf, &mut writer)
+            }
+            result = trade_rx.recv() => match result {
+                Ok(update) => {
+                    let now = nord::epoch_ms();
+                    update.trades.iter().try_for_each(|trade| {
+                        output::handle_trade(
+                            &update.market_symbol, trade, now, &mut flows,
+                            json_mode, &mut buf, &mut writer,
+                        )
+                    })
+                }
+                Err(broadcast::error::RecvError::Lagged(n)) => {
+                    warn!(skipped = n, "trade stream lagged");
+                    Ok(())
+                }
+                Err(broadcast::error::RecvError::Closed) => {
+                    warn!("01 WebSocket closed");
+                    return;
+                }
+            },
+            _ = cancel.cancelled() => {
+                info!("01 feed stopped");
+                return;
+            }
+        };
+        if let Err(e) = written {
+            debug!(error = %e, "stdout closed");
+            cancel.cancel();
+            return;
+        }
+    }
+}
+
+/// Non-empty signal updates of one market, tagged with its symbol.
+fn signal_updates(
+    symbol: String,
+    rx: watch::Receiver<Option<nord::BookSignals>>,
+) -> impl Stream<Item = (String, nord::BookSignals)> {
+    stream::unfold((symbol, rx), |(symbol, mut rx)| async move {
+        loop {
+            rx.changed().await.ok()?;
+            let signals = rx.borrow_and_update().clone();
+            if let Some(signals) = signals {
+                return Some(((symbol.clone(), signals), (symbol, rx)));
+            }
+        }
+    })
+}